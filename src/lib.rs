@@ -0,0 +1,2365 @@
+// The calculating (and coloring) part of JLR-Mandelbrot, as a library.
+//
+// Everything in here works only with plain buffers and numbers:  there
+// are no windows, files, or printing, so it can be embedded in other
+// programs (see render_into_buffer()).  The JLR-Mandelbrot program
+// itself (src/main.rs) adds the window, the command-line switches,
+// the screenshots, and so on.
+
+
+// The following "allow"s keep clippy from complaining about
+// this library's deliberately explicit style (such as the
+// "return ()" statements and the spelled-out if/else chains):
+#![allow(clippy::collapsible_if)]
+#![allow(clippy::format_in_format_args)]
+#![allow(clippy::identity_op)]
+#![allow(clippy::if_same_then_else)]
+#![allow(clippy::needless_borrow)]
+#![allow(clippy::needless_return)]
+#![allow(clippy::partialeq_to_none)]
+#![allow(clippy::print_literal)]
+#![allow(clippy::ptr_arg)]
+#![allow(clippy::redundant_field_names)]
+#![allow(clippy::unused_unit)]
+
+
+// The WebAssembly entry points (only built with the "wasm" feature):
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+
+// The Float type defines the type of floating-point values
+// to use when calculating the fractal.  It should really
+// be set to the biggest float type available (which is
+// f64 today, but might be f128 tomorrow).  But if you're
+// curious, you can change it to f32 for comparison purposes.
+// (Or, without changing anything, use --float=f32, which calculates
+// the pixels in f32 while everything else stays in Float; see
+// EscapeFloat and FloatType.)
+pub type Float = f64;
+
+
+// The floating-point types that the pixels can be calculated in:  the
+// viewport (WindowAndViewportInfo), the conversion of each pixel's row
+// & column to x & y, and calculate_escape_details() (and the functions
+// it calls) are all generic over this.  Everything else uses Float.
+//
+// (Another type, like a bigger float, would only need to implement
+// these few functions to be usable for calculating the pixels.)
+pub trait EscapeFloat: Copy + PartialEq + PartialOrd + std::fmt::Debug + std::fmt::Display
+                       + std::ops::Add<Output = Self>
+                       + std::ops::Sub<Output = Self>
+                       + std::ops::Mul<Output = Self>
+                       + std::ops::Div<Output = Self> {
+    fn from_float(value: Float) -> Self;
+    fn to_float(self) -> Float;
+    fn abs(self) -> Self;
+    fn is_finite(self) -> bool;
+    fn is_normal(self) -> bool;
+}
+impl EscapeFloat for f32 {
+    #[inline]
+    fn from_float(value: Float) -> Self { value as f32 }
+    #[inline]
+    fn to_float(self) -> Float { self as Float }
+    #[inline]
+    fn abs(self) -> Self { f32::abs(self) }
+    #[inline]
+    fn is_finite(self) -> bool { f32::is_finite(self) }
+    #[inline]
+    fn is_normal(self) -> bool { f32::is_normal(self) }
+}
+#[allow(clippy::unnecessary_cast)]  // (The casts are needed if Float is changed to f32.)
+impl EscapeFloat for f64 {
+    #[inline]
+    fn from_float(value: Float) -> Self { value as f64 }
+    #[inline]
+    fn to_float(self) -> Float { self as Float }
+    #[inline]
+    fn abs(self) -> Self { f64::abs(self) }
+    #[inline]
+    fn is_finite(self) -> bool { f64::is_finite(self) }
+    #[inline]
+    fn is_normal(self) -> bool { f64::is_normal(self) }
+}
+
+
+// The floating-point types that the pixels can be calculated in
+// (see --float=TYPE and EscapeFloat).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FloatType {
+    F32,  // (Faster on some computers, but only good for about 7 digits.)
+    F64,  // (Good for about 16 digits.)
+}
+impl FloatType {
+    // Returns the FloatType with the given name
+    // (as used by --float=TYPE), if there is one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "f32" => Some(FloatType::F32),
+            "f64" => Some(FloatType::F64),
+            _ => None,
+        }
+    }
+
+    // Returns the name of this FloatType (the inverse of from_name()).
+    pub fn name(&self) -> &'static str {
+        match self {
+            FloatType::F32 => "f32",
+            FloatType::F64 => "f64",
+        }
+    }
+}
+
+
+// The formulas that can be iterated for each point (see --fractal=NAME):
+// z^2 + c, for the Mandelbrot set (and its Julia sets), or conj(z)^2 + c,
+// for the Tricorn (also called the Mandelbar), where z is flipped upside
+// down (its imaginary part negated) before it's squared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Formula {
+    Mandelbrot,  // (z^2 + c)
+    Tricorn,  // (conj(z)^2 + c)
+}
+impl Formula {
+    // Returns the Formula with the given name
+    // (as used by --fractal=NAME), if there is one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "mandelbrot" => Some(Formula::Mandelbrot),
+            "tricorn" => Some(Formula::Tricorn),
+            _ => None,
+        }
+    }
+
+    // Returns the name of this Formula (the inverse of from_name()).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Formula::Mandelbrot => "mandelbrot",
+            Formula::Tricorn => "tricorn",
+        }
+    }
+
+    // Returns what the product xy (of z = x+yi) is multiplied by to get
+    // the imaginary part of the squared z:  2 for the Mandelbrot set, as
+    // z^2 = x^2 - y^2 + 2xyi, and -2 for the Tricorn, as conj(z)^2 =
+    // x^2 - y^2 - 2xyi.  (That's the only difference between the two,
+    // so the Tricorn costs nothing extra to calculate.)
+    pub fn product_factor<T: EscapeFloat>(&self) -> T {
+        match self {
+            Formula::Mandelbrot => T::from_float(2.0),
+            Formula::Tricorn => T::from_float(-2.0),
+        }
+    }
+}
+
+
+// The ways of measuring how far z is from 0 for the escape test (see
+// --norm=NAME):  the usual (L2, or Euclidean) distance, sqrt(x^2 + y^2),
+// escapes outside a circle; the max-norm, max(|x|, |y|), outside a
+// square; and the L1 (or taxicab) norm, |x| + |y|, outside a diamond.
+// The escape radius for each is chosen so that its shape holds the
+// circle of radius 2 (2 for L2 and max, and 2*sqrt(2) for L1), so a
+// point that escapes really does escape, and the set itself comes out
+// the same; only the escape values (and so the bands of colors)
+// around it change shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Norm {
+    L2,
+    Max,
+    L1,
+}
+impl Norm {
+    // Returns the Norm with the given name
+    // (as used by --norm=NAME), if there is one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "l2" => Some(Norm::L2),
+            "max" => Some(Norm::Max),
+            "l1" => Some(Norm::L1),
+            _ => None,
+        }
+    }
+
+    // Returns the name of this Norm (the inverse of from_name()).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Norm::L2 => "l2",
+            Norm::Max => "max",
+            Norm::L1 => "l1",
+        }
+    }
+
+    // Returns true if z = x+yi is outside this norm's escape radius.
+    // (x_squared and y_squared are x*x and y*y, which are already
+    // calculated for squaring z, so the usual L2 test costs nothing
+    // extra.)
+    #[inline]
+    pub fn escaped<T: EscapeFloat>(&self, x: T, y: T, x_squared: T, y_squared: T) -> bool {
+        match self {
+            Norm::L2 => x_squared + y_squared > T::from_float(4.0),
+            Norm::Max => x_squared > T::from_float(4.0) || y_squared > T::from_float(4.0),
+            Norm::L1 => x.abs() + y.abs() > T::from_float(2.0 * std::f64::consts::SQRT_2 as Float),
+        }
+    }
+}
+
+
+// The ways the viewport can be laid out in the window
+// (see --projection=TYPE and convert_row_and_column_to_x_and_y()).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    Rectangular,  // (x goes across the window, and y goes up it.)
+    LogPolar,  // (The angle around the center goes across, and the distance from it shrinks going down.)
+}
+impl Projection {
+    // Returns the Projection with the given name
+    // (as used by --projection=TYPE), if there is one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rectangular" => Some(Projection::Rectangular),
+            "logpolar" => Some(Projection::LogPolar),
+            _ => None,
+        }
+    }
+
+    // Returns the name of this Projection (the inverse of from_name()).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Projection::Rectangular => "rectangular",
+            Projection::LogPolar => "logpolar",
+        }
+    }
+}
+
+
+// The palette used by color_components() cycles through three "legs"
+// (red to green, green to blue, and blue to red) of this many colors
+// each, for NUM_COLORS colors in all.
+//
+// The palette is anchored to the escape value itself (it just cycles
+// every NUM_COLORS iterations, or every --color-period iterations),
+// not stretched to fit the bailout, so
+// an escape value gets the same color whatever the bailout is.  That
+// keeps the colors from shifting when zooming in deeper (and raising
+// the bailout to go with it).
+const NUM_COLORS_PER_LEG: usize = 30;
+pub const NUM_COLORS: usize = NUM_COLORS_PER_LEG * 3;
+
+
+// Defining your own color palette is pretty easy if you know the RGB
+// value of each color.
+//
+// First, decide on the color for a point belonging to the actual set,
+// and set it as MANDELBROT_SET_COLOR.
+//
+// Then, decide what RGB triplet gets returned for a given i (iteration value).
+//
+// (Note:  These triplets range from 0.0 to 255.0 (inclusive).  They
+// don't have to be whole numbers; color() rounds them down to u8s,
+// and --dither spreads out the fractions with dithering.)
+pub fn color_components(i: Option<usize>) -> (Float, Float, Float) {
+
+    const MANDELBROT_SET_COLOR: (Float, Float, Float) = (0.0, 0.0, 102.0);  // (dark blue)
+    if i == None {
+        return MANDELBROT_SET_COLOR
+    }
+
+    let i = i.unwrap();
+
+    // If you want to write your own code that takes i as input
+    // and returns an RGB triplet, do it here.
+
+    let i = i % NUM_COLORS;
+    let remainder = i % NUM_COLORS_PER_LEG;
+
+    let value1 = (NUM_COLORS_PER_LEG - remainder) as Float * 255.0 / NUM_COLORS_PER_LEG as Float;
+    let value2 = remainder as Float * 255.0 / NUM_COLORS_PER_LEG as Float;
+
+    let leg = i / NUM_COLORS_PER_LEG;
+    match leg {
+        0 => (value1, value2, 0.0),
+        1 => (0.0, value1, value2),
+        2 => (value2, 0.0, value1),
+        // Should never get here, but include just in case:
+        _ => panic!("Reached state that should never have been reached."),
+    }
+}
+
+
+// Returns the iteration value that gives the same color as i would
+// with the palette running the other way (for --invert).  Inverting
+// twice gives back the original color (but with i % NUM_COLORS,
+// which color_components() doesn't tell apart from i).
+pub fn inverted_palette_index(i: usize) -> usize {
+    NUM_COLORS - 1 - (i % NUM_COLORS)
+}
+
+
+// Returns the color (as a u8 RGB triplet) for a given i (iteration
+// value), or for a point in the set if i is None.  (The palette itself
+// is defined in color_components().)
+pub fn color(i: Option<usize>) -> (u8, u8, u8) {
+    let (r, g, b) = color_components(i);
+    // (Rounding down keeps the colors the same as when
+    // the palette was calculated with whole numbers.)
+    let to_u8 = |value: Float| value.clamp(0.0, 255.0) as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+
+// A convenience function to turn RBG values
+// (from 0 to 255, inclusive) into a u32 integer.
+pub fn rgb_to_u32(r: u8, g: u8, b: u8) -> u32 {
+    ((r as u32) << 16) | ((g as u32) << 8) | (b as u32)
+}
+
+
+// Returns the color for a point inside the set whose orbit fell into
+// a cycle of the given period (as used by --interior-coloring=period).
+// Each period gets its own hue, with neighboring periods getting very
+// different hues (as they're spaced apart by the "golden angle").
+// If the period is unknown, then the usual set color is returned.
+//
+// (Like color_components(), the returned values range from 0.0 to
+// 255.0, and period_color() is the same but rounded to u8s.)
+pub fn period_color_components(period: Option<usize>) -> (Float, Float, Float) {
+    let period = match period {
+        Some(period) => period,
+        None => return color_components(None),
+    };
+
+    const GOLDEN_ANGLE: Float = 137.507_764;  // (in degrees)
+    let hue = ((period - 1) as Float * GOLDEN_ANGLE) % 360.0;
+
+    // Convert the hue (with a fixed saturation and value,
+    // so that the set's interior stays somewhat dark) to RGB:
+    let (saturation, value): (Float, Float) = (0.7, 0.6);
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match (hue / 60.0) as usize {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = value - chroma;
+    ((r + m) * 255.0, (g + m) * 255.0, (b + m) * 255.0)
+}
+
+
+// Returns the color for a point inside the set whose orbit fell into
+// a cycle of the given period, as a u8 RGB triplet.  (See
+// period_color_components().)
+pub fn period_color(period: Option<usize>) -> (u8, u8, u8) {
+    let (r, g, b) = period_color_components(period);
+    let to_u8 = |value: Float| value.round().clamp(0.0, 255.0) as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+
+// How many random colors a random palette (see Palette::random()) is
+// blended from, spaced evenly around it.  (More would give busier
+// palettes; fewer, plainer ones.)
+pub const RANDOM_PALETTE_CONTROL_POINTS: usize = 6;
+
+
+// A palette of colors that replaces the built-in one (of
+// color_components()) for escaping points (see --palette=random).
+// It's generated from a seed, so the same seed always gives the same
+// palette (on any computer), and the seed is all that needs saving
+// to draw it again.  (A random palette has NUM_COLORS colors, but a
+// palette can have any number of them (at least one); fewer colors
+// are stretched out to go around the palette once, blended by
+// sample_palette().)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    pub seed: u64,
+    pub colors: Vec<(u8, u8, u8)>,
+}
+impl Palette {
+    // Returns a random palette:  RANDOM_PALETTE_CONTROL_POINTS random
+    // colors, spaced evenly around the palette, with the colors between
+    // them blended along a cosine curve (so the blending slows down into
+    // and out of each control point, instead of turning sharply there).
+    // Like the built-in palette, it wraps around, with the last color
+    // blending back into the first, so no bands jump in color.
+    pub fn random(seed: u64) -> Self {
+        let mut rng = Xorshift64::new(seed);
+        let control_points: Vec<[Float; 3]> = (0..RANDOM_PALETTE_CONTROL_POINTS)
+            .map(|_| [0; 3].map(|_| rng.next_float() * 255.0))
+            .collect();
+        let colors = (0..NUM_COLORS).map(|i| {
+            let position = (i * RANDOM_PALETTE_CONTROL_POINTS) as Float / NUM_COLORS as Float;
+            let before = position.floor() as usize;
+            let after = (before + 1) % RANDOM_PALETTE_CONTROL_POINTS;
+            let fraction = (1.0 - ((position - before as Float) * std::f64::consts::PI as Float).cos()) / 2.0;
+            let [r, g, b] = [0, 1, 2].map(|channel| {
+                let (start, end) = (control_points[before][channel], control_points[after][channel]);
+                (start + (end - start) * fraction).round().clamp(0.0, 255.0) as u8
+            });
+            (r, g, b)
+        }).collect();
+        Self { seed, colors }
+    }
+}
+
+
+// Returns the color components (from 0.0 to 255.0, not rounded) at
+// the given (fractional) index into the palette's colors, blending
+// linearly between the colors on either side of it.  (The palette
+// wraps around, so the last color blends back into the first.)
+fn palette_components_at(palette: &Palette, index: Float) -> (Float, Float, Float) {
+    let length = palette.colors.len();
+    let before = index.floor();
+    let fraction = index - before;
+    let before = (before as i64).rem_euclid(length as i64) as usize;
+    let (r1, g1, b1) = palette.colors[before];
+    let (r2, g2, b2) = palette.colors[(before + 1) % length];
+    let blend = |start: u8, end: u8| start as Float + (end as Float - start as Float) * fraction;
+    (blend(r1, r2), blend(g1, g2), blend(b1, b2))
+}
+
+
+// Returns the color at the position t of the palette, where 0.0 is
+// its first color, and 1.0 is once all the way around it (back to
+// the first color again), blending linearly between the two colors
+// on either side of it, so that even a palette of just a few colors
+// goes smoothly from one to the next, without any hard steps.
+pub fn sample_palette(palette: &Palette, t: Float) -> (u8, u8, u8) {
+    rounded_color(palette_components_at(palette, t * palette.colors.len() as Float))
+}
+
+
+// Rounds color components (from 0.0 to 255.0) to a u8 RGB triplet.
+fn rounded_color((r, g, b): (Float, Float, Float)) -> (u8, u8, u8) {
+    let to_u8 = |value: Float| value.round().clamp(0.0, 255.0) as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+
+// Returns the index (see palette_components_at()) into the palette's
+// colors for a given i (iteration value), with the palette stretched
+// (or squeezed) to NUM_COLORS, like the built-in palette.  (For a
+// palette of NUM_COLORS colors, that's just i's own color, exactly.)
+fn configured_palette_index(palette: &Palette, i: usize) -> Float {
+    ((i % NUM_COLORS) * palette.colors.len()) as Float / NUM_COLORS as Float
+}
+
+
+// Returns the color components (from 0.0 to 255.0) for a given i
+// (iteration value), from the config's palette, if it has one
+// (sampled like sample_palette() does), or else from the built-in one
+// (see color_components()).  Points in the set (i of None) always get
+// the built-in set color.
+pub fn configured_color_components(i: Option<usize>, config: &Config) -> (Float, Float, Float) {
+    match (i, &config.palette) {
+        (Some(i), Some(palette)) => palette_components_at(palette, configured_palette_index(palette, i)),
+        _ => color_components(i),
+    }
+}
+
+
+// Returns the same color as configured_color_components(),
+// as a u8 RGB triplet (like color() does).
+pub fn configured_color(i: Option<usize>, config: &Config) -> (u8, u8, u8) {
+    match (i, &config.palette) {
+        (Some(i), Some(palette)) => rounded_color(palette_components_at(palette, configured_palette_index(palette, i))),
+        _ => color(i),
+    }
+}
+
+
+// The 4x4 "Bayer matrix" used for ordered dithering (see dither()).
+// Each of the 16 pixels of a 4x4 block gets a different threshold,
+// spread out so that no two neighboring pixels have similar ones.
+const BAYER_MATRIX: [[u8; 4]; 4] = [[ 0,  8,  2, 10],
+                                    [12,  4, 14,  6],
+                                    [ 3, 11,  1,  9],
+                                    [15,  7, 13,  5]];
+
+
+// Converts a color component (from 0.0 to 255.0, but not necessarily
+// a whole number) to a u8 with "ordered dithering":  instead of always
+// being rounded the same way, it's rounded up or down depending on the
+// pixel's row & column, so that a 4x4 block of pixels averages out to
+// (nearly) the exact component.  That hides the banding that rounding
+// every pixel the same way makes in smooth gradients.
+//
+// (Whole numbers are never changed, so colors with
+// no fractions to lose are left exactly as they were.)
+pub fn dither(value: Float, row: usize, column: usize) -> u8 {
+    let threshold = (BAYER_MATRIX[row % 4][column % 4] as Float + 0.5) / 16.0;
+    (value + threshold).floor().clamp(0.0, 255.0) as u8
+}
+
+
+
+// The main Mandelbrot set calculation function.
+// Given an (x, y) coordinate, it will return the number
+// of iterations needed to determine that the coordinate
+// is not part of the Mandelbrot set (or None if it is
+// part of the set).
+//
+// Note:  Using a value of None for c will make it be
+//        set to the passed-in (x,y), which is ideal
+//        for calculating the Mandelbrot set.
+//        For Julia sets, where c is the same for
+//        each and every coordinate, c can be passed
+//        in as Some((some_x as Float, some_y as Float)).
+//
+// The threshold specifies what's considered "close enough"
+// for x and y values when detecting cycles.  (Half the
+// length of a pixel is probably good enough.)
+//
+// The bailout value is the maximum number of times
+// Znext = Z + c
+// gets carried out (not counting the times for
+// cycle detection).  Without one, DEFAULT_MAX_ITERATIONS
+// is used instead, so that a point whose cycle is never
+// detected (with no threshold, or too small of one) can't
+// keep it going forever.
+pub fn calculate_escape_value<T: EscapeFloat>(x: T, y: T,
+                                              c: Option<(T, T)>,
+                                              threshold: Option<T>,
+                                              bailout: Option<usize>) -> Option<usize> {
+    calculate_escape_details(x, y, c, threshold, bailout, false).escape_value
+}
+
+
+// The details of calculating a single point,
+// as returned by calculate_escape_details().
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EscapeDetails {
+    // The same value calculate_escape_value() returns:
+    // the number of iterations it took to escape,
+    // or None if the point is part of the set.
+    pub escape_value: Option<usize>,
+    // For points that are part of the set, the length of the
+    // cycle that the point's orbit fell into (1 for the main
+    // cardioid, 2 for the big bulb to its left, and so on).
+    // This is None for escaping points, for points that hit
+    // the bailout before a cycle was detected, and whenever
+    // the period wasn't asked for.
+    pub period: Option<usize>,
+    // The number of iterations it took to find out whether the point
+    // escapes (that is, until it escaped, fell into a cycle, or hit the
+    // bailout).  This is the same as escape_value for escaping points.
+    pub iterations: usize,
+    // True if the point was only considered part of the set because
+    // it hit the bailout (so a higher bailout might show it escaping).
+    pub hit_bailout: bool,
+    // For escaping points, the magnitude of z (|z|, more than 2) right
+    // after it escaped; 0.0 for every other point.  (Together with the
+    // escape value, this gives the point's potential; see
+    // escape_potential().)
+    pub escape_magnitude: Float,
+}
+
+
+// Does the same calculations as calculate_escape_value(),
+// but returns more details about the point (see EscapeDetails).
+//
+// If measure_period is true, then whenever a cycle is detected,
+// its length (period) is measured as well.  (That takes a little
+// extra time, so it's only done if asked for.)
+//
+// (The calculations can be done in f32 instead of Float; see EscapeFloat.)
+pub fn calculate_escape_details<T: EscapeFloat>(x: T, y: T,
+                                                c: Option<(T, T)>,
+                                                threshold: Option<T>,
+                                                bailout: Option<usize>,
+                                                measure_period: bool) -> EscapeDetails {
+    let bailout = bailout.or(Some(DEFAULT_MAX_ITERATIONS));  // (See calculate_escape_value().)
+    calculate_cancellable_escape_details(x, y, c, threshold, bailout, measure_period, (Formula::Mandelbrot, Norm::L2, None))
+}
+
+
+// How many iterations go by between checks of the cancel flag (see
+// Config::cancel_flag) while a point is being calculated.  (Checking
+// it takes next to no time, but there's no need to do it often:  this
+// many iterations take well under a millisecond.)  It's also checked
+// before the first iteration, so that once it's set, the rest of the
+// image's pixels aren't calculated at all.
+const CANCEL_CHECK_INTERVAL: usize = 1 << 16;
+
+
+// The EscapeDetails of a point that was given up on (as if it had hit
+// the bailout) before it was calculated at all, because the cancel
+// flag was set.
+const CANCELLED_ESCAPE_DETAILS: EscapeDetails = EscapeDetails {
+    escape_value: None,
+    period: None,
+    iterations: 0,
+    hit_bailout: true,
+    escape_magnitude: 0.0,
+};
+
+
+// The EscapeDetails of a point whose coordinates (or c) aren't all
+// finite numbers (see all_finite()).  Such a point is nowhere in the
+// plane, let alone in the set, so it's said to escape right away (and
+// infinitely far).  Otherwise a NaN would fail every test (the escape
+// test and cycle detection alike), and be iterated until the bailout,
+// or forever without one.
+const NON_FINITE_ESCAPE_DETAILS: EscapeDetails = EscapeDetails {
+    escape_value: Some(0),
+    period: None,
+    iterations: 0,
+    hit_bailout: false,
+    escape_magnitude: Float::INFINITY,
+};
+
+
+// Returns true if none of the given numbers are infinity or NaN.
+fn all_finite<T: EscapeFloat, const N: usize>(numbers: [T; N]) -> bool {
+    numbers.iter().all(|number| number.is_finite())
+}
+
+
+// Returns true if the given cancel flag (if there is one) was set.
+fn cancelled(cancel_flag: Option<&std::sync::atomic::AtomicBool>) -> bool {
+    cancel_flag.is_some_and(|flag| flag.load(std::sync::atomic::Ordering::Relaxed))
+}
+
+
+// Does the work of calculate_escape_details(), iterating the given
+// formula (which is the Mandelbrot set's z^2 + c there), with the escape
+// test measured by the given norm (L2 there).  And if the
+// cancel_flag gets set (by another thread) while the point is being
+// calculated, gives up on it, returning it as if it had hit the
+// bailout.  (See Config::cancel_flag.)
+fn calculate_cancellable_escape_details<T: EscapeFloat>(x: T, y: T,
+                                                        c: Option<(T, T)>,
+                                                        threshold: Option<T>,
+                                                        bailout: Option<usize>,
+                                                        measure_period: bool,
+                                                        (formula, norm, cancel_flag): (Formula, Norm, Option<&std::sync::atomic::AtomicBool>))
+                                                            -> EscapeDetails {
+    let (c_x, c_y) = c.unwrap_or((x, y));
+    let zero = T::from_float(0.0);
+    let product_factor = formula.product_factor::<T>();  // (See Formula::product_factor().)
+    let threshold = threshold.unwrap_or(zero);
+
+    if !all_finite([x, y, c_x, c_y]) {
+        return NON_FINITE_ESCAPE_DETAILS
+    }
+
+    let escaped = |iterations: usize, magnitude_squared: T| EscapeDetails {
+        escape_value: Some(iterations),
+        period: None,
+        iterations,
+        hit_bailout: false,
+        escape_magnitude: magnitude_squared.to_float().sqrt(),
+    };
+    let bailed_out = |iterations: usize| EscapeDetails {
+        escape_value: None,
+        period: None,
+        iterations,
+        hit_bailout: true,
+        escape_magnitude: 0.0,
+    };
+    // When a cycle is detected, the slow point is (close enough to)
+    // being on the cycle, so the period is measured starting from it.
+    // (The period can't be more than the number of iterations the
+    // fast point took to catch up to the slow point.)
+    let cycle_detected = |x_slow: T, y_slow: T, iterations: usize| {
+        let period = if measure_period {
+            measure_cycle_period(x_slow, y_slow, (c_x, c_y), formula, threshold, iterations + 1)
+        } else {
+            None
+        };
+        EscapeDetails { escape_value: None, period, iterations, hit_bailout: false, escape_magnitude: 0.0 }
+    };
+
+    if cancelled(cancel_flag) {
+        return CANCELLED_ESCAPE_DETAILS
+    }
+    let mut iterations = 0;
+    let (mut x_slow, mut y_slow) = (x, y);
+    let (mut x_fast, mut y_fast) = (x, y);
+
+    // (Squaring z = x+yi takes three multiplications:  x*x and y*y
+    // (which are needed for the escape test anyway), and x*y for
+    // double_the_product.  Getting 2xy from (x+y)^2 - x^2 - y^2
+    // instead also takes three, but was measured to be slower, and
+    // loses precision, so 2xy is used.  See test_three_multiply_square().)
+    loop {
+        let (x_squared, y_squared) = (x_fast * x_fast, y_fast * y_fast);
+        if norm.escaped(x_fast, y_fast, x_squared, y_squared) {
+            return escaped(iterations, x_squared + y_squared)
+        }
+        let difference_of_squares = x_squared - y_squared;
+        let double_the_product = product_factor * x_fast * y_fast;
+        (x_fast, y_fast) = (difference_of_squares + c_x, double_the_product + c_y);
+        // Check to see if we've encountered this point before:
+        if threshold == zero {  // (if no threshold was specified)
+            if (x_fast, y_fast) == (x_slow, y_slow) {
+                return cycle_detected(x_slow, y_slow, iterations)
+            }
+        } else {  // (the threshold was specified)
+            if (x_fast - x_slow).abs() <= threshold && (y_fast - y_slow).abs() <= threshold {
+                return cycle_detected(x_slow, y_slow, iterations)
+            }
+        }
+        iterations += 1;
+        if let Some(bailout_to_use) = bailout {
+            if iterations == bailout_to_use {
+                return bailed_out(iterations)
+            }
+        }
+
+        let (x_squared, y_squared) = (x_fast * x_fast, y_fast * y_fast);
+        if norm.escaped(x_fast, y_fast, x_squared, y_squared) {
+            return escaped(iterations, x_squared + y_squared)
+        }
+        let difference_of_squares = x_squared - y_squared;
+        let double_the_product = product_factor * x_fast * y_fast;
+        (x_fast, y_fast) = (difference_of_squares + c_x, double_the_product + c_y);
+        // Check to see if we've encountered this point before:
+        if threshold == zero {  // (if no threshold was specified)
+            if (x_fast, y_fast) == (x_slow, y_slow) {
+                return cycle_detected(x_slow, y_slow, iterations)
+            }
+        } else {  // (the threshold was specified)
+            if (x_fast - x_slow).abs() <= threshold && (y_fast - y_slow).abs() <= threshold {
+                return cycle_detected(x_slow, y_slow, iterations)
+            }
+        }
+        iterations += 1;
+        if let Some(bailout_to_use) = bailout {
+            if iterations == bailout_to_use {
+                return bailed_out(iterations)
+            }
+        }
+
+        let (x_squared, y_squared) = (x_slow * x_slow, y_slow * y_slow);
+        let difference_of_squares = x_squared - y_squared;
+        let double_the_product = product_factor * x_slow * y_slow;
+        (x_slow, y_slow) = (difference_of_squares + c_x, double_the_product + c_y);
+        // Check to see if we've encountered this point before:
+        if threshold == zero {  // (if no threshold was specified)
+            if (x_fast, y_fast) == (x_slow, y_slow) {
+                return cycle_detected(x_slow, y_slow, iterations)
+            }
+        } else {  // (the threshold was specified)
+            if (x_fast - x_slow).abs() <= threshold && (y_fast - y_slow).abs() <= threshold {
+                return cycle_detected(x_slow, y_slow, iterations)
+            }
+        }
+        // Do not increment the iterations variable here,
+        // as we only do so after advancing the "fast" point cycle.
+
+        // (The iterations go up by two each time around the loop,
+        // so this is reached every CANCEL_CHECK_INTERVAL / 2 times.)
+        if iterations % CANCEL_CHECK_INTERVAL == 0 && cancelled(cancel_flag) {
+            return bailed_out(iterations)  // (Nobody's waiting for this point anymore.)
+        }
+
+        // (There's no time-out here:  without a bailout, a point that
+        // never escapes or falls into a detected cycle keeps going.
+        // Config::max_iterations is what keeps that from happening.)
+    }
+}
+
+
+
+// Does the same calculations as calculate_escape_details(), but
+// without looking for cycles:  only the "fast" point is iterated,
+// until it escapes or the bailout is reached (which is needed, since
+// otherwise points in the set would be iterated forever).  Escaping
+// points get the same escape values either way, but every point in
+// the set takes the full bailout number of iterations.
+//
+// Which is quicker?  Each iteration here is cheaper (there's no
+// "slow" point to advance, and nothing to compare), so this is
+// quicker for images that are mostly outside the set, or that
+// use a small bailout.  But cycle detection usually stops long
+// before the bailout for points inside the set, so it's quicker
+// for images with a lot of the set in them and a big bailout.
+// (See test_no_cycle_detection_speed().)
+pub fn calculate_escape_details_without_cycle_detection<T: EscapeFloat>(x: T, y: T,
+                                                                        c: Option<(T, T)>,
+                                                                        bailout: usize) -> EscapeDetails {
+    calculate_cancellable_escape_details_without_cycle_detection(x, y, c, bailout, (Formula::Mandelbrot, Norm::L2, None))
+}
+
+
+// Does the work of calculate_escape_details_without_cycle_detection(),
+// iterating the given formula (with the given norm), but gives up on the point if the
+// cancel_flag gets set (just like calculate_cancellable_escape_details()
+// does).
+fn calculate_cancellable_escape_details_without_cycle_detection<T: EscapeFloat>(x: T, y: T,
+                                                                                c: Option<(T, T)>,
+                                                                                bailout: usize,
+                                                                                (formula, norm, cancel_flag): (Formula, Norm, Option<&std::sync::atomic::AtomicBool>))
+                                                                                    -> EscapeDetails {
+    let (c_x, c_y) = c.unwrap_or((x, y));
+    let product_factor = formula.product_factor::<T>();
+    if !all_finite([x, y, c_x, c_y]) {
+        return NON_FINITE_ESCAPE_DETAILS
+    }
+    let (mut x, mut y) = (x, y);
+    for iterations in 0..bailout {
+        if iterations % CANCEL_CHECK_INTERVAL == 0 && cancelled(cancel_flag) {
+            return EscapeDetails { escape_value: None, period: None, iterations, hit_bailout: true, escape_magnitude: 0.0 }
+        }
+        let (x_squared, y_squared) = (x * x, y * y);
+        if norm.escaped(x, y, x_squared, y_squared) {
+            return EscapeDetails {
+                escape_value: Some(iterations),
+                period: None,
+                iterations,
+                hit_bailout: false,
+                escape_magnitude: (x_squared + y_squared).to_float().sqrt(),
+            }
+        }
+        (x, y) = (x_squared - y_squared + c_x, product_factor * x * y + c_y);
+    }
+    EscapeDetails { escape_value: None, period: None, iterations: bailout, hit_bailout: true, escape_magnitude: 0.0 }
+}
+
+
+// Given a point (x, y) that's (close enough to being) on an attracting
+// cycle (of the given formula, with the given c), returns the length of
+// that cycle:  the number of iterations it takes for the point to come
+// back to (within threshold of) itself.  Returns None if it doesn't
+// come back within max_period iterations.
+//
+// Before measuring, the point is iterated another max_period times,
+// so that it's closer to the cycle.  (Otherwise, a point that spirals
+// or zig-zags into its cycle can come back close to itself after
+// two or more trips around the cycle before it does after one trip,
+// making the period look like a multiple of what it really is.)
+pub fn measure_cycle_period<T: EscapeFloat>(x: T, y: T,
+                                            (c_x, c_y): (T, T),
+                                            formula: Formula,
+                                            threshold: T,
+                                            max_period: usize) -> Option<usize> {
+    let product_factor = formula.product_factor::<T>();
+    let (mut x, mut y) = (x, y);
+    for _ in 0..max_period {
+        (x, y) = (x * x - y * y + c_x, product_factor * x * y + c_y);
+    }
+
+    let (mut x_next, mut y_next) = (x, y);
+    for period in 1..=max_period {
+        (x_next, y_next) = (x_next * x_next - y_next * y_next + c_x,
+                            product_factor * x_next * y_next + c_y);
+        if (x_next - x).abs() <= threshold && (y_next - y).abs() <= threshold {
+            return Some(period)
+        }
+    }
+    None
+}
+
+
+// This structure contains information about the viewport
+// (that is, the cartesian coordinate bounds and spans).
+// It also contains the physical (width, height) of the
+// window (in pixels) and the zoom_level.
+//
+// With the exception of width and height (in pixels)
+// and the zoom_level, everything is a floating point
+// number, as they refer to the mathematical measurements
+// of the fractal itself.
+//
+// The numbers are normally Floats, but can be any EscapeFloat (so that
+// the pixels can be calculated in f32 with --float=f32; see converted()).
+#[allow(dead_code)]  // (There are some fields that aren't read, but might be in the future.)
+#[derive(Debug, Clone)]
+pub struct WindowAndViewportInfo<T = Float> {
+    pub width: usize,  // (in pixels)
+    pub height: usize,  // (in pixels)
+    pub center_x: T,
+    pub center_y: T,
+    pub span: T,
+    pub distance_from_center_to_edge: T,  // (half of the span)
+    pub min_x: T,
+    pub max_x: T,
+    pub min_y: T,
+    pub max_y: T,
+    pub delta_x: T,
+    pub delta_y: T,
+    pub zoom_level: isize,
+    pub projection: Projection,  // (Rectangular, unless with_projection() says otherwise.)
+}
+impl<T: EscapeFloat> WindowAndViewportInfo<T> {
+    pub fn new(width: usize, height: usize,  // (in pixels)
+               center_x: T, center_y: T, distance_from_center_to_edge: T,
+               zoom_level: isize)
+                   -> Self {
+
+        // (A distance of zero would put every pixel on the same point,
+        // making a solid-colored image without complaint.)
+        assert!(distance_from_center_to_edge.is_finite() && distance_from_center_to_edge > T::from_float(0.0),
+                "FATAL ERROR:  The distance from the center to the edge must be a (finite) number more than zero, not {distance_from_center_to_edge}.");
+        // (And a center of infinity or NaN would turn every pixel's
+        // coordinates into NaN, drawing garbage just as quietly.)
+        assert!(center_x.is_finite() && center_y.is_finite(),
+                "FATAL ERROR:  The center must be a pair of (finite) numbers, not {center_x},{center_y}.");
+
+        let span = distance_from_center_to_edge * T::from_float(2.0);
+        let min_x = center_x - distance_from_center_to_edge;
+        let max_x = center_x + distance_from_center_to_edge;
+        let min_y = center_y - distance_from_center_to_edge;
+        let max_y = center_y + distance_from_center_to_edge;
+        let delta_x = (max_x - min_x) / T::from_float(width as Float);
+        let delta_y = (max_y - min_y) / T::from_float(height as Float);
+
+        Self {
+            width,
+            height,
+            center_x,
+            center_y,
+            span,
+            distance_from_center_to_edge,
+            min_x,
+            max_x,
+            min_y,
+            max_y,
+            delta_x,
+            delta_y,
+            zoom_level,
+            projection: Projection::Rectangular,
+        }
+    }
+
+    // Returns true if new() would accept the given center and distance
+    // (that is, if they're all finite, and the distance is more than
+    // zero), so that views worked out along the way (like zooming way
+    // out, where they can overflow to infinity) can be checked first.
+    pub fn is_valid_view(center_x: T, center_y: T, distance_from_center_to_edge: T) -> bool {
+        center_x.is_finite() && center_y.is_finite()
+            && distance_from_center_to_edge.is_finite() && distance_from_center_to_edge > T::from_float(0.0)
+    }
+
+    // Returns the same viewport, laid out in the window with the given
+    // projection instead.  (The center and distance mean the same thing
+    // either way; see convert_row_and_column_to_x_and_y().)
+    pub fn with_projection(self, projection: Projection) -> Self {
+        Self { projection, ..self }
+    }
+
+    // Returns the size of the smallest pixel, in the Mandelbrot's domain.
+    // Every pixel is delta_x wide normally, but with the log-polar
+    // projection, they shrink going down the window, so the smallest
+    // ones are on the bottom row.
+    pub fn smallest_pixel_size(&self) -> T {
+        match self.projection {
+            Projection::Rectangular => self.delta_x,
+            Projection::LogPolar => {
+                let step = log_polar_step(self.width);
+                let bottom_radius = self.distance_from_center_to_edge.to_float()
+                                        * (-step * self.height as Float).exp();
+                T::from_float(bottom_radius * step)
+            }
+        }
+    }
+
+    // Returns true if this viewport is too small (or too big) to be
+    // drawn:  if the distance between pixels has underflowed to zero
+    // (or a subnormal number) or overflowed to infinity, or if it's
+    // so small that neighboring pixels land on the same Float, all of
+    // which would draw a meaningless, solid-colored image.
+    pub fn is_degenerate(&self) -> bool {
+        !self.delta_x.is_normal() || !self.delta_y.is_normal()
+            || self.center_x + self.delta_x == self.center_x
+            || self.center_y + self.delta_y == self.center_y
+            || (self.projection == Projection::LogPolar && {
+                    // (The pixels nearest the center are much smaller.)
+                    let pixel_size = self.smallest_pixel_size();
+                    !pixel_size.is_normal()
+                        || self.center_x + pixel_size == self.center_x
+                        || self.center_y + pixel_size == self.center_y
+                })
+    }
+
+    // Like is_degenerate(), but also checks that the viewport can be
+    // drawn in the given FloatType:  a view that's fine in Float can
+    // still underflow (or overflow) when converted to f32.
+    pub fn is_degenerate_for(&self, float_type: FloatType) -> bool {
+        self.is_degenerate() || match float_type {
+            FloatType::F32 => self.converted::<f32>().is_none_or(|info| info.is_degenerate()),
+            FloatType::F64 => self.converted::<f64>().is_none_or(|info| info.is_degenerate()),
+        }
+    }
+
+    // Returns the same viewport, but with its numbers in another
+    // EscapeFloat type.  (Only the center and distance are converted;
+    // everything else is worked out again in the new type, just like
+    // new() would if it were given them in the first place.)  Returns
+    // None if the center or distance can't be held in the new type,
+    // e.g. a distance of 1e-46 (which is zero in f32).
+    pub fn converted<U: EscapeFloat>(&self) -> Option<WindowAndViewportInfo<U>> {
+        let center_x = U::from_float(self.center_x.to_float());
+        let center_y = U::from_float(self.center_y.to_float());
+        let distance_from_center_to_edge = U::from_float(self.distance_from_center_to_edge.to_float());
+        if !WindowAndViewportInfo::is_valid_view(center_x, center_y, distance_from_center_to_edge) {
+            return None
+        }
+        Some(WindowAndViewportInfo::new(self.width, self.height,
+                                        center_x, center_y, distance_from_center_to_edge,
+                                        self.zoom_level)
+            .with_projection(self.projection))
+    }
+}
+
+
+// With the log-polar projection, the angle (in radians) between
+// neighboring columns, which is also the natural log of how much
+// the distance from the center shrinks from one row to the next.
+// (Making them the same keeps the pixels square, so the shapes of
+// the set aren't stretched.)
+pub fn log_polar_step(width: usize) -> Float {
+    std::f64::consts::TAU as Float / width as Float
+}
+
+
+// Converts a row&column coordinate (with row=0 & column=0 as the center
+// of upper-right pixel) to the Mandelbrot's domain's x,y coordinate:
+// (This works in whatever EscapeFloat type the viewport is in.)
+//
+// With the log-polar projection, the columns go once around the center
+// (counterclockwise, starting from the right), and the rows go in
+// towards it:  the top row is distance_from_center_to_edge away, and
+// each row after it is closer by a factor of e^log_polar_step(), so
+// a square window shows a range of distances of about 535 to 1, and
+// zooming in just scrolls the image up.  (The angle and distance are
+// worked out in Float, as EscapeFloat doesn't have sin() or exp().)
+pub fn convert_row_and_column_to_x_and_y<T: EscapeFloat>(info: &WindowAndViewportInfo<T>,
+                                                         row: T, column: T) -> (T, T) {
+    match info.projection {
+        Projection::Rectangular => {
+            let half = T::from_float(0.5);
+            let x = info.min_x + info.delta_x * (column + half);
+            let y = info.max_y - info.delta_y * (row + half);
+            (x, y)
+        }
+        Projection::LogPolar => {
+            let step = log_polar_step(info.width);
+            let angle = (column.to_float() + 0.5) * step;
+            let radius = info.distance_from_center_to_edge.to_float() * (-(row.to_float() + 0.5) * step).exp();
+            let x = info.center_x + T::from_float(radius * angle.cos());
+            let y = info.center_y + T::from_float(radius * angle.sin());
+            (x, y)
+        }
+    }
+}
+
+
+// The inverse of convert_row_and_column_to_x_and_y():  converts the
+// Mandelbrot's domain's x,y coordinate to a row&column coordinate
+// (which may be fractional, and may be outside of the window).
+//
+// (With the log-polar projection, the center itself is infinitely far
+// down, so its row is infinite.)
+pub fn convert_x_and_y_to_row_and_column(info: &WindowAndViewportInfo,
+                                         x: Float, y: Float) -> (Float, Float) {
+    match info.projection {
+        Projection::Rectangular => {
+            let row = (info.max_y - y) / info.delta_y - 0.5;
+            let column = (x - info.min_x) / info.delta_x - 0.5;
+            (row, column)
+        }
+        Projection::LogPolar => {
+            let step = log_polar_step(info.width);
+            let (offset_x, offset_y) = (x - info.center_x, y - info.center_y);
+            let angle = offset_y.atan2(offset_x).rem_euclid(std::f64::consts::TAU as Float);
+            let row = (info.distance_from_center_to_edge / offset_x.hypot(offset_y)).ln() / step - 0.5;
+            let column = angle / step - 0.5;
+            (row, column)
+        }
+    }
+}
+
+
+// Calculates the EscapeDetails of the pixel
+// at the given row & column of the viewport.
+//
+// With --float=f32, the viewport is converted to f32 first, so that
+// the pixel's x & y are worked out in f32, too (just as if Float were
+// f32).  (Converting the viewport for every pixel takes a handful of
+// calculations, which is nothing next to calculating the pixel.)
+pub fn calculate_pixel_details(info: &WindowAndViewportInfo,
+                               row: usize, column: usize,
+                               config: &Config,
+                               threshold: Float) -> EscapeDetails {
+    // (A viewport that can't be held in the FloatType at all, which
+    // is_degenerate_for() turns away up front, is treated like a
+    // non-finite point rather than panicking.)
+    match config.float_type {
+        FloatType::F32 => info.converted::<f32>().map_or(NON_FINITE_ESCAPE_DETAILS, |info| {
+            calculate_pixel_details_in(&info, row, column, config, threshold)
+        }),
+        FloatType::F64 => info.converted::<f64>().map_or(NON_FINITE_ESCAPE_DETAILS, |info| {
+            calculate_pixel_details_in(&info, row, column, config, threshold)
+        }),
+    }
+}
+
+
+// Does the work of calculate_pixel_details(), in the EscapeFloat
+// type that the given viewport is in.
+fn calculate_pixel_details_in<T: EscapeFloat>(info: &WindowAndViewportInfo<T>,
+                                              row: usize, column: usize,
+                                              config: &Config,
+                                              threshold: Float) -> EscapeDetails {
+    // Convert row & column into x & y:
+    let (x, y) = convert_row_and_column_to_x_and_y(info, T::from_float(row as Float), T::from_float(column as Float));
+
+    // Is (x, y) part of the set?  Let's find out.
+    calculate_point_details_in(x, y, config, T::from_float(threshold))
+}
+
+
+// Calculates the EscapeDetails of the point (x, y) with the given
+// config, in the EscapeFloat type given by --float=TYPE (see
+// calculate_point_details_in()).
+pub fn calculate_point_details(x: Float, y: Float,
+                           config: &Config,
+                           threshold: Float) -> EscapeDetails {
+    match config.float_type {
+        FloatType::F32 => calculate_point_details_in(f32::from_float(x), f32::from_float(y), config, f32::from_float(threshold)),
+        FloatType::F64 => calculate_point_details_in(f64::from_float(x), f64::from_float(y), config, f64::from_float(threshold)),
+    }
+}
+
+
+// The default for the most iterations any one point is ever given
+// (see Config::max_iterations).  That's far more than any bailout
+// that's practical to draw with, but still only a fraction of a
+// second for a single point.
+pub const DEFAULT_MAX_ITERATIONS: usize = 100_000_000;
+
+
+// Returns the number of iterations a point is calculated for (at
+// most) with the given config:  the smaller of the bailout and
+// max_iterations, or None if neither is set.
+pub fn iteration_limit(config: &Config) -> Option<usize> {
+    match (config.bailout, config.max_iterations) {
+        (Some(bailout), Some(max_iterations)) => Some(bailout.min(max_iterations)),
+        (bailout, max_iterations) => bailout.or(max_iterations),
+    }
+}
+
+
+// Calculates the EscapeDetails of the point (x, y) with the given
+// config:  with calculate_escape_details() normally, or with
+// calculate_escape_details_without_cycle_detection() if cycle
+// detection was turned off (and there's a bailout to stop at).
+// Either way, a point that reaches the iteration_limit() is taken to
+// be part of the set, as if it had hit the bailout.
+fn calculate_point_details_in<T: EscapeFloat>(x: T, y: T,
+                                              config: &Config,
+                                              threshold: T) -> EscapeDetails {
+    let c = config.c.map(|(c_x, c_y)| (T::from_float(c_x), T::from_float(c_y)));
+    let iteration_settings = (config.formula, config.norm, config.cancel_flag.as_deref());
+    let limit = iteration_limit(config);
+    match (config.cycle_detection, limit) {
+        (false, Some(limit)) => calculate_cancellable_escape_details_without_cycle_detection(x, y, c, limit, iteration_settings),
+        _ => {
+            // (The period is only needed if it'll be used for coloring.)
+            let measure_period = config.interior_coloring == InteriorColoring::Period;
+            calculate_cancellable_escape_details(x, y, c, Some(threshold), limit, measure_period, iteration_settings)
+        }
+    }
+}
+
+
+// Calculates the EscapeDetails of the point (x, y) with the given
+// config (just like a pixel there would be calculated), and also
+// returns its orbit:  the values z takes, starting with z = (x, y)
+// and ending with z after details.iterations iterations.  (For a
+// point that escapes, that last z is the one that escaped.)
+//
+// The orbit is recorded by going over the same iterations again once
+// the details are known, so that calculating the details themselves
+// isn't slowed down at all by this (it's only meant for looking into
+// single points, to see why they're calculated the way they are).
+pub fn calculate_orbit(x: Float, y: Float,
+                       config: &Config,
+                       threshold: Float) -> (EscapeDetails, Vec<(Float, Float)>) {
+    let details = calculate_point_details(x, y, config, threshold);
+    let (c_x, c_y) = config.c.unwrap_or((x, y));
+    let product_factor = config.formula.product_factor::<Float>();
+    let mut orbit = Vec::with_capacity(details.iterations + 1);
+    let (mut z_x, mut z_y) = (x, y);
+    orbit.push((z_x, z_y));
+    for _ in 0..details.iterations {
+        // (The same calculations, in the same order, as
+        // calculate_escape_details(), so the values match exactly.
+        // (With --float=f32, the orbit is still recorded in Float,
+        // so it can stray a little from the one that was calculated.))
+        let (x_squared, y_squared) = (z_x * z_x, z_y * z_y);
+        (z_x, z_y) = (x_squared - y_squared + c_x, product_factor * z_x * z_y + c_y);
+        orbit.push((z_x, z_y));
+    }
+    (details, orbit)
+}
+
+
+// The escape radius that external rays start from, and how many
+// points are placed along each ray every time its angle is doubled
+// (that is, every time it gets one more iteration deep).  (These are
+// the values suggested by Claude Heiland-Allen in his "Newton's method
+// for external rays" posts; more points per doubling would follow the
+// ray more closely, but more slowly.)
+const RAY_ESCAPE_RADIUS: Float = 65536.0;
+const RAY_POINTS_PER_DOUBLING: usize = 8;
+const RAY_MAX_NEWTON_STEPS: usize = 64;
+
+
+// Traces the external ray of the Mandelbrot set with the given angle
+// (numerator / denominator, in turns, from 0 (inclusive) to 1
+// (exclusive)), and returns the points (x, y) along it, starting far
+// outside the set and heading in towards the point where it lands on
+// the set's boundary.
+//
+// An external ray is a field line of the set's (complex) potential:
+// every point c on it has phi(c) with the same angle, where phi is the
+// function that maps the outside of the set onto the outside of the
+// unit circle.  Since z(n) is about phi(c)^(2^n) for large z, each
+// point is found by Newton's method, solving z(n) = r e^(i 2^n angle)
+// for c (starting from the point before it), with the radius r slowly
+// shrinking, and with n (and the angle's doublings) going up by one
+// whenever r gets back down to sqrt(RAY_ESCAPE_RADIUS).  (The angle is
+// a fraction, so that it can be doubled exactly any number of times.)
+//
+// Tracing stops once the ray gets within min_distance of the set
+// (like the width of a pixel, going by the usual distance estimate,
+// 2 |z| ln|z| / |dz/dc|), or once max_points points have been
+// found, or once Newton's method stops converging (which happens when
+// the ray gets close enough to the set that a Float can't follow it).
+pub fn external_ray((numerator, denominator): (u64, u64),
+                    min_distance: Float,
+                    max_points: usize) -> Vec<(Float, Float)> {
+    assert!(denominator > 0 && numerator < denominator && denominator < 1 << 62,
+            "FATAL ERROR:  The angle of an external ray must be from 0/1 up to (but not including) 1/1.");
+    let angle_of = |numerator: u64| 2.0 * std::f64::consts::PI as Float * numerator as Float / denominator as Float;
+
+    let mut numerator = numerator;  // (The numerator of the angle after being doubled n times.)
+    let (mut c_x, mut c_y) = (RAY_ESCAPE_RADIUS * angle_of(numerator).cos(),
+                              RAY_ESCAPE_RADIUS * angle_of(numerator).sin());
+    let mut points = vec![(c_x, c_y)];
+    for n in 0.. {
+        for step in 0..RAY_POINTS_PER_DOUBLING {
+            let radius = RAY_ESCAPE_RADIUS.powf(0.5_f64.powf((step as f64 + 0.5) / RAY_POINTS_PER_DOUBLING as f64) as Float);
+            let (target_x, target_y) = (radius * angle_of(numerator).cos(), radius * angle_of(numerator).sin());
+
+            // Solve z(n+1) = target (for c) with Newton's method:
+            let mut converged = false;
+            let mut distance_estimate = Float::INFINITY;
+            for _ in 0..RAY_MAX_NEWTON_STEPS {
+                // (z, and its derivative dz/dc, after n+1 iterations.)
+                let (mut z_x, mut z_y, mut dz_x, mut dz_y): (Float, Float, Float, Float) = (0.0, 0.0, 0.0, 0.0);
+                for _ in 0..=n {
+                    (dz_x, dz_y) = (2.0 * (z_x * dz_x - z_y * dz_y) + 1.0, 2.0 * (z_x * dz_y + z_y * dz_x));
+                    (z_x, z_y) = (z_x * z_x - z_y * z_y + c_x, 2.0 * z_x * z_y + c_y);
+                }
+                // (c -= (z - target) / dz)
+                let (error_x, error_y) = (z_x - target_x, z_y - target_y);
+                let dz_squared = dz_x * dz_x + dz_y * dz_y;
+                let (step_x, step_y) = ((error_x * dz_x + error_y * dz_y) / dz_squared,
+                                        (error_y * dz_x - error_x * dz_y) / dz_squared);
+                if !step_x.is_finite() || !step_y.is_finite() {
+                    break
+                }
+                (c_x, c_y) = (c_x - step_x, c_y - step_y);
+                distance_estimate = 2.0 * radius * radius.ln() / dz_squared.sqrt();
+                if step_x.hypot(step_y) <= Float::EPSILON * 16.0 * c_x.hypot(c_y).max(1.0) {
+                    converged = true;
+                    break
+                }
+            }
+            if !converged {
+                return points
+            }
+
+            points.push((c_x, c_y));
+            if distance_estimate < min_distance || points.len() >= max_points {
+                return points
+            }
+        }
+        numerator = (2 * numerator) % denominator;
+    }
+    points
+}
+
+
+// Returns the escape value to look up in the palette for a pixel
+// with the given escape value (which is only different if --invert
+// was given, or the palette is shifted by a palette_offset, as when
+// the colors are cycling; points in the set are never changed).
+pub fn palette_index(escape_value: Option<usize>, config: &Config) -> Option<usize> {
+    // (The offset is added after taking the remainder, so that
+    // even an escape value of usize::MAX can't overflow.)
+    let shifted = |i: usize| if config.palette_offset == 0 {
+        i
+    } else {
+        (i % NUM_COLORS + config.palette_offset % NUM_COLORS) % NUM_COLORS
+    };
+    match escape_value {
+        Some(i) if config.invert => Some(inverted_palette_index(shifted(i))),
+        Some(i) => Some(shifted(i)),
+        None => None,
+    }
+}
+
+
+// Returns the potential of a point that escaped, or None for a point
+// in the set (whose potential is 0).  The potential is the
+// "electrostatic" potential of a charged Mandelbrot (or Julia) set:
+//
+//    G = ln|z(n)| / 2^n
+//
+// where z(n) is z after n iterations (the point's escape value), and
+// |z(n)| is its escape_magnitude.  Since |z| gets squared (more or
+// less) every iteration once it's that big, ln|z| doubles every
+// iteration, so it doesn't matter (much) which iteration G is worked
+// out at; that's what makes G change smoothly from pixel to pixel
+// (unlike the escape value, which jumps by whole iterations).
+//
+// (G gets very small deep down near the set:  for escape values over
+// about 1,000, it's too small for a Float, and this returns 0.0.
+// potential_palette_position() works out -log2(G) without that problem.)
+pub fn escape_potential(details: &EscapeDetails) -> Option<Float> {
+    details.escape_value.map(|n| details.escape_magnitude.ln() / (2.0 as Float).powi(n as i32))
+}
+
+
+// Returns the (fractional) position in the palette for a point with
+// the given EscapeDetails with --coloring=potential, or None for a
+// point in the set.  The position is -log2(G) (see escape_potential()),
+// worked out as
+//
+//    -log2(ln|z(n)| / 2^n) = n - log2(ln|z(n)|)
+//
+// so that it doesn't underflow.  (Taking the log spreads the potential
+// out to be about one "iteration" per palette color, like escape-time
+// coloring, but without the bands.)  That's then placed in the
+// palette like any escape value (see scaled_palette_position()).
+pub fn potential_palette_position(details: &EscapeDetails, config: &Config) -> Option<Float> {
+    details.escape_value.map(|n| scaled_palette_position(n as Float - details.escape_magnitude.ln().log2(), config))
+}
+
+
+// Returns the (fractional) position in the palette for the given
+// (fractional) number of iterations, with the palette stretched (or
+// squeezed) so that going through it once takes --color-period
+// iterations.  If --invert was given, the palette is run the other way
+// (like palette_index() does), and it's shifted by the palette_offset.
+// (With the default color period, a whole number of iterations gives
+// the same color as palette_index().)
+pub fn scaled_palette_position(iterations: Float, config: &Config) -> Float {
+    let position = (iterations * NUM_COLORS as Float / config.color_period as Float
+                    + (config.palette_offset % NUM_COLORS) as Float).rem_euclid(NUM_COLORS as Float);
+    if config.invert { NUM_COLORS as Float - 1.0 - position } else { position }
+}
+
+
+// Returns the color components (from 0.0 to 255.0) for a fractional
+// position in the palette (like potential_palette_position() returns),
+// blending the two palette colors on either side of it.  (The palette
+// is the config's, if it has one; see configured_color_components().)
+pub fn palette_color_components(position: Float, config: &Config) -> (Float, Float, Float) {
+    let i = position.floor();
+    let fraction = position - i;
+    let i = (i as i64).rem_euclid(NUM_COLORS as i64) as usize;
+    let (r1, g1, b1) = configured_color_components(Some(i), config);
+    let (r2, g2, b2) = configured_color_components(Some(i + 1), config);
+    (r1 + (r2 - r1) * fraction, g1 + (g2 - g1) * fraction, b1 + (b2 - b1) * fraction)
+}
+
+
+// Returns the color (as a u32 integer) to plot
+// for a pixel with the given EscapeDetails.
+pub fn pixel_color(details: &EscapeDetails, config: &Config) -> u32 {
+    let (r, g, b) = match (details.escape_value, config.interior_coloring) {
+        (None, InteriorColoring::Period) => period_color(details.period),
+        (Some(_), _) if config.coloring == Coloring::Potential || config.color_period != NUM_COLORS => {
+            // (Rounded down and clamped, like color() does, so
+            // that no color period can give an out-of-range value.)
+            let (r, g, b) = pixel_color_components(details, config);
+            let to_u8 = |value: Float| value.clamp(0.0, 255.0) as u8;
+            (to_u8(r), to_u8(g), to_u8(b))
+        }
+        (escape_value, _) => configured_color(palette_index(escape_value, config), config),
+    };
+    rgb_to_u32(r, g, b)
+}
+
+
+// Returns the color components (from 0.0 to 255.0, not rounded)
+// to plot for a pixel with the given EscapeDetails.
+pub fn pixel_color_components(details: &EscapeDetails, config: &Config) -> (Float, Float, Float) {
+    match (details.escape_value, config.interior_coloring) {
+        (None, InteriorColoring::Period) => period_color_components(details.period),
+        (Some(_), _) if config.coloring == Coloring::Potential =>
+            palette_color_components(potential_palette_position(details, config).unwrap(), config),
+        (Some(escape_value), _) if config.color_period != NUM_COLORS =>
+            palette_color_components(scaled_palette_position(escape_value as Float, config), config),
+        (escape_value, _) => configured_color_components(palette_index(escape_value, config), config),
+    }
+}
+
+
+// A small (and fast) random number generator:  the "xorshift64"
+// generator.  It's nowhere near good enough for cryptography, but
+// it's plenty for jittering samples, and given the same seed it
+// always returns the same numbers (on any computer).
+pub struct Xorshift64 {
+    state: u64,
+}
+impl Xorshift64 {
+    pub fn new(seed: u64) -> Self {
+        // Scramble the seed (with the "SplitMix64" function) so that
+        // similar seeds still give very different numbers.  (The state
+        // must never be zero, or the generator would only return zeros.)
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        Self { state: if z == 0 { 1 } else { z } }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    // Returns a random number from 0.0 (inclusive) to 1.0 (exclusive).
+    pub fn next_float(&mut self) -> Float {
+        (self.next_u64() >> 11) as Float / (1u64 << 53) as Float
+    }
+}
+
+
+// Returns the color (as a u32 integer) to plot for the pixel at the
+// given row & column, whose (center) EscapeDetails are given.
+//
+// Normally that's just the color of the details, but if --aa-samples
+// was given, the pixel is anti-aliased instead:  the colors of that
+// many points, randomly placed inside the pixel, are averaged.  Each
+// pixel gets its own random number generator, seeded from --aa-seed
+// and the pixel's row & column, so the result is the same every run
+// (no matter what order, or how many threads, the pixels are drawn in).
+//
+// If --dither was given, the color is then dithered (see dither())
+// instead of just being rounded.
+pub fn pixel_color_at(info: &WindowAndViewportInfo,
+                      row: usize, column: usize,
+                      details: &EscapeDetails,
+                      config: &Config,
+                      threshold: Float) -> u32 {
+    let (r, g, b) = match config.aa_samples {
+        None if !config.dither => return pixel_color(details, config),
+        None => pixel_color_components(details, config),
+        Some(number_of_samples) => average_of_jittered_samples(info, row, column, config, threshold, number_of_samples),
+    };
+    if config.dither {
+        rgb_to_u32(dither(r, row, column), dither(g, row, column), dither(b, row, column))
+    } else {
+        let to_u8 = |value: Float| value.round().clamp(0.0, 255.0) as u8;
+        rgb_to_u32(to_u8(r), to_u8(g), to_u8(b))
+    }
+}
+
+
+// Returns the average color components of number_of_samples points,
+// randomly placed inside the pixel at the given row & column.
+// (See pixel_color_at().)
+fn average_of_jittered_samples(info: &WindowAndViewportInfo,
+                               row: usize, column: usize,
+                               config: &Config,
+                               threshold: Float,
+                               number_of_samples: usize) -> (Float, Float, Float) {
+    // (The seed is scrambled before the row & column are mixed in,
+    // so that nearby seeds don't just give shifted copies of each other.)
+    let pixel_number = ((row as u64) << 32) | column as u64;
+    let mut rng = Xorshift64::new(Xorshift64::new(config.aa_seed).next_u64() ^ pixel_number);
+    let (mut r_total, mut g_total, mut b_total) = (0.0, 0.0, 0.0);
+    for _ in 0..number_of_samples {
+        // (convert_row_and_column_to_x_and_y() adds 0.5 to get to
+        // the center of the pixel, so subtract it back out here.)
+        let (x, y) = convert_row_and_column_to_x_and_y(info,
+                                                       row as Float - 0.5 + rng.next_float(),
+                                                       column as Float - 0.5 + rng.next_float());
+        let sample_details = calculate_point_details(x, y, config, threshold);
+        let (r, g, b) = pixel_color_components(&sample_details, config);
+        (r_total, g_total, b_total) = (r_total + r, g_total + g, b_total + b);
+    }
+    let n = number_of_samples as Float;
+    (r_total / n, g_total / n, b_total / n)
+}
+
+
+// Returns the colors (see pixel_color_at()) of the given pixels
+// (as (row, column) pairs), whose EscapeDetails are given in the same
+// order.  (If anti-aliasing, the work is split among all the CPUs.)
+pub fn pixel_colors(pixels: &[(usize, usize)],
+                    escape_details: &[EscapeDetails],
+                    info: &WindowAndViewportInfo,
+                    config: &Config,
+                    threshold: Float) -> Vec<u32> {
+    if config.aa_samples.is_none() && !config.dither {
+        return escape_details.iter().map(|details| pixel_color(details, config)).collect()
+    }
+
+    let number_of_threads = number_of_threads(config);
+    if number_of_threads == 1 {  // (Don't start any threads where they aren't available, like in WebAssembly.)
+        return pixels.iter()
+                     .zip(escape_details.iter())
+                     .map(|(&(row, column), details)| pixel_color_at(info, row, column, details, config, threshold))
+                     .collect()
+    }
+    let chunk_size = pixels.len().div_ceil(number_of_threads).max(1);
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = pixels.chunks(chunk_size).zip(escape_details.chunks(chunk_size)).map(|(chunk, details_chunk)| {
+            scope.spawn(move || {
+                chunk.iter()
+                     .zip(details_chunk.iter())
+                     .map(|(&(row, column), details)| pixel_color_at(info, row, column, details, config, threshold))
+                     .collect::<Vec<u32>>()
+            })
+        }).collect();
+
+        handles.into_iter()
+               .flat_map(|handle| handle.join().unwrap())
+               .collect()
+    })
+}
+
+
+// Returns the (row, column) of every pixel of a width x height
+// window, row by row.
+pub fn all_pixels(width: usize, height: usize) -> Vec<(usize, usize)> {
+    (0..height).flat_map(|row| (0..width).map(move |column| (row, column)))
+               .collect()
+}
+
+
+// Returns the threshold (see calculate_escape_value()) to use for
+// detecting cycles in the given viewport:  the width of its smallest
+// pixel times config.cycle_threshold_factor (so it shrinks as the image
+// is zoomed in on).
+//
+// A bigger threshold finds cycles sooner, but can mistake points that
+// escape very slowly (near the boundary of the set) for points in
+// the set, making the boundary look "blobby".  A smaller one is more
+// accurate there, but takes longer.
+//
+// With --no-cycle-detect, cycles aren't looked for at all (see
+// calculate_escape_details_without_cycle_detection()), and 0.0 is
+// returned, as there's no threshold to use.
+pub fn cycle_threshold(info: &WindowAndViewportInfo, config: &Config) -> Float {
+    if config.cycle_detection {
+        info.smallest_pixel_size() * config.cycle_threshold_factor
+    } else {
+        0.0
+    }
+}
+
+
+// Returns how many threads to split the work of calculating pixels
+// among:  one per CPU, but no more than config.max_threads.
+pub fn number_of_threads(config: &Config) -> usize {
+    let number_of_cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+    config.max_threads.map_or(number_of_cpus, |max_threads| number_of_cpus.min(max_threads)).max(1)
+}
+
+
+// render_escape_details() splits the image into chunks of whole rows,
+// each holding about TARGET_CHUNK_BYTES of EscapeDetails, but with at
+// least MIN_CHUNKS_PER_THREAD chunks for each thread (so that when some
+// chunks take longer than others, the threads that are done can take
+// on the rest).  (Neither number has been tuned, or timed against any
+// other split, on a machine with more than one CPU.)
+const TARGET_CHUNK_BYTES: usize = 64 * 1024;
+const MIN_CHUNKS_PER_THREAD: usize = 4;
+
+
+// Returns how many rows each chunk of an image of the given size
+// should have, when split among number_of_threads threads.
+pub fn rows_per_chunk(width: usize, height: usize, number_of_threads: usize) -> usize {
+    let bytes_per_row = width * std::mem::size_of::<EscapeDetails>();
+    let rows_that_fit_in_cache = TARGET_CHUNK_BYTES / bytes_per_row.max(1);
+    let rows_for_every_thread = height / (number_of_threads * MIN_CHUNKS_PER_THREAD).max(1);
+    rows_that_fit_in_cache.min(rows_for_every_thread).max(1)
+}
+
+
+// Calculates the EscapeDetails of every pixel of the given viewport
+// (without a window, and using all the CPUs), and returns them
+// row by row (so the returned Vec has a length of width x height).
+//
+// The rows are split into chunks (see rows_per_chunk()), and each
+// thread keeps taking the next chunk that's left until there are
+// none, writing the chunk's details straight into its own part of
+// the returned Vec.  (Whereas calculate_pixel_details_in_parallel(),
+// which --bands and --progressive still use, as their pixels aren't
+// whole rows, gives each thread one big piece.)  With --fast-fill,
+// each chunk is filled in by fast_fill_rows() instead of having every
+// one of its pixels calculated.
+pub fn render_escape_details(info: &WindowAndViewportInfo, config: &Config) -> Vec<EscapeDetails> {
+    render_escape_details_in_chunks(info, config, number_of_threads(config))
+}
+
+
+// Does the work of render_escape_details(), with the given number
+// of threads (which may be more than there are CPUs).
+pub fn render_escape_details_in_chunks(info: &WindowAndViewportInfo,
+                                       config: &Config,
+                                       number_of_threads: usize) -> Vec<EscapeDetails> {
+    render_and_count_escape_details(info, config, number_of_threads).0
+}
+
+
+// Does the work of render_escape_details_in_chunks(), also returning
+// how many pixels were calculated.  (That's every pixel, except with
+// --fast-fill, where the rest were filled in.)
+pub fn render_and_count_escape_details(info: &WindowAndViewportInfo,
+                                       config: &Config,
+                                       number_of_threads: usize) -> (Vec<EscapeDetails>, usize) {
+    let threshold = cycle_threshold(info, config);
+    let mut escape_details = vec![EscapeDetails::default(); info.width * info.height];
+    let number_calculated = render_rows(info, config, threshold, (0, &mut escape_details), number_of_threads);
+    (escape_details, number_calculated)
+}
+
+
+// Calculates the EscapeDetails of the given rows (whole rows of the
+// viewport, starting at first_row) the way render_escape_details()
+// does, splitting them into chunks among the given number of threads.
+// Returns how many pixels were calculated.
+fn render_rows(info: &WindowAndViewportInfo,
+               config: &Config,
+               threshold: Float,
+               (first_row, rows): (usize, &mut [EscapeDetails]),
+               number_of_threads: usize) -> usize {
+    let width = info.width.max(1);  // (So an empty image doesn't make chunks of zero pixels.)
+    // (Returns how many of the chunk's pixels were calculated.)
+    let calculate_rows = |first_row: usize, chunk: &mut [EscapeDetails]| -> usize {
+        if let Some(min_size) = config.fast_fill_min_size {
+            return fast_fill_rows(info, config, threshold, (first_row, chunk), min_size)
+        }
+        for (i, details) in chunk.iter_mut().enumerate() {
+            if cancelled(config.cancel_flag.as_deref()) {
+                return i  // (The rest of the chunk is left as it was.)
+            }
+            let (row, column) = (first_row + i / width, i % width);
+            *details = calculate_pixel_details(info, row, column, config, threshold);
+        }
+        chunk.len()
+    };
+
+    if number_of_threads <= 1 {  // (Don't start any threads where they aren't available, like in WebAssembly.)
+        return calculate_rows(first_row, rows)
+    }
+    let number_of_rows = rows.len() / width;
+    let rows_per_chunk = match config.fast_fill_min_size {
+        // (With --fast-fill, each chunk needs room inside its border
+        // to fill, or else every one of its pixels gets calculated.)
+        Some(min_size) => rows_per_chunk(info.width, number_of_rows, number_of_threads)
+                              .max(min_size.saturating_mul(FAST_FILL_MIN_CHUNK_SIZES)),
+        None => rows_per_chunk(info.width, number_of_rows, number_of_threads),
+    };
+    let chunks = std::sync::Mutex::new(rows.chunks_mut(rows_per_chunk * width).enumerate());
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..number_of_threads).map(|_| {
+            scope.spawn(|| {
+                let mut number_calculated = 0;
+                loop {
+                    let next_chunk = chunks.lock().unwrap().next();
+                    match next_chunk {
+                        Some((chunk_number, chunk)) =>
+                            number_calculated += calculate_rows(first_row + chunk_number * rows_per_chunk, chunk),
+                        None => break number_calculated,
+                    }
+                }
+            })
+        }).collect();
+
+        handles.into_iter()
+               .map(|handle| handle.join().unwrap())
+               .sum()
+    })
+}
+
+
+// The default for the smallest rectangle (in pixels, along its longer
+// side) that --fast-fill will fill in without calculating its inside
+// (see fill_rectangle()).
+pub const DEFAULT_FAST_FILL_MIN_SIZE: usize = 8;
+
+// With --fast-fill, each chunk that render_escape_details() splits the
+// image into is at least this many times the min_size rows tall (so
+// that splitting the image among threads doesn't leave chunks with
+// nothing inside their borders to fill in).
+const FAST_FILL_MIN_CHUNK_SIZES: usize = 8;
+
+
+// Returns true if --fast-fill can fill in rectangles of escaped pixels
+// with the config's coloring, and not just rectangles of the set.
+// (--coloring=potential colors every escaped pixel by its own
+// escape_magnitude, so filling them in would leave flat blocks.)
+pub fn fast_fill_fills_escaped_pixels(config: &Config) -> bool {
+    config.coloring != Coloring::Potential
+}
+
+
+// Calculates the EscapeDetails of a chunk of whole rows (starting at
+// first_row) the way render_escape_details() does, but with the
+// "Mariani-Silver" algorithm (for --fast-fill):  only the border of the
+// chunk is calculated at first, and then fill_rectangle() fills in the
+// inside, calculating as few of its pixels as it can.  Returns how
+// many pixels it calculated.
+fn fast_fill_rows(info: &WindowAndViewportInfo,
+                  config: &Config,
+                  threshold: Float,
+                  (first_row, chunk): (usize, &mut [EscapeDetails]),
+                  min_size: usize) -> usize {
+    let width = info.width;
+    let height = chunk.len().checked_div(width).unwrap_or(0);
+    if height == 0 {
+        return 0
+    }
+    let mut number_calculated = 0;
+    let mut calculate = |chunk: &mut [EscapeDetails], row: usize, column: usize| {
+        if cancelled(config.cancel_flag.as_deref()) {
+            // (Every pixel left is given up on the same way, so the
+            // rest of the chunk gets filled in right away.)
+            chunk[row * width + column] = CANCELLED_ESCAPE_DETAILS;
+            return
+        }
+        number_calculated += 1;
+        chunk[row * width + column] = calculate_pixel_details(info, first_row + row, column, config, threshold);
+    };
+    for column in 0..width {
+        calculate(chunk, 0, column);
+        calculate(chunk, height - 1, column);
+    }
+    for row in 1..height.saturating_sub(1) {
+        calculate(chunk, row, 0);
+        calculate(chunk, row, width - 1);
+    }
+    let fill_escaped = fast_fill_fills_escaped_pixels(config);
+    fill_rectangle(chunk, width, (0, 0, height - 1, width - 1), (min_size, fill_escaped), &mut calculate);
+    number_calculated
+}
+
+
+// Fills in the inside of the given rectangle (its top row, left column,
+// bottom row, and right column, inclusive) of the chunk (which is width
+// pixels wide), whose border has already been calculated.
+//
+// If every pixel of the border escaped after the same number of
+// iterations (or is in the set, with the same period), then the inside
+// is filled in with that, without calculating any of it.  Otherwise,
+// the rectangle is split in half across its longer side, and (once the
+// line between the halves is calculated, giving each half its border)
+// each half is filled in the same way.  Rectangles no bigger than
+// min_size (along their longer side) are never filled in; every pixel
+// inside them is calculated.  And unless fill_escaped is true, only a
+// border that's all in the set is filled in (see
+// fast_fill_fills_escaped_pixels()).
+//
+// (Since the Mandelbrot set is connected, with no holes, a border that's
+// all in the set really does have nothing but the set inside it.  But
+// a thin filament of escape values (or a small piece of a Julia set)
+// can pass right through a rectangle without touching its border, and
+// get filled over.  A bigger min_size makes that less likely.)
+fn fill_rectangle(chunk: &mut [EscapeDetails],
+                  width: usize,
+                  (top, left, bottom, right): (usize, usize, usize, usize),
+                  (min_size, fill_escaped): (usize, bool),
+                  calculate: &mut impl FnMut(&mut [EscapeDetails], usize, usize)) {
+    if bottom <= top + 1 || right <= left + 1 {
+        return  // (There's no inside to fill.)
+    }
+    let (rows, columns) = (bottom - top + 1, right - left + 1);
+    if rows.max(columns) <= min_size {
+        for row in top + 1..bottom {
+            for column in left + 1..right {
+                calculate(chunk, row, column);
+            }
+        }
+        return
+    }
+
+    let first = chunk[top * width + left];
+    let matches_first = |details: &EscapeDetails|
+        (details.escape_value, details.period, details.hit_bailout) == (first.escape_value, first.period, first.hit_bailout);
+    let mut border = (left..=right).flat_map(|column| [(top, column), (bottom, column)])
+                                   .chain((top + 1..bottom).flat_map(|row| [(row, left), (row, right)]));
+    if (fill_escaped || first.escape_value.is_none()) && border.all(|(row, column)| matches_first(&chunk[row * width + column])) {
+        for row in top + 1..bottom {
+            chunk[row * width + left + 1..row * width + right].fill(first);
+        }
+        return
+    }
+
+    if columns >= rows {
+        let middle = (left + right) / 2;
+        for row in top + 1..bottom {
+            calculate(chunk, row, middle);
+        }
+        fill_rectangle(chunk, width, (top, left, bottom, middle), (min_size, fill_escaped), calculate);
+        fill_rectangle(chunk, width, (top, middle, bottom, right), (min_size, fill_escaped), calculate);
+    } else {
+        let middle = (top + bottom) / 2;
+        for column in left + 1..right {
+            calculate(chunk, middle, column);
+        }
+        fill_rectangle(chunk, width, (top, left, middle, right), (min_size, fill_escaped), calculate);
+        fill_rectangle(chunk, width, (middle, left, bottom, right), (min_size, fill_escaped), calculate);
+    }
+}
+
+
+// Calculates the EscapeDetails of the given pixels (as (row, column)
+// pairs), splitting the work evenly among as many threads as there are
+// CPUs (see number_of_threads()).  The returned details are in the
+// same order as the given pixels.
+pub fn calculate_pixel_details_in_parallel(pixels: &[(usize, usize)],
+                                           info: &WindowAndViewportInfo,
+                                           config: &Config,
+                                           threshold: Float) -> Vec<EscapeDetails> {
+    let number_of_threads = number_of_threads(config);
+    // (Once the cancel flag is set, the pixels left aren't calculated.)
+    let calculate = |&(row, column): &(usize, usize)| {
+        if cancelled(config.cancel_flag.as_deref()) {
+            CANCELLED_ESCAPE_DETAILS
+        } else {
+            calculate_pixel_details(info, row, column, config, threshold)
+        }
+    };
+    if number_of_threads == 1 {  // (Don't start any threads where they aren't available, like in WebAssembly.)
+        return pixels.iter().map(calculate).collect()
+    }
+    let chunk_size = pixels.len().div_ceil(number_of_threads).max(1);
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = pixels.chunks(chunk_size).map(|chunk| {
+            scope.spawn(move || {
+                chunk.iter()
+                     .map(calculate)
+                     .collect::<Vec<EscapeDetails>>()
+            })
+        }).collect();
+
+        handles.into_iter()
+               .flat_map(|handle| handle.join().unwrap())
+               .collect()
+    })
+}
+
+
+// Colors the image_buffer as line art:  only the pixels on the boundary
+// of the set (that is, pixels in the set that are next to a pixel that
+// isn't) get the line_color, and every other pixel gets the
+// background_color.  (Both buffers must have a length of width x height.)
+pub fn draw_boundary(escape_details: &[EscapeDetails],
+                     image_buffer: &mut [u32],
+                     width: usize, height: usize,
+                     line_color: (u8, u8, u8),
+                     background_color: (u8, u8, u8)) {
+    let in_set = |row: usize, column: usize| escape_details[row * width + column].escape_value.is_none();
+    let line_color = rgb_to_u32(line_color.0, line_color.1, line_color.2);
+    let background_color = rgb_to_u32(background_color.0, background_color.1, background_color.2);
+
+    for row in 0..height {
+        for column in 0..width {
+            let on_boundary = in_set(row, column)
+                              && ((row > 0 && !in_set(row - 1, column))
+                                  || (row + 1 < height && !in_set(row + 1, column))
+                                  || (column > 0 && !in_set(row, column - 1))
+                                  || (column + 1 < width && !in_set(row, column + 1)));
+            image_buffer[row * width + column] = if on_boundary { line_color } else { background_color };
+        }
+    }
+}
+
+
+// Returns the brightness ("luma") of a color, from 0.0 to 1.0.
+pub fn luma(color: u32) -> f32 {
+    let r = ((color >> 16) & 0xff) as f32;
+    let g = ((color >> 8) & 0xff) as f32;
+    let b = (color & 0xff) as f32;
+    (0.299 * r + 0.587 * g + 0.114 * b) / 255.0
+}
+
+
+// Returns the color that's the given fraction (from 0.0 to 1.0)
+// of the way from color_a to color_b.
+pub fn blend_colors(color_a: u32, color_b: u32, fraction: f32) -> u32 {
+    let blend_channel = |shift: u32| {
+        let a = ((color_a >> shift) & 0xff) as f32;
+        let b = ((color_b >> shift) & 0xff) as f32;
+        ((a + (b - a) * fraction).round() as u32) << shift
+    };
+    blend_channel(16) | blend_channel(8) | blend_channel(0)
+}
+
+
+// Scales the source image (of source_width by source_height pixels)
+// up (or down) to fill the destination image (of destination_width by
+// destination_height pixels), with bilinear filtering:  each pixel of
+// the destination gets a blend of the four source pixels nearest to
+// it, so the result looks smooth instead of blocky.
+pub fn upscale_bilinear(source: &[u32], source_width: usize, source_height: usize,
+                        destination: &mut [u32], destination_width: usize, destination_height: usize) {
+    assert_eq!(source.len(), source_width * source_height,
+               "FATAL ERROR:  source_width x source_height does not equal the length of the source.");
+    assert_eq!(destination.len(), destination_width * destination_height,
+               "FATAL ERROR:  destination_width x destination_height does not equal the length of the destination.");
+    if source.is_empty() {
+        return ()
+    }
+
+    // Returns the two source rows (or columns) on either side of the
+    // destination row (or column) i, and how far it is from the first
+    // one to the second.  (Pixel centers are lined up, so the edges of
+    // both images line up, too.)
+    let neighbors = |i: usize, source_size: usize, destination_size: usize| -> (usize, usize, f32) {
+        let position = ((i as f32 + 0.5) * source_size as f32 / destination_size as f32 - 0.5)
+                           .clamp(0.0, (source_size - 1) as f32);
+        let first = position.floor() as usize;
+        let second = (first + 1).min(source_size - 1);
+        (first, second, position - first as f32)
+    };
+
+    for row in 0..destination_height {
+        let (top, bottom, row_fraction) = neighbors(row, source_height, destination_height);
+        for column in 0..destination_width {
+            let (left, right, column_fraction) = neighbors(column, source_width, destination_width);
+            let top_color = blend_colors(source[top * source_width + left],
+                                         source[top * source_width + right], column_fraction);
+            let bottom_color = blend_colors(source[bottom * source_width + left],
+                                            source[bottom * source_width + right], column_fraction);
+            destination[row * destination_width + column] = blend_colors(top_color, bottom_color, row_fraction);
+        }
+    }
+}
+
+
+// Smooths the jagged edges of a finished image, in the spirit of FXAA
+// ("Fast Approximate Anti-Aliasing"):  any pixel whose brightness
+// differs enough from its four neighbors' is on an edge, so it gets
+// blended with the neighbor across that edge (more so the more it
+// stands out from its neighbors).  Only the colors in image_buffer
+// are used, so no fractal math is redone, which makes this much
+// cheaper than calculating extra points per pixel (but less accurate).
+pub fn apply_fxaa(image_buffer: &mut [u32], width: usize, height: usize) {
+    // Edges with less contrast than this are left alone:
+    const EDGE_THRESHOLD: f32 = 0.125;
+    const EDGE_THRESHOLD_MIN: f32 = 0.03;
+
+    let original = image_buffer.to_vec();
+    let luma_at = |row: usize, column: usize| luma(original[row * width + column]);
+
+    for row in 0..height {
+        for column in 0..width {
+            // (Pixels on the image's edges use themselves for any missing neighbors.)
+            let up = row.saturating_sub(1);
+            let down = (row + 1).min(height - 1);
+            let left = column.saturating_sub(1);
+            let right = (column + 1).min(width - 1);
+
+            let luma_middle = luma_at(row, column);
+            let luma_up = luma_at(up, column);
+            let luma_down = luma_at(down, column);
+            let luma_left = luma_at(row, left);
+            let luma_right = luma_at(row, right);
+
+            let luma_max = luma_middle.max(luma_up).max(luma_down).max(luma_left).max(luma_right);
+            let luma_min = luma_middle.min(luma_up).min(luma_down).min(luma_left).min(luma_right);
+            let luma_range = luma_max - luma_min;
+            if luma_range < EDGE_THRESHOLD_MIN.max(luma_max * EDGE_THRESHOLD) {
+                continue  // (Not on an edge.)
+            }
+
+            // The edge is horizontal if the brightness changes more
+            // going up and down than it does going left and right:
+            let vertical_change = (luma_up + luma_down - 2.0 * luma_middle).abs();
+            let horizontal_change = (luma_left + luma_right - 2.0 * luma_middle).abs();
+            let (neighbor_a, luma_a, neighbor_b, luma_b) = if vertical_change >= horizontal_change {
+                ((up, column), luma_up, (down, column), luma_down)
+            } else {
+                ((row, left), luma_left, (row, right), luma_right)
+            };
+            // Blend toward whichever neighbor is across the edge (that
+            // is, whichever one differs more from this pixel):
+            let (across_row, across_column) = if (luma_a - luma_middle).abs() >= (luma_b - luma_middle).abs() {
+                neighbor_a
+            } else {
+                neighbor_b
+            };
+
+            // The more this pixel stands out from its neighbors, the more it's blended
+            // (up to half-way, smoothed with the "smoothstep" curve):
+            let luma_average = (luma_up + luma_down + luma_left + luma_right) / 4.0;
+            let amount = ((luma_average - luma_middle).abs() / luma_range).clamp(0.0, 1.0);
+            let amount = amount * amount * (3.0 - 2.0 * amount);
+            let fraction = 0.25 + 0.25 * amount;
+
+            image_buffer[row * width + column] = blend_colors(original[row * width + column],
+                                                              original[across_row * width + across_column],
+                                                              fraction);
+        }
+    }
+}
+
+
+// Sharpens a finished image with an "unsharp mask":  each pixel is
+// pushed away from the blurred average of it and its eight neighbors,
+// by amount times the difference between them, so that detail along
+// the boundary of the set (where neighboring colors differ the most)
+// stands out more, while flat areas are left as they were.  (Each
+// color component is clamped to 0-255.)  Like apply_fxaa(), this only
+// uses the colors in image_buffer, so no fractal math is redone.
+pub fn apply_unsharp_mask(image_buffer: &mut [u32], width: usize, height: usize, amount: Float) {
+    let original = image_buffer.to_vec();
+    let component = |color: u32, shift: u32| ((color >> shift) & 0xff) as Float;
+
+    for row in 0..height {
+        for column in 0..width {
+            // (Pixels on the image's edges use themselves for any missing neighbors.)
+            let rows = [row.saturating_sub(1), row, (row + 1).min(height - 1)];
+            let columns = [column.saturating_sub(1), column, (column + 1).min(width - 1)];
+            let pixel = original[row * width + column];
+            let mut sharpened = 0;
+            for shift in [16, 8, 0] {
+                let blurred = rows.iter()
+                                  .flat_map(|&neighbor_row| columns.iter().map(move |&neighbor_column| (neighbor_row, neighbor_column)))
+                                  .map(|(neighbor_row, neighbor_column)| component(original[neighbor_row * width + neighbor_column], shift))
+                                  .sum::<Float>() / 9.0;
+                let value = component(pixel, shift);
+                let value = (value + amount * (value - blurred)).round().clamp(0.0, 255.0) as u32;
+                sharpened |= value << shift;
+            }
+            image_buffer[row * width + column] = sharpened;
+        }
+    }
+}
+
+
+// Once every pixel of an image has been calculated (and colored),
+// this does any extra work on the image that needs the whole image
+// to be done first (like finding the boundary for --coloring=boundary,
+// smoothing the edges for --antialias=fxaa, or sharpening it for
+// --sharpen).
+pub fn post_process_image(escape_details: &[EscapeDetails],
+                          image_buffer: &mut [u32],
+                          width: usize, height: usize,
+                          config: &Config) {
+    if config.coloring == Coloring::Boundary {
+        draw_boundary(escape_details, image_buffer, width, height,
+                      config.boundary_color, config.boundary_background_color);
+    }
+    if config.antialiasing == Antialiasing::Fxaa {
+        apply_fxaa(image_buffer, width, height);
+    }
+    if config.sharpen > 0.0 {
+        apply_unsharp_mask(image_buffer, width, height, config.sharpen);
+    }
+}
+
+
+// The ways the inside of the set can be colored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InteriorColoring {
+    Solid,  // (Every point in the set gets the same color.)
+    Period,  // (Points are colored by the period of the cycle their orbit falls into.)
+}
+impl InteriorColoring {
+    // The name (as used by --interior-coloring=TYPE) and a description
+    // of every InteriorColoring.  (Both from_name() and --list-palettes
+    // go by this list, so a new InteriorColoring only needs adding here.)
+    pub const ALL: &'static [(&'static str, &'static str, InteriorColoring)] = &[
+        ("solid", "Every point in the set gets the same color.", InteriorColoring::Solid),
+        ("period", "Points are colored by the period of the cycle their orbit falls into.", InteriorColoring::Period),
+    ];
+
+    // Returns the InteriorColoring with the given name
+    // (as used by --interior-coloring=TYPE), if there is one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter()
+                 .find(|(registered_name, _, _)| *registered_name == name)
+                 .map(|&(_, _, interior_coloring)| interior_coloring)
+    }
+
+    // Returns the name of this InteriorColoring (the
+    // inverse of from_name()).
+    pub fn name(&self) -> &'static str {
+        Self::ALL.iter().find(|(_, _, interior_coloring)| interior_coloring == self).unwrap().0
+    }
+}
+
+
+// The ways the image can be colored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Coloring {
+    EscapeTime,  // (Points are colored by how many iterations they took to escape.)
+    Boundary,  // (Only the boundary of the set is drawn, like line art.)
+    Potential,  // (Points are colored smoothly by their potential.)
+}
+impl Coloring {
+    // The name (as used by --coloring=TYPE) and a description of
+    // every Coloring.  (Both from_name() and --list-palettes go by
+    // this list, so a new Coloring only needs adding here.)
+    pub const ALL: &'static [(&'static str, &'static str, Coloring)] = &[
+        ("escape-time", "Points are colored by how many iterations they took to escape.", Coloring::EscapeTime),
+        ("boundary", "Only the boundary of the set is drawn, like line art.", Coloring::Boundary),
+        ("potential", "Points are colored smoothly by their potential (ln|z| / 2^n).", Coloring::Potential),
+    ];
+
+    // Returns the Coloring with the given name
+    // (as used by --coloring=TYPE), if there is one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter()
+                 .find(|(registered_name, _, _)| *registered_name == name)
+                 .map(|&(_, _, coloring)| coloring)
+    }
+
+    // Returns the name of this Coloring (the inverse of from_name()).
+    pub fn name(&self) -> &'static str {
+        Self::ALL.iter().find(|(_, _, coloring)| coloring == self).unwrap().0
+    }
+}
+
+
+// The ways the finished image can be anti-aliased.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Antialiasing {
+    None,  // (The image is left as it was calculated.)
+    Fxaa,  // (Jagged edges are found and blended, FXAA-style, after the image is done.)
+}
+impl Antialiasing {
+    // Returns the Antialiasing with the given name
+    // (as used by --antialias=TYPE), if there is one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(Antialiasing::None),
+            "fxaa" => Some(Antialiasing::Fxaa),
+            _ => None,
+        }
+    }
+}
+
+
+// This structure holds the settings for calculating and coloring the
+// fractal, which the user chose with command-line switches.  (Some of
+// them, like the bailout, can also be changed while the program is
+// running.)  The viewport itself isn't one of them; it's given to
+// the functions that draw with a WindowAndViewportInfo.
+//
+// Programs using this library can start with Config::new() and change
+// just the settings they care about.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub bailout: Option<usize>,
+    pub c: Option<(Float, Float)>,  // Sometimes known as (x0, y0).  (Used for Julia sets.)
+    pub number_of_bands: Option<usize>,
+    // With --fast-fill, the smallest rectangle that render_escape_details()
+    // (and render_into_buffer()) can fill in without calculating its
+    // inside (see fill_rectangle()); None for calculating every pixel.
+    pub fast_fill_min_size: Option<usize>,
+    pub interior_coloring: InteriorColoring,
+    pub coloring: Coloring,
+    pub boundary_color: (u8, u8, u8),
+    pub boundary_background_color: (u8, u8, u8),
+    pub antialiasing: Antialiasing,
+    pub sharpen: Float,  // (How strongly the finished image is sharpened (0 for not at all); see --sharpen.)
+    pub aa_samples: Option<usize>,  // (The number of jittered samples per pixel, if anti-aliasing.)
+    pub aa_seed: u64,
+    pub dither: bool,
+    pub invert: bool,
+    pub float_type: FloatType,  // (What the pixels are calculated in.)
+    pub formula: Formula,  // (What's iterated for each point; see --fractal.)
+    pub norm: Norm,  // (How the escape test measures z; see --norm.)
+    pub projection: Projection,  // (How the viewport is laid out in the window.)
+    pub color_period: usize,  // (How many iterations it takes to go through the palette once.)
+    pub palette_offset: usize,  // (How many palette colors the colors are shifted by; see --color-cycle.)
+    pub palette: Option<Palette>,  // (Replaces the built-in palette, if given; see --palette.)
+    pub cycle_threshold_factor: Float,
+    pub cycle_detection: bool,
+    // The most iterations any one point is given, whatever the bailout
+    // is (or isn't), so that no point (say, one in the set whose orbit
+    // cycle detection never quite catches) can be iterated forever.
+    // A point that reaches it is taken to be part of the set, just
+    // like one that hits the bailout.  (None for no limit at all.)
+    pub max_iterations: Option<usize>,
+    // While this is set (by another thread), every point still being
+    // calculated with this config is given up on, as if it had hit
+    // the bailout, so that an image nobody wants anymore (say, as the
+    // user zoomed in again) stops right away, even in the middle of a
+    // point that takes a very long time.  (None for never.)
+    pub cancel_flag: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>,
+    pub max_threads: Option<usize>,
+}
+impl Config {
+    pub fn new() -> Self {
+        Self {
+            bailout: None,
+            c: None,
+            number_of_bands: None,
+            fast_fill_min_size: None,
+            interior_coloring: InteriorColoring::Solid,
+            coloring: Coloring::EscapeTime,
+            boundary_color: (0, 0, 0),  // (black)
+            boundary_background_color: (255, 255, 255),  // (white)
+            antialiasing: Antialiasing::None,
+            sharpen: 0.0,
+            aa_samples: None,
+            aa_seed: 0,
+            dither: false,
+            invert: false,
+            float_type: FloatType::F64,
+            formula: Formula::Mandelbrot,
+            norm: Norm::L2,
+            projection: Projection::Rectangular,
+            color_period: NUM_COLORS,  // (One palette color per iteration.)
+            palette_offset: 0,
+            palette: None,  // (The built-in palette of color_components().)
+            // (A quarter of a pixel:)
+            cycle_threshold_factor: 0.25,
+            cycle_detection: true,
+            max_iterations: Some(DEFAULT_MAX_ITERATIONS),
+            cancel_flag: None,
+            max_threads: None,  // (One thread per CPU.)
+        }
+    }
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+// Colors the image_buffer (of the viewport described by info) from
+// the already-calculated escape_details of its pixels (row by row),
+// including any post-processing (see post_process_image()).
+pub fn color_escape_details(escape_details: &[EscapeDetails],
+                            image_buffer: &mut [u32],
+                            info: &WindowAndViewportInfo,
+                            config: &Config) {
+    assert_eq!(escape_details.len(), info.width * info.height,
+               "FATAL ERROR:  width x height does not equal the number of escape details.");
+    assert_eq!(image_buffer.len(), info.width * info.height,
+               "FATAL ERROR:  width x height does not equal the length of the image buffer.");
+
+    let threshold = cycle_threshold(info, config);
+    let colors = pixel_colors(&all_pixels(info.width, info.height), escape_details, info, config, threshold);
+    image_buffer.copy_from_slice(&colors);
+    post_process_image(escape_details, image_buffer, info.width, info.height, config);
+}
+
+
+// Draws the fractal of the viewport described by info into the
+// buffer, using the given config.  The buffer is filled row by row,
+// with each pixel as a 0RGB u32 (the same format minifb uses), so
+// it must have a length of exactly info.width x info.height.
+//
+// This is the function for embedding the renderer in other programs:
+// it opens no windows, touches no files, and prints nothing.
+//
+// As for threads:  this function already splits its work among all
+// the CPUs (with scoped threads that are finished by the time it
+// returns), so there's usually no need to call it from several
+// threads.  But it's safe to:  it only reads info and config (both
+// of which are Sync), writes only to the given buffer, and keeps no
+// global state, so calls on different buffers never interfere.
+// (To split one image among your own threads, call it once per
+// piece, with each piece's viewport in its own info.)
+//
+// (See render_into_buffer_with_progress() for showing a progress bar,
+// or for being able to cancel a long render.)
+pub fn render_into_buffer(buffer: &mut [u32], info: &WindowAndViewportInfo, config: &Config) {
+    render_into_buffer_with_progress(buffer, info, config, None, None);
+}
+
+
+// The fewest number of tiles (groups of rows) that an image is split
+// into by render_into_buffer_with_progress().  Progress is reported,
+// and cancellation is checked for, between tiles.  (With --fast-fill,
+// there can be fewer:  each tile has to be tall enough to give every
+// thread a chunk worth filling in; see FAST_FILL_MIN_CHUNK_SIZES.)
+const MIN_NUMBER_OF_PROGRESS_TILES: usize = 64;
+
+// The least amount of time between calls to the progress callback
+// of render_into_buffer_with_progress().  (So that even a very fast
+// render doesn't spend its time drawing progress bars.)
+const MIN_PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+
+
+// Does the same thing as render_into_buffer(), but for long renders:
+//
+//  * If progress is given, it's called (on the calling thread) with
+//    the fraction of the image done so far, from 0.0 to 1.0.  It's
+//    called no more often than every MIN_PROGRESS_INTERVAL (except
+//    that it's always called with 1.0 once the image is done).
+//
+//  * If cancel is given, it's checked between tiles (groups of rows),
+//    and if it's ever true, the render stops early.  (Set it from
+//    another thread, such as a GUI's "Cancel" button.)
+//
+// Returns true if the whole image was drawn, or false if it was
+// cancelled (in which case the buffer is only partly drawn).
+pub fn render_into_buffer_with_progress(buffer: &mut [u32],
+                                        info: &WindowAndViewportInfo,
+                                        config: &Config,
+                                        mut progress: Option<&mut dyn FnMut(f32)>,
+                                        cancel: Option<&std::sync::atomic::AtomicBool>) -> bool {
+    assert_eq!(buffer.len(), info.width * info.height,
+               "FATAL ERROR:  width x height does not equal the length of the buffer.");
+
+    let is_cancelled = || cancel.is_some_and(|cancel| cancel.load(std::sync::atomic::Ordering::Relaxed));
+    let threshold = cycle_threshold(info, config);
+    let number_of_threads = number_of_threads(config);
+    let min_rows_per_tile = config.fast_fill_min_size.map_or(1, |min_size| {
+        min_size.saturating_mul(FAST_FILL_MIN_CHUNK_SIZES).saturating_mul(number_of_threads)
+    });
+    let rows_per_tile = (info.height / MIN_NUMBER_OF_PROGRESS_TILES).max(min_rows_per_tile);
+    // (The clock is only read if there's a progress callback to time.)
+    let mut last_progress_time = progress.as_ref().map(|_| std::time::Instant::now());
+
+    let mut escape_details = vec![EscapeDetails::default(); info.width * info.height];
+    for first_row in (0..info.height).step_by(rows_per_tile) {
+        if is_cancelled() {
+            return false
+        }
+
+        let last_row = (first_row + rows_per_tile).min(info.height);
+        let tile_pixels: Vec<(usize, usize)> = (first_row..last_row)
+                                                   .flat_map(|row| (0..info.width).map(move |column| (row, column)))
+                                                   .collect();
+        let tile_range = first_row * info.width..last_row * info.width;
+        let tile_escape_details = &mut escape_details[tile_range.clone()];
+        render_rows(info, config, threshold, (first_row, tile_escape_details), number_of_threads);
+        let tile_colors = pixel_colors(&tile_pixels, tile_escape_details, info, config, threshold);
+        buffer[tile_range].copy_from_slice(&tile_colors);
+
+        if let (Some(progress), Some(last_time)) = (progress.as_mut(), last_progress_time.as_mut()) {
+            if last_time.elapsed() >= MIN_PROGRESS_INTERVAL && last_row < info.height {
+                progress(last_row as f32 / info.height as f32);
+                *last_time = std::time::Instant::now();
+            }
+        }
+    }
+
+    if is_cancelled() {
+        return false
+    }
+    post_process_image(&escape_details, buffer, info.width, info.height, config);
+    if let Some(progress) = progress.as_mut() {
+        progress(1.0);
+    }
+    true
+}
@@ -18,6 +18,241 @@
 // 2022-11-28:  Added the --bailout=NUMBER switch.
 // 2022-11-30:  Added printing of coordinates (to stdout) with the C key.
 // 2022-12-01:  Added the --julia=X,Y switch.
+// 2026-10-15:  Added the --bands=NUMBER switch, which draws the set
+//              with multiple threads, one growing circle at a time.
+// 2026-10-15:  Added the --script=FILE switch, to drive the viewer
+//              with commands read from a file (or from stdin).
+// 2026-10-15:  Added the --format=ppm switch (for writing the image to
+//              stdout without opening a window) and the --quiet switch.
+// 2026-10-15:  Added a crosshair (toggled with the X key or --crosshair)
+//              that's drawn on top of the fractal.
+// 2026-10-15:  Added the --interior-coloring=period switch, which colors
+//              the inside of the set by the period of each point's cycle.
+// 2026-10-15:  Added the --center=X,Y, --distance=NUMBER, and --dry-run switches.
+// 2026-10-15:  Added the --cache-dir=DIR switch, for saving (and reusing)
+//              the calculated escape values of each image.
+// 2026-10-15:  The --size=NUMBER switch now rejects sizes that are too big.
+// 2026-10-15:  Added the --coloring=boundary switch, for line-art images
+//              of just the boundary of the set.
+// 2026-10-15:  Added the --config=PATH switch, for reading settings
+//              from a TOML file.
+// 2026-10-15:  Added the --antialias=fxaa switch, which smooths jagged
+//              edges of the finished image (without recalculating it).
+// 2026-10-15:  The + and - keys now double and halve the bailout value.
+// 2026-10-15:  Added a minimap (toggled with the M key) that shows
+//              where the current view is in the whole set.
+// 2026-10-15:  Added the --aa-samples=N and --aa-seed=S switches, for
+//              anti-aliasing by averaging N randomly-placed samples
+//              per pixel (the same every run, for a given seed).
+// 2026-10-15:  Tried squaring z with (x+y)^2 - x^2 - y^2 instead of
+//              2xy, but it was slower (and not bit-identical), so the
+//              original way was kept.  (See test_three_multiply_square().)
+// 2026-10-15:  Added the --profile switch, which prints how many
+//              iterations each image took, and where the time went.
+// 2026-10-15:  Added an overlay (toggled with the H key) that tints
+//              every pixel that hit the bailout, to show where a
+//              higher bailout would help.
+// 2026-10-15:  Moved the calculating and coloring code into a library
+//              (src/lib.rs), so other programs can draw the fractal
+//              into their own buffers (with render_into_buffer()).
+// 2026-10-15:  Added WebAssembly entry points (src/wasm.rs, built with
+//              the "wasm" feature) for drawing the set in a browser.
+// 2026-10-15:  Added the --dither switch, which hides the banding in
+//              smooth color gradients with ordered (Bayer) dithering.
+// 2026-10-15:  Added the --invert switch, which runs the color
+//              palette the other way.
+// 2026-10-15:  Added the --cycle-threshold=FACTOR switch (the threshold
+//              for detecting cycles used to always be a quarter of a
+//              pixel), and the --no-cycle-detect switch.
+// 2026-10-15:  With --no-cycle-detect, only the "fast" point is
+//              iterated now, which is quicker when most of the image
+//              is outside the set, or the bailout is small.
+// 2026-10-15:  Added the --coord-format=TYPE switch, for printing
+//              coordinates (with the C key) in scientific notation or
+//              as a command line.  Decimal coordinates now get more
+//              decimal places the more the image is zoomed in.
+// 2026-10-15:  Decimal coordinates are now rounded to just enough
+//              decimal places to tell neighboring pixels apart.
+// 2026-10-15:  Added the --progressive switch, which draws each image
+//              with a low bailout first, and then refines it with
+//              higher and higher bailouts (as long as the view stays
+//              the same).
+// 2026-10-15:  Ctrl+clicking now prints the orbit of the clicked point.
+// 2026-10-15:  Ctrl+clicking now also draws the orbit on top of the
+//              image (hidden and shown again with the O key).
+// 2026-10-15:  Images drawn without a window (like with --format=ppm)
+//              are now split among the threads in chunks of rows,
+//              which each thread takes one at a time.  Also added the
+//              --max-threads=NUMBER switch.
+// 2026-10-15:  Inputs that don't change the image (like saving a
+//              screenshot) are now all handled in one place, so none
+//              of them make an image being drawn start over.
+// 2026-10-15:  Added the --preview-scale=NUMBER switch, which shows a
+//              quick, low-resolution preview of each image (stretched
+//              to fill the window) before drawing it in full.
+// 2026-10-15:  Zooming in (or out, or going to a place) that would make
+//              the pixels too close together to tell apart (or the
+//              distance overflow) is now refused with a message, instead
+//              of drawing a solid-colored image.  The same goes for a
+//              --distance that's too small for its --center.
+// 2026-10-15:  The JLR_MANDELBROT_SIZE and JLR_MANDELBROT_BAILOUT
+//              environment variables now give the defaults for --size
+//              and --bailout (overridden by a config file, or switches).
+// 2026-10-15:  Added the --contact-sheet switch, which saves a labeled
+//              grid of small images (of Julia sets with neighboring c
+//              values, or of the Mandelbrot set colored each way).
+// 2026-10-15:  The X,Y values of --center, --julia, and the script's
+//              goto command are now parsed by parse_coordinate_pair(),
+//              which allows spaces and the Unicode minus sign, and says
+//              which value is wrong (and why) when one is.
+// 2026-10-15:  Errors now exit with different exit codes (2 for bad
+//              switches, 3 for I/O errors, and 4 for window errors),
+//              and a screenshot that can't be saved is reported instead
+//              of crashing the program.
+// 2026-10-15:  Added the --list-palettes and --list-fractals switches.
+//              The colorings are now listed in Coloring::ALL (and
+//              InteriorColoring::ALL), which their parsers go by, too.
+// 2026-10-15:  Pressing the I key shows (or hides) the escape value of
+//              the pixel under the mouse, next to the mouse pointer.
+// 2026-10-15:  Added the --sidecar switch, which saves the settings of
+//              each screenshot (as a --config file) next to it.
+// 2026-10-15:  PNG screenshots now have their settings stored inside
+//              them (in an iTXt chunk), and can be given to --config.
+// 2026-10-15:  The ] and [ keys zoom in and out, keeping the center.
+// 2026-10-15:  Added the --rays=ANGLES switch, which draws the external
+//              rays of the Mandelbrot set with the given angles.
+// 2026-10-15:  Added the --coloring=potential switch, which colors
+//              points smoothly by their potential, ln|z| / 2^n.  (So
+//              EscapeDetails now has the escape_magnitude, |z|, too.)
+// 2026-10-15:  Added the --color-period=NUMBER switch, which sets how
+//              many iterations it takes to go through the palette once,
+//              and the < and > keys, which change it (and recolor the
+//              image from its escape details, without recalculating it).
+// 2026-10-15:  Added the --float-bench switch, which times calculating
+//              the image in f32 and in f64, and counts the differences.
+//              (calculate_escape_details() is now generic over the
+//              EscapeFloat trait, so it can be called with either.)
+// 2026-10-15:  Added the --float=TYPE switch, which calculates the
+//              pixels in f32 (or f64).  WindowAndViewportInfo and
+//              convert_row_and_column_to_x_and_y() are now generic over
+//              EscapeFloat, too, so the pixels' x & y are worked out
+//              in the chosen type.
+// 2026-10-15:  Pressing the B key saves a script that runs the program
+//              again with the switches that draw the current view.
+// 2026-10-15:  Added the --projection=logpolar switch, which lays the
+//              view out by the angle around the center and the log of
+//              the distance from it, so a deep zoom fits in one image.
+// 2026-10-15:  Added the --smooth-labels switch, which draws the
+//              letters of labels anti-aliased, worked out from the
+//              built-in glyphs (so there's still no font to load).
+// 2026-10-15:  Added the --auto-zoom switch, which keeps zooming in
+//              on the most interesting part of the image by itself.
+// 2026-10-15:  Added the --watch switch, which applies the --config
+//              file's settings again whenever it's saved.
+// 2026-10-15:  Added the --depth=16 switch, for saving screenshots
+//              with 16 bits per color channel.
+// 2026-10-15:  Pressing the F key switches between the Mandelbrot set
+//              and a Julia set, drawing the current view again.
+// 2026-10-15:  Added color cycling (toggled with the A key or
+//              --color-cycle), which keeps shifting the palette of the
+//              finished image, so that its colors seem to flow.
+// 2026-10-15:  Added --bailout=auto, which picks a bailout from how far
+//              the view is zoomed in (and picks it again at every zoom).
+// 2026-10-15:  Drawing now pauses (and the window is checked less
+//              often) while the window isn't active, such as when it's
+//              minimized, unless a script, --watch, or --auto-zoom is
+//              running things.
+// 2026-10-15:  Added the --diff=PATH switch, which draws the image with
+//              two sets of settings (the second with the settings from
+//              the PATH config file applied), and saves an image that
+//              shows (and counts) the pixels that came out differently.
+// 2026-10-15:  Added the --screenshot-ssaa=N switch, which draws saved
+//              screenshots again with N samples per pixel, while the
+//              image in the window stays quick to draw.
+// 2026-10-15:  Added the --notify switch, which rings the console's bell
+//              whenever an image is finished.
+// 2026-10-15:  Added the --deepzoom=PATH switch, which saves a pyramid
+//              of tiles (in the Deep Zoom format that viewers like
+//              OpenSeadragon read), so the view can be explored online.
+// 2026-10-15:  After each image, the highest escape value is printed,
+//              along with a suggestion to raise the bailout when it
+//              came close to it.
+// 2026-10-15:  Added the --background=R,G,B switch, for the color of
+//              the window before anything's drawn in it (and of the
+//              empty spots of a contact sheet).
+// 2026-10-15:  Error messages now go to stderr instead of stdout, and
+//              the welcome banner isn't printed until startup has
+//              succeeded (so it never comes before an error).
+// 2026-10-15:  Shift+arrow keys nudge the center by a single pixel
+//              (printing the new center), for fine-framing a view.
+// 2026-10-15:  All of the inputs given in the same frame (like a key
+//              pressed while clicking) are now handled, one per frame,
+//              instead of only the first one.  (Quitting still wins.)
+// 2026-10-15:  Added the --julia-atlas switch, which saves a grid of
+//              small Julia sets, each for the c at its spot in the
+//              Mandelbrot set's plane.
+// 2026-10-15:  Added the --title=STRING switch for the window's title,
+//              which otherwise is now the name of the fractal drawn.
+// 2026-10-15:  The Z key zooms in on the point under the mouse (and
+//              Shift+Z zooms out from it), without having to click.
+// 2026-10-15:  Added the --export-npy=PATH switch, which saves the
+//              escape values as a NumPy array.
+// 2026-10-15:  Added the --sharpen=AMOUNT switch, which sharpens the
+//              finished image (and its screenshots) with an unsharp mask.
+// 2026-10-15:  The pixels are now calculated on other threads while the
+//              window keeps being updated, and zooming (or quitting)
+//              cancels the calculation at once, even in the middle of
+//              a pixel that takes a very long time.
+// 2026-10-15:  Added the --goto=NAME switch, which starts at one of the
+//              set's famous places, and --list-locations to list them.
+// 2026-10-15:  The T key shows (or hides) how long the last image took
+//              to draw, and its magnification, in the window's corner.
+// 2026-10-15:  Added the --stdin-coords switch, which prints the escape
+//              values of points read from stdin, for scripting.
+// 2026-10-15:  Added the --double-buffer switch, which draws each image
+//              off-screen, showing only finished images.
+// 2026-10-15:  The ) and ( keys double and halve the number of
+//              anti-aliasing samples per pixel, drawing the image again.
+// 2026-10-15:  The window size is also checked against what a 32-bit
+//              machine can index, and the screenshot functions check
+//              their width x height for overflow.
+// 2026-10-15:  Shift and a number key bookmarks the current view, and
+//              the number key by itself goes back to it.
+// 2026-10-15:  Added the --fractal=tricorn switch, which draws the
+//              Tricorn (iterating conj(z)^2 + c) instead.
+// 2026-10-15:  Added the --frame-budget-ms=T switch, which limits the
+//              calculating to T milliseconds per window update, so
+//              the window refreshes at a steady pace while drawing.
+// 2026-10-15:  Added the --norm=NAME switch, which chooses how the
+//              escape test measures z:  l2 (the default), max, or l1.
+// 2026-10-15:  Added the --palette=random and --palette-seed=S
+//              switches, which color the image with a smooth random
+//              palette (a new one each run, unless the seed is given).
+// 2026-10-15:  Infinite and NaN coordinates are refused (in config
+//              files, and when zooming out overflows), and points with
+//              them escape right away instead of iterating forever.
+// 2026-10-15:  Added the --version switch, which prints the version
+//              and how this copy of the program was built.
+// 2026-10-15:  Added the --fast-fill switch, which skips calculating
+//              the insides of rectangles whose borders all have the
+//              same escape value (the Mariani-Silver algorithm).
+// 2026-10-15:  Added the --crop=X0,Y0,X1,Y1 switch, which zooms in on
+//              a rectangle of the starting view, and saves just it.
+// 2026-10-15:  Added the --max-iterations switch, which limits how
+//              long any one point is iterated (by default, too), so
+//              no point can take forever, even without a bailout.
+// 2026-10-15:  calculate_escape_value() and calculate_escape_details()
+//              stop at DEFAULT_MAX_ITERATIONS when given no bailout.
+// 2026-10-15:  Added the D key, which shows the bailout, the cycle
+//              detection threshold, and the size of a pixel.
+// 2026-10-15:  Palettes of just a few colors are blended smoothly from
+//              one color to the next (see sample_palette()).
+// 2026-10-15:  Added the --export-heightmap=PATH switch, which saves
+//              the escape values as a 16-bit PNG or an OBJ mesh.
+// 2026-10-15:  Added the zoomin, zoomout, and pan script commands,
+//              which move relative to the current view.
+// 2026-10-15:  Added the --stats=PATH switch, which saves the
+//              iteration statistics of each image to a file.
 // ----------
 
 
@@ -28,6 +263,8 @@ NOTE:  This program uses several crates, so to compile
 chrono = "0.4.23"
 image = "0.23"
 minifb = "0.23"
+serde = { version = "1", features = ["derive"] }
+toml = "0.8"
 
        in the [dependencies] section of your "Cargo.toml" file.
 *////////////////////////////////////////////////////////////////////
@@ -39,68 +276,91 @@ minifb = "0.23"
 // #![allow(unused_mut)]
 // #![allow(unused_variables)]
 
+// The following "allow"s keep clippy from complaining about
+// this program's deliberately explicit style (such as the
+// "return ()" statements and the spelled-out if/else chains):
+#![allow(clippy::collapsible_if)]
+#![allow(clippy::format_in_format_args)]
+#![allow(clippy::identity_op)]
+#![allow(clippy::if_same_then_else)]
+#![allow(clippy::needless_borrow)]
+#![allow(clippy::needless_return)]
+#![allow(clippy::partialeq_to_none)]
+#![allow(clippy::print_literal)]
+#![allow(clippy::ptr_arg)]
+#![allow(clippy::redundant_field_names)]
+#![allow(clippy::unused_unit)]
 
-// The Float type defines the type of floating-point values
-// to use when calculating the fractal.  It should really
-// be set to the biggest float type available (which is
-// f64 today, but might be f128 tomorrow).  But if you're
-// curious, you can change it to f32 for comparison purposes.
-type Float = f64;
 
+use jlr_mandelbrot::*;
 
-// The default width and height of the display window in pixels:
-const DEFAULT_WINDOW_SIZE: usize = 512;
 
+// The biggest width and height allowed for the window (in pixels).
+// Every pixel needs several bytes of memory (see
+// bytes_needed_for_window()), so a much bigger window would quickly
+// run out of memory.  (If you have lots of memory and need bigger
+// images, feel free to raise it.)
+const MAX_WINDOW_SIZE: usize = 8192;
 
-// Defining your own color palette is pretty easy if you know the RGB
-// value of each color.
-//
-// First, decide on the color for a point belonging to the actual set,
-// and set it as MANDELBROT_SET_COLOR.
-//
-// Then, decide what RGB triplet gets returned for a given i (iteration value).
-//
-// (Note:  These u8 triplets range from 0 to 255 (inclusive).)
-fn color(i: Option<usize>) -> (u8, u8, u8) {
+// The default width and height of the display window in pixels:
+const DEFAULT_WINDOW_SIZE: usize = 512;
 
-    const MANDELBROT_SET_COLOR: (u8, u8, u8) = (0, 0, 102);  // (dark blue)
-    if i == None {
-        return MANDELBROT_SET_COLOR
+// This structure holds the settings the user chose with command-line
+// switches that only matter to this program:  the window, the view it
+// starts on, the files it reads and writes, and what it prints.  (The
+// settings for calculating and coloring the fractal itself are in the
+// library's Config.)
+#[derive(Debug, Clone)]
+struct ProgramConfig {
+    window_size: usize,  // (in pixels)
+    center: (Float, Float),  // (The original center.)
+    distance_from_center_to_edge: Float,  // (The original distance.)
+    dry_run: bool,
+    cache_dir: Option<String>,
+    script_filename: Option<String>,
+    save_sidecar: bool,  // (Whether screenshots get a .toml file of their settings.)
+    screenshot_bit_depth: usize,  // (Bits per color channel of saved screenshots:  8 or 16.)
+    screenshot_aa_samples: Option<usize>,  // (If screenshots are drawn again, anti-aliased; see --screenshot-ssaa.)
+    write_ppm_to_stdout: bool,
+    quiet: bool,
+    profile: bool,
+}
+impl ProgramConfig {
+    fn new() -> Self {
+        Self {
+            window_size: DEFAULT_WINDOW_SIZE,
+            center: (-0.5, 0.0),
+            distance_from_center_to_edge: 1.725,
+            dry_run: false,
+            cache_dir: None,
+            script_filename: None,
+            save_sidecar: false,
+            screenshot_bit_depth: 8,
+            screenshot_aa_samples: None,
+            write_ppm_to_stdout: false,
+            quiet: false,
+            profile: false,
+        }
     }
 
-    let i = i.unwrap();
-
-    // If you want to write your own code that takes i as input
-    // and returns a u8 RGB triplet, do it here.
-
-    const NUM_COLORS_PER_LEG: usize = 30;
-    let num_colors = NUM_COLORS_PER_LEG * 3;
-    let i = i % num_colors;
-    let remainder = i % NUM_COLORS_PER_LEG;
-
-    let value1 = (NUM_COLORS_PER_LEG - remainder) * 255 / NUM_COLORS_PER_LEG;
-    let value2 = remainder * 255 / NUM_COLORS_PER_LEG;
-
-    let value1 = value1.try_into().unwrap();  // (Converts from usize to u8.)
-    let value2 = value2.try_into().unwrap();  // (Converts from usize to u8.)
-
-    let leg = i / NUM_COLORS_PER_LEG;
-    match leg {
-        0 => (value1, value2, 0),
-        1 => (0, value1, value2),
-        2 => (value2, 0, value1),
-        // Should never get here, but include just in case:
-        _ => panic!("Reached state that should never have been reached."),
+    // Returns the view the program starts on (at zoom level 0), as
+    // given by the --size, --center, --distance, and --projection.
+    fn starting_info(&self, config: &Config) -> WindowAndViewportInfo {
+        let (center_x, center_y) = self.center;
+        WindowAndViewportInfo::new(
+            self.window_size, self.window_size,  // (in pixels)
+            center_x, center_y,
+            self.distance_from_center_to_edge,
+            0)
+            .with_projection(config.projection)
     }
 }
 
-
-// A convenience function to turn RBG values
-// (from 0 to 255, inclusive) into a u32 integer.
-fn rgb_to_u32(r: u8, g: u8, b: u8) -> u32 {
-    ((r as u32) << 16) | ((g as u32) << 8) | (b as u32)
-}
-
+// The exit codes of this program, so that scripts running it can
+// tell what went wrong.  (It exits with 0 when nothing did.)
+const EXIT_USAGE_ERROR: i32 = 2;  // (A bad switch, environment variable, or config file.)
+const EXIT_IO_ERROR: i32 = 3;  // (A file that couldn't be read, or an image that couldn't be written.)
+const EXIT_DISPLAY_ERROR: i32 = 4;  // (The window couldn't be opened or updated.)
 
 // This structure is an iterator that returns pixel coordinates
 // (row, column) starting at the specified (start_row, start_column)
@@ -173,179 +433,118 @@ impl Iterator for RowAndColumnIterator {
 }
 
 
+// Returns every pixel coordinate (as (row, column)) of a width x height
+// window, swirling outward from the center of the window (see
+// RowAndColumnIterator), so that an image is drawn from its center out.
+fn swirling_pixels(width: usize, height: usize) -> impl Iterator<Item = (usize, usize)> {
+    let (half_width, half_height) = (width / 2, height / 2);  // (in pixels)
+    RowAndColumnIterator::new(half_height as isize, half_width as isize)
+        // Check to see if the (current_row, current_column) pixel
+        // coordinate is in the window.  If not, keep looking
+        // until we find one that is in the window:
+        .filter(move |&(row, column)| (0..height as isize).contains(&row) && (0..width as isize).contains(&column))
+        .map(|(row, column)| (row as usize, column as usize))
+        .take(width * height)
+}
 
-// The main Mandelbrot set calculation function.
-// Given an (x, y) coordinate, it will return the number
-// of iterations needed to determine that the coordinate
-// is not part of the Mandelbrot set (or None if it is
-// part of the set).
-//
-// Note:  Using a value of None for c will make it be
-//        set to the passed-in (x,y), which is ideal
-//        for calculating the Mandelbrot set.
-//        For Julia sets, where c is the same for
-//        each and every coordinate, c can be passed
-//        in as Some((some_x as Float, some_y as Float)).
-//
-// The threshold specifies what's considered "close enough"
-// for x and y values when detecting cycles.  (Half the
-// length of a pixel is probably good enough.)
-//
-// The bailout value is the maximum number of times
-// Znext = Z + c
-// gets carried out (not counting the times for
-// cycle detection).
-fn calculate_escape_value(x: Float, y: Float,
-                          c: Option<(Float, Float)>,
-                          threshold: Option<Float>,
-                          bailout: Option<usize>) -> Option<usize> {
-    let (c_x, c_y) = c.unwrap_or((x, y));
-    let threshold = threshold.unwrap_or(0.0);
-
-    let mut iterations = 0;
-    let (mut x_slow, mut y_slow) = (x, y);
-    let (mut x_fast, mut y_fast) = (x, y);
-
-    let _start_of_loop = std::time::Instant::now();
 
-    loop {
-        let (x_squared, y_squared) = (x_fast * x_fast, y_fast * y_fast);
-        if x_squared + y_squared > 4.0 {
-            return Some(iterations)
-        }
-        let difference_of_squares = x_squared - y_squared;
-        let double_the_product = 2.0 * x_fast * y_fast;
-        (x_fast, y_fast) = (difference_of_squares + c_x, double_the_product + c_y);
-        // Check to see if we've encountered this point before:
-        if threshold == 0.0 {  // (if no threshold was specified)
-            if (x_fast, y_fast) == (x_slow, y_slow) {
-                return None
-            }
-        } else {  // (the threshold was specified)
-            if (x_fast - x_slow).abs() <= threshold && (y_fast - y_slow).abs() <= threshold {
-                return None
-            }
-        }
-        iterations += 1;
-        if let Some(bailout_to_use) = bailout {
-            if iterations == bailout_to_use {
-                return None
-            }
-        }
+// How often (in milliseconds) the window is updated (and checked for
+// input) while waiting on pixels being calculated by other threads.
+const CALCULATION_POLL_INTERVAL: u64 = 1;
 
-        let (x_squared, y_squared) = (x_fast * x_fast, y_fast * y_fast);
-        if x_squared + y_squared > 4.0 {
-            return Some(iterations)
-        }
-        let difference_of_squares = x_squared - y_squared;
-        let double_the_product = 2.0 * x_fast * y_fast;
-        (x_fast, y_fast) = (difference_of_squares + c_x, double_the_product + c_y);
-        // Check to see if we've encountered this point before:
-        if threshold == 0.0 {  // (if no threshold was specified)
-            if (x_fast, y_fast) == (x_slow, y_slow) {
-                return None
-            }
-        } else {  // (the threshold was specified)
-            if (x_fast - x_slow).abs() <= threshold && (y_fast - y_slow).abs() <= threshold {
-                return None
-            }
-        }
-        iterations += 1;
-        if let Some(bailout_to_use) = bailout {
-            if iterations == bailout_to_use {
-                return None
-            }
-        }
+// How often (in microseconds) the window is updated while drawing
+// with --frame-budget-ms.  (About 60 times a second.)
+const FRAME_INTERVAL: u64 = 16600;
 
-        let (x_squared, y_squared) = (x_slow * x_slow, y_slow * y_slow);
-        let difference_of_squares = x_squared - y_squared;
-        let double_the_product = 2.0 * x_slow * y_slow;
-        (x_slow, y_slow) = (difference_of_squares + c_x, double_the_product + c_y);
-        // Check to see if we've encountered this point before:
-        if threshold == 0.0 {  // (if no threshold was specified)
-            if (x_fast, y_fast) == (x_slow, y_slow) {
-                return None
-            }
-        } else {  // (the threshold was specified)
-            if (x_fast - x_slow).abs() <= threshold && (y_fast - y_slow).abs() <= threshold {
-                return None
-            }
-        }
-        // Do not increment the iterations variable here,
-        // as we only do so after advancing the "fast" point cycle.
 
-        // Remove (or comment-out) the following "continue;" line
-        // to allow a "time-out" if calculation gets too long:
-        continue;
-        #[allow(unreachable_code)]
-        if iterations % 1_000_000 == 0 {
-            if _start_of_loop.elapsed().as_millis() >= 1_000 {
-                return None  // (Taking so much time, we'll assume it's part of the set.)
-            }
-        }
+// With --frame-budget-ms, this keeps track of how much time the
+// drawing thread has spent calculating since the window was last
+// updated.  Once it's spent the whole budget, it waits for the next
+// update before going on (picking up from the same place in its
+// swirl), so that the window keeps refreshing at a steady pace, no
+// matter how long the pixels take.  (A single pixel still isn't split
+// up, so one that takes longer than the budget can go over it.)
+#[derive(Clone)]
+struct FrameBudget {
+    budget: std::time::Duration,
+    spent: std::time::Duration,
+}
+
+impl FrameBudget {
+    fn new(milliseconds: u64) -> FrameBudget {
+        FrameBudget { budget: std::time::Duration::from_millis(milliseconds),
+                      spent: std::time::Duration::ZERO }
+    }
+
+    // Starts on the budget for the next frame:
+    fn new_frame(&mut self) {
+        self.spent = std::time::Duration::ZERO;
+    }
+
+    // Adds the time taken by a pixel, and returns whether
+    // the budget for this frame is now used up:
+    fn spend(&mut self, time: std::time::Duration) -> bool {
+        self.spent += time;
+        self.spent >= self.budget
     }
 }
 
 
-// This structure contains information about the viewport
-// (that is, the cartesian coordinate bounds and spans).
-// It also contains the physical (width, height) of the
-// window (in pixels) and the zoom_level.
-//
-// With the exception of width and height (in pixels)
-// and the zoom_level, everything is a floating point
-// number, as they refer to the mathematical measurements
-// of the fractal itself.
-#[allow(dead_code)]  // (There are some fields that aren't read, but might be in the future.)
-#[derive(Debug)]
-struct WindowAndViewportInfo {
-    width: usize,  // (in pixels)
-    height: usize,  // (in pixels)
-    center_x: Float,
-    center_y: Float,
-    span: Float,
-    distance_from_center_to_edge: Float,  // (half of the span)
-    min_x: Float,
-    max_x: Float,
-    min_y: Float,
-    max_y: Float,
-    delta_x: Float,
-    delta_y: Float,
-    zoom_level: isize,
-}
-impl WindowAndViewportInfo {
-    fn new(width: usize, height: usize,  // (in pixels)
-           center_x: Float, center_y: Float, distance_from_center_to_edge: Float,
-           zoom_level: isize)
-               -> Self {
-
-        let span = distance_from_center_to_edge * 2.0;
-        let min_x = center_x - distance_from_center_to_edge;
-        let max_x = center_x + distance_from_center_to_edge;
-        let min_y = center_y - distance_from_center_to_edge;
-        let max_y = center_y + distance_from_center_to_edge;
-        let delta_x = (max_x - min_x) / width as Float;
-        let delta_y = (max_y - min_y) / height as Float;
+// How many calculated pixels can be waiting on the window's thread
+// (see send_swirling_pixels()) before the thread calculating them
+// stops and waits, too.  (So that while the window's thread is
+// waiting for the window to be active again, the calculating stops,
+// instead of filling up memory with pixels nobody's looking at.)
+const SWIRL_CHANNEL_CAPACITY: usize = 10_000;
 
-        Self {
-            width,
-            height,
-            center_x,
-            center_y,
-            span,
-            distance_from_center_to_edge,
-            min_x,
-            max_x,
-            min_y,
-            max_y,
-            delta_x,
-            delta_y,
-            zoom_level,
+
+// Calculates (and colors) every pixel, swirling outward from the
+// center of the window, and sends each one (along with how long it
+// took) to the window's thread as it's done.  Once the channel is
+// full, this waits for the window's thread to catch up, and picks
+// up from the same place in its swirl, so a paused window pauses
+// this, too.  Stops early if the window's thread stops listening.
+//
+// (With --frame-budget-ms, every window update is sent back on the
+// frame_receiver, so this knows when a new frame starts.)
+fn send_swirling_pixels(info: &WindowAndViewportInfo, config: &Config, threshold: Float,
+                        mut frame_budget: Option<FrameBudget>,
+                        sender: std::sync::mpsc::SyncSender<(usize, usize, EscapeDetails, u32, std::time::Duration)>,
+                        frame_receiver: std::sync::mpsc::Receiver<()>) {
+    for (row, column) in swirling_pixels(info.width, info.height) {
+        let calculation_start_time = std::time::Instant::now();
+        let details = calculate_pixel_details(info, row, column, config, threshold);
+        let color = pixel_color_at(info, row, column, &details, config, threshold);
+        let calculation_time = calculation_start_time.elapsed();
+        // (If the receiver's gone, nobody wants the rest of the pixels.)
+        if sender.send((row, column, details, color, calculation_time)).is_err() {
+            break
+        }
+        if let Some(frame_budget) = &mut frame_budget {
+            // (If the window was updated in the meantime,
+            // this pixel's time counts toward the new frame.)
+            if frame_receiver.try_iter().count() > 0 {
+                frame_budget.new_frame();
+            }
+            if frame_budget.spend(calculation_time) {
+                // (If the frame sender's gone, so is the window loop.)
+                if frame_receiver.recv().is_err() {
+                    break
+                }
+                frame_budget.new_frame();
+            }
         }
     }
 }
 
 
+// Why drawing an image was stopped before it was finished.
+enum Interruption {
+    Quit,
+    StartOver,  // (For an input that changes the image, which is then drawn instead.)
+}
+
+
 // The reason for the existence of this MouseInfo struct
 // is because the minifb::Window class does not have a
 // way to detect if a mouse button was JUST pressed or
@@ -417,256 +616,8014 @@ impl MouseInfo {
 }
 
 
-// Converts a row&column coordinate (with row=0 & column=0 as the center
-// of upper-right pixel) to the Mandelbrot's domain's x,y coordinate:
-fn convert_row_and_column_to_x_and_y(info: &WindowAndViewportInfo,
-                                     row: Float, column: Float) -> (Float, Float) {
-    let x = info.min_x + info.delta_x * (column + 0.5);
-    let y = info.max_y - info.delta_y * (row + 0.5);
-    (x, y)
-}
+// Splits every pixel coordinate (as (row, column)) of a width x height
+// window into number_of_bands bands, according to each pixel's distance
+// from the center of the window.  The first band is a small circle
+// at the center, and each band after that is a ring around the
+// previous one (so drawing the bands in order makes a growing circle).
+//
+// (The last bands may be only partial rings, as they reach
+// into the corners of the window.)
+fn schedule_bands(width: usize, height: usize,
+                  number_of_bands: usize) -> Vec<Vec<(usize, usize)>> {
+    let (half_width, half_height) = (width / 2, height / 2);  // (in pixels)
+    let distance_from_center = |row: usize, column: usize| -> Float {
+        let row_offset = row as Float - half_height as Float;
+        let column_offset = column as Float - half_width as Float;
+        (row_offset * row_offset + column_offset * column_offset).sqrt()
+    };
 
+    // The farthest any pixel can be from the center is
+    // at one of the corners.  (We add one to it so that
+    // the farthest pixel still falls into the last band.)
+    let max_distance = [(0, 0), (0, width - 1), (height - 1, 0), (height - 1, width - 1)]
+                           .iter()
+                           .map(|&(row, column)| distance_from_center(row, column))
+                           .fold(0.0, Float::max) + 1.0;
+    let band_width = max_distance / number_of_bands as Float;
 
-// Saves a screenshot to disk with the given filename.
-// (The image_buffer must have a length of width x height.)
-fn save_screenshot_to_filename(image_buffer: &Vec<u32>, width: usize, height: usize, filename: &str) -> () {
-    // Verify that the length of the image_buffer
-    // equals the width x height.  Otherwise, things
-    // will break spectacularly:
-    assert_eq!(image_buffer.len(), width * height,
-               "FATAL ERROR:  width x height does not equal the length of the image buffer.");
+    let mut bands: Vec<Vec<(usize, usize)>> = vec![Vec::new(); number_of_bands];
+    for row in 0..height {
+        for column in 0..width {
+            let band = (distance_from_center(row, column) / band_width) as usize;
+            bands[band.min(number_of_bands - 1)].push((row, column));
+        }
+    }
 
-    let mut screenshot_buffer = image::ImageBuffer::new(width as u32, height as u32);
+    // Within each band, sort the pixels by distance, so that
+    // even a wide band gets drawn from the center -> out:
+    for band in bands.iter_mut() {
+        band.sort_by(|&(row1, column1), &(row2, column2)|
+            distance_from_center(row1, column1).total_cmp(&distance_from_center(row2, column2)));
+    }
 
-    for (x, y, pixel) in screenshot_buffer.enumerate_pixels_mut() {
-        let (x, y): (usize, usize) = (x as usize, y as usize);  // (Convert from u32 to usize.)
-        let i: usize = y * width + x;
-        let image_buffer_pixel = image_buffer[i] & 0xff_ff_ff;
+    bands
+}
 
-        let r = image_buffer_pixel >> 16 & 0xff;
-        let g = image_buffer_pixel >>  8 & 0xff;
-        let b = image_buffer_pixel >>  0 & 0xff;
 
-        *pixel = image::Rgb([r as u8, g as u8, b as u8]);
+// This structure keeps count of where the work (and time) of drawing
+// an image went, for the --profile switch.  The time spent calculating
+// (and coloring) pixels and the time spent updating the window are
+// measured separately, so they can be compared with the total time.
+#[derive(Debug)]
+struct RenderProfile {
+    number_of_pixels: usize,
+    total_iterations: u64,
+    max_iterations: usize,
+    calculation_time: std::time::Duration,
+    display_time: std::time::Duration,
+}
+impl RenderProfile {
+    fn new() -> Self {
+        Self {
+            number_of_pixels: 0,
+            total_iterations: 0,
+            max_iterations: 0,
+            calculation_time: std::time::Duration::ZERO,
+            display_time: std::time::Duration::ZERO,
+        }
     }
 
-    screenshot_buffer.save(filename).unwrap();
-    println!("Saved screenshot to a file named:  {filename}");
+    // Counts a calculated pixel.
+    fn add_pixel(&mut self, details: &EscapeDetails) {
+        self.number_of_pixels += 1;
+        self.total_iterations += details.iterations as u64;
+        self.max_iterations = self.max_iterations.max(details.iterations);
+    }
+
+    // Returns a summary of the profile, for an image
+    // that took total_time to draw from start to finish.
+    fn summary(&self, total_time: std::time::Duration) -> String {
+        let total_seconds = total_time.as_secs_f64().max(Float::MIN_POSITIVE);
+        let calculation_seconds = self.calculation_time.as_secs_f64();
+        let display_seconds = self.display_time.as_secs_f64();
+        let iterations_per_pixel = self.total_iterations as Float / self.number_of_pixels.max(1) as Float;
+        let counts = format!("Profile:  {} pixels, {} iterations ({:.1} per pixel, {} at most)",
+                             self.number_of_pixels, self.total_iterations,
+                             iterations_per_pixel, self.max_iterations);
+        let times = format!("          {:.3} sec. calculating ({:.1}%), {:.3} sec. updating the window ({:.1}%)",
+                            calculation_seconds, 100.0 * calculation_seconds / total_seconds,
+                            display_seconds, 100.0 * display_seconds / total_seconds);
+        format!("{counts}\n{times}")
+    }
 }
 
 
-// Saves a screenshot to disk with a calculated filename.
-// (The image_buffer must have a length of width x height.)
-fn save_screenshot(image_buffer: &Vec<u32>, width: usize, height: usize) -> () {
-    let now = chrono::Utc::now();
-    let filename = now.format("jlr-mandelbrot.screenshot.%Y%m%d.%H%M%S.%3f.png").to_string();
-    save_screenshot_to_filename(&image_buffer, width, height, &filename)
+// How close (as a fraction of the bailout) the highest escape value
+// of an image has to come to the bailout for escape_value_report() to
+// suggest raising it.  (Points that escape that slowly are likely to
+// have neighbors that would escape a little more slowly still, but
+// are drawn as part of the set instead.)
+const BAILOUT_SUGGESTION_FRACTION: Float = 0.9;
+
+
+// Returns the report printed after each image:  the highest escape
+// value of any of its pixels, and (if it came within
+// BAILOUT_SUGGESTION_FRACTION of the bailout) a suggestion to double
+// the bailout.
+fn escape_value_report(escape_details: &[EscapeDetails], bailout: Option<usize>) -> String {
+    let highest_escape_value = escape_details.iter().filter_map(|details| details.escape_value).max();
+    match (highest_escape_value, bailout) {
+        (None, _) => "No pixels escaped.".to_string(),
+        (Some(highest), None) => format!("Highest escape value:  {highest}"),
+        (Some(highest), Some(bailout)) if highest as Float >= BAILOUT_SUGGESTION_FRACTION * bailout as Float => {
+            let suggested_bailout = adjusted_bailout(Some(bailout), true, None).unwrap();
+            format!("Highest escape value:  {highest} (of a bailout of {bailout})\n\
+                     Some points took almost the bailout to escape, so some of the pixels drawn as part of\n\
+                     the set probably aren't.  Try --bailout={suggested_bailout} (or press the + key) to see more detail.")
+        }
+        (Some(highest), Some(bailout)) => format!("Highest escape value:  {highest} (of a bailout of {bailout})"),
+    }
 }
 
 
-// Prints screen coordinates and mouse coordinates to the console.
-fn print_coordinates(window: &minifb::Window, info: &WindowAndViewportInfo) {
-    let upper_left = (info.min_x, info.max_y);
-    let upper_right = (info.max_x, info.max_y);
-    let center = (info.center_x, info.center_y);
-    let lower_left = (info.min_x, info.min_y);
-    let lower_right = (info.max_x, info.min_y);
-    let (mouse_column, mouse_row) = window.get_mouse_pos(minifb::MouseMode::Pass).unwrap();
-    let mouse_cursor = convert_row_and_column_to_x_and_y(
-                           &info,
-                           mouse_row as Float, mouse_column as Float);
-    let mouse_cursor = (mouse_cursor.0, mouse_cursor.1);
-    // We want to round the numbers to use only a specified
-    // number of digits of precision, so that they don't take
-    // up too much of the line:
-    let round_tuple_of_floats = |p: (Float, Float), decimal_places: isize| -> (Float, Float) {
-        let p0 = p.0 * (10.0 as Float).powi(decimal_places as i32);
-        let p0 = p0.round();
-        let p0 = p0 / (10.0 as Float).powi(decimal_places as i32);
-        let p1 = p.1 * (10.0 as Float).powi(decimal_places as i32);
-        let p1 = p1.round();
-        let p1 = p1 / (10.0 as Float).powi(decimal_places as i32);
-        (p0, p1)
+// The number of bins in the histogram of escape values that --stats
+// saves.  (Each covers the same range of escape values, from 0 up to
+// the highest one in the image.)
+const STATS_HISTOGRAM_BINS: usize = 20;
+
+
+// Returns the report that --stats saves after each image (of the
+// viewport described by info, drawn with the config), as text that's
+// meant to be read as well as parsed:  where the image is and what it
+// was drawn with, how many of its pixels are in the set, the lowest,
+// highest, mean, and median escape values of the ones that escaped,
+// and a histogram of their escape values.  (The timestamp is when
+// it was saved.)
+fn iteration_stats_text(escape_details: &[EscapeDetails], info: &WindowAndViewportInfo, config: &Config,
+                        timestamp: &str) -> String {
+    let number_of_pixels = escape_details.len();
+    let mut escape_values: Vec<usize> = escape_details.iter().filter_map(|details| details.escape_value).collect();
+    escape_values.sort_unstable();
+    let in_set = number_of_pixels - escape_values.len();
+    let hit_bailout = escape_details.iter().filter(|details| details.hit_bailout).count();
+    let total_iterations: u64 = escape_details.iter().map(|details| details.iterations as u64).sum();
+    let percent = |count: usize| 100.0 * count as Float / number_of_pixels.max(1) as Float;
+
+    let mut text = format!("# The iteration statistics of an image drawn by JLR-Mandelbrot, saved {timestamp}.\n\
+                            # (Of {}, at zoom level {}.)\n",
+                           fractal_name(config.formula, config.c), info.zoom_level);
+    text += &format!("center = {:?}, {:?}\n", info.center_x, info.center_y);
+    text += &format!("distance = {:?}\n", info.distance_from_center_to_edge);
+    text += &format!("size = {} x {}\n", info.width, info.height);
+    text += &format!("bailout = {}\n", config.bailout.map_or("none".to_string(), |bailout| bailout.to_string()));
+    text += &format!("max iterations = {}\n",
+                     config.max_iterations.map_or("none".to_string(), |max_iterations| max_iterations.to_string()));
+    text += "\n";
+    text += &format!("pixels = {number_of_pixels}\n");
+    text += &format!("in set = {in_set} ({:.2}%)\n", percent(in_set));
+    text += &format!("hit the bailout = {hit_bailout} ({:.2}%)\n", percent(hit_bailout));
+    text += &format!("escaped = {} ({:.2}%)\n", escape_values.len(), percent(escape_values.len()));
+    text += &format!("iterations calculated = {total_iterations} ({:.1} per pixel)\n",
+                     total_iterations as Float / number_of_pixels.max(1) as Float);
+    let (Some(&lowest), Some(&highest)) = (escape_values.first(), escape_values.last()) else {
+        return text + "\n(No pixels escaped.)\n"
     };
-    let decimal_places = 7;
-    print!("Screen coordinates:
---------------------------------------------------------------
-|{: <29}  {: >29}|
-|{: ^60}|
-|{: <29}  {: >29}|
---------------------------------------------------------------
-Mouse coordinates:  {}
-",
-        format!("{:?}", round_tuple_of_floats(upper_left, decimal_places)),
-        format!("{:?}", round_tuple_of_floats(upper_right, decimal_places)),
-        format!("{:?}", round_tuple_of_floats(center, decimal_places)),
-        format!("{:?}", round_tuple_of_floats(lower_left, decimal_places)),
-        format!("{:?}", round_tuple_of_floats(lower_right, decimal_places)),
-        format!("{:?}", round_tuple_of_floats(mouse_cursor, decimal_places)));
+    let mean = escape_values.iter().map(|&escape_value| escape_value as Float).sum::<Float>()
+                   / escape_values.len() as Float;
+    let middle = escape_values.len() / 2;
+    let median = if escape_values.len().is_multiple_of(2) {
+        (escape_values[middle - 1] + escape_values[middle]) as Float / 2.0
+    } else {
+        escape_values[middle] as Float
+    };
+    text += "\n";
+    text += "escape values (of the pixels that escaped):\n";
+    text += &format!("   lowest = {lowest}\n");
+    text += &format!("   highest = {highest}\n");
+    text += &format!("   mean = {mean:.2}\n");
+    text += &format!("   median = {median}\n");
+
+    text += "\n";
+    text += "histogram (escape values = number of pixels):\n";
+    let bin_width = (highest + 1).div_ceil(STATS_HISTOGRAM_BINS);
+    let mut counts = vec![0; (highest + 1).div_ceil(bin_width)];
+    for &escape_value in &escape_values {
+        counts[escape_value / bin_width] += 1;
+    }
+    for (bin, count) in counts.iter().enumerate() {
+        let first = bin * bin_width;
+        let last = (first + bin_width - 1).min(highest);
+        text += &format!("   {first} to {last} = {count}\n");
+    }
+    text
 }
 
 
-// This enum reflects the user's choices.
-enum UserInput {
-    Nothing,
-    Quit,
-    SaveScreenShot,
-    ShowCoordinates,
-    ZoomIn(Float, Float),  // (x, y) of the new center.  (Where the user clicked.)
-    ZoomOut(Float, Float),  // (x, y) of the new center.  (NOT where the user clicked!)
+// Saves the iteration_stats_text() of an image to the given path
+// (for --stats), returning an error message if it couldn't be saved.
+fn save_iteration_stats(path: &str, escape_details: &[EscapeDetails], info: &WindowAndViewportInfo,
+                        config: &Config) -> Result<(), String> {
+    let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
+    std::fs::write(path, iteration_stats_text(escape_details, info, config, &timestamp))
+        .map_err(|error| format!("Unable to save the iteration statistics to \"{path}\":  {error}"))
 }
 
 
-// Based on the Window and WindowAndViewportInfo,
-// this checks to see if the user gave any input.
-fn get_user_input(window: &minifb::Window,
-                  info: &WindowAndViewportInfo,
-                  mouse_info: &mut MouseInfo) -> UserInput {
+// With --progressive, images are first drawn with a bailout of
+// PROGRESSIVE_FIRST_BAILOUT, which is then multiplied by
+// PROGRESSIVE_BAILOUT_STEP for each pass after that, until it
+// reaches the --bailout (or, if there's no bailout, until it passes
+// PROGRESSIVE_MAX_RAMP_BAILOUT, after which no bailout is used).
+const PROGRESSIVE_FIRST_BAILOUT: usize = 64;
+const PROGRESSIVE_BAILOUT_STEP: usize = 4;
+const PROGRESSIVE_MAX_RAMP_BAILOUT: usize = 4096;
 
-    mouse_info.set_mouse_buttons_pressed(
-                window.get_mouse_down(minifb::MouseButton::Left),
-                window.get_mouse_down(minifb::MouseButton::Right));
 
-    if !window.is_open() || window.is_key_down(minifb::Key::Escape)
-                         || window.is_key_down(minifb::Key::Q) {
-        return UserInput::Quit
-    } else if window.is_key_released(minifb::Key::S) {  // S => Save ScreenShot
-        return UserInput::SaveScreenShot
-    } else if window.is_key_released(minifb::Key::C) {  // C => Coordinates
-        return UserInput::ShowCoordinates
-    } else if mouse_info.left_mouse_button_just_released() {  // (Left mouse button WAS down, but no longer.)
-        let (column, row) = window.get_mouse_pos(minifb::MouseMode::Pass).unwrap();
-        let (x, y) = convert_row_and_column_to_x_and_y(&info, row as Float, column as Float);
-        return UserInput::ZoomIn(x, y)
-    } else if mouse_info.right_mouse_button_just_released() {  // (Right mouse button WAS down, but no longer.)
-        let (column, row) = window.get_mouse_pos(minifb::MouseMode::Pass).unwrap();
-        let (x, y) = convert_row_and_column_to_x_and_y(&info, row as Float, column as Float);
-        return UserInput::ZoomOut(2.0 * info.center_x - x, 2.0 * info.center_y - y)
+// Returns the bailouts to draw an image with, pass by pass, with
+// --progressive.  The last one is always final_bailout itself.
+fn progressive_bailouts(final_bailout: Option<usize>) -> Vec<Option<usize>> {
+    let mut bailouts = Vec::new();
+    let mut bailout = PROGRESSIVE_FIRST_BAILOUT;
+    let ramp_limit = final_bailout.unwrap_or(PROGRESSIVE_MAX_RAMP_BAILOUT + 1);
+    while bailout < ramp_limit {
+        bailouts.push(Some(bailout));
+        bailout = bailout.saturating_mul(PROGRESSIVE_BAILOUT_STEP);
     }
-
-    return UserInput::Nothing
+    bailouts.push(final_bailout);
+    bailouts
 }
 
 
-#[allow(dead_code)]
-fn test_color_function() {
-    println!();
-    println!("Testing the color() function:");
-    let n = 0;   println!("{}: {:?}", n, color(Some(n)));
-    let n = 15;  println!("{}: {:?}", n, color(Some(n)));
-    let n = 30;  println!("{}: {:?}", n, color(Some(n)));
-    let n = 45;  println!("{}: {:?}", n, color(Some(n)));
-    let n = 60;  println!("{}: {:?}", n, color(Some(n)));
-    let n = 75;  println!("{}: {:?}", n, color(Some(n)));
-    let n = 90;  println!("{}: {:?}", n, color(Some(n)));
-    let n = None;  println!("{:?}: {:?}", n, color(n));
-    println!();
+// This structure keeps track of the passes still to be done for a
+// --progressive image, after its first (quick, low-bailout) pass has
+// been drawn.  Only pixels that hit the bailout in one pass can change
+// in the next one (a point that escaped, or fell into a cycle, before
+// the bailout does the same no matter how high the bailout is), so
+// each pass only recalculates those.
+struct ProgressiveRefinement {
+    remaining_bailouts: Vec<Option<usize>>,  // (The next one is last.)
+    config: Config,  // (The user's settings, but with this pass's bailout.)
+    pixels: Vec<(usize, usize)>,  // (The pixels this pass still has to recalculate.)
+    quiet: bool,  // (With --quiet, the passes aren't announced.)
 }
+impl ProgressiveRefinement {
+    // Returns the refinement to do after the first pass has been drawn
+    // with bailouts[0], or None if there's nothing left to refine.
+    fn new(bailouts: &[Option<usize>], config: &Config, quiet: bool) -> Option<Self> {
+        if bailouts.len() <= 1 {
+            return None
+        }
+        let remaining_bailouts = bailouts[1..].iter().rev().copied().collect();
+        Some(Self { remaining_bailouts, config: config.clone(), pixels: Vec::new(), quiet })
+    }
 
+    // Recalculates (and recolors) pixels for up to time_limit, starting
+    // the next pass whenever one is finished.  Returns true once the
+    // last pass is finished.
+    fn refine(&mut self,
+              escape_details: &mut [EscapeDetails],
+              image_buffer: &mut [u32],
+              info: &WindowAndViewportInfo,
+              threshold: Float,
+              time_limit: std::time::Duration) -> bool {
+        const CHUNK_SIZE: usize = 1024;  // (pixels, between checks of the time)
+        let start_time = std::time::Instant::now();
+        loop {
+            if self.pixels.is_empty() {
+                // Start the next pass (if there is one), with every
+                // pixel that hit the bailout of the last one:
+                self.config.bailout = match self.remaining_bailouts.pop() {
+                    Some(bailout) => bailout,
+                    None => return true,
+                };
+                self.pixels = all_pixels(info.width, info.height).into_iter()
+                                  .filter(|&(row, column)| escape_details[row * info.width + column].hit_bailout)
+                                  .collect();
+                if self.pixels.is_empty() {
+                    return true  // (Nothing left that a higher bailout could change.)
+                }
+                if !self.quiet {
+                    println!("Zoom level {}:  Refining {} pixels with a bailout of {}.",
+                             info.zoom_level, self.pixels.len(),
+                             self.config.bailout.map_or("(none)".to_string(), |bailout| bailout.to_string()));
+                }
+            }
+            if start_time.elapsed() >= time_limit {
+                return false
+            }
 
-// Returns the help text suitable for printing when the
-// user specifies the --help switch.
-fn help_text() -> String {
-    format!("
+            let chunk_start = self.pixels.len().saturating_sub(CHUNK_SIZE);
+            let chunk: Vec<(usize, usize)> = self.pixels.drain(chunk_start..).collect();
+            let chunk_escape_details = calculate_pixel_details_in_parallel(&chunk, info, &self.config, threshold);
+            let chunk_colors = pixel_colors(&chunk, &chunk_escape_details, info, &self.config, threshold);
+            for ((&(row, column), details), &color) in chunk.iter().zip(chunk_escape_details.iter()).zip(chunk_colors.iter()) {
+                let i = row * info.width + column;
+                escape_details[i] = *details;
+                image_buffer[i] = color;
+            }
 
-Program:  JLR-Mandelbrot
-          A Mandelbrot set viewer.
+            // Post-processing (like --antialias=fxaa) needs the whole
+            // image, so once the pass is done, the image is recolored:
+            if self.pixels.is_empty() && (self.config.coloring == Coloring::Boundary
+                                          || self.config.antialiasing != Antialiasing::None) {
+                color_escape_details(escape_details, image_buffer, info, &self.config);
+            }
+        }
+    }
+}
 
-Usage:  jlr-mandelbrot [options]
-Example usages:
-   jlr-mandelbrot
-   jlr-mandelbrot --size=256
-   jlr-mandelbrot --bailout=150
-   jlr-mandelbrot --julia=-0.835,-0.232
 
-Options:
-   -h, --help
-      Shows this help text and exits.
-   --size=NUMBER
-      Displays the image in a square window of NUMBER by NUMBER pixels.
-      ({default_size} is the default.)
-   --bailout=NUMBER
-      Uses a bailout number, or a maximum number of iterations.
-      If this number is reached, then a point is considered to
-      be part of the set.  (A bailout number is not used by default.)
-   --julia=X,Y
-      Instead of a Mandelbrot set, a Julia set will be generated
-      using X+Yi as the value for c.
+// The first bytes of every escape-cache file (see --cache-dir).
+// (Change the number at the end whenever the file format, or the
+// math that calculates the escape details, changes, so that old
+// cache files stop being used.)
+const ESCAPE_CACHE_MAGIC: &[u8; 8] = b"JLRESC04";
 
-Once the image is displayed:
-   A left-click of the mouse zooms in.
-   A right-click of the mouse zooms out.
-   Pressing the C key will print coordinates to the console.
-   Pressing the S key will save a screenshot in PNG format.
-   Pressing the Q key will quit.
-   Pressing the Escape key will also quit.
 
-Author:  Jean-Luc Romano
-e-mail:  {username}@{domain}.{suffix}
+// Returns the name of the file (in cache_dir) that holds the escape
+// details for the given viewport and settings.  The name is a hash of
+// everything that affects the calculation (the window size, center,
+// distance, threshold, c, bailout, whether periods are measured,
+// whether cycles are detected at all, and the --float type),
+// so changing any of them gives a different filename.
+//
+// (Rust's own DefaultHasher isn't used, as it isn't guaranteed to
+// give the same hash in different versions of Rust.  Instead, the
+// simple and stable 64-bit FNV-1a hash is used.)
+fn escape_cache_filename(cache_dir: &str,
+                         info: &WindowAndViewportInfo,
+                         config: &Config,
+                         threshold: Float) -> std::path::PathBuf {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut add_to_hash = |bytes: &[u8]| {
+        for &byte in bytes {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    };
 
-", default_size = DEFAULT_WINDOW_SIZE,
-   username = "jl_post", domain = "hotmail", suffix = "com")
+    add_to_hash(ESCAPE_CACHE_MAGIC);
+    add_to_hash(&(info.width as u64).to_le_bytes());
+    add_to_hash(&(info.height as u64).to_le_bytes());
+    add_to_hash(&info.center_x.to_le_bytes());
+    add_to_hash(&info.center_y.to_le_bytes());
+    add_to_hash(&info.distance_from_center_to_edge.to_le_bytes());
+    add_to_hash(&threshold.to_le_bytes());
+    match config.c {
+        None => add_to_hash(&[0]),
+        Some((c_x, c_y)) => {
+            add_to_hash(&[1]);
+            add_to_hash(&c_x.to_le_bytes());
+            add_to_hash(&c_y.to_le_bytes());
+        }
+    }
+    match config.bailout {
+        None => add_to_hash(&[0]),
+        Some(bailout) => {
+            add_to_hash(&[1]);
+            add_to_hash(&(bailout as u64).to_le_bytes());
+        }
+    }
+    add_to_hash(&[(config.interior_coloring == InteriorColoring::Period) as u8]);
+    add_to_hash(&[config.cycle_detection as u8]);
+    add_to_hash(&(config.max_iterations.unwrap_or(0) as u64).to_le_bytes());
+    // (Filling in rectangles can give different details than calculating
+    // them, and which ones get filled in depends on the coloring.)
+    add_to_hash(&(config.fast_fill_min_size.unwrap_or(0) as u64).to_le_bytes());
+    if config.fast_fill_min_size.is_some() {
+        add_to_hash(&[fast_fill_fills_escaped_pixels(config) as u8]);
+    }
+    add_to_hash(config.float_type.name().as_bytes());
+    add_to_hash(config.projection.name().as_bytes());
+    add_to_hash(config.formula.name().as_bytes());
+    add_to_hash(config.norm.name().as_bytes());
+
+    std::path::Path::new(cache_dir).join(format!("{hash:016x}.escapes"))
 }
 
 
-#[allow(dead_code)]
-fn test_calculate_escape_value_function() {
-    println!();
-    println!("Testing the calculate_escape_value() function:");
-    let (x, y) = (0.0, 0.0);  println!("{:?}: {:?}", (x, y), calculate_escape_value(x, y, None, None, None));
-    let (x, y) = (-1., 0.4);  println!("{:?}: {:?}", (x, y), calculate_escape_value(x, y, None, None, None));
-    let (x, y) = (0.25, 0.5);  println!("{:?}: {:?}", (x, y), calculate_escape_value(x, y, None, Some(0.001), None));
-    let (x, y) = (-1., 0.25);  println!("{:?}: {:?}", (x, y), calculate_escape_value(x, y, None, Some(0.001), None));
-    let (x, y) = (-1., -0.25);  println!("{:?}: {:?}", (x, y), calculate_escape_value(x, y, None, Some(0.001), None));
-    let (x, y) = (0.25, -0.5);  println!("{:?}: {:?}", (x, y), calculate_escape_value(x, y, None, Some(0.001), None));
-    println!();
+// Saves the escape details to a cache file.  The file is
+// ESCAPE_CACHE_MAGIC followed by five little-endian u64 values
+// for each pixel:  its escape value, its period (with u64::MAX
+// standing for None), its number of iterations, whether it
+// hit the bailout (1 if so, 0 if not), and its escape magnitude
+// (as a Float).
+fn save_escape_details(filename: &std::path::Path,
+                       escape_details: &[EscapeDetails]) -> std::io::Result<()> {
+    let to_u64 = |value: Option<usize>| value.map_or(u64::MAX, |value| value as u64);
+
+    let mut bytes = Vec::with_capacity(ESCAPE_CACHE_MAGIC.len() + escape_details.len() * 40);
+    bytes.extend_from_slice(ESCAPE_CACHE_MAGIC);
+    for details in escape_details {
+        bytes.extend_from_slice(&to_u64(details.escape_value).to_le_bytes());
+        bytes.extend_from_slice(&to_u64(details.period).to_le_bytes());
+        bytes.extend_from_slice(&(details.iterations as u64).to_le_bytes());
+        bytes.extend_from_slice(&(details.hit_bailout as u64).to_le_bytes());
+        bytes.extend_from_slice(&details.escape_magnitude.to_le_bytes());
+    }
+
+    if let Some(cache_dir) = filename.parent() {
+        std::fs::create_dir_all(cache_dir)?;
+    }
+    // Write to a temporary file first, and then rename it, so that
+    // an interrupted write never leaves a broken cache file behind:
+    let temporary_filename = filename.with_extension("tmp");
+    std::fs::write(&temporary_filename, &bytes)?;
+    std::fs::rename(&temporary_filename, filename)
 }
 
 
-#[allow(dead_code)]
-fn test_row_and_column_iterator() {
-    println!();
-    println!("Testing the RowAndColumnIterator:");
-    let mut row_and_column_iterator = RowAndColumnIterator::new(0, 0);
-    for _ in 0..50 {
-        println!("{:?}", row_and_column_iterator.next().unwrap());
+// The value that --export-npy gives the points in the set (which
+// have no escape value).
+const NPY_IN_SET: i32 = -1;
+
+
+// Returns the escape values of a width x height image as a NumPy .npy
+// file (for --export-npy), which numpy.load() reads as a 2-D array of
+// little-endian 32-bit ints ('<i4') with the shape (height, width),
+// one row per row of pixels, from the top.  Points in the set get
+// NPY_IN_SET, and escape values too big for an i32 are capped at
+// i32::MAX.
+//
+// The file is the "\x93NUMPY" magic string, the format version (1.0),
+// the length of the header (as a little-endian u16), and the header
+// itself:  a Python dict describing the array, padded with spaces
+// (and ended with a newline) so that the array data starts on a
+// multiple of 64 bytes, as the .npy format asks.
+fn npy_bytes(escape_details: &[EscapeDetails], width: usize, height: usize) -> Vec<u8> {
+    assert_eq!(escape_details.len(), width * height);
+    const MAGIC: &[u8] = b"\x93NUMPY\x01\x00";
+    let mut header = format!("{{'descr': '<i4', 'fortran_order': False, 'shape': ({height}, {width}), }}");
+    let unpadded_length = MAGIC.len() + 2 + header.len() + 1;  // (The 2 is for the header's length, and the 1 is for its newline.)
+    header += &" ".repeat((64 - unpadded_length % 64) % 64);
+    header += "\n";
+
+    let mut bytes = Vec::with_capacity(MAGIC.len() + 2 + header.len() + escape_details.len() * 4);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    for details in escape_details {
+        let value = details.escape_value.map_or(NPY_IN_SET, |escape_value| escape_value.min(i32::MAX as usize) as i32);
+        bytes.extend_from_slice(&value.to_le_bytes());
     }
-    println!();
+    bytes
 }
 
 
-#[allow(dead_code)]
-fn test_all() {
-    println!();
-    test_color_function();
-    println!();
-    test_calculate_escape_value_function();
-    println!();
-    test_row_and_column_iterator();
-    println!();
+// How high (as a fraction of the width of the grid) the highest
+// vertices of an --export-heightmap OBJ file are.
+const HEIGHTMAP_OBJ_HEIGHT: Float = 0.25;
+
+
+// Returns the height (from 0.0 to 1.0) of every pixel (row by row)
+// for --export-heightmap:  each escape value divided by the highest
+// one in the image, so the slowest-escaping points are the highest,
+// with the points in the set (which never escape) as a plateau at the
+// top, 1.0.
+fn heightmap_heights(escape_details: &[EscapeDetails]) -> Vec<Float> {
+    let highest_escape_value = escape_details.iter()
+                                             .filter_map(|details| details.escape_value)
+                                             .max()
+                                             .unwrap_or(0)
+                                             .max(1);
+    escape_details.iter()
+                  .map(|details| details.escape_value
+                                        .map_or(1.0, |escape_value| escape_value as Float / highest_escape_value as Float))
+                  .collect()
 }
 
 
-fn main() {
-    // These are "practically global" variables, in that
-    // they're used (and sometimes changed) all throughout
-    // the main() function:
-    let mut window_size_to_use: usize = DEFAULT_WINDOW_SIZE;
-    let mut bailout_value_to_use: Option<usize> = None;
-    let mut c: Option<(Float, Float)> = None;  // Sometimes known as (x0, y0).
-    let mut original_center_to_use: (Float, Float) = (-0.5, 0.0);
-    let original_distance_from_center_to_edge: Float = 1.725;
+// Returns the heights (see heightmap_heights()) of a width x height
+// image as a Wavefront OBJ file (for --export-heightmap), for Blender
+// and other 3-D programs:  a grid of vertices, one for every step-th
+// pixel across and down (and the last ones, so the whole image is
+// covered), each made into two triangles with its neighbors.  The y
+// axis is up, as usual for OBJ files, and the grid's width (along x)
+// is 1.0, with the top row of the image at z = 0.0, so the image
+// reads the right way around from above.  Every triangle is wound
+// counterclockwise from above, so they all face up.
+fn heightmap_obj(heights: &[Float], width: usize, height: usize, step: usize) -> String {
+    assert_eq!(heights.len(), width * height);
+    let samples = |length: usize| -> Vec<usize> {
+        let mut samples: Vec<usize> = (0..length).step_by(step).collect();
+        if samples.last() != Some(&(length - 1)) {
+            samples.push(length - 1);
+        }
+        samples
+    };
+    let (rows, columns) = (samples(height), samples(width));
+    let size = (width.max(height) - 1).max(1) as Float;
+
+    let mut obj = format!("# A heightmap of escape values, saved by JLR-Mandelbrot.\n\
+                           # ({} x {} vertices, from a {width} x {height} image.)\n",
+                          columns.len(), rows.len());
+    for &row in &rows {
+        for &column in &columns {
+            obj += &format!("v {} {} {}\n", column as Float / size,
+                                            heights[row * width + column] * HEIGHTMAP_OBJ_HEIGHT,
+                                            row as Float / size);
+        }
+    }
+    // (Vertices are numbered from 1, in the order they were written.)
+    let vertex = |row_index: usize, column_index: usize| row_index * columns.len() + column_index + 1;
+    for row_index in 0..rows.len() - 1 {
+        for column_index in 0..columns.len() - 1 {
+            let (upper_left, upper_right) = (vertex(row_index, column_index), vertex(row_index, column_index + 1));
+            let (lower_left, lower_right) = (vertex(row_index + 1, column_index), vertex(row_index + 1, column_index + 1));
+            obj += &format!("f {upper_left} {lower_left} {upper_right}\n");
+            obj += &format!("f {upper_right} {lower_left} {lower_right}\n");
+        }
+    }
+    obj
+}
+
+
+// Saves the heights (see heightmap_heights()) of a width x height
+// image as a 16-bit grayscale PNG file (for --export-heightmap), with
+// the highest points white, for displacement mapping.
+fn save_heightmap_png(heights: &[Float], width: usize, height: usize, filename: &str) -> Result<(), String> {
+    assert_eq!(heights.len(), width * height);
+    // (PNG files store 16-bit values with their most significant byte first.)
+    let bytes: Vec<u8> = heights.iter()
+                                .flat_map(|height| ((height * u16::MAX as Float).round() as u16).to_be_bytes())
+                                .collect();
+    image::save_buffer(filename, &bytes, width as u32, height as u32, image::ColorType::L16)
+        .map_err(|error| format!("Unable to save the heightmap to a file named \"{filename}\":  {error}"))
+}
+
+
+// Returns what --stdin-coords prints for a line of its input:  the
+// line's X and Y (just as they were given), followed by the escape
+// value of X+Yi (calculated with the config, just like a pixel there
+// would be), or "in-set" if it's in the set.  The X and Y can be
+// separated by spaces (or tabs), or by a comma.  Returns an error
+// message if the line doesn't hold a valid pair of numbers.
+fn stdin_coords_line(line: &str, config: &Config, threshold: Float) -> Result<String, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let (x_text, y_text) = match words[..] {
+        [x_text, y_text] => (x_text, y_text),
+        [pair] if pair.contains(',') => pair.split_once(',').unwrap(),
+        _ => return Err(format!("\"{}\" isn't an \"X Y\" pair of numbers.", line.trim())),
+    };
+    let (x, y) = (parse_number("X", x_text)?, parse_number("Y", y_text)?);
+    let details = calculate_point_details(x, y, config, threshold);
+    match details.escape_value {
+        Some(escape_value) => Ok(format!("{x_text} {y_text} {escape_value}")),
+        None => Ok(format!("{x_text} {y_text} in-set")),
+    }
+}
+
+
+// Loads the escape details of number_of_pixels pixels from a cache file
+// (as written by save_escape_details()).  Returns None if the file
+// doesn't exist, or if it isn't a valid cache file of the right size.
+fn load_escape_details(filename: &std::path::Path,
+                       number_of_pixels: usize) -> Option<Vec<EscapeDetails>> {
+    let bytes = std::fs::read(filename).ok()?;
+    if !bytes.starts_with(ESCAPE_CACHE_MAGIC)
+           || bytes.len() != ESCAPE_CACHE_MAGIC.len() + number_of_pixels * 40 {
+        return None
+    }
+
+    let from_bytes = |bytes: &[u8]| -> Option<usize> {
+        match u64::from_le_bytes(bytes.try_into().unwrap()) {
+            u64::MAX => None,
+            value => Some(value as usize),
+        }
+    };
+    Some(bytes[ESCAPE_CACHE_MAGIC.len()..]
+             .chunks_exact(40)
+             .map(|chunk| EscapeDetails {
+                 escape_value: from_bytes(&chunk[..8]),
+                 period: from_bytes(&chunk[8..16]),
+                 iterations: u64::from_le_bytes(chunk[16..24].try_into().unwrap()) as usize,
+                 hit_bailout: u64::from_le_bytes(chunk[24..32].try_into().unwrap()) != 0,
+                 escape_magnitude: Float::from_le_bytes(chunk[32..].try_into().unwrap()),
+             })
+             .collect())
+}
+
+
+// Returns the escape details of every pixel in the view, loaded from
+// the cache_dir if they're there, or else calculated (and saved to the
+// cache_dir, if there is one).  Also returns how long the calculating
+// took, or None if they were loaded from the cache.
+fn cached_escape_details(info: &WindowAndViewportInfo, config: &Config,
+                         cache_dir: Option<&str>) -> (Vec<EscapeDetails>, Option<std::time::Duration>) {
+    let threshold = cycle_threshold(info, config);
+    let cache_filename = cache_dir.map(|cache_dir| escape_cache_filename(cache_dir, info, config, threshold));
+    if let Some(escape_details) = cache_filename.as_ref()
+                                      .and_then(|filename| load_escape_details(filename, info.width * info.height)) {
+        return (escape_details, None)
+    }
+
+    let calculation_start_time = std::time::Instant::now();
+    let escape_details = render_escape_details(info, config);
+    let calculation_time = calculation_start_time.elapsed();
+    if let Some(filename) = &cache_filename {
+        if let Err(error) = save_escape_details(filename, &escape_details) {
+            eprintln!("Warning:  Unable to save to the cache file {}:  {error}", filename.display());
+        }
+    }
+    (escape_details, Some(calculation_time))
+}
+
+
+// Returns the image_buffer (of width x height pixels, each a 0RGB
+// u32) as an image the image crate can save.
+fn rgb_image(image_buffer: &[u32], width: usize, height: usize) -> image::RgbImage {
+    // (As the image_buffer holds exactly width x height pixels, no
+    // y * width + x below can overflow, or go past its end.)
+    assert_eq!(Some(image_buffer.len()), width.checked_mul(height),
+               "FATAL ERROR:  width x height does not equal the length of the image buffer.");
+    let mut rgb_image = image::ImageBuffer::new(width as u32, height as u32);
+
+    for (x, y, pixel) in rgb_image.enumerate_pixels_mut() {
+        let (x, y): (usize, usize) = (x as usize, y as usize);  // (Convert from u32 to usize.)
+        let i: usize = y * width + x;
+        let image_buffer_pixel = image_buffer[i] & 0xff_ff_ff;
+
+        let r = image_buffer_pixel >> 16 & 0xff;
+        let g = image_buffer_pixel >>  8 & 0xff;
+        let b = image_buffer_pixel >>  0 & 0xff;
+
+        *pixel = image::Rgb([r as u8, g as u8, b as u8]);
+    }
+
+    rgb_image
+}
+
+
+// Saves an image to disk with the given filename, returning
+// an error message if it couldn't be saved.  If settings (the text
+// of a config file) are given, and the image is saved as a PNG,
+// they're stored inside it (see embed_png_text()).
+// (The image_buffer must have a length of width x height.)
+fn save_image_to_filename(image_buffer: &Vec<u32>, width: usize, height: usize, filename: &str,
+                          settings: Option<&str>) -> Result<(), String> {
+    // Verify that the length of the image_buffer
+    // equals the width x height.  Otherwise, things
+    // will break spectacularly:  (And the width x height
+    // is checked for overflow, as on a 32-bit machine a
+    // wrapped-around product could happen to match.)
+    assert_eq!(Some(image_buffer.len()), width.checked_mul(height),
+               "FATAL ERROR:  width x height does not equal the length of the image buffer.");
+
+    let screenshot_buffer = rgb_image(image_buffer, width, height);
+
+    let result = match settings {
+        Some(settings) if is_png_filename(filename) => {
+            let mut png = Vec::new();
+            image::png::PngEncoder::new(&mut png)
+                .encode(&screenshot_buffer, width as u32, height as u32, image::ColorType::Rgb8)
+                .map_err(|error| error.to_string())
+                .and_then(|()| std::fs::write(filename, embed_png_text(&png, PNG_SETTINGS_KEYWORD, settings))
+                                   .map_err(|error| error.to_string()))
+        }
+        _ => screenshot_buffer.save(filename).map_err(|error| error.to_string()),
+    };
+    result.map_err(|error| format!("Unable to save a screenshot to a file named \"{filename}\":  {error}"))?;
+    println!("Saved screenshot to a file named:  {filename}");
+    Ok(())
+}
+
+
+// Saves a screenshot (of the viewport described by info) to disk
+// with the given filename, returning an error message if it couldn't
+// be saved.  The settings it was drawn with (see
+// screenshot_settings_text()) are stored inside it (if it's a PNG),
+// and, with --sidecar, saved next to it, too.
+// (The image_buffer must have a length of info.width x info.height.)
+fn save_screenshot_to_filename(image_buffer: &Vec<u32>, escape_details: &[EscapeDetails],
+                               info: &WindowAndViewportInfo, config: &Config, program_config: &ProgramConfig,
+                               filename: &str) -> Result<(), String> {
+    let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
+    let settings = screenshot_settings_text(filename, info, config, &timestamp);
+    if program_config.screenshot_bit_depth == 16 {
+        let pixels = deep_color_pixels(image_buffer, escape_details, config);
+        save_deep_image_to_filename(&pixels, info.width, info.height, filename, &settings)?;
+    } else {
+        save_image_to_filename(image_buffer, info.width, info.height, filename, Some(&settings))?;
+    }
+    if program_config.save_sidecar {
+        let sidecar_filename = std::path::Path::new(filename).with_extension("toml");
+        std::fs::write(&sidecar_filename, settings)
+            .map_err(|error| format!("Unable to save the settings to a file named \"{}\":  {error}",
+                                     sidecar_filename.display()))?;
+        println!("Saved its settings to a file named:  {}", sidecar_filename.display());
+    }
+    Ok(())
+}
+
+
+// Returns true if the filename ends in .png (in any case).
+fn is_png_filename(filename: &str) -> bool {
+    std::path::Path::new(filename).extension()
+                                  .is_some_and(|extension| extension.eq_ignore_ascii_case("png"))
+}
+
+
+// Returns true if the filename ends in .obj (in any case).
+fn is_obj_filename(filename: &str) -> bool {
+    std::path::Path::new(filename).extension()
+                                  .is_some_and(|extension| extension.eq_ignore_ascii_case("obj"))
+}
+
+
+// Saves a screenshot to disk with a calculated filename.
+// (The image_buffer must have a length of info.width x info.height.)
+fn save_screenshot(image_buffer: &Vec<u32>, escape_details: &[EscapeDetails],
+                   info: &WindowAndViewportInfo, config: &Config, program_config: &ProgramConfig) -> Result<(), String> {
+    let now = chrono::Utc::now();
+    let filename = now.format("jlr-mandelbrot.screenshot.%Y%m%d.%H%M%S.%3f.png").to_string();
+    save_screenshot_to_filename(&image_buffer, escape_details, info, config, program_config, &filename)
+}
+
+
+// Calculates the viewport described by info again, anti-aliased with
+// the given number of samples per pixel (see --aa-samples), for a
+// screenshot saved with --screenshot-ssaa.  Returns the new image, its
+// escape details, and the config it was drawn with (which is the one
+// to save with it, so that it can be drawn again the same way).
+fn supersampled_screenshot(info: &WindowAndViewportInfo,
+                           config: &Config,
+                           number_of_samples: usize) -> (Vec<u32>, Vec<EscapeDetails>, Config) {
+    let config = Config { aa_samples: Some(number_of_samples), ..config.clone() };
+    let escape_details = render_escape_details(info, &config);
+    let mut image_buffer = vec![0; info.width * info.height];
+    color_escape_details(&escape_details, &mut image_buffer, info, &config);
+    (image_buffer, escape_details, config)
+}
+
+
+// Returns the colors of a screenshot with 16 bits per color channel
+// (three u16 values per pixel, for --depth=16), so that smooth colors
+// (like --coloring=potential's) don't show bands.  Each pixel gets its
+// unrounded color components (see pixel_color_components()), scaled up
+// to 16 bits, wherever they agree with the screenshot's 8-bit pixel.
+// Everywhere else (on overlays, on pixels changed afterwards, like by
+// --coloring=boundary or --antialias, on pixels averaged from
+// --aa-samples, and on pixels that aren't calculated yet), the 8-bit
+// pixel is just scaled up, so the image looks the same either way.
+fn deep_color_pixels(screenshot_buffer: &[u32], escape_details: &[EscapeDetails], config: &Config) -> Vec<u16> {
+    assert_eq!(screenshot_buffer.len(), escape_details.len(),
+               "FATAL ERROR:  The screenshot and its escape details are different sizes.");
+    let mut pixels = Vec::with_capacity(screenshot_buffer.len() * 3);
+    for (&pixel, details) in screenshot_buffer.iter().zip(escape_details) {
+        let channels = [pixel >> 16 & 0xff, pixel >> 8 & 0xff, pixel & 0xff];
+        let (r, g, b) = pixel_color_components(details, config);
+        // (The 8-bit pixel may have been rounded either way, like
+        // with --dither, so anything within 1 of it agrees.)
+        let agrees = [r, g, b].iter().zip(channels).all(|(&component, channel)| (component - channel as Float).abs() < 1.0);
+        if agrees {
+            pixels.extend([r, g, b].map(|component| (component.clamp(0.0, 255.0) * 257.0).round() as u16));
+        } else {
+            pixels.extend(channels.map(|channel| channel as u16 * 257));
+        }
+    }
+    pixels
+}
+
+
+// Saves the 16-bit colors (as returned by deep_color_pixels()) to a PNG
+// file, with the settings stored inside it, just like
+// save_image_to_filename() does with 8-bit colors.  (Only PNG files can
+// be saved this way, as most other formats, like JPEG, can't have 16
+// bits per color channel.)
+fn save_deep_image_to_filename(pixels: &[u16], width: usize, height: usize, filename: &str,
+                               settings: &str) -> Result<(), String> {
+    assert_eq!(Some(pixels.len()), width.checked_mul(height).and_then(|number_of_pixels| number_of_pixels.checked_mul(3)),
+               "FATAL ERROR:  width x height x 3 does not equal the number of 16-bit color values.");
+    if !is_png_filename(filename) {
+        return Err(format!("Unable to save a screenshot to a file named \"{filename}\":  \
+                            Only PNG files can be saved with --depth=16."))
+    }
+
+    // (PNG files store 16-bit values with their most significant byte first.)
+    let bytes: Vec<u8> = pixels.iter().flat_map(|value| value.to_be_bytes()).collect();
+    let mut png = Vec::new();
+    image::png::PngEncoder::new(&mut png)
+        .encode(&bytes, width as u32, height as u32, image::ColorType::Rgb16)
+        .map_err(|error| error.to_string())
+        .and_then(|()| std::fs::write(filename, embed_png_text(&png, PNG_SETTINGS_KEYWORD, settings))
+                           .map_err(|error| error.to_string()))
+        .map_err(|error| format!("Unable to save a screenshot to a file named \"{filename}\":  {error}"))?;
+    println!("Saved screenshot (with 16 bits per color channel) to a file named:  {filename}");
+    Ok(())
+}
+
+
+// The keyword of the PNG text chunk that a screenshot's settings
+// are stored in.
+const PNG_SETTINGS_KEYWORD: &str = "jlr-mandelbrot-params";
+
+// The 8 bytes every PNG file starts with.
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+
+// Returns the CRC-32 (the kind used by PNG files, and zip files) of
+// the bytes.  (It's calculated a bit at a time, which is slow, but
+// it's only used on the few bytes of a text chunk.)
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+
+// Returns a copy of the png (the bytes of a whole PNG file) with the
+// text stored in it under the given keyword, as an (uncompressed)
+// iTXt chunk right after the IHDR chunk.  Programs that show PNG
+// files ignore such chunks, but they can be read back with
+// read_png_text().
+fn embed_png_text(png: &[u8], keyword: &str, text: &str) -> Vec<u8> {
+    // (The iTXt chunk's data is:  the keyword, a 0 byte, 0 and 0 for
+    // "not compressed," an empty language tag and an empty translated
+    // keyword (each followed by a 0 byte), and then the UTF-8 text.)
+    let mut data = keyword.as_bytes().to_vec();
+    data.extend_from_slice(&[0, 0, 0, 0, 0]);
+    data.extend_from_slice(text.as_bytes());
+
+    // (Each chunk is its length, its type, its data, and the CRC-32
+    // of its type and data.  The IHDR chunk always comes first, and
+    // is always 13 bytes long, so it ends 8 + 4 + 4 + 13 + 4 bytes in.)
+    let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+    chunk.extend_from_slice(b"iTXt");
+    chunk.extend_from_slice(&data);
+    chunk.extend_from_slice(&crc32(&chunk[4..]).to_be_bytes());
+
+    let end_of_ihdr = PNG_SIGNATURE.len() + 4 + 4 + 13 + 4;
+    [&png[..end_of_ihdr], &chunk, &png[end_of_ihdr..]].concat()
+}
+
+
+// Returns the text stored under the given keyword (by
+// embed_png_text(), or any other uncompressed iTXt or tEXt chunk) in
+// the png (the bytes of a whole PNG file), or None if there isn't any.
+fn read_png_text(png: &[u8], keyword: &str) -> Option<String> {
+    if !png.starts_with(&PNG_SIGNATURE) {
+        return None
+    }
+    let mut rest = &png[PNG_SIGNATURE.len()..];
+    while rest.len() >= 12 {
+        let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+        let (chunk_type, data) = (&rest[4..8], rest.get(8..8 + length)?);
+        let text = match chunk_type {
+            b"iTXt" => data.strip_prefix(keyword.as_bytes())
+                           .and_then(|data| data.strip_prefix(&[0, 0]))  // (Not compressed.)
+                           .and_then(|data| data.get(1..))  // (The compression method.)
+                           .and_then(|data| data.splitn(3, |&byte| byte == 0).nth(2))  // (After the language & translation.)
+                           .and_then(|text| String::from_utf8(text.to_vec()).ok()),
+            b"tEXt" => data.strip_prefix(keyword.as_bytes())
+                           .and_then(|data| data.strip_prefix(&[0]))
+                           .map(|text| text.iter().map(|&byte| byte as char).collect()),  // (Latin-1.)
+            _ => None,
+        };
+        if text.is_some() {
+            return text
+        }
+        rest = rest.get(12 + length..)?;
+    }
+    None
+}
+
+
+// Returns the settings that the screenshot with the given filename
+// was drawn with, in the same format as a --config file, so that it
+// can be drawn again with --config=SIDECAR_FILE (or with the PNG
+// itself, which has them stored inside it).  (The zoom level and the
+// time it was saved can't be set in a config file, so they're only
+// mentioned in its comments.)
+fn screenshot_settings_text(image_filename: &str, info: &WindowAndViewportInfo, config: &Config,
+                           timestamp: &str) -> String {
+    let fractal = match (config.formula, config.c) {
+        (Formula::Mandelbrot, Some(_)) => "a Julia set",
+        (Formula::Mandelbrot, None) => "the Mandelbrot set",
+        (Formula::Tricorn, Some(_)) => "a Julia set of the Tricorn",
+        (Formula::Tricorn, None) => "the Tricorn",
+    };
+    let mut text = format!("# The settings of the screenshot \"{image_filename}\"\n\
+                            # (of {fractal}, at zoom level {}), saved {timestamp}.\n\
+                            # Draw it again with:  jlr-mandelbrot --config=THIS_FILE\n",
+                           info.zoom_level);
+    text += &format!("size = {}\n", info.width);
+    text += &format!("center = [{:?}, {:?}]\n", info.center_x, info.center_y);
+    text += &format!("distance = {:?}\n", info.distance_from_center_to_edge);
+    if let Some(bailout) = config.bailout {
+        text += &format!("bailout = {bailout}\n");
+    }
+    if let Some((c_x, c_y)) = config.c {
+        text += &format!("julia = [{c_x:?}, {c_y:?}]\n");
+    }
+    if config.formula != Formula::Mandelbrot {
+        text += &format!("fractal = \"{}\"\n", config.formula.name());
+    }
+    if config.norm != Norm::L2 {
+        text += &format!("norm = \"{}\"\n", config.norm.name());
+    }
+    text += &format!("coloring = \"{}\"\n", config.coloring.name());
+    text += &format!("interior_coloring = \"{}\"\n", config.interior_coloring.name());
+    text += &format!("invert = {}\n", config.invert);
+    text += &format!("color_period = {}\n", config.color_period);
+    if let Some(palette) = &config.palette {
+        text += &format!("palette_seed = {}\n", palette.seed);
+    }
+    text += &format!("float = \"{}\"\n", config.float_type.name());
+    text += &format!("projection = \"{}\"\n", info.projection.name());
+    if config.coloring == Coloring::Boundary {
+        let (r, g, b) = config.boundary_color;
+        text += &format!("boundary_color = [{r}, {g}, {b}]\n");
+        let (r, g, b) = config.boundary_background_color;
+        text += &format!("boundary_background = [{r}, {g}, {b}]\n");
+    }
+    text
+}
+
+
+// Returns the command-line switches that draw the current view again:
+// the same window size, center, distance, bailout, fractal, and
+// coloring (the same settings that screenshot_settings_text() saves).
+// Settings that are at their defaults are left out.
+fn command_line_switches(info: &WindowAndViewportInfo, config: &Config) -> Vec<String> {
+    let mut switches = vec![format!("--size={}", info.width),
+                            format!("--center={:?},{:?}", info.center_x, info.center_y),
+                            format!("--distance={:?}", info.distance_from_center_to_edge)];
+    if let Some(bailout) = config.bailout {
+        switches.push(format!("--bailout={bailout}"));
+    }
+    if let Some((c_x, c_y)) = config.c {
+        switches.push(format!("--julia={c_x:?},{c_y:?}"));
+    }
+    if config.formula != Formula::Mandelbrot {
+        switches.push(format!("--fractal={}", config.formula.name()));
+    }
+    if config.norm != Norm::L2 {
+        switches.push(format!("--norm={}", config.norm.name()));
+    }
+    switches.push(format!("--coloring={}", config.coloring.name()));
+    switches.push(format!("--interior-coloring={}", config.interior_coloring.name()));
+    if config.invert {
+        switches.push("--invert".to_string());
+    }
+    if config.color_period != NUM_COLORS {
+        switches.push(format!("--color-period={}", config.color_period));
+    }
+    if let Some(palette) = &config.palette {
+        switches.push(format!("--palette-seed={}", palette.seed));
+    }
+    if config.float_type != FloatType::F64 {
+        switches.push(format!("--float={}", config.float_type.name()));
+    }
+    if info.projection != Projection::Rectangular {
+        switches.push(format!("--projection={}", info.projection.name()));
+    }
+    if config.coloring == Coloring::Boundary {
+        let (r, g, b) = config.boundary_color;
+        switches.push(format!("--boundary-color={r},{g},{b}"));
+        let (r, g, b) = config.boundary_background_color;
+        switches.push(format!("--boundary-background={r},{g},{b}"));
+    }
+    switches
+}
+
+
+// Returns the text of a script (a shell script, or a Windows .cmd file
+// if for_windows is true) that runs the program with the given switches
+// (plus any given to the script itself).  Anything that the shell would
+// treat specially (like spaces in the program's path) is quoted.
+fn command_line_script_text(program: &str, switches: &[String], timestamp: &str, for_windows: bool) -> String {
+    let is_plain = |text: &str| !text.is_empty()
+                                && text.chars().all(|c| c.is_ascii_alphanumeric() || "_-+=.,:/\\".contains(c));
+    let quote = |text: &str| match (is_plain(text), for_windows) {
+        (true, _) => text.to_string(),
+        (false, true) => format!("\"{text}\""),
+        (false, false) => format!("'{}'", text.replace('\'', "'\\''")),
+    };
+    let command = std::iter::once(program).chain(switches.iter().map(String::as_str))
+                                          .map(quote)
+                                          .collect::<Vec<_>>()
+                                          .join(" ");
+    if for_windows {
+        format!("@echo off\r\nrem The JLR-Mandelbrot view saved {timestamp}.\r\n{command} %*\r\n")
+    } else {
+        format!("#!/bin/sh\n# The JLR-Mandelbrot view saved {timestamp}.\n{command} \"$@\"\n")
+    }
+}
+
+
+// Saves a script (see command_line_script_text()) that draws the current
+// view again into the current directory, with the time in its name
+// (like a screenshot), and returns its filename.  On Windows, it's a
+// .cmd file; everywhere else, it's an (executable) shell script.
+fn save_command_line(info: &WindowAndViewportInfo, config: &Config) -> Result<String, String> {
+    let for_windows = cfg!(windows);
+    let now = chrono::Utc::now();
+    let extension = if for_windows { "cmd" } else { "sh" };
+    let filename = now.format(&format!("jlr-mandelbrot.view.%Y%m%d.%H%M%S.%3f.{extension}")).to_string();
+    // (The program's full path is used if it can be found, so the
+    // script works even if the program isn't on the PATH.)
+    let program = std::env::current_exe().ok()
+                      .and_then(|path| path.to_str().map(str::to_string))
+                      .unwrap_or_else(|| "jlr-mandelbrot".to_string());
+    let text = command_line_script_text(&program, &command_line_switches(info, config),
+                                        &now.format("%Y-%m-%d %H:%M:%S UTC").to_string(), for_windows);
+    std::fs::write(&filename, text)
+        .map_err(|error| format!("Unable to save the command line to \"{filename}\":  {error}"))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&filename, std::fs::Permissions::from_mode(0o755))
+            .map_err(|error| format!("Unable to make \"{filename}\" executable:  {error}"))?;
+    }
+    Ok(filename)
+}
+
+
+// This structure holds which overlays (things drawn on top
+// of the fractal, like the crosshair) are currently shown.
+//
+// Overlays are never drawn into the image_buffer itself.  Instead,
+// they're drawn onto a copy of it right before it's shown in the
+// window (see update_window()), so they can be turned on and off
+// without recalculating (or damaging) the fractal image.
+#[derive(Debug)]
+struct Overlays {
+    show_crosshair: bool,
+    bake_crosshair_into_screenshots: bool,
+    show_minimap: bool,
+    minimap: Option<Minimap>,  // (Only calculated once the minimap is first shown.)
+    show_bailout_heatmap: bool,
+    bailout_tint_color: (u8, u8, u8),
+    bailout_tint_opacity: f32,  // (From 0.0 (invisible) to 1.0 (solid).)
+    show_orbit: bool,
+    orbit: Option<Vec<(Float, Float)>>,  // (The orbit of the last Ctrl+clicked point.)
+    show_pixel_info: bool,
+    hovered_pixel: Option<(usize, usize)>,  // (The row & column under the mouse, once the image is done.)
+    ray_angles: Vec<(u64, u64)>,  // (The angles given with --rays, as fractions of a turn.)
+    rays: Vec<Vec<(Float, Float)>>,  // (The traced rays of those angles, for the current viewport.)
+    smooth_labels: bool,  // (Whether labels are drawn with anti-aliased letters; see --smooth-labels.)
+    show_render_stats: bool,
+    render_stats: Option<String>,  // (The render_stats_text() of the last finished image.)
+    show_diagnostics: bool,
+    diagnostics: Vec<String>,  // (The diagnostics_text() of the image being drawn, a line at a time.)
+}
+impl Overlays {
+    fn new() -> Self {
+        Self {
+            show_crosshair: false,
+            bake_crosshair_into_screenshots: false,
+            show_minimap: false,
+            minimap: None,
+            show_bailout_heatmap: false,
+            bailout_tint_color: (255, 0, 255),  // (magenta)
+            bailout_tint_opacity: 0.5,
+            show_orbit: false,
+            orbit: None,
+            show_pixel_info: false,
+            hovered_pixel: None,
+            ray_angles: Vec::new(),
+            rays: Vec::new(),
+            smooth_labels: false,
+            show_render_stats: false,
+            render_stats: None,
+            show_diagnostics: false,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    // Returns true if any overlay needs to be drawn.
+    fn any_shown(&self) -> bool {
+        self.show_crosshair || self.show_minimap || self.show_bailout_heatmap || self.show_orbit
+            || (self.show_pixel_info && self.hovered_pixel.is_some()) || !self.rays.is_empty()
+            || (self.show_render_stats && self.render_stats.is_some())
+            || (self.show_diagnostics && !self.diagnostics.is_empty())
+    }
+
+    // Traces the external rays (of the angles given with --rays) for
+    // the viewport described by info, following each one until its
+    // points are less than a pixel apart.  (This is done once for each
+    // viewport, as it's much too slow to do every time they're drawn.)
+    fn trace_rays(&mut self, info: &WindowAndViewportInfo) {
+        self.rays = self.ray_angles.iter()
+                                   .map(|&angle| external_ray(angle, info.delta_x, RAY_MAX_POINTS))
+                                   .collect();
+    }
+
+    // Shows the given orbit (from a Ctrl+click), in place of
+    // any orbit that was shown before.  (Only the first
+    // ORBIT_DRAW_LIMIT values of the orbit are kept.)
+    fn show_orbit(&mut self, mut orbit: Vec<(Float, Float)>) {
+        orbit.truncate(ORBIT_DRAW_LIMIT);
+        self.orbit = Some(orbit);
+        self.show_orbit = true;
+    }
+
+    // Hides the orbit (or shows the last one again).  Returns
+    // false if there's no orbit, as no point was Ctrl+clicked yet.
+    fn toggle_orbit(&mut self) -> bool {
+        if self.orbit.is_none() {
+            return false
+        }
+        self.show_orbit = !self.show_orbit;
+        true
+    }
+
+    // Shows (or hides) the minimap, calculating it
+    // first if it's being shown for the first time.
+    fn toggle_minimap(&mut self, info: &WindowAndViewportInfo, c: Option<(Float, Float)>) {
+        self.show_minimap = !self.show_minimap;
+        if self.show_minimap && self.minimap.is_none() {
+            self.minimap = Some(Minimap::new(info.width, info.height, c));
+        }
+    }
+
+    // Draws every shown overlay onto the buffer.  (The escape_details
+    // are those of the pixels in the buffer.)
+    fn draw(&self, buffer: &mut [u32], info: &WindowAndViewportInfo, escape_details: &[EscapeDetails]) {
+        if self.show_bailout_heatmap {
+            draw_bailout_heatmap(buffer, escape_details, self.bailout_tint_color, self.bailout_tint_opacity);
+        }
+        if self.show_crosshair {
+            draw_crosshair(buffer, info.width, info.height);
+        }
+        for ray in &self.rays {
+            draw_ray(buffer, info, ray);
+        }
+        if let (true, Some(orbit)) = (self.show_orbit, &self.orbit) {
+            draw_orbit(buffer, info, orbit);
+        }
+        if let (true, Some(minimap)) = (self.show_minimap, &self.minimap) {
+            minimap.draw(buffer, info);
+        }
+        if let (true, Some(pixel)) = (self.show_pixel_info, self.hovered_pixel) {
+            draw_pixel_info(buffer, info, escape_details, pixel, self.smooth_labels);
+        }
+        if let (true, Some(render_stats)) = (self.show_render_stats, &self.render_stats) {
+            draw_render_stats(buffer, info, render_stats, self.smooth_labels);
+        }
+        if self.show_diagnostics {
+            draw_diagnostics(buffer, info, &self.diagnostics, self.smooth_labels);
+        }
+    }
+
+    // Draws only the shown overlays that the user
+    // wants to appear in saved screenshots.
+    fn draw_for_screenshot(&self, buffer: &mut [u32], width: usize, height: usize) {
+        if self.show_crosshair && self.bake_crosshair_into_screenshots {
+            draw_crosshair(buffer, width, height);
+        }
+    }
+}
+
+
+// Draws a crosshair (a small plus sign) centered on the pixel at
+// (width/2, height/2) of the buffer.  Every pixel of the crosshair
+// is the inverse of the color under it, so the crosshair stands out
+// no matter what colors the fractal has there.
+fn draw_crosshair(buffer: &mut [u32], width: usize, height: usize) {
+    let (center_row, center_column) = (height / 2, width / 2);
+    let arm_length = (width.min(height) / 32).max(4);  // (in pixels)
+
+    let first_column = center_column.saturating_sub(arm_length);
+    let last_column = (center_column + arm_length).min(width - 1);
+    for column in first_column..=last_column {
+        buffer[center_row * width + column] ^= 0xff_ff_ff;
+    }
+
+    let first_row = center_row.saturating_sub(arm_length);
+    let last_row = (center_row + arm_length).min(height - 1);
+    for row in first_row..=last_row {
+        if row != center_row {  // (The center pixel was already inverted above.)
+            buffer[row * width + center_column] ^= 0xff_ff_ff;
+        }
+    }
+}
+
+
+// The most values of an orbit that are drawn by draw_orbit().
+// (Orbits can have millions of values, and since the overlay is
+// drawn every time the window is updated, that would be too slow.)
+const ORBIT_DRAW_LIMIT: usize = 10_000;
+
+
+// Draws an orbit (as returned by calculate_orbit()) onto the buffer,
+// as white lines connecting each z to the next, with the starting
+// point (where the user clicked) marked in green, so the user can see
+// how z bounced around before escaping (or settling down).
+fn draw_orbit(buffer: &mut [u32], info: &WindowAndViewportInfo, orbit: &[(Float, Float)]) {
+    const LINE_COLOR: u32 = 0xff_ff_ff;  // (white)
+    const START_COLOR: u32 = 0x00_ff_00;  // (green)
+    let to_row_and_column = |&(x, y): &(Float, Float)| convert_x_and_y_to_row_and_column(info, x, y);
+
+    for segment in orbit.windows(2) {
+        draw_segment(buffer, info, segment[0], segment[1], LINE_COLOR);
+    }
+    if let Some(start) = orbit.first() {
+        let (row, column) = to_row_and_column(start);
+        for (row_offset, column_offset) in [(0.0, 0.0), (-1.0, 0.0), (1.0, 0.0), (0.0, -1.0), (0.0, 1.0)] {
+            let (row, column) = (row + row_offset, column + column_offset);
+            draw_line(buffer, info.width, info.height, (row, column), (row, column), START_COLOR);
+        }
+    }
+}
+
+
+// The most points traced along each external ray (see --rays).
+// (Rays can take a very long time to get within a pixel of the set
+// when zoomed in, especially the ones landing on the roots of bulbs.)
+const RAY_MAX_POINTS: usize = 20_000;
+
+
+// Draws an external ray (as returned by external_ray()) onto the
+// buffer, as yellow lines connecting each of its points to the next.
+fn draw_ray(buffer: &mut [u32], info: &WindowAndViewportInfo, ray: &[(Float, Float)]) {
+    const RAY_COLOR: u32 = 0xff_ff_00;  // (yellow)
+    for segment in ray.windows(2) {
+        draw_segment(buffer, info, segment[0], segment[1], RAY_COLOR);
+    }
+}
+
+
+// Draws a line (see draw_line()) between two points of the Mandelbrot's
+// domain, onto the buffer of the window described by info.
+//
+// With --projection=logpolar, a line that would cross the seam where
+// the angle wraps around (or that ends at the center, which is
+// infinitely far down) isn't drawn, as it would be drawn the wrong way
+// across the whole window.  (The rest are still drawn straight, though
+// they'd really be curved in that projection; orbits and rays are made
+// of short enough steps that it hardly shows.)
+fn draw_segment(buffer: &mut [u32], info: &WindowAndViewportInfo,
+                start: (Float, Float), end: (Float, Float), color: u32) {
+    let start = convert_x_and_y_to_row_and_column(info, start.0, start.1);
+    let end = convert_x_and_y_to_row_and_column(info, end.0, end.1);
+    if info.projection == Projection::LogPolar
+           && (!start.0.is_finite() || !end.0.is_finite()
+               || (end.1 - start.1).abs() > info.width as Float / 2.0) {
+        return ()
+    }
+    draw_line(buffer, info.width, info.height, start, end, color);
+}
+
+
+// Parses the angle of an external ray (for --rays), which can be a
+// fraction (like 1/3) or a decimal (like 0.25) of a turn, from 0
+// (inclusive) to 1 (exclusive).  It's returned as a fraction (numerator,
+// denominator) in lowest terms, or as an error message.  (Decimals are
+// turned into fractions exactly, digit by digit, so that 0.1 is 1/10.)
+fn parse_ray_angle(text: &str) -> Result<(u64, u64), String> {
+    let invalid = || format!("The angle \"{text}\" must be a fraction (like 1/3) or a decimal (like 0.25) from 0 up to 1.");
+    let (numerator, denominator) = match text.trim().split_once('/') {
+        Some((numerator_text, denominator_text)) => {
+            (numerator_text.trim().parse::<u64>().map_err(|_| invalid())?,
+             denominator_text.trim().parse::<u64>().map_err(|_| invalid())?)
+        }
+        None => {
+            let (whole_text, digits) = text.trim().split_once('.').unwrap_or((text.trim(), ""));
+            if !matches!(whole_text, "" | "0") || (whole_text.is_empty() && digits.is_empty())
+               || digits.len() > 18 || !digits.chars().all(|digit| digit.is_ascii_digit()) {
+                return Err(invalid())
+            }
+            (digits.parse::<u64>().unwrap_or(0), 10_u64.pow(digits.len() as u32))
+        }
+    };
+    if denominator == 0 || numerator >= denominator {
+        return Err(invalid())
+    }
+    let greatest_common_divisor = {
+        let (mut a, mut b) = (numerator, denominator);
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    };
+    let (numerator, denominator) = (numerator / greatest_common_divisor, denominator / greatest_common_divisor);
+    if denominator >= 1 << 62 {
+        return Err(format!("The denominator of the angle \"{text}\" is too big."))
+    }
+    Ok((numerator, denominator))
+}
+
+
+// Draws a line onto the buffer (of the given width and height) from
+// one (row, column) to another, in the given color.  The ends of the
+// line can be anywhere (even far outside the buffer, as an orbit's
+// escaping point usually is when zoomed in); the line is clipped to
+// the buffer first, so drawing it never takes more steps than the
+// buffer is wide or tall.
+fn draw_line(buffer: &mut [u32], width: usize, height: usize,
+             start: (Float, Float), end: (Float, Float), color: u32) {
+    // (Clip the line to the buffer, with the Liang-Barsky algorithm:
+    // the part of the line from start + t0*delta to start + t1*delta
+    // is the part inside every edge of the buffer.)
+    let (delta_row, delta_column) = (end.0 - start.0, end.1 - start.1);
+    let (mut t0, mut t1): (Float, Float) = (0.0, 1.0);
+    let edges = [(-delta_column, start.1 + 0.5),  // (left edge)
+                 (delta_column, width as Float - 0.5 - start.1),  // (right edge)
+                 (-delta_row, start.0 + 0.5),  // (top edge)
+                 (delta_row, height as Float - 0.5 - start.0)];  // (bottom edge)
+    for (p, q) in edges {
+        if p == 0.0 {
+            if q < 0.0 {
+                return ()  // (The line is parallel to, and outside of, this edge.)
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+    if t0 > t1 {
+        return ()  // (None of the line is inside the buffer.)
+    }
+    let (start_row, start_column) = (start.0 + t0 * delta_row, start.1 + t0 * delta_column);
+    let (end_row, end_column) = (start.0 + t1 * delta_row, start.1 + t1 * delta_column);
+
+    // Step along the (clipped) line one pixel at a time:
+    let steps = (end_row - start_row).abs().max((end_column - start_column).abs()).ceil() as usize;
+    for step in 0..=steps {
+        let t = if steps == 0 { 0.0 } else { step as Float / steps as Float };
+        // (Rounding half-way values up, so that a line clipped to the
+        // -0.5 edge of the buffer still starts in row or column 0.)
+        let row = (start_row + t * (end_row - start_row) + 0.5).floor();
+        let column = (start_column + t * (end_column - start_column) + 0.5).floor();
+        if (0.0..height as Float).contains(&row) && (0.0..width as Float).contains(&column) {
+            buffer[row as usize * width + column as usize] = color;
+        }
+    }
+}
+
+
+// Returns the rows (top to bottom) of the 3x5-pixel glyph for the
+// given character, with each row's three pixels in the lowest three
+// bits (left to right).  Lowercase letters are drawn as uppercase,
+// and characters without a glyph are drawn as a question mark.
+fn glyph(character: char) -> [u8; 5] {
+    match character.to_ascii_uppercase() {
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b110, 0b001, 0b010, 0b100, 0b111],
+        '3' => [0b110, 0b001, 0b010, 0b001, 0b110],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b110, 0b001, 0b110],
+        '6' => [0b011, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b110],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        '=' => [0b000, 0b111, 0b000, 0b111, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        '(' => [0b001, 0b010, 0b010, 0b010, 0b001],
+        ')' => [0b100, 0b010, 0b010, 0b010, 0b100],
+        ' ' => [0b000, 0b000, 0b000, 0b000, 0b000],
+        _ => [0b110, 0b001, 0b010, 0b000, 0b010],  // (a question mark)
+    }
+}
+
+
+// Returns how much of each pixel (from 0.0 to 1.0, row by row) the
+// glyph() of the given character covers when it's drawn smoothly at
+// the given scale, in a 3*scale x 5*scale box.  Instead of each glyph
+// pixel being a hard-edged square, the glyph pixels are blended
+// bilinearly and cut off half-way, which rounds off the corners and
+// the diagonals, and each pixel is sampled 4x4 times, so the edges
+// are anti-aliased.  (So there's no font file to load:  the glyphs
+// are worked out from the same built-in 3x5 ones.)
+fn smooth_glyph_coverage(character: char, scale: usize) -> Vec<f32> {
+    const SAMPLES: usize = 4;  // (per side, in each pixel)
+    let bits = glyph(character);
+    let is_lit = |glyph_row: isize, glyph_column: isize| {
+        let lit = (0..5).contains(&glyph_row) && (0..3).contains(&glyph_column)
+                      && bits[glyph_row as usize] & (0b100 >> glyph_column) != 0;
+        if lit { 1.0 } else { 0.0 }
+    };
+    // (Where a sample falls, in glyph pixels, measured from the
+    // center of the glyph's upper-left pixel:)
+    let to_glyph = |pixel: usize, sample: usize|
+                       (pixel as f32 + (sample as f32 + 0.5) / SAMPLES as f32) / scale as f32 - 0.5;
+
+    let (width, height) = (3 * scale, 5 * scale);
+    let mut coverage = vec![0.0; width * height];
+    for row in 0..height {
+        for column in 0..width {
+            let mut samples_covered = 0;
+            for (sample_row, sample_column) in (0..SAMPLES).flat_map(|r| (0..SAMPLES).map(move |c| (r, c))) {
+                let (y, x) = (to_glyph(row, sample_row), to_glyph(column, sample_column));
+                let (top, left) = (y.floor(), x.floor());
+                let (fraction_down, fraction_across) = (y - top, x - left);
+                let (top, left) = (top as isize, left as isize);
+                let value = is_lit(top, left) * (1.0 - fraction_across) * (1.0 - fraction_down)
+                            + is_lit(top, left + 1) * fraction_across * (1.0 - fraction_down)
+                            + is_lit(top + 1, left) * (1.0 - fraction_across) * fraction_down
+                            + is_lit(top + 1, left + 1) * fraction_across * fraction_down;
+                if value >= 0.5 {
+                    samples_covered += 1;
+                }
+            }
+            coverage[row * width + column] = samples_covered as f32 / (SAMPLES * SAMPLES) as f32;
+        }
+    }
+    coverage
+}
+
+
+// Draws the text onto the buffer (of the given width and height) as
+// a label:  white letters (each glyph() pixel drawn as a scale x scale
+// square) on a black box, with its upper-left corner at the given
+// row & column.  Whatever doesn't fit in the buffer is cut off.
+//
+// If smooth is true (see --smooth-labels), the letters are drawn with
+// smooth_glyph_coverage() instead, blended onto the box by how much
+// of each pixel they cover.  (At a scale of 1, there's nothing to
+// smooth, so they're drawn the same either way.)
+fn draw_label(buffer: &mut [u32], width: usize, height: usize,
+              text: &str, (row, column): (usize, usize), scale: usize, smooth: bool) {
+    const TEXT_COLOR: u32 = 0xff_ff_ff;  // (white)
+    const BOX_COLOR: u32 = 0x00_00_00;  // (black)
+    let mut plot = |row: usize, column: usize, color: u32| {
+        if row < height && column < width {
+            buffer[row * width + column] = color;
+        }
+    };
+
+    // (Each character is 3 pixels wide, plus 1 for the space between
+    // characters, and the box has a 1-pixel margin all around.)
+    let box_width = (text.chars().count() * 4 + 1) * scale;
+    let box_height = 7 * scale;
+    for box_row in 0..box_height {
+        for box_column in 0..box_width {
+            plot(row + box_row, column + box_column, BOX_COLOR);
+        }
+    }
+    if smooth && scale > 1 {
+        for (i, character) in text.chars().enumerate() {
+            let coverage = smooth_glyph_coverage(character, scale);
+            let (top, left) = (row + scale, column + (1 + i * 4) * scale);
+            for (glyph_row, coverage_row) in coverage.chunks_exact(3 * scale).enumerate() {
+                for (glyph_column, &fraction) in coverage_row.iter().enumerate() {
+                    let (row, column) = (top + glyph_row, left + glyph_column);
+                    if fraction > 0.0 && row < height && column < width {
+                        let pixel = &mut buffer[row * width + column];
+                        *pixel = blend_colors(*pixel, TEXT_COLOR, fraction);
+                    }
+                }
+            }
+        }
+        return ()
+    }
+    for (i, character) in text.chars().enumerate() {
+        for (glyph_row, bits) in glyph(character).iter().enumerate() {
+            for glyph_column in 0..3 {
+                if bits & (0b100 >> glyph_column) != 0 {
+                    for (dr, dc) in (0..scale).flat_map(|dr| (0..scale).map(move |dc| (dr, dc))) {
+                        plot(row + (1 + glyph_row) * scale + dr,
+                             column + (1 + i * 4 + glyph_column) * scale + dc,
+                             TEXT_COLOR);
+                    }
+                }
+            }
+        }
+    }
+}
+
+
+// Returns the text shown (with the I key) for a pixel with the
+// given EscapeDetails:  its escape value, or that it's in the set.
+fn pixel_info_text(details: &EscapeDetails) -> String {
+    match (details.escape_value, details.hit_bailout) {
+        (Some(1), _) => "escaped after 1 iteration".to_string(),
+        (Some(escape_value), _) => format!("escaped after {escape_value} iterations"),
+        (None, true) => "in set (hit the bailout)".to_string(),
+        (None, false) => "in set".to_string(),
+    }
+}
+
+
+// Draws the pixel_info_text() of the pixel at the given (row, column)
+// as a label just below and to the right of it (or wherever else
+// near it the label fits, if it's near the edge of the buffer).
+fn draw_pixel_info(buffer: &mut [u32], info: &WindowAndViewportInfo,
+                   escape_details: &[EscapeDetails], (row, column): (usize, usize),
+                   smooth_labels: bool) {
+    const OFFSET: usize = 12;  // (in pixels; so the label isn't hidden under the mouse pointer)
+    let text = pixel_info_text(&escape_details[row * info.width + column]);
+    let scale = if info.width >= 400 { 2 } else { 1 };
+    // (See draw_label() for the size of its box.)
+    let (label_width, label_height) = ((text.chars().count() * 4 + 1) * scale, 7 * scale);
+
+    let label_row = if row + OFFSET + label_height <= info.height {
+        row + OFFSET
+    } else {
+        row.saturating_sub(OFFSET + label_height)
+    };
+    let label_column = if column + OFFSET + label_width <= info.width {
+        column + OFFSET
+    } else {
+        column.saturating_sub(OFFSET + label_width)
+    };
+    draw_label(buffer, info.width, info.height, &text, (label_row, label_column), scale, smooth_labels);
+}
+
+
+// Returns how many times bigger things look in the viewport described
+// by info than they do in the starting view (of a plain ProgramConfig::new()).
+fn magnification(info: &WindowAndViewportInfo) -> Float {
+    ProgramConfig::new().distance_from_center_to_edge / info.distance_from_center_to_edge
+}
+
+
+// Returns the text shown (with the T key) for an image that took the
+// given number of seconds to draw, at the given magnification.  (Very
+// high magnifications are given in scientific notation, so they fit.)
+fn render_stats_text(seconds: Float, magnification: Float) -> String {
+    let magnification = if magnification < 1e6 {
+        format!("{magnification:.1}")
+    } else {
+        format!("{magnification:.2e}")
+    };
+    format!("{seconds:.3} sec.  {magnification}x zoom")
+}
+
+
+// Draws the render_stats (see render_stats_text()) as a label in the
+// upper-left corner of the buffer.  (The minimap is in the lower-right
+// corner, so the two never overlap.)
+fn draw_render_stats(buffer: &mut [u32], info: &WindowAndViewportInfo, render_stats: &str, smooth_labels: bool) {
+    let scale = if info.width >= 400 { 2 } else { 1 };
+    let margin = 4 * scale;  // (in pixels)
+    draw_label(buffer, info.width, info.height, render_stats, (margin, margin), scale, smooth_labels);
+}
+
+
+// Returns the lines shown (with the D key) for the viewport described
+// by info, drawn with the given config and cycle-detection threshold
+// (see cycle_threshold()):  the bailout, the threshold, and the size
+// of a pixel, so that odd-looking parts of the image can be traced
+// back to them.  (The threshold is a fraction of a pixel; see
+// --cycle-threshold.)
+fn diagnostics_text(info: &WindowAndViewportInfo, config: &Config, threshold: Float) -> Vec<String> {
+    vec![
+        format!("bailout = {}", config.bailout.map_or("none".to_string(), |bailout| bailout.to_string())),
+        format!("threshold = {threshold:.3e}"),
+        format!("delta x = {:.3e}", info.delta_x),
+        format!("delta y = {:.3e}", info.delta_y),
+    ]
+}
+
+
+// Draws the diagnostics (see diagnostics_text()) as labels, one line
+// above another, in the lower-left corner of the buffer.  (The render
+// stats are in the upper-left corner, and the minimap is in the
+// lower-right one, so none of them overlap.)
+fn draw_diagnostics(buffer: &mut [u32], info: &WindowAndViewportInfo, diagnostics: &[String], smooth_labels: bool) {
+    let scale = if info.width >= 400 { 2 } else { 1 };
+    let margin = 4 * scale;  // (in pixels)
+    let line_height = 8 * scale;  // (See draw_label() for the height of its box.)
+    for (i, line) in diagnostics.iter().rev().enumerate() {
+        if let Some(row) = info.height.checked_sub(margin + (i + 1) * line_height) {
+            draw_label(buffer, info.width, info.height, line, (row, margin), scale, smooth_labels);
+        }
+    }
+}
+
+
+// Tints every pixel of the buffer that hit the bailout (according
+// to its escape_details) with the tint_color, so the user can see
+// where raising the bailout would show more detail.  The opacity
+// ranges from 0.0 (no tint at all) to 1.0 (solid tint_color).
+fn draw_bailout_heatmap(buffer: &mut [u32],
+                        escape_details: &[EscapeDetails],
+                        tint_color: (u8, u8, u8),
+                        opacity: f32) {
+    let tint_color = rgb_to_u32(tint_color.0, tint_color.1, tint_color.2);
+    for (pixel, details) in buffer.iter_mut().zip(escape_details.iter()) {
+        if details.hit_bailout {
+            *pixel = blend_colors(*pixel, tint_color, opacity);
+        }
+    }
+}
+
+
+// The bailout used for calculating the minimap.  (The minimap is too
+// small to show much detail, so there's no point in going higher.)
+const MINIMAP_BAILOUT: usize = 250;
+
+
+// This structure holds a small image of the whole Mandelbrot set,
+// shown in the bottom-right corner of the window, with the current
+// view marked on it so the user can tell where they are when zoomed
+// way in.  (It's calculated only once, as the whole set never changes.)
+//
+// When viewing a Julia set, the view isn't part of the Mandelbrot set
+// at all, so the minimap instead marks the point c (as Julia sets
+// with c in the Mandelbrot set are connected, and the rest aren't).
+#[derive(Debug)]
+struct Minimap {
+    info: WindowAndViewportInfo,  // (The minimap's size, and where it shows.)
+    image_buffer: Vec<u32>,
+    c: Option<(Float, Float)>,  // (Used for Julia sets.)
+}
+impl Minimap {
+    // Calculates a minimap sized to fit the corner
+    // of a window with the given width and height.
+    fn new(window_width: usize, window_height: usize, c: Option<(Float, Float)>) -> Self {
+        let smaller_side = window_width.min(window_height);
+        let size = (smaller_side / 4).max(16).min(smaller_side);  // (in pixels)
+        let info = WindowAndViewportInfo::new(size, size, -0.5, 0.0, 1.725, 0);
+        let threshold = info.delta_x / 4.0;
+
+        let mut image_buffer = vec![0u32; size * size];
+        for row in 0..size {
+            for column in 0..size {
+                let (x, y) = convert_row_and_column_to_x_and_y(&info, row as Float, column as Float);
+                let escape_value = calculate_escape_value(x, y, None, Some(threshold), Some(MINIMAP_BAILOUT));
+                let (r, g, b) = color(escape_value);
+                image_buffer[row * size + column] = rgb_to_u32(r, g, b);
+            }
+        }
+
+        Self { info, image_buffer, c }
+    }
+
+    // Returns the (row, column) of the minimap's top-left corner
+    // in a window with the given width and height.
+    fn top_left_corner(&self, window_width: usize, window_height: usize) -> (usize, usize) {
+        let size = self.info.width;
+        let margin = if window_width.min(window_height) >= size + 16 { 8 } else { 0 };  // (in pixels)
+        (window_height - size - margin, window_width - size - margin)
+    }
+
+    // Draws the minimap onto the buffer (of the window described
+    // by info), marking where info's view is on the minimap.
+    fn draw(&self, buffer: &mut [u32], info: &WindowAndViewportInfo) {
+        let size = self.info.width;
+        let (top, left) = self.top_left_corner(info.width, info.height);
+        for row in 0..size {
+            let start = (top + row) * info.width + left;
+            buffer[start..start + size].copy_from_slice(&self.image_buffer[row * size..(row + 1) * size]);
+        }
+
+        // Inverts the pixel of the minimap at the given (possibly
+        // out-of-bounds) row and column, so the marker always stands out:
+        let mut invert = |row: isize, column: isize| {
+            if (0..size as isize).contains(&row) && (0..size as isize).contains(&column) {
+                buffer[(top + row as usize) * info.width + left + column as usize] ^= 0xff_ff_ff;
+            }
+        };
+        // Returns the (row, column) of the minimap's pixel holding x+yi:
+        let to_row_and_column = |x: Float, y: Float| {
+            (((self.info.max_y - y) / self.info.delta_y).floor() as isize,
+             ((x - self.info.min_x) / self.info.delta_x).floor() as isize)
+        };
+        // Marks a point with a small plus sign:
+        let mut mark_point = |row: isize, column: isize| {
+            invert(row, column);
+            for offset in 1..=2 {
+                invert(row - offset, column);
+                invert(row + offset, column);
+                invert(row, column - offset);
+                invert(row, column + offset);
+            }
+        };
+
+        if let Some((c_x, c_y)) = self.c {
+            let (row, column) = to_row_and_column(c_x, c_y);
+            mark_point(row, column);
+            return ()
+        }
+
+        let (top_row, left_column) = to_row_and_column(info.min_x, info.max_y);
+        let (bottom_row, right_column) = to_row_and_column(info.max_x, info.min_y);
+        if right_column - left_column < 3 || bottom_row - top_row < 3 {
+            // (The view is too small to see as a rectangle, so mark its center instead.)
+            let (row, column) = to_row_and_column(info.center_x, info.center_y);
+            mark_point(row, column);
+        } else {
+            // (Clamp the rectangle to just outside the minimap, so a huge
+            // view doesn't mean looping over lots of pixels that aren't there.)
+            let clamp = |value: isize| value.clamp(-1, size as isize);
+            let (top_row, bottom_row) = (clamp(top_row), clamp(bottom_row));
+            let (left_column, right_column) = (clamp(left_column), clamp(right_column));
+            for column in left_column..=right_column {
+                invert(top_row, column);
+                invert(bottom_row, column);
+            }
+            for row in (top_row + 1)..bottom_row {
+                invert(row, left_column);
+                invert(row, right_column);
+            }
+        }
+    }
+}
+
+
+// With --double-buffer, this holds the last finished image, which
+// stays in the window while the next image is drawn off-screen (in
+// the image_buffer), until it's finished.  (See shown_image().)
+struct FinishedImage {
+    image_buffer: Vec<u32>,
+    escape_details: Vec<EscapeDetails>,
+    info: WindowAndViewportInfo,  // (The viewport it shows.)
+}
+
+
+// Returns the image that the window should show (along with its escape
+// details, and the viewport it shows) while an image is being drawn:
+// the finished_image, with --double-buffer, or else the image being
+// drawn (as described by the other arguments), so far.  (The viewport
+// is returned so that clicks on the shown image go where they look
+// like they go, and so its overlays are drawn in the right places.)
+fn shown_image<'a>(finished_image: Option<&'a FinishedImage>,
+                   image_buffer: &'a [u32],
+                   escape_details: &'a [EscapeDetails],
+                   info: &'a WindowAndViewportInfo) -> (&'a [u32], &'a [EscapeDetails], &'a WindowAndViewportInfo) {
+    match finished_image {
+        Some(finished_image) => (&finished_image.image_buffer, &finished_image.escape_details, &finished_image.info),
+        None => (image_buffer, escape_details, info),
+    }
+}
+
+
+// Shows the image_buffer in the window, with the shown overlays
+// drawn on top of it.  (The overlays are drawn onto display_buffer,
+// which is just scratch space, so the image_buffer is left untouched.)
+fn update_window(window: &mut minifb::Window,
+                 image_buffer: &[u32],
+                 escape_details: &[EscapeDetails],
+                 display_buffer: &mut Vec<u32>,
+                 overlays: &Overlays,
+                 info: &WindowAndViewportInfo) {
+    let buffer = if overlays.any_shown() {
+        display_buffer.clear();
+        display_buffer.extend_from_slice(image_buffer);
+        overlays.draw(display_buffer, info, escape_details);
+        display_buffer
+    } else {
+        image_buffer
+    };
+    if let Err(error) = window.update_with_buffer(buffer, info.width, info.height) {
+        eprintln!("Error:  Unable to update the window:  {error}");
+        std::process::exit(EXIT_DISPLAY_ERROR)
+    }
+}
+
+
+// Returns a copy of the image_buffer, with the overlays that
+// the user wants in screenshots drawn on top of it.
+fn screenshot_image(image_buffer: &[u32], overlays: &Overlays,
+                    width: usize, height: usize) -> Vec<u32> {
+    let mut screenshot_buffer = image_buffer.to_vec();
+    overlays.draw_for_screenshot(&mut screenshot_buffer, width, height);
+    screenshot_buffer
+}
+
+
+// Writes the image_buffer as a binary PPM (P6) image.  The format
+// is simple enough (a short text header followed by the raw RGB bytes)
+// that we don't need the image crate to write it.
+// (The image_buffer must have a length of width x height.)
+fn write_ppm(image_buffer: &[u32], width: usize, height: usize,
+             writer: &mut impl std::io::Write) -> std::io::Result<()> {
+    assert_eq!(image_buffer.len(), width * height,
+               "FATAL ERROR:  width x height does not equal the length of the image buffer.");
+
+    write!(writer, "P6\n{width} {height}\n255\n")?;
+
+    let rgb_bytes: Vec<u8> = image_buffer.iter()
+                                         .flat_map(|pixel| [(pixel >> 16 & 0xff) as u8,
+                                                            (pixel >>  8 & 0xff) as u8,
+                                                            (pixel >>  0 & 0xff) as u8])
+                                         .collect();
+    writer.write_all(&rgb_bytes)?;
+    writer.flush()
+}
+
+
+// The default number of thumbnails across (and down) a --contact-sheet
+// of Julia sets, and how far apart the c values of neighboring
+// thumbnails are.
+const DEFAULT_CONTACT_SHEET_GRID_SIZE: usize = 4;
+const CONTACT_SHEET_C_STEP: Float = 0.02;
+
+
+// Returns the thumbnails (a label, and the config to draw it with)
+// of a --contact-sheet.  For a Julia set, that's a grid_size x
+// grid_size grid of Julia sets, with c values around the one given
+// (going right along x, and up along y).  For the Mandelbrot set,
+// it's one thumbnail for each way it can be colored.
+fn contact_sheet_thumbnails(config: &Config, grid_size: usize) -> Vec<(String, Config)> {
+    match config.c {
+        Some((c_x, c_y)) => {
+            let offset = |i: usize| (i as Float - (grid_size - 1) as Float / 2.0) * CONTACT_SHEET_C_STEP;
+            (0..grid_size).flat_map(|row| (0..grid_size).map(move |column| (row, column)))
+                          .map(|(row, column)| {
+                              let c = (c_x + offset(column), c_y - offset(row));
+                              (format!("c={:.3},{:.3}", c.0, c.1), Config { c: Some(c), ..config.clone() })
+                          })
+                          .collect()
+        }
+        None => {
+            let (boundary_color, background_color) = (config.boundary_color, config.boundary_background_color);
+            [("escape-time", Coloring::EscapeTime, InteriorColoring::Solid, false),
+             ("escape-time period", Coloring::EscapeTime, InteriorColoring::Period, false),
+             ("escape-time inverted", Coloring::EscapeTime, InteriorColoring::Solid, true),
+             ("escape-time period inverted", Coloring::EscapeTime, InteriorColoring::Period, true),
+             ("boundary", Coloring::Boundary, InteriorColoring::Solid, false),
+             ("boundary reversed", Coloring::Boundary, InteriorColoring::Solid, true),
+             ("potential", Coloring::Potential, InteriorColoring::Solid, false)]
+                .into_iter()
+                .map(|(label, coloring, interior_coloring, invert)| {
+                    let mut config = Config { coloring, interior_coloring, invert, ..config.clone() };
+                    if coloring == Coloring::Boundary && invert {
+                        // ("Inverting" boundary coloring swaps its colors.)
+                        (config.boundary_color, config.boundary_background_color) = (background_color, boundary_color);
+                    }
+                    (label.to_string(), config)
+                })
+                .collect()
+        }
+    }
+}
+
+
+// Returns how many columns and rows of thumbnails are needed to lay
+// out the given number of thumbnails in a grid that's as square as
+// it can be (without going taller than it is wide).
+fn contact_sheet_layout(number_of_thumbnails: usize) -> (usize, usize) {
+    let columns = (1..).find(|columns| columns * columns >= number_of_thumbnails).unwrap();
+    let rows = number_of_thumbnails.div_ceil(columns).max(1);
+    (columns, rows)
+}
+
+
+// The default number of cells across (and down) a --julia-atlas, and
+// the distance (from center to edge) that each cell's Julia set is
+// drawn with, around 0,0.  (Every Julia set fits in the circle of
+// radius 2, as any point outside of it escapes.)
+const DEFAULT_JULIA_ATLAS_GRID_SIZE: usize = 8;
+const JULIA_ATLAS_DISTANCE: Float = 2.0;
+
+
+// Returns the cells (an empty label, and the config to draw it with)
+// of a --julia-atlas:  a grid_size x grid_size grid of Julia sets,
+// one for each c at the center of a cell of the given view (center and
+// distance) of the Mandelbrot set's plane (going right along x, and up
+// along y), so that each one is drawn where its c would be in the
+// Mandelbrot set.  (The cells are too small to label.)
+fn julia_atlas_cells(config: &Config, (center_x, center_y): (Float, Float), distance: Float,
+                     grid_size: usize) -> Vec<(String, Config)> {
+    let cell_size = 2.0 * distance / grid_size as Float;
+    let offset = |i: usize| (i as Float + 0.5) * cell_size - distance;
+    (0..grid_size).flat_map(|row| (0..grid_size).map(move |column| (row, column)))
+                  .map(|(row, column)| {
+                      let c = (center_x + offset(column), center_y - offset(row));
+                      (String::new(), Config { c: Some(c), ..config.clone() })
+                  })
+                  .collect()
+}
+
+
+// A rectangle of pixels to --crop, as (left, top, right, bottom):
+// the pixel coordinates of its corners in the starting view, where
+// 0,0 is the upper-left corner of the window, and --size,--size is
+// the lower-right one.  (So its width is right - left, and its
+// height is bottom - top.)
+type CropRectangle = (usize, usize, usize, usize);
+
+
+// Parses the "X0,Y0,X1,Y1" of --crop, returning an error message if
+// it's not four whole numbers, with X0 left of X1 and Y0 above Y1.
+// (Whether it fits inside of the window isn't known until all of the
+// arguments have been read; see crop_view().)
+fn parse_crop_rectangle(text: &str) -> Result<CropRectangle, String> {
+    let text_values: Vec<_> = text.split(",").collect();
+    if text_values.len() != 4 {
+        return Err(format!("The rectangle \"{text}\" needs exactly four values (X0,Y0,X1,Y1)."))
+    }
+    let mut values = [0usize; 4];
+    for (value, (value_text, name)) in values.iter_mut().zip(text_values.iter().zip(["X0", "Y0", "X1", "Y1"])) {
+        *value = match value_text.trim().parse() {
+            Ok(value) => value,
+            _ => return Err(format!("The {name} value in the rectangle \"{text}\" must be a whole number of pixels.")),
+        };
+    }
+    let [left, top, right, bottom] = values;
+    if left >= right {
+        return Err(format!("X0 ({left}) must be less than X1 ({right})."))
+    }
+    if top >= bottom {
+        return Err(format!("Y0 ({top}) must be less than Y1 ({bottom})."))
+    }
+    Ok((left, top, right, bottom))
+}
+
+
+// Returns the view to draw a --crop with:  a square output_size x
+// output_size view centered on the middle of the crop rectangle (of
+// the view described by info), just wide enough to hold its longer
+// side, so it's zoomed in as far as it can be.  Also returns the part
+// of that view's image that's the crop rectangle (as a CropRectangle
+// of its pixels), which is as wide as the output_size if the crop
+// rectangle is wider than it is tall, and as tall as it otherwise.
+// (The pixels stay square, as every view's do; the rest of the view
+// is just cut off.)  Returns an error message if the rectangle doesn't
+// fit inside of the view, or the view isn't rectangular.
+fn crop_view(info: &WindowAndViewportInfo, (left, top, right, bottom): CropRectangle,
+             output_size: usize) -> Result<(WindowAndViewportInfo, CropRectangle), String> {
+    if info.projection != Projection::Rectangular {
+        return Err("--crop only works with --projection=rectangular.".to_string())
+    }
+    if right > info.width || bottom > info.height {
+        return Err(format!("The rectangle {left},{top},{right},{bottom} doesn't fit inside of the {} x {} window.",
+                           info.width, info.height))
+    }
+    let (crop_width, crop_height) = (right - left, bottom - top);
+    let crop_size = crop_width.max(crop_height);
+    // (The corners of pixels are half a pixel from their centers.)
+    let (center_x, center_y) = convert_row_and_column_to_x_and_y(info,
+                                                                 (top + bottom) as Float / 2.0 - 0.5,
+                                                                 (left + right) as Float / 2.0 - 0.5);
+    let distance = info.delta_x * crop_size as Float / 2.0;
+    let view = WindowAndViewportInfo::new(
+        output_size, output_size,  // (in pixels)
+        center_x, center_y,
+        distance,
+        info.zoom_level);
+    let scaled = |length: usize| ((length * output_size) as Float / crop_size as Float).round().max(1.0) as usize;
+    let (output_width, output_height) = (scaled(crop_width), scaled(crop_height));
+    let (output_left, output_top) = ((output_size - output_width) / 2, (output_size - output_height) / 2);
+    Ok((view, (output_left, output_top, output_left + output_width, output_top + output_height)))
+}
+
+
+// Returns the part of the image (of the given width) inside of the
+// rectangle, row by row.
+fn cropped_image(image_buffer: &[u32], width: usize, (left, top, right, bottom): CropRectangle) -> Vec<u32> {
+    image_buffer.chunks(width)
+                .skip(top)
+                .take(bottom - top)
+                .flat_map(|row| row[left..right].iter().copied())
+                .collect()
+}
+
+
+// The color of the empty spots of a contact sheet,
+// unless another one is given with --background.
+const CONTACT_SHEET_EMPTY_COLOR: u32 = 0x40_40_40;  // (dark gray)
+
+
+// Draws each thumbnail (see contact_sheet_thumbnails()) of the
+// viewport centered on center with the given distance (from center
+// to edge) into a thumbnail_size x thumbnail_size square, tiles them
+// into one image (left to right, then top to bottom, with any empty
+// spots left in the empty_color), and labels each one (unless its
+// label is empty).  Returns the image, with its width and height.
+fn render_contact_sheet(thumbnails: &[(String, Config)], thumbnail_size: usize,
+                        center: (Float, Float), distance_from_center_to_edge: Float,
+                        smooth_labels: bool, empty_color: u32)
+                            -> (Vec<u32>, usize, usize) {
+    let (columns, rows) = contact_sheet_layout(thumbnails.len());
+    let (width, height) = (columns * thumbnail_size, rows * thumbnail_size);
+    let mut sheet = vec![empty_color; width * height];
+
+    let info = WindowAndViewportInfo::new(
+        thumbnail_size, thumbnail_size,  // (in pixels)
+        center.0, center.1,
+        distance_from_center_to_edge,
+        0);
+    let label_scale = if thumbnail_size >= 256 { 2 } else { 1 };
+    let mut thumbnail = vec![0u32; thumbnail_size * thumbnail_size];
+    for (i, (label, config)) in thumbnails.iter().enumerate() {
+        render_into_buffer(&mut thumbnail, &info, config);
+        if !label.is_empty() {
+            let label_row = thumbnail_size.saturating_sub(7 * label_scale);
+            draw_label(&mut thumbnail, thumbnail_size, thumbnail_size, label, (label_row, 0), label_scale, smooth_labels);
+        }
+
+        let (top, left) = (i / columns * thumbnail_size, i % columns * thumbnail_size);
+        for (row, thumbnail_row) in thumbnail.chunks_exact(thumbnail_size).enumerate() {
+            let start = (top + row) * width + left;
+            sheet[start..start + thumbnail_size].copy_from_slice(thumbnail_row);
+        }
+    }
+    (sheet, width, height)
+}
+
+
+// The delay (in seconds) between zooms with --auto-zoom,
+// unless another one is given with --auto-zoom=SECONDS.
+const DEFAULT_AUTO_ZOOM_DELAY: f64 = 1.0;
+
+// How far (in pixels, in every direction) most_interesting_pixel()
+// looks around each pixel.
+const AUTO_ZOOM_NEIGHBORHOOD: usize = 4;
+
+
+// Returns the (row, column) of the most "interesting" pixel in the
+// middle half of the image (for --auto-zoom):  the one with the most
+// variety in the escape values around it (by the variance of their
+// logarithms), which is where the boundary of the set has the most
+// detail.  (Ties go to the pixel nearest the center.)  Only the middle
+// half is looked at, so that zooming in on the pixel keeps the new view
+// inside of the old one.
+//
+// Only the points that escaped are counted, as the edge between them
+// and the points in the set would otherwise stand out the most, and
+// that edge is often just where the bailout happened to cut things
+// off, which only gets smoother (and duller) when zoomed in on.
+//
+// Returns None if there's no variety at all in the middle half (if it's
+// all inside of the set, say), as there's nothing there to zoom in on.
+fn most_interesting_pixel(escape_details: &[EscapeDetails], width: usize, height: usize) -> Option<(usize, usize)> {
+    let value = |row: usize, column: usize| {
+        escape_details[row * width + column].escape_value
+            .map(|escape_value| (escape_value as Float).ln())
+    };
+    let (center_row, center_column) = (height as Float / 2.0, width as Float / 2.0);
+
+    let mut best: Option<((usize, usize), Float, Float)> = None;  // (The pixel, its variance, and its distance.)
+    for row in height / 4..height - height / 4 {
+        for column in width / 4..width - width / 4 {
+            let rows = row.saturating_sub(AUTO_ZOOM_NEIGHBORHOOD)..(row + AUTO_ZOOM_NEIGHBORHOOD + 1).min(height);
+            let columns = column.saturating_sub(AUTO_ZOOM_NEIGHBORHOOD)..(column + AUTO_ZOOM_NEIGHBORHOOD + 1).min(width);
+            let values: Vec<Float> = rows.flat_map(|neighbor_row| columns.clone().map(move |neighbor_column| (neighbor_row, neighbor_column)))
+                                         .filter_map(|(neighbor_row, neighbor_column)| value(neighbor_row, neighbor_column))
+                                         .collect();
+            if values.len() < 2 {
+                continue
+            }
+            // (The mean is found first, so that a neighborhood of all
+            // the same value gets a variance of exactly zero.)
+            let count = values.len() as Float;
+            let mean = values.iter().sum::<Float>() / count;
+            let variance = values.iter().map(|value| (value - mean) * (value - mean)).sum::<Float>() / count;
+            let distance = (row as Float - center_row).hypot(column as Float - center_column);
+            let is_better = match best {
+                None => variance > 0.0,
+                Some((_, best_variance, best_distance)) =>
+                    variance > best_variance || (variance == best_variance && distance < best_distance),
+            };
+            if is_better {
+                best = Some(((row, column), variance, distance));
+            }
+        }
+    }
+    best.map(|(pixel, _, _)| pixel)
+}
+
+
+// Parses the SECONDS of --auto-zoom=SECONDS (the delay between
+// zooms), which can be any number from 0 up.
+fn parse_auto_zoom_delay(text: &str) -> Result<std::time::Duration, String> {
+    match text.parse::<f64>() {
+        Ok(seconds) if seconds.is_finite() && seconds >= 0.0 => Ok(std::time::Duration::from_secs_f64(seconds)),
+        _ => Err(format!("The delay \"{text}\" must be a number of seconds (0 or more).")),
+    }
+}
+
+
+// The results of --float-bench (see float_benchmark()).
+struct FloatBenchmark {
+    f32_time: std::time::Duration,
+    f64_time: std::time::Duration,
+    number_of_pixels: usize,
+    different_pixels: usize,  // (The pixels whose escape values weren't the same with f32 and f64.)
+}
+
+
+// Calculates the escape value of every pixel of the viewport described
+// by info twice, once with --float=f32 and once with --float=f64 (see
+// calculate_pixel_details()), timing each, and counts the pixels that
+// got different escape values.
+//
+// The pixels are calculated one after the other, on just one thread,
+// so that the timings aren't thrown off by how the work gets split up.
+fn float_benchmark(info: &WindowAndViewportInfo, config: &Config) -> FloatBenchmark {
+    let threshold = cycle_threshold(info, config);
+    let pixels = all_pixels(info.width, info.height);
+    let time_escape_values = |float_type: FloatType| {
+        let config = Config { float_type, ..config.clone() };
+        let start_time = std::time::Instant::now();
+        let escape_values: Vec<Option<usize>> =
+            pixels.iter()
+                  .map(|&(row, column)| calculate_pixel_details(info, row, column, &config, threshold).escape_value)
+                  .collect();
+        (escape_values, start_time.elapsed())
+    };
+    let (f32_escape_values, f32_time) = time_escape_values(FloatType::F32);
+    let (f64_escape_values, f64_time) = time_escape_values(FloatType::F64);
+
+    FloatBenchmark {
+        f32_time,
+        f64_time,
+        number_of_pixels: pixels.len(),
+        different_pixels: f32_escape_values.iter().zip(&f64_escape_values).filter(|(a, b)| a != b).count(),
+    }
+}
+
+
+// Returns the report that --float-bench prints.
+fn float_benchmark_text(benchmark: &FloatBenchmark) -> String {
+    let speedup = benchmark.f64_time.as_secs_f64() / benchmark.f32_time.as_secs_f64().max(1e-9);
+    format!("f64:  {:.3} seconds\n\
+             f32:  {:.3} seconds ({speedup:.2} times as fast as f64)\n\
+             {} of the {} pixels ({:.2}%) got a different escape value with f32.\n",
+            benchmark.f64_time.as_secs_f64(),
+            benchmark.f32_time.as_secs_f64(),
+            benchmark.different_pixels, benchmark.number_of_pixels,
+            100.0 * benchmark.different_pixels as f64 / benchmark.number_of_pixels.max(1) as f64)
+}
+
+
+// The colors that --diff draws the pixels that came out differently
+// with:  the pixels that were in the set with one config but not the
+// other, and the pixels that escaped with both, but not after the
+// same number of iterations.  (Neither is a palette color, whose
+// components always add up to 255.)  The pixels that came out the
+// same are drawn darkened by DIFF_DARKENING, so that these stand out.
+const DIFF_MEMBERSHIP_COLOR: u32 = 0xff_ff_ff;  // (white)
+const DIFF_ESCAPE_VALUE_COLOR: u32 = 0xff_00_ff;  // (magenta)
+const DIFF_DARKENING: f32 = 0.6;
+
+
+// The results of --diff (see diff_image()).
+#[derive(Debug, PartialEq)]
+struct EscapeDifference {
+    number_of_pixels: usize,
+    different_membership: usize,  // (The pixels in the set with only one of the configs.)
+    different_escape_values: usize,  // (The pixels that escaped with both, but after different numbers of iterations.)
+}
+
+
+// Returns the image that --diff saves, comparing the escape details
+// of the same pixels calculated with two configs:  the pixels that
+// came out the same keep their colors (from first_colors) but are
+// darkened, and the ones that didn't are drawn in one of the DIFF
+// colors.  Also returns how many pixels differed (and how).
+fn diff_image(first_escape_details: &[EscapeDetails],
+              second_escape_details: &[EscapeDetails],
+              first_colors: &[u32]) -> (Vec<u32>, EscapeDifference) {
+    let mut difference = EscapeDifference {
+        number_of_pixels: first_escape_details.len(),
+        different_membership: 0,
+        different_escape_values: 0,
+    };
+    let image_buffer = first_escape_details.iter()
+                                           .zip(second_escape_details)
+                                           .zip(first_colors)
+                                           .map(|((first, second), &color)| {
+        match (first.escape_value, second.escape_value) {
+            (a, b) if a == b => blend_colors(color, 0, DIFF_DARKENING),
+            (Some(_), Some(_)) => {
+                difference.different_escape_values += 1;
+                DIFF_ESCAPE_VALUE_COLOR
+            }
+            _ => {
+                difference.different_membership += 1;
+                DIFF_MEMBERSHIP_COLOR
+            }
+        }
+    }).collect();
+    (image_buffer, difference)
+}
+
+
+// Calculates every pixel of the viewport described by info with both
+// configs (for --diff), and returns the diff_image() of the two.  (The
+// first config's colors are used for the pixels that came out the same.)
+fn render_diff(info: &WindowAndViewportInfo,
+               first_config: &Config,
+               second_config: &Config) -> (Vec<u32>, EscapeDifference) {
+    let (first_escape_details, second_escape_details) = (render_escape_details(info, first_config),
+                                                         render_escape_details(info, second_config));
+    let mut first_colors = vec![0; info.width * info.height];
+    color_escape_details(&first_escape_details, &mut first_colors, info, first_config);
+    diff_image(&first_escape_details, &second_escape_details, &first_colors)
+}
+
+
+// Returns the report that --diff prints.
+fn escape_difference_text(difference: &EscapeDifference) -> String {
+    let percent = |count: usize| 100.0 * count as f64 / difference.number_of_pixels.max(1) as f64;
+    format!("{} of the {} pixels ({:.2}%) were in the set with only one of the configs (drawn in white).\n\
+             {} of the {} pixels ({:.2}%) escaped with both, but with different escape values (drawn in magenta).\n",
+            difference.different_membership, difference.number_of_pixels, percent(difference.different_membership),
+            difference.different_escape_values, difference.number_of_pixels, percent(difference.different_escape_values))
+}
+
+
+// The default size (in pixels) of the square tiles that --deepzoom
+// saves, and the default number of levels of tiles (see
+// deepzoom_full_size()), along with the most levels allowed.  (Every
+// level has four times as many tiles as the one before it, so with
+// the most levels, the biggest level has 16,384 of them.)
+const DEFAULT_DEEPZOOM_TILE_SIZE: usize = 256;
+const DEFAULT_DEEPZOOM_LEVELS: usize = 4;
+const MAX_DEEPZOOM_LEVELS: usize = 8;
+
+
+// Returns the width (and height) in pixels of the biggest image that
+// --deepzoom saves tiles of:  number_of_levels levels of tiles, with
+// the first being a single tile, and each one after it twice as wide.
+// (Being a whole number of tiles wide, every tile of every level
+// is a square; see deepzoom_tile_info().)
+fn deepzoom_full_size(tile_size: usize, number_of_levels: usize) -> usize {
+    tile_size << (number_of_levels - 1)
+}
+
+
+// Returns the width (and height) of each level of a Deep Zoom image
+// with the given full size, from level 0 (1 x 1 pixels) up to the full
+// size, with each level half the size (rounded up) of the next one, as
+// Deep Zoom viewers expect.
+fn deepzoom_level_sizes(full_size: usize) -> Vec<usize> {
+    let mut sizes = vec![full_size];
+    while *sizes.last().unwrap() > 1 {
+        sizes.push(sizes.last().unwrap().div_ceil(2));
+    }
+    sizes.reverse();
+    sizes
+}
+
+
+// Returns the Deep Zoom descriptor (the .dzi file) of a square image
+// of the given full size, split into PNG tiles of the given size.
+fn deepzoom_descriptor(full_size: usize, tile_size: usize) -> String {
+    format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <Image xmlns=\"http://schemas.microsoft.com/deepzoom/2008\" TileSize=\"{tile_size}\" Overlap=\"0\" Format=\"png\">\n  \
+             <Size Width=\"{full_size}\" Height=\"{full_size}\"/>\n\
+             </Image>\n")
+}
+
+
+// Returns the viewport of the tile (at the given tile row & column) of
+// a level whose viewport is described by level_info, so that drawing
+// the tile draws exactly those pixels of the level.  (The tiles on the
+// right and bottom edges are cut short if the level isn't a whole
+// number of tiles wide; that only happens to levels that are smaller
+// than one tile, whose one tile is the whole (square) level.)
+fn deepzoom_tile_info(level_info: &WindowAndViewportInfo, tile_size: usize,
+                      (tile_row, tile_column): (usize, usize)) -> WindowAndViewportInfo {
+    let (first_row, first_column) = (tile_row * tile_size, tile_column * tile_size);
+    let width = tile_size.min(level_info.width - first_column);
+    let height = tile_size.min(level_info.height - first_row);
+    let center_x = level_info.min_x + level_info.delta_x * (first_column as Float + width as Float / 2.0);
+    let center_y = level_info.max_y - level_info.delta_y * (first_row as Float + height as Float / 2.0);
+    WindowAndViewportInfo::new(width, height, center_x, center_y,
+                               level_info.delta_x * width as Float / 2.0, level_info.zoom_level)
+}
+
+
+// Draws the tiles of every level of a Deep Zoom image of the view in
+// the config (with the given tile size and number of levels; see
+// deepzoom_full_size()), and saves them in the layout Deep Zoom viewers
+// read:  the descriptor in PATH.dzi, and the tiles in PATH_files, with
+// a directory for each level (0 being the smallest) holding its tiles
+// as COLUMN_ROW.png.  Returns the number of tiles saved.
+fn save_deepzoom(path: &str, config: &Config, program_config: &ProgramConfig,
+                 tile_size: usize, number_of_levels: usize) -> Result<usize, String> {
+    let full_size = deepzoom_full_size(tile_size, number_of_levels);
+    let descriptor_filename = format!("{path}.dzi");
+    if let Some(directory) = std::path::Path::new(&descriptor_filename).parent().filter(|directory| !directory.as_os_str().is_empty()) {
+        std::fs::create_dir_all(directory)
+            .map_err(|error| format!("Unable to create the directory \"{}\":  {error}", directory.display()))?;
+    }
+    std::fs::write(&descriptor_filename, deepzoom_descriptor(full_size, tile_size))
+        .map_err(|error| format!("Unable to save a file named \"{descriptor_filename}\":  {error}"))?;
+
+    let (center_x, center_y) = program_config.center;
+    let level_sizes = deepzoom_level_sizes(full_size);
+    let mut number_of_tiles = 0;
+    for (level, &level_size) in level_sizes.iter().enumerate() {
+        let level_directory = format!("{path}_files/{level}");
+        std::fs::create_dir_all(&level_directory)
+            .map_err(|error| format!("Unable to create the directory \"{level_directory}\":  {error}"))?;
+        let level_info = WindowAndViewportInfo::new(level_size, level_size, center_x, center_y,
+                                                    program_config.distance_from_center_to_edge, 0);
+        let tiles_across = level_size.div_ceil(tile_size);
+        if !program_config.quiet {
+            let number_of_level_tiles = tiles_across * tiles_across;
+            println!("Level {level} of {}:  {level_size} x {level_size} pixels, in {number_of_level_tiles} tile{}.",
+                     level_sizes.len() - 1, if number_of_level_tiles == 1 { "" } else { "s" });
+        }
+        for tile_row in 0..tiles_across {
+            for tile_column in 0..tiles_across {
+                let tile_info = deepzoom_tile_info(&level_info, tile_size, (tile_row, tile_column));
+                let mut tile = vec![0; tile_info.width * tile_info.height];
+                render_into_buffer(&mut tile, &tile_info, config);
+                let tile_filename = format!("{level_directory}/{tile_column}_{tile_row}.png");
+                rgb_image(&tile, tile_info.width, tile_info.height).save(&tile_filename)
+                    .map_err(|error| format!("Unable to save a tile to a file named \"{tile_filename}\":  {error}"))?;
+                number_of_tiles += 1;
+            }
+        }
+    }
+    Ok(number_of_tiles)
+}
+
+
+// The ways coordinates can be printed with the C key (see --coord-format).
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CoordinateFormat {
+    Decimal,      // (Rounded to more decimal places the more it's zoomed in.)
+    Scientific,   // (Like 1.2345678e-9, for deep zooms.)
+    CommandLine,  // (The switches that would start the program with the same view.)
+}
+impl CoordinateFormat {
+    // Returns the CoordinateFormat with the given name
+    // (as used by --coord-format=TYPE), if there is one.
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "decimal" => Some(CoordinateFormat::Decimal),
+            "scientific" => Some(CoordinateFormat::Scientific),
+            "command-line" => Some(CoordinateFormat::CommandLine),
+            _ => None,
+        }
+    }
+}
+
+
+// Prints screen coordinates and mouse coordinates to the console.
+fn print_coordinates(window: &minifb::Window,
+                     info: &WindowAndViewportInfo,
+                     config: &Config,
+                     format: CoordinateFormat) {
+    let (mouse_column, mouse_row) = window.get_mouse_pos(minifb::MouseMode::Pass).unwrap();
+    let mouse_cursor = convert_row_and_column_to_x_and_y(
+                           &info,
+                           mouse_row as Float, mouse_column as Float);
+    print!("{}", format_coordinates(info, mouse_cursor, config, format));
+}
+
+
+// Returns how many decimal places coordinates should be rounded to
+// in the given viewport:  enough that neighboring pixels still get
+// different coordinates, with one more to spare (but never fewer
+// than 7, which used to always be used).  A fixed number of decimal
+// places isn't enough, since once the image is zoomed in far
+// enough, all four corners would be rounded to the same numbers.
+fn decimal_places_for(info: &WindowAndViewportInfo) -> isize {
+    let pixel_size = info.smallest_pixel_size();
+    let decimal_places = (-pixel_size.log10()).ceil() + 1.0;
+    if decimal_places.is_finite() {
+        (decimal_places as isize).max(7)
+    } else {
+        7
+    }
+}
+
+
+// Returns the text that print_coordinates() prints:  the screen
+// coordinates (in a box showing the corners and the center) and
+// the mouse coordinates, in the given format.
+fn format_coordinates(info: &WindowAndViewportInfo,
+                      mouse_cursor: (Float, Float),
+                      config: &Config,
+                      format: CoordinateFormat) -> String {
+    let upper_left = (info.min_x, info.max_y);
+    let upper_right = (info.max_x, info.max_y);
+    let center = (info.center_x, info.center_y);
+    let lower_left = (info.min_x, info.min_y);
+    let lower_right = (info.max_x, info.min_y);
+
+    if format == CoordinateFormat::CommandLine {
+        // (Rust prints floats with as many digits as it takes to
+        // read them back exactly, so nothing is lost here.)
+        let mut command_line = format!("jlr-mandelbrot --center={},{} --distance={}",
+                                       info.center_x, info.center_y,
+                                       info.distance_from_center_to_edge);
+        if let Some((c_x, c_y)) = config.c {
+            command_line += &format!(" --julia={c_x},{c_y}");
+        }
+        if let Some(bailout) = config.bailout {
+            command_line += &format!(" --bailout={bailout}");
+        }
+        if info.projection != Projection::Rectangular {
+            command_line += &format!(" --projection={}", info.projection.name());
+        }
+        return format!("Command line:\n{command_line}\nMouse coordinates:  {},{}\n",
+                       mouse_cursor.0, mouse_cursor.1)
+    }
+
+    // We want to round the numbers to use only a specified
+    // number of digits of precision, so that they don't take
+    // up too much of the line (but enough of them to tell
+    // neighboring pixels apart; see decimal_places_for()):
+    let decimal_places = decimal_places_for(info);
+    let round_tuple_of_floats = |p: (Float, Float), decimal_places: isize| -> (Float, Float) {
+        let p0 = p.0 * (10.0 as Float).powi(decimal_places as i32);
+        let p0 = p0.round();
+        let p0 = p0 / (10.0 as Float).powi(decimal_places as i32);
+        let p1 = p.1 * (10.0 as Float).powi(decimal_places as i32);
+        let p1 = p1.round();
+        let p1 = p1 / (10.0 as Float).powi(decimal_places as i32);
+        (p0, p1)
+    };
+    let format_point = |p: (Float, Float)| -> String {
+        match format {
+            CoordinateFormat::Scientific => {
+                // (The digits after the decimal point of 1.2345e-9 go down
+                // to the same place as decimal_places would, but no further
+                // than a Float can tell apart, which is about 15 digits.)
+                let digits_for = |value: Float| -> usize {
+                    let magnitude = value.abs().log10().floor();
+                    let magnitude = if magnitude.is_finite() { magnitude as isize } else { 0 };
+                    (decimal_places + magnitude).clamp(1, 15) as usize
+                };
+                format!("({:.*e}, {:.*e})", digits_for(p.0), p.0, digits_for(p.1), p.1)
+            }
+            _ => format!("{:?}", round_tuple_of_floats(p, decimal_places)),
+        }
+    };
+    let corners = [format_point(upper_left), format_point(upper_right),
+                   format_point(lower_left), format_point(lower_right)];
+    // (The box gets wider if the numbers don't fit.)
+    let width = corners.iter().map(|corner| corner.len()).max().unwrap().max(29);
+    let line = "-".repeat(2 * width + 4);
+    format!("Screen coordinates:
+{line}
+|{: <width$}  {: >width$}|
+|{: ^full_width$}|
+|{: <width$}  {: >width$}|
+{line}
+Mouse coordinates:  {}
+",
+        corners[0], corners[1],
+        format_point(center),
+        corners[2], corners[3],
+        format_point(mouse_cursor),
+        full_width = 2 * width + 2)
+}
+
+
+// Orbits longer than this have only their first and last
+// ORBIT_PRINT_LIMIT / 2 values printed by format_orbit().
+const ORBIT_PRINT_LIMIT: usize = 1000;
+
+
+// Prints the orbit of the point (x, y) to the console, along
+// with what was decided about the point (see format_orbit()).
+// The orbit is returned, so that it can be drawn, too.
+fn print_orbit(x: Float, y: Float, info: &WindowAndViewportInfo, config: &Config) -> Vec<(Float, Float)> {
+    let threshold = cycle_threshold(info, config);
+    let (details, orbit) = calculate_orbit(x, y, config, threshold);
+    print!("{}", format_orbit(x, y, &details, &orbit));
+    orbit
+}
+
+
+// Returns the text that print_orbit() prints:  every z of the orbit
+// (numbered by iteration), followed by the point's verdict.
+fn format_orbit(x: Float, y: Float,
+                details: &EscapeDetails,
+                orbit: &[(Float, Float)]) -> String {
+    let mut text = format!("Orbit of ({x}, {y}):\n");
+    for (iteration, &(z_x, z_y)) in orbit.iter().enumerate() {
+        if orbit.len() > ORBIT_PRINT_LIMIT && iteration == ORBIT_PRINT_LIMIT / 2 {
+            text += &format!("   ... ({} more) ...\n", orbit.len() - ORBIT_PRINT_LIMIT);
+        }
+        if orbit.len() > ORBIT_PRINT_LIMIT
+           && (ORBIT_PRINT_LIMIT / 2 .. orbit.len() - ORBIT_PRINT_LIMIT / 2).contains(&iteration) {
+            continue  // (Skipped, so as not to flood the console.)
+        }
+        text += &format!("   {iteration:>8}:  ({z_x}, {z_y})\n");
+    }
+    let verdict = match details {
+        EscapeDetails { escape_value: Some(escape_value), .. } =>
+            format!("Escaped after {escape_value} iterations."),
+        EscapeDetails { hit_bailout: true, iterations, .. } =>
+            format!("Hit the bailout after {iterations} iterations, so it's taken to be in the set."),
+        EscapeDetails { period: Some(period), iterations, .. } =>
+            format!("Fell into a cycle (of period {period}) after {iterations} iterations, so it's in the set."),
+        EscapeDetails { iterations, .. } =>
+            format!("Fell into a cycle after {iterations} iterations, so it's in the set."),
+    };
+    text + &format!("Verdict:  {verdict}\n")
+}
+
+
+// This enum reflects the user's choices.
+enum UserInput {
+    Nothing,
+    Quit,
+    SaveScreenShot,
+    SaveCommandLine,
+    ShowCoordinates,
+    ToggleCrosshair,
+    ToggleMinimap,
+    ToggleBailoutHeatmap,
+    ToggleOrbit,
+    TogglePixelInfo,
+    ToggleRenderStats,
+    ToggleDiagnostics,
+    ZoomIn(Float, Float),  // (x, y) of the new center.  (Where the user clicked.)
+    ZoomOut(Float, Float),  // (x, y) of the new center.  (NOT where the user clicked!)
+    GoTo(Float, Float, Float),  // (x, y) of the new center, and the new distance from center to edge.
+    SetBailout(usize),
+    IncreaseBailout,
+    DecreaseBailout,
+    IncreaseColorPeriod,
+    DecreaseColorPeriod,
+    SaveScreenShotAs(String),  // (The filename to save the screenshot to.)
+    PrintOrbit(Float, Float),  // (x, y) of the point whose orbit to print.  (Where the user clicked.)
+    NextFractal,
+    ToggleColorCycle,
+    Nudge(isize, isize),  // (columns, rows) to move the center by.  (Shift+arrow keys.)
+    IncreaseAaSamples,
+    DecreaseAaSamples,
+    SaveBookmark(usize),  // (The number of the bookmark, from 1 to NUMBER_OF_BOOKMARKS.)
+    GoToBookmark(usize),  // (Ditto.)
+}
+impl UserInput {
+    // Returns true for the inputs that change the fractal image itself
+    // (like zooming in, or changing the bailout), so it has to be drawn
+    // again from scratch.  The rest only change what's shown on top of
+    // it, or what's done with it, so they can be handled in the middle
+    // of drawing an image without starting it over (see
+    // handle_display_input()).
+    //
+    // (Changing the color period, or turning color cycling on or off,
+    // only needs a finished image to be recolored, but an image that's
+    // still being drawn is started over, so that none of its pixels are
+    // left with the old colors.  handle_display_input() can't handle
+    // them, as they change the main loop's own settings.)
+    fn changes_the_image(&self) -> bool {
+        match self {
+            UserInput::ZoomIn(..) | UserInput::ZoomOut(..) | UserInput::GoTo(..)
+            | UserInput::SetBailout(_) | UserInput::IncreaseBailout | UserInput::DecreaseBailout
+            | UserInput::IncreaseColorPeriod | UserInput::DecreaseColorPeriod | UserInput::ToggleColorCycle
+            | UserInput::NextFractal | UserInput::Nudge(..)
+            | UserInput::IncreaseAaSamples | UserInput::DecreaseAaSamples | UserInput::GoToBookmark(_) => true,
+            UserInput::Nothing | UserInput::Quit | UserInput::SaveScreenShot | UserInput::SaveCommandLine
+            | UserInput::ShowCoordinates | UserInput::ToggleCrosshair | UserInput::ToggleMinimap | UserInput::ToggleBailoutHeatmap
+            | UserInput::ToggleOrbit | UserInput::TogglePixelInfo | UserInput::ToggleRenderStats | UserInput::ToggleDiagnostics
+            | UserInput::SaveScreenShotAs(_)
+            | UserInput::PrintOrbit(..) | UserInput::SaveBookmark(_) => false,
+        }
+    }
+}
+
+
+// Handles the user inputs that don't change the fractal image (see
+// UserInput::changes_the_image()), whether or not the image is done
+// being drawn.  (Inputs that do change it, along with Quit, are
+// left for the main event loop to handle, and are ignored here.)
+// Afterwards, user_input is set to UserInput::Nothing, so that
+// (for example) a toggle isn't toggled right back.  (The escape
+// details are those of the image_buffer, for --depth=16 screenshots.)
+fn handle_display_input(user_input: &mut UserInput,
+                        window: &minifb::Window,
+                        (image_buffer, escape_details): (&[u32], &[EscapeDetails]),
+                        (overlays, bookmarks): (&mut Overlays, &mut Bookmarks),
+                        info: &WindowAndViewportInfo,
+                        (config, program_config): (&Config, &ProgramConfig),
+                        coordinate_format: CoordinateFormat) {
+    match user_input {
+        UserInput::SaveScreenShot | UserInput::SaveScreenShotAs(_) => {
+            // (With --screenshot-ssaa, the image is drawn again,
+            // anti-aliased, and that's what gets saved instead.)
+            let supersampled = program_config.screenshot_aa_samples.map(|number_of_samples| {
+                let start_time = std::time::Instant::now();
+                let supersampled = supersampled_screenshot(info, config, number_of_samples);
+                println!("Drew the screenshot with {number_of_samples} samples per pixel in {} sec.",
+                         start_time.elapsed().as_micros() as Float / 1e6);
+                supersampled
+            });
+            let (image_buffer, escape_details, config) = match &supersampled {
+                Some((image_buffer, escape_details, config)) => (&image_buffer[..], &escape_details[..], config),
+                None => (image_buffer, escape_details, config),
+            };
+            let screenshot_buffer = screenshot_image(image_buffer, overlays, info.width, info.height);
+            let result = match user_input {
+                UserInput::SaveScreenShotAs(filename) =>
+                    save_screenshot_to_filename(&screenshot_buffer, escape_details, info, config, program_config, filename),
+                _ => save_screenshot(&screenshot_buffer, escape_details, info, config, program_config),
+            };
+            if let Err(message) = result {
+                eprintln!("Error:  {message}");
+            }
+        }
+        UserInput::ShowCoordinates => print_coordinates(window, info, config, coordinate_format),
+        UserInput::SaveCommandLine => {
+            match save_command_line(info, config) {
+                Ok(filename) => println!("Saved the command line for this view to \"{filename}\"."),
+                Err(message) => eprintln!("Error:  {message}"),
+            }
+        }
+        UserInput::PrintOrbit(x, y) => overlays.show_orbit(print_orbit(*x, *y, info, config)),
+        UserInput::ToggleOrbit => {
+            if !overlays.toggle_orbit() {
+                println!("(There's no orbit to show yet.  Ctrl+click on a point to show its orbit.)");
+            }
+        }
+        UserInput::ToggleCrosshair => overlays.show_crosshair = !overlays.show_crosshair,
+        UserInput::TogglePixelInfo => overlays.show_pixel_info = !overlays.show_pixel_info,
+        UserInput::ToggleRenderStats => overlays.show_render_stats = !overlays.show_render_stats,
+        UserInput::ToggleDiagnostics => overlays.show_diagnostics = !overlays.show_diagnostics,
+        UserInput::SaveBookmark(number) => {
+            bookmarks.save(*number, info);
+            println!("Bookmarked this view as {number}.  (Press {number} to come back to it.)");
+        }
+        UserInput::ToggleMinimap => overlays.toggle_minimap(info, config.c),
+        UserInput::ToggleBailoutHeatmap => {
+            overlays.show_bailout_heatmap = !overlays.show_bailout_heatmap;
+            if overlays.show_bailout_heatmap && config.bailout.is_none() {
+                println!("(No bailout is being used, so no pixels will be tinted.)");
+            }
+        }
+        _ => return (),  // (Left for the main event loop.)
+    }
+    *user_input = UserInput::Nothing;
+}
+
+
+// This enum reflects the commands that can be given in a script
+// (specified with the --script=FILE switch).  Each line of the
+// script can hold one command, and they look like this:
+//
+//    goto X Y DISTANCE   (or:  goto X,Y DISTANCE)
+//    zoomin FACTOR
+//    zoomout FACTOR
+//    pan DX DY
+//    bailout NUMBER
+//    save FILENAME
+//    wait MILLISECONDS
+//    quit
+//
+// (Blank lines and lines starting with a '#' are ignored.)
+//
+// zoomin, zoomout, and pan are relative to the current view:  zoomin 2
+// zooms in (keeping the center) by a factor of 2, and pan 0.1 -0.1
+// moves the center right by a tenth of the view's width, and down by
+// a tenth of its height.
+#[derive(Debug, PartialEq)]
+enum ScriptCommand {
+    GoTo(Float, Float, Float),  // (x, y) of the new center, and the new distance from center to edge.
+    ZoomIn(Float),  // (The factor to zoom in by.)
+    ZoomOut(Float),  // (The factor to zoom out by.)
+    Pan(Float, Float),  // (How far to move the center right and up, in fractions of the view's span.)
+    Bailout(usize),
+    Save(String),  // (The filename to save the screenshot to.)
+    Wait(u64),  // (in milliseconds)
+    Quit,
+}
+impl ScriptCommand {
+    // Converts this command into the UserInput that does the same
+    // thing, so that the main loop can handle both the same way.
+    // (Returns None for commands that have no UserInput equivalent.)
+    // The relative commands are turned into a GoTo from the current
+    // view, described by info.
+    fn to_user_input(&self, info: &WindowAndViewportInfo) -> Option<UserInput> {
+        let (center_x, center_y, distance) = (info.center_x, info.center_y, info.distance_from_center_to_edge);
+        match self {
+            ScriptCommand::GoTo(x, y, distance) => Some(UserInput::GoTo(*x, *y, *distance)),
+            ScriptCommand::ZoomIn(factor) => Some(UserInput::GoTo(center_x, center_y, distance / factor)),
+            ScriptCommand::ZoomOut(factor) => Some(UserInput::GoTo(center_x, center_y, distance * factor)),
+            ScriptCommand::Pan(dx, dy) => Some(UserInput::GoTo(center_x + dx * info.span, center_y + dy * info.span, distance)),
+            ScriptCommand::Bailout(bailout) => Some(UserInput::SetBailout(*bailout)),
+            ScriptCommand::Save(filename) => Some(UserInput::SaveScreenShotAs(filename.clone())),
+            ScriptCommand::Wait(_) => None,
+            ScriptCommand::Quit => Some(UserInput::Quit),
+        }
+    }
+}
+
+
+// Parses one line of a script into a ScriptCommand.
+// Returns Ok(None) if the line is blank or is a comment,
+// and an error message if the line can't be understood.
+fn parse_script_line(line: &str) -> Result<Option<ScriptCommand>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None)
+    }
+
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let (command, arguments) = (words[0], &words[1..]);
+
+    match command {
+        "goto" => {
+            // (The X and Y can also be given together, as X,Y.)
+            let ((x, y), distance_text) = match arguments {
+                [x_text, y_text, distance_text] => ((parse_number("X", x_text)?, parse_number("Y", y_text)?),
+                                                    distance_text),
+                [x_and_y_text, distance_text] if x_and_y_text.contains(',') => {
+                    (parse_coordinate_pair(x_and_y_text)
+                         .map_err(|message| format!("The X,Y value (\"{x_and_y_text}\") is not valid:  {message}"))?,
+                     distance_text)
+                }
+                _ => return Err(format!("The goto command needs exactly three values (X Y DISTANCE), not {}.",
+                                        arguments.len())),
+            };
+            let distance = parse_number("DISTANCE", distance_text)?;
+            if !distance.is_finite() || distance <= 0.0 {
+                return Err("The DISTANCE of the goto command must be a (finite) number more than zero.".to_string())
+            }
+            Ok(Some(ScriptCommand::GoTo(x, y, distance)))
+        }
+        "zoomin" | "zoomout" => {
+            if arguments.len() != 1 {
+                return Err(format!("The {command} command needs exactly one value (FACTOR)."))
+            }
+            let factor = parse_number("FACTOR", arguments[0])?;
+            if factor <= 0.0 {
+                return Err(format!("The FACTOR of the {command} command must be a number more than zero."))
+            }
+            Ok(Some(if command == "zoomin" { ScriptCommand::ZoomIn(factor) } else { ScriptCommand::ZoomOut(factor) }))
+        }
+        "pan" => {
+            if arguments.len() != 2 {
+                return Err(format!("The pan command needs exactly two values (DX DY), not {}.", arguments.len()))
+            }
+            Ok(Some(ScriptCommand::Pan(parse_number("DX", arguments[0])?, parse_number("DY", arguments[1])?)))
+        }
+        "bailout" => {
+            if arguments.len() != 1 {
+                return Err("The bailout command needs exactly one value (NUMBER).".to_string())
+            }
+            match arguments[0].parse() {
+                Ok(bailout) => Ok(Some(ScriptCommand::Bailout(bailout))),
+                _ => Err(format!("\"{}\" is not a valid bailout number.", arguments[0])),
+            }
+        }
+        "save" => {
+            // The filename is everything after the "save" command,
+            // so that it can contain spaces:
+            let filename = line["save".len()..].trim();
+            if filename.is_empty() {
+                return Err("The save command needs a FILENAME.".to_string())
+            }
+            Ok(Some(ScriptCommand::Save(filename.to_string())))
+        }
+        "wait" => {
+            if arguments.len() != 1 {
+                return Err("The wait command needs exactly one value (MILLISECONDS).".to_string())
+            }
+            match arguments[0].parse() {
+                Ok(milliseconds) => Ok(Some(ScriptCommand::Wait(milliseconds))),
+                _ => Err(format!("\"{}\" is not a valid number of milliseconds.", arguments[0])),
+            }
+        }
+        "quit" => {
+            if !arguments.is_empty() {
+                return Err("The quit command takes no values.".to_string())
+            }
+            Ok(Some(ScriptCommand::Quit))
+        }
+        _ => Err(format!("Unknown command \"{command}\"."))
+    }
+}
+
+
+// Starts a thread that reads script commands (one per line)
+// from the file with the given filename (or from stdin if the
+// filename is "-"), and returns a channel Receiver that the
+// commands can be fetched from as they become available.
+//
+// Lines that can't be parsed are reported (to stdout) and skipped.
+fn start_reading_script(filename: &str) -> std::sync::mpsc::Receiver<ScriptCommand> {
+    use std::io::BufRead;
+
+    let reader: Box<dyn BufRead + Send> = if filename == "-" {
+        Box::new(std::io::BufReader::new(std::io::stdin()))
+    } else {
+        match std::fs::File::open(filename) {
+            Ok(file) => Box::new(std::io::BufReader::new(file)),
+            Err(error) => {
+                eprintln!("Error:  Unable to open the script file \"{filename}\":  {error}");
+                std::process::exit(EXIT_IO_ERROR)
+            }
+        }
+    };
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for (line_number, line) in reader.lines().enumerate() {
+            let line = match line {
+                Ok(line) => line,
+                Err(error) => {
+                    eprintln!("Error:  Unable to read line {} of the script:  {error}", line_number + 1);
+                    break
+                }
+            };
+            match parse_script_line(&line) {
+                Ok(Some(command)) => {
+                    if sender.send(command).is_err() {
+                        break  // (The main loop is gone, so there's no point in continuing.)
+                    }
+                }
+                Ok(None) => (),  // (Blank line or comment.)
+                Err(message) => eprintln!("Error:  Line {} of the script:  {message}", line_number + 1),
+            }
+        }
+    });
+
+    receiver
+}
+
+
+// Rings the console's bell (for --notify), so that someone doing
+// something else while a long image is drawn knows it's finished.
+// (The bell character is flushed right away, as print!() without a
+// newline would otherwise leave it waiting in stdout's buffer.)
+fn ring_bell() {
+    use std::io::Write;
+    print!("\x07");
+    let _ = std::io::stdout().flush();
+}
+
+
+// How often (in milliseconds) the window is checked
+// while it isn't active (see wait_while_inactive()).
+const INACTIVE_UPDATE_INTERVAL: u64 = 100;
+
+
+// Waits (checking the window every INACTIVE_UPDATE_INTERVAL) until
+// the window is active again, or closed, so that an image isn't drawn
+// while the window is minimized or another window has the focus.
+//
+// The click that makes the window active again isn't a zoom, so this
+// also waits for the mouse buttons to be let go of, and then forgets
+// that they were pressed.
+fn wait_while_inactive(window: &mut minifb::Window, mouse_info: &mut MouseInfo, quiet: bool) {
+    if !quiet {
+        println!("(Drawing is paused until the window is active again.)");
+    }
+    let interval = std::time::Duration::from_millis(INACTIVE_UPDATE_INTERVAL);
+    while window.is_open() && (!window.is_active()
+                               || window.get_mouse_down(minifb::MouseButton::Left)
+                               || window.get_mouse_down(minifb::MouseButton::Right)) {
+        std::thread::sleep(interval);
+        window.update();
+    }
+    // (Twice, so that neither button was "just released.")
+    mouse_info.set_mouse_buttons_pressed(false, false);
+    mouse_info.set_mouse_buttons_pressed(false, false);
+}
+
+
+// Based on the Window and WindowAndViewportInfo, this checks to see
+// if the user gave any input, returning all of it (in the order it
+// should be handled), so that pressing a key and clicking in the same
+// frame doesn't lose either one.  Quitting wins over everything else,
+// so if the user wants to quit, that's the only input returned.
+fn get_user_inputs(window: &minifb::Window,
+                   info: &WindowAndViewportInfo,
+                   mouse_info: &mut MouseInfo) -> Vec<UserInput> {
+
+    mouse_info.set_mouse_buttons_pressed(
+                window.get_mouse_down(minifb::MouseButton::Left),
+                window.get_mouse_down(minifb::MouseButton::Right));
+
+    if !window.is_open() || window.is_key_down(minifb::Key::Escape)
+                         || window.is_key_down(minifb::Key::Q) {
+        return vec![UserInput::Quit]
+    }
+
+    let mut user_inputs = Vec::new();
+    // (Each key is only checked for being released, so this is
+    // usually empty, or has just one input.)
+    let key_inputs = [
+        (minifb::Key::S, UserInput::SaveScreenShot),  // S => Save ScreenShot
+        (minifb::Key::B, UserInput::SaveCommandLine),  // B => Bookmark (the command line for this view)
+        (minifb::Key::C, UserInput::ShowCoordinates),  // C => Coordinates
+        (minifb::Key::X, UserInput::ToggleCrosshair),  // X => (X marks the spot)
+        (minifb::Key::M, UserInput::ToggleMinimap),  // M => Minimap
+        (minifb::Key::H, UserInput::ToggleBailoutHeatmap),  // H => Heatmap
+        (minifb::Key::O, UserInput::ToggleOrbit),  // O => Orbit
+        (minifb::Key::A, UserInput::ToggleColorCycle),  // A => Animate (the colors)
+        (minifb::Key::F, UserInput::NextFractal),  // F => Fractal (the next one)
+        (minifb::Key::I, UserInput::TogglePixelInfo),  // I => Info (about the pixel under the mouse)
+        (minifb::Key::T, UserInput::ToggleRenderStats),  // T => Time (it took to draw the image)
+        (minifb::Key::D, UserInput::ToggleDiagnostics),  // D => Diagnostics
+        (minifb::Key::RightBracket, UserInput::ZoomIn(info.center_x, info.center_y)),  // ] => Zoom in, keeping the center.
+        (minifb::Key::LeftBracket, UserInput::ZoomOut(info.center_x, info.center_y)),  // [ => Zoom out, keeping the center.
+        (minifb::Key::Equal, UserInput::IncreaseBailout),  // (The + key shares its key with =.)
+        (minifb::Key::NumPadPlus, UserInput::IncreaseBailout),
+        (minifb::Key::Minus, UserInput::DecreaseBailout),
+        (minifb::Key::NumPadMinus, UserInput::DecreaseBailout),
+        (minifb::Key::Period, UserInput::IncreaseColorPeriod),  // (The > key shares its key with the period.)
+        (minifb::Key::Comma, UserInput::DecreaseColorPeriod),  // (The < key shares its key with the comma.)
+    ];
+    for (key, user_input) in key_inputs {
+        if window.is_key_released(key) {
+            user_inputs.push(user_input);
+        }
+    }
+
+    let shift_down = window.is_key_down(minifb::Key::LeftShift) || window.is_key_down(minifb::Key::RightShift);
+
+    // Z => Zoom in on the mouse (and Shift+Z => Zoom out from it),
+    // just as clicking there would, but without having to click:
+    if window.is_key_released(minifb::Key::Z) {
+        let (x, y) = keyboard_zoom_target(info, window.get_mouse_pos(minifb::MouseMode::Discard));
+        if shift_down {
+            let (center_x, center_y) = zoom_out_center(info, x, y);
+            user_inputs.push(UserInput::ZoomOut(center_x, center_y));
+        } else {
+            user_inputs.push(UserInput::ZoomIn(x, y));
+        }
+    }
+
+    // 1 through 8 => Go to that bookmark (and Shift+1 through Shift+8
+    // => Bookmark the current view under that number):
+    let number_keys = [minifb::Key::Key1, minifb::Key::Key2, minifb::Key::Key3, minifb::Key::Key4,
+                       minifb::Key::Key5, minifb::Key::Key6, minifb::Key::Key7, minifb::Key::Key8];
+    for (i, key) in number_keys.into_iter().enumerate() {
+        if window.is_key_released(key) {
+            user_inputs.push(if shift_down { UserInput::SaveBookmark(i + 1) } else { UserInput::GoToBookmark(i + 1) });
+        }
+    }
+
+    if shift_down {
+        // ) => More anti-aliasing samples, and ( => fewer.  (On most
+        // keyboards, they share their keys with 0 and 9.)
+        if window.is_key_released(minifb::Key::Key0) {
+            user_inputs.push(UserInput::IncreaseAaSamples);
+        }
+        if window.is_key_released(minifb::Key::Key9) {
+            user_inputs.push(UserInput::DecreaseAaSamples);
+        }
+
+        // Shift+arrow keys => Nudge the center by a single pixel.
+        let nudges = [(minifb::Key::Left, (-1, 0)), (minifb::Key::Right, (1, 0)),
+                      (minifb::Key::Up, (0, -1)), (minifb::Key::Down, (0, 1))];
+        for (key, (columns, rows)) in nudges {
+            if window.is_key_released(key) {
+                user_inputs.push(UserInput::Nudge(columns, rows));
+            }
+        }
+    }
+
+    if mouse_info.left_mouse_button_just_released() {  // (Left mouse button WAS down, but no longer.)
+        let (column, row) = window.get_mouse_pos(minifb::MouseMode::Pass).unwrap();
+        let (x, y) = convert_row_and_column_to_x_and_y(&info, row as Float, column as Float);
+        if window.is_key_down(minifb::Key::LeftCtrl) || window.is_key_down(minifb::Key::RightCtrl) {
+            user_inputs.push(UserInput::PrintOrbit(x, y));  // (Ctrl+click prints the orbit instead of zooming in.)
+        } else {
+            user_inputs.push(UserInput::ZoomIn(x, y));
+        }
+    }
+    if mouse_info.right_mouse_button_just_released() {  // (Right mouse button WAS down, but no longer.)
+        let (column, row) = window.get_mouse_pos(minifb::MouseMode::Pass).unwrap();
+        let (x, y) = convert_row_and_column_to_x_and_y(&info, row as Float, column as Float);
+        let (center_x, center_y) = zoom_out_center(info, x, y);
+        user_inputs.push(UserInput::ZoomOut(center_x, center_y));
+    }
+
+    return user_inputs
+}
+
+
+// Returns the new center for zooming out from the point (x, y) (where
+// the user right-clicked).  This is NOT the point itself:  it's the
+// point mirrored through the current center, which (with the distance
+// doubled) keeps (x, y) right under the mouse.  (A right-click at the
+// center keeps the center, so it undoes a left-click there.)
+fn zoom_out_center(info: &WindowAndViewportInfo, x: Float, y: Float) -> (Float, Float) {
+    (2.0 * info.center_x - x, 2.0 * info.center_y - y)
+}
+
+
+// Returns the viewport after zooming in (for UserInput::ZoomIn)
+// or out (for UserInput::ZoomOut), with (x, y) as its new center.
+fn zoomed_info(info: &WindowAndViewportInfo, x: Float, y: Float, zoom_in: bool) -> WindowAndViewportInfo {
+    let (distance, zoom_level) = if zoom_in {
+        (info.distance_from_center_to_edge / 2.0, info.zoom_level + 1)
+    } else {
+        (info.distance_from_center_to_edge * 2.0, info.zoom_level - 1)
+    };
+    WindowAndViewportInfo::new(info.width, info.height, x, y, distance, zoom_level)
+        .with_projection(info.projection)
+}
+
+
+// Returns the point that the Z key zooms in on:  the one under the
+// mouse (whose position, as (column, row), is None when it's outside
+// of the window), or the center, if the mouse isn't over the window.
+fn keyboard_zoom_target(info: &WindowAndViewportInfo,
+                        mouse_position: Option<(f32, f32)>) -> (Float, Float) {
+    match mouse_position {
+        Some((column, row)) => convert_row_and_column_to_x_and_y(info, row as Float, column as Float),
+        None => (info.center_x, info.center_y),
+    }
+}
+
+
+// How many views can be bookmarked (see Bookmarks), one for each of
+// the number keys from 1 to 8.  (Shift+9 and Shift+0 are already the
+// ( and ) keys, so there's no bookmark 9.)
+const NUMBER_OF_BOOKMARKS: usize = 8;
+
+
+// The views bookmarked (with Shift and a number key) while the program
+// runs, to jump back to (with just the number key).  Each one is the
+// (x, y) of the view's center, and its distance from the center to the
+// edge, just like a UserInput::GoTo has.  (They're forgotten once the
+// program exits.  To keep a view for later, use the B key.)
+#[derive(Debug, Default)]
+struct Bookmarks {
+    views: [Option<(Float, Float, Float)>; NUMBER_OF_BOOKMARKS],
+}
+impl Bookmarks {
+    // Bookmarks the view described by info under the given number
+    // (from 1 to NUMBER_OF_BOOKMARKS), in place of any view that
+    // was bookmarked under it before.
+    fn save(&mut self, number: usize, info: &WindowAndViewportInfo) {
+        self.views[number - 1] = Some((info.center_x, info.center_y, info.distance_from_center_to_edge));
+    }
+
+    // Returns the input that goes to the view bookmarked under the
+    // given number, or None if no view was bookmarked under it yet.
+    fn go_to(&self, number: usize) -> Option<UserInput> {
+        self.views[number - 1].map(|(x, y, distance_from_center_to_edge)| UserInput::GoTo(x, y, distance_from_center_to_edge))
+    }
+}
+
+
+// Adds the new_inputs (from get_user_inputs()) to the end of the
+// pending_inputs, and returns the first one of them to be handled
+// (or UserInput::Nothing, if there aren't any).  The rest are left
+// for the next times this is called, which is once per frame, so
+// that no input is lost when several come in the same frame.  But
+// quitting doesn't wait behind the others:  it's returned at once.
+fn next_user_input(pending_inputs: &mut std::collections::VecDeque<UserInput>,
+                   new_inputs: Vec<UserInput>) -> UserInput {
+    if new_inputs.iter().any(|user_input| matches!(user_input, UserInput::Quit)) {
+        pending_inputs.clear();
+        return UserInput::Quit
+    }
+    pending_inputs.extend(new_inputs);
+    pending_inputs.pop_front().unwrap_or(UserInput::Nothing)
+}
+
+
+// Returns the bailout value to use after the + key (if increase is
+// true) or the - key (if increase is false) is pressed.  The bailout
+// is doubled or halved (but is never less than 1).  With no bailout
+// (that is, unlimited iterations) there is nothing to increase, and
+// decreasing starts from half the highest escape value in the current
+// image, so that the change is actually visible.
+fn adjusted_bailout(bailout: Option<usize>,
+                    increase: bool,
+                    highest_escape_value: Option<usize>) -> Option<usize> {
+    match (bailout, increase) {
+        (Some(bailout), true) => Some(bailout.saturating_mul(2)),
+        (Some(bailout), false) => Some((bailout / 2).max(1)),
+        (None, true) => None,
+        (None, false) => Some((highest_escape_value.unwrap_or(1) / 2).max(1)),
+    }
+}
+
+
+// The bailout that --bailout=auto uses for the whole set (or anything
+// zoomed out further), and how much it goes up every time the view is
+// zoomed in by another factor of two.
+const AUTO_BAILOUT_BASE: Float = 250.0;
+const AUTO_BAILOUT_PER_ZOOM_LEVEL: Float = 100.0;
+
+
+// Returns the bailout that --bailout=auto uses for a view with the
+// given distance from its center to its edge.  The deeper the zoom,
+// the longer the points near the boundary of the set take to escape
+// (so they need a higher bailout to not be drawn as part of it), so
+// the bailout goes up with the number of times the view of the whole
+// set (about 2 from the center to the edge) has been halved.
+fn automatic_bailout(distance_from_center_to_edge: Float) -> usize {
+    let zoom_levels = (2.0 / distance_from_center_to_edge).log2().max(0.0);
+    (AUTO_BAILOUT_BASE + AUTO_BAILOUT_PER_ZOOM_LEVEL * zoom_levels).round() as usize
+}
+
+
+// The smallest and biggest color periods (see --color-period) allowed.
+// (With a period of 1, every escaping point would get the same color.)
+const MIN_COLOR_PERIOD: usize = 2;
+const MAX_COLOR_PERIOD: usize = 1_000_000;
+
+
+// The strongest sharpening allowed (see --sharpen).  (Past this, the
+// image is mostly just black and white speckles.)
+const MAX_SHARPEN: Float = 5.0;
+
+
+// Returns the color period to use after the > key (if increase is
+// true) or the < key (if increase is false) is pressed.  The period
+// is made a quarter longer (or shorter), but always changes by at
+// least one iteration, and stays from MIN_COLOR_PERIOD up to
+// MAX_COLOR_PERIOD.
+fn adjusted_color_period(color_period: usize, increase: bool) -> usize {
+    let new_color_period = if increase {
+        ((color_period as Float * 1.25).round() as usize).max(color_period + 1)
+    } else {
+        ((color_period as Float / 1.25).round() as usize).min(color_period.saturating_sub(1))
+    };
+    new_color_period.clamp(MIN_COLOR_PERIOD, MAX_COLOR_PERIOD)
+}
+
+
+// The most anti-aliasing samples per pixel that the ) key goes up to.
+// (Past this, an image takes ages to draw, for next to no difference.)
+const MAX_AA_SAMPLES_KEY: usize = 64;
+
+
+// Returns the number of anti-aliasing samples per pixel (see
+// --aa-samples) to use after the ) key (if increase is true) or the
+// ( key (if increase is false) is pressed.  The number is doubled or
+// halved, where None (no anti-aliasing) counts as one sample, and
+// going down to one sample turns anti-aliasing off.  The ) key goes no
+// higher than MAX_AA_SAMPLES_KEY (but doesn't lower a number that was
+// already higher, from --aa-samples).
+fn adjusted_aa_samples(aa_samples: Option<usize>, increase: bool) -> Option<usize> {
+    let samples = aa_samples.unwrap_or(1);
+    let new_samples = if increase {
+        samples.saturating_mul(2).min(MAX_AA_SAMPLES_KEY).max(samples)
+    } else {
+        samples / 2
+    };
+    Some(new_samples).filter(|&samples| samples > 1)
+}
+
+
+// How long (in milliseconds) each color cycling (see --color-cycle)
+// step is shown before the palette is shifted by another color.
+const COLOR_CYCLE_INTERVAL: u64 = 40;
+
+
+// Returns the palette offset for the next step of color cycling:
+// one color further along the palette, wrapping around to 0 once
+// it's gone through the whole palette.
+fn next_palette_offset(palette_offset: usize) -> usize {
+    (palette_offset + 1) % NUM_COLORS
+}
+
+
+// Parses the NUMBER of --color-period=NUMBER.
+fn parse_color_period(color_period_text: &str) -> Result<usize, String> {
+    match color_period_text.parse() {
+        Ok(color_period) if (MIN_COLOR_PERIOD..=MAX_COLOR_PERIOD).contains(&color_period) => Ok(color_period),
+        _ => Err(format!("The color period of \"{color_period_text}\" must be a number from {MIN_COLOR_PERIOD} to {MAX_COLOR_PERIOD}.")),
+    }
+}
+
+
+#[allow(dead_code)]
+fn test_color_function() {
+    println!();
+    println!("Testing the color() function:");
+    let n = 0;   println!("{}: {:?}", n, color(Some(n)));
+    let n = 15;  println!("{}: {:?}", n, color(Some(n)));
+    let n = 30;  println!("{}: {:?}", n, color(Some(n)));
+    let n = 45;  println!("{}: {:?}", n, color(Some(n)));
+    let n = 60;  println!("{}: {:?}", n, color(Some(n)));
+    let n = 75;  println!("{}: {:?}", n, color(Some(n)));
+    let n = 90;  println!("{}: {:?}", n, color(Some(n)));
+    let n = None;  println!("{:?}: {:?}", n, color(n));
+    println!();
+}
+
+
+// Parses a number given by the user (such as the X of --center=X,Y,
+// whose name is given for the error messages), returning an error
+// message saying what's wrong with it if it's not a finite number.
+// Scientific notation (like 1e-3), a leading '+', surrounding spaces,
+// and the Unicode minus sign (which often comes along when copying
+// numbers from web pages) are all allowed.  (The decimal point is
+// always a '.', whatever the computer's language is set to.)
+fn parse_number(name: &str, text: &str) -> Result<Float, String> {
+    let trimmed_text = text.trim();
+    if trimmed_text.is_empty() {
+        return Err(format!("The {name} value is missing."))
+    }
+    match trimmed_text.replace('\u{2212}', "-").parse::<Float>() {
+        Ok(value) if value.is_finite() => Ok(value),
+        Ok(_) => Err(format!("The {name} value (\"{trimmed_text}\") is not a finite number.")),
+        _ => Err(format!("The {name} value (\"{trimmed_text}\") is not a valid number.")),
+    }
+}
+
+
+// Parses an "X,Y" pair of coordinates (like the ones given to
+// --center and --julia), returning an error message (saying which
+// of X or Y is wrong, and why) if they're not valid.  (See
+// parse_number() for what's allowed in each number.)
+fn parse_coordinate_pair(text: &str) -> Result<(Float, Float), String> {
+    if text.trim().is_empty() {
+        return Err("The X and Y values are both missing.".to_string())
+    }
+    let text_values: Vec<_> = text.split(",").collect();
+    match text_values.len() {
+        2 => Ok((parse_number("X", text_values[0])?, parse_number("Y", text_values[1])?)),
+        1 => Err("It needs a comma between the X and the Y.".to_string()),
+        _ => Err("It has more than one comma.  (Use a '.', not a ',', for the decimal point.)".to_string()),
+    }
+}
+
+
+// Parses an "R,G,B" color (where R, G, and B range from 0 to 255,
+// inclusive), returning an error message if it's not valid.
+fn parse_rgb_triplet(text: &str) -> Result<(u8, u8, u8), String> {
+    let text_values: Vec<_> = text.split(",").collect();
+    if text_values.len() != 3 {
+        return Err(format!("The color \"{text}\" needs exactly three values (R,G,B)."))
+    }
+    let mut values = [0u8; 3];
+    for (value, (value_text, name)) in values.iter_mut().zip(text_values.iter().zip(["R", "G", "B"])) {
+        *value = match value_text.trim().parse() {
+            Ok(value) => value,
+            _ => return Err(format!("The {name} value in the color \"{text}\" must be a number from 0 to 255.")),
+        };
+    }
+    Ok((values[0], values[1], values[2]))
+}
+
+
+#[allow(dead_code)]
+fn test_color_function_over_a_large_range() {
+    println!();
+    println!("Testing the color() function over a large range of iteration values:");
+    // The color() function should never panic, and every color it returns
+    // (for points outside the set) should come from one of the three legs
+    // of the color cycle:  one channel is zero, and the other two add up
+    // to (about) 255.
+    for i in 0..100_000 {
+        let (r, g, b) = color(Some(i));
+        let channels = [r, g, b];
+        let sum: usize = channels.iter().map(|&channel| channel as usize).sum();
+        assert!(channels.contains(&0), "color(Some({i})) = {:?} has no zero channel", (r, g, b));
+        assert!((254..=255).contains(&sum), "color(Some({i})) = {:?} has a channel sum of {sum}", (r, g, b));
+    }
+    assert_eq!(color(None), (0, 0, 102));
+    println!("color() returned valid colors for every value from 0 to 99,999 (and for None).");
+    println!();
+}
+
+
+// This structure holds the settings that can be given in a TOML
+// config file (with the --config=PATH switch).  Every setting is
+// optional, and any setting given on the command line overrides
+// the one in the file.  A config file looks like this:
+//
+//    size = 600
+//    bailout = 1000
+//    center = [-0.745, 0.1]
+//    distance = 0.01
+//    julia = [-0.8, 0.156]
+//    fractal = "tricorn"
+//    norm = "max"
+//    coloring = "boundary"
+//    interior_coloring = "period"
+//    boundary_color = [255, 255, 0]
+//    boundary_background = [0, 0, 64]
+//    bands = 16
+//    antialias = "fxaa"
+//    aa_samples = 8
+//    aa_seed = 12345
+//    dither = true
+//    invert = true
+//    color_period = 45
+//    palette = "random"
+//    palette_seed = 12345
+//    float = "f32"
+//    projection = "logpolar"
+//    cycle_threshold = 0.1
+//    cycle_detect = false
+//    max_threads = 4
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    size: Option<usize>,
+    bailout: Option<usize>,
+    center: Option<(Float, Float)>,
+    distance: Option<Float>,
+    julia: Option<(Float, Float)>,
+    fractal: Option<String>,
+    norm: Option<String>,
+    coloring: Option<String>,
+    interior_coloring: Option<String>,
+    boundary_color: Option<(u8, u8, u8)>,
+    boundary_background: Option<(u8, u8, u8)>,
+    bands: Option<usize>,
+    antialias: Option<String>,
+    aa_samples: Option<usize>,
+    aa_seed: Option<u64>,
+    dither: Option<bool>,
+    invert: Option<bool>,
+    color_period: Option<usize>,
+    palette: Option<String>,
+    palette_seed: Option<u64>,
+    float: Option<String>,
+    projection: Option<String>,
+    cycle_threshold: Option<Float>,
+    cycle_detect: Option<bool>,
+    max_threads: Option<usize>,
+}
+impl ConfigFile {
+    // Reads and parses the TOML config file with the given filename.
+    // (The error message for a file that can't be parsed includes
+    // the line and column where the problem was found.)
+    // (The file can also be a PNG screenshot, which has the settings
+    // it was drawn with stored inside it; see read_png_text().)
+    fn load(filename: &str) -> Result<Self, String> {
+        let bytes = std::fs::read(filename)
+                        .map_err(|error| format!("Unable to read the config file \"{filename}\":  {error}"))?;
+        let text = if bytes.starts_with(&PNG_SIGNATURE) {
+            read_png_text(&bytes, PNG_SETTINGS_KEYWORD)
+                .ok_or(format!("The PNG file \"{filename}\" has no JLR-Mandelbrot settings stored in it."))?
+        } else {
+            String::from_utf8(bytes)
+                .map_err(|error| format!("Unable to read the config file \"{filename}\":  {error}"))?
+        };
+        toml::from_str(&text)
+            .map_err(|error| format!("Unable to parse the config file \"{filename}\":\n{error}"))
+    }
+
+    // Copies every setting given in this file into the config (and
+    // the program's config), checking them the same way the
+    // command-line switches are.
+    fn apply_to(&self, config: &mut Config, program_config: &mut ProgramConfig) -> Result<(), String> {
+        if let Some(size) = self.size {
+            program_config.window_size = parse_window_size(&size.to_string())?;
+        }
+        if let Some(bailout) = self.bailout {
+            config.bailout = Some(bailout);
+        }
+        // (TOML allows inf and nan, which the command line doesn't.)
+        let finite = |(x, y): (Float, Float)| x.is_finite() && y.is_finite();
+        if let Some(julia) = self.julia {
+            if !finite(julia) {
+                return Err("The julia value must be a pair of (finite) numbers.".to_string())
+            }
+            config.c = Some(julia);
+            program_config.center = (0.0, 0.0);  // We'll start centered for Julia sets.
+        }
+        if let Some(center) = self.center {
+            if !finite(center) {
+                return Err("The center must be a pair of (finite) numbers.".to_string())
+            }
+            program_config.center = center;
+        }
+        if let Some(distance) = self.distance {
+            if !distance.is_finite() || distance <= 0.0 {
+                return Err("The distance must be a (finite) number more than zero.".to_string())
+            }
+            program_config.distance_from_center_to_edge = distance;
+        }
+        if let Some(coloring) = &self.coloring {
+            config.coloring = Coloring::from_name(coloring)
+                                  .ok_or(format!("\"{coloring}\" is not a valid coloring."))?;
+        }
+        if let Some(interior_coloring) = &self.interior_coloring {
+            config.interior_coloring = InteriorColoring::from_name(interior_coloring)
+                                           .ok_or(format!("\"{interior_coloring}\" is not a valid interior coloring."))?;
+        }
+        if let Some(boundary_color) = self.boundary_color {
+            config.boundary_color = boundary_color;
+        }
+        if let Some(boundary_background) = self.boundary_background {
+            config.boundary_background_color = boundary_background;
+        }
+        if let Some(bands) = self.bands {
+            if bands == 0 {
+                return Err("The number of bands must be more than zero.".to_string())
+            }
+            config.number_of_bands = Some(bands);
+        }
+        if let Some(antialias) = &self.antialias {
+            config.antialiasing = Antialiasing::from_name(antialias)
+                                      .ok_or(format!("\"{antialias}\" is not a valid antialias type."))?;
+        }
+        if let Some(aa_samples) = self.aa_samples {
+            if aa_samples == 0 {
+                return Err("The number of anti-aliasing samples must be more than zero.".to_string())
+            }
+            config.aa_samples = Some(aa_samples);
+        }
+        if let Some(aa_seed) = self.aa_seed {
+            config.aa_seed = aa_seed;
+        }
+        if let Some(dither) = self.dither {
+            config.dither = dither;
+        }
+        if let Some(invert) = self.invert {
+            config.invert = invert;
+        }
+        if let Some(color_period) = self.color_period {
+            config.color_period = parse_color_period(&color_period.to_string())?;
+        }
+        if let Some(palette) = &self.palette {
+            config.palette = parse_palette_name(palette, config.palette.take())?;
+        }
+        if let Some(palette_seed) = self.palette_seed {
+            config.palette = Some(Palette::random(palette_seed));
+        }
+        if let Some(fractal) = &self.fractal {
+            config.formula = Formula::from_name(fractal)
+                                 .ok_or(format!("\"{fractal}\" is not a valid fractal."))?;
+        }
+        if let Some(norm) = &self.norm {
+            config.norm = Norm::from_name(norm)
+                              .ok_or(format!("\"{norm}\" is not a valid norm."))?;
+        }
+        if let Some(float) = &self.float {
+            config.float_type = FloatType::from_name(float)
+                                    .ok_or(format!("\"{float}\" is not a valid float type."))?;
+        }
+        if let Some(projection) = &self.projection {
+            config.projection = Projection::from_name(projection)
+                                    .ok_or(format!("\"{projection}\" is not a valid projection."))?;
+        }
+        if let Some(cycle_threshold) = self.cycle_threshold {
+            if !cycle_threshold.is_finite() || cycle_threshold <= 0.0 {
+                return Err("The cycle threshold must be a (finite) number more than zero.".to_string())
+            }
+            config.cycle_threshold_factor = cycle_threshold;
+        }
+        if let Some(cycle_detect) = self.cycle_detect {
+            config.cycle_detection = cycle_detect;
+        }
+        if let Some(max_threads) = self.max_threads {
+            if max_threads == 0 {
+                return Err("The maximum number of threads must be more than zero.".to_string())
+            }
+            config.max_threads = Some(max_threads);
+        }
+        Ok(())
+    }
+}
+
+
+// Returns true if the config turns off cycle detection without a
+// bailout to go with it.  (Without cycle detection, most points in the
+// set would be iterated forever, so a bailout is needed.)  That's
+// checked once all the settings are in, at startup, for --diff, and
+// whenever --watch reloads the config file.
+fn needs_a_bailout(config: &Config) -> bool {
+    !config.cycle_detection && config.bailout.is_none()
+}
+
+
+// How often (at most) the config file is checked for changes with --watch.
+const WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+
+// Watches a config file (for --watch), by checking the time it was
+// last modified every WATCH_INTERVAL.  (Checking the time is enough,
+// and it works everywhere, without needing any other crates.)
+struct ConfigFileWatcher {
+    filename: String,
+    last_modified: Option<std::time::SystemTime>,  // (None if it couldn't be found out.)
+    last_checked: std::time::Instant,
+}
+impl ConfigFileWatcher {
+    fn new(filename: &str) -> Self {
+        Self {
+            filename: filename.to_string(),
+            last_modified: Self::modified_time(filename),
+            last_checked: std::time::Instant::now(),
+        }
+    }
+
+    // Returns the time the file was last modified, if it can be found out.
+    fn modified_time(filename: &str) -> Option<std::time::SystemTime> {
+        std::fs::metadata(filename).and_then(|metadata| metadata.modified()).ok()
+    }
+
+    // Returns true if the file has been modified since the last time
+    // it was checked (if it's time to check it again).
+    fn has_changed(&mut self) -> bool {
+        if self.last_checked.elapsed() < WATCH_INTERVAL {
+            return false
+        }
+        self.last_checked = std::time::Instant::now();
+        let modified = Self::modified_time(&self.filename);
+        if modified.is_some() && modified != self.last_modified {
+            self.last_modified = modified;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+
+// Returns true if the image has to be calculated again to go from the
+// old viewport and config to the new ones (for --watch), or false if
+// recoloring its escape details is enough.  That's decided by whether
+// the escape cache would use a different file for them (see
+// escape_cache_filename()), as that's named after every setting that
+// goes into calculating them.
+fn config_change_needs_recalculation((old_info, old_config): (&WindowAndViewportInfo, &Config),
+                                     (new_info, new_config): (&WindowAndViewportInfo, &Config)) -> bool {
+    let cache_filename = |info: &WindowAndViewportInfo, config: &Config| {
+        escape_cache_filename("", info, config, cycle_threshold(info, config))
+    };
+    cache_filename(old_info, old_config) != cache_filename(new_info, new_config)
+}
+
+
+// Returns the viewport to draw once the config file (with --watch)
+// goes from the old program config to the new one:  the new center
+// and distance, if either one changed (starting over at zoom level
+// 0), or else the same view as before, in the new projection.
+// Returns an error message if that view can't be drawn (in the new
+// FloatType), so the last settings can be kept instead.
+fn reloaded_view(info: &WindowAndViewportInfo,
+                 old_program_config: &ProgramConfig, new_program_config: &ProgramConfig,
+                 new_config: &Config) -> Result<WindowAndViewportInfo, String> {
+    let (center_x, center_y) = new_program_config.center;
+    let new_info = if old_program_config.center != new_program_config.center
+                      || old_program_config.distance_from_center_to_edge != new_program_config.distance_from_center_to_edge {
+        WindowAndViewportInfo::new(info.width, info.height,
+                                   center_x, center_y,
+                                   new_program_config.distance_from_center_to_edge,
+                                   0)
+            .with_projection(new_config.projection)
+    } else {
+        info.clone().with_projection(new_config.projection)
+    };
+    if new_info.is_degenerate_for(new_config.float_type) {
+        return Err(degenerate_view_message(&new_info, new_config.float_type))
+    }
+    Ok(new_info)
+}
+
+
+// Returns the error message for a viewport that can't be drawn in the
+// given FloatType (see is_degenerate_for()), telling apart a view
+// that's too big (its edges overflow to infinity) from one that's too
+// small (its pixels are too close together to tell apart).  (The
+// numbers are written with {:?}, so that, say, 1e308 isn't written
+// out with all 309 of its digits.)
+fn degenerate_view_message(info: &WindowAndViewportInfo, float_type: FloatType) -> String {
+    fn overflows<T: EscapeFloat>(info: &WindowAndViewportInfo) -> bool {
+        let (center_x, center_y) = (T::from_float(info.center_x), T::from_float(info.center_y));
+        let distance = T::from_float(info.distance_from_center_to_edge);
+        [center_x - distance, center_x + distance, center_y - distance, center_y + distance, distance + distance]
+            .iter().any(|edge| !edge.is_finite())
+    }
+    let (too_big, in_float_type) = match float_type {
+        FloatType::F32 => (overflows::<f32>(info), " in f32"),
+        FloatType::F64 => (overflows::<f64>(info), ""),
+    };
+    let (center_x, center_y, distance) = (info.center_x, info.center_y, info.distance_from_center_to_edge);
+    if too_big {
+        format!("A distance of {distance:?} is too big to draw around the center {center_x:?},{center_y:?}{in_float_type}.  \
+                 (The edges of the view would be too far out to represent.)")
+    } else {
+        format!("A distance of {distance:?} is too small to draw around the center {center_x:?},{center_y:?}{in_float_type}.  \
+                 (The pixels would be too close together to tell apart.)")
+    }
+}
+
+
+// Returns the number of bytes of memory needed for the buffers of a
+// width x height window (the image_buffer, the display_buffer, and
+// the escape details), or None if that number is too big to even
+// fit in a usize (which can happen, especially on 32-bit machines).
+//
+// As every buffer's length fits in a usize, so does every pixel's
+// index into it (row * width + column), so once a window size has
+// passed this check, the indexing math can't overflow.
+fn bytes_needed_for_window(width: usize, height: usize) -> Option<usize> {
+    bytes_needed_for_window_within(width, height, usize::MAX as u64).map(|bytes| bytes as usize)
+}
+
+
+// The most bytes that can be addressed on a 32-bit machine, where
+// a usize (and so every buffer's length) is only 32 bits.
+const MAX_BYTES_ON_32_BIT: u64 = u32::MAX as u64;
+
+
+// Does the work of bytes_needed_for_window(), for a machine that can
+// address up to max_bytes bytes, so that the limit of a 32-bit machine
+// (MAX_BYTES_ON_32_BIT) can be checked on any machine.  (The math is
+// done in u64s, which are big enough for any window size whose width
+// and height each fit in a u32.)
+fn bytes_needed_for_window_within(width: usize, height: usize, max_bytes: u64) -> Option<u64> {
+    let bytes_per_pixel = (2 * std::mem::size_of::<u32>() + std::mem::size_of::<EscapeDetails>()) as u64;
+    (width as u64).checked_mul(height as u64)?
+                  .checked_mul(bytes_per_pixel)
+                  .filter(|&bytes| bytes <= max_bytes)
+}
+
+
+// Parses the NUMBER of the --size=NUMBER switch, returning
+// an error message if it's not a valid window size.
+fn parse_window_size(size_text: &str) -> Result<usize, String> {
+    let size: usize = match size_text.parse() {
+        Ok(size) => size,
+        _ => return Err(format!("--size={size_text} has an invalid value of \"{size_text}\".")),
+    };
+    if size == 0 {
+        return Err("The NUMBER in --size=NUMBER must be more than zero.".to_string())
+    }
+    if size > MAX_WINDOW_SIZE {
+        return Err(format!("The NUMBER in --size=NUMBER can't be more than {MAX_WINDOW_SIZE}."))
+    }
+    if bytes_needed_for_window(size, size).is_none() {
+        return Err(format!("A window of {size} x {size} pixels needs more memory than this machine can address."))
+    }
+    Ok(size)
+}
+
+
+// Parses the NUMBER of the --bailout=NUMBER switch, returning None
+// for "auto" (see automatic_bailout()), or an error message if it's
+// neither.
+fn parse_bailout(bailout_text: &str) -> Result<Option<usize>, String> {
+    match bailout_text.parse() {
+        Ok(bailout) => Ok(Some(bailout)),
+        _ if bailout_text == "auto" => Ok(None),
+        _ => Err(format!("--bailout={bailout_text} has an invalid value of \"{bailout_text}\".  \
+                          (It must be a whole number, or \"auto\".)")),
+    }
+}
+
+
+// Applies the JLR_MANDELBROT_SIZE and JLR_MANDELBROT_BAILOUT
+// environment variables (as looked up by the lookup function) to the
+// program config and config (and whether the bailout is "auto"),
+// checking them the same way --size and --bailout are.  (Variables
+// that aren't set, or are set to nothing, are ignored, and so are
+// those whose switch is in the command-line args, which overrides
+// them anyway.)
+fn apply_environment_variables((config, program_config, auto_bailout): (&mut Config, &mut ProgramConfig, &mut bool),
+                               args: &[String],
+                               lookup: impl Fn(&str) -> Option<String>) -> Result<(), String> {
+    let given = |prefix: &str| args.iter().take_while(|arg| *arg != "--").any(|arg| arg.starts_with(prefix));
+    let lookup = |name: &str| lookup(name).filter(|value| !value.is_empty());
+    if let Some(size_text) = lookup("JLR_MANDELBROT_SIZE").filter(|_| !given("--size=")) {
+        program_config.window_size = parse_window_size(&size_text)
+                                 .map_err(|message| format!("{message}  (It came from the JLR_MANDELBROT_SIZE environment variable.)"))?;
+    }
+    if let Some(bailout_text) = lookup("JLR_MANDELBROT_BAILOUT").filter(|_| !given("--bailout=")) {
+        config.bailout = parse_bailout(&bailout_text)
+                             .map_err(|message| format!("{message}  (It came from the JLR_MANDELBROT_BAILOUT environment variable.)"))?;
+        *auto_bailout = config.bailout.is_none();
+    }
+    Ok(())
+}
+
+
+// Returns the names in the registry (like Coloring::ALL), quoted
+// and joined for an error message, like:  "a", "b", or "c"
+fn quoted_names<T>(registry: &[(&str, &str, T)]) -> String {
+    let names: Vec<String> = registry.iter().map(|(name, _, _)| format!("\"{name}\"")).collect();
+    match names.as_slice() {
+        [] => String::new(),
+        [name] => name.clone(),
+        [first, second] => format!("{first} or {second}"),
+        [all_but_last @ .., last] => format!("{}, or {last}", all_but_last.join(", ")),
+    }
+}
+
+
+// Returns the text printed by --list-palettes:  every name (and
+// description) that --coloring, --interior-coloring, and --palette
+// accept, with the defaults marked.
+fn palette_list_text() -> String {
+    let default_config = Config::new();
+    let mut text = String::new();
+    let mut add_registry = |heading: &str, registry: Vec<(&str, &str, bool)>| {
+        let name_width = registry.iter().map(|(name, _, _)| name.len()).max().unwrap_or(0);
+        text += &format!("{heading}\n");
+        for (name, description, is_default) in registry {
+            let default_note = if is_default { "  (the default)" } else { "" };
+            text += &format!("   {name:name_width$}   {description}{default_note}\n");
+        }
+    };
+    add_registry("Colorings (--coloring=TYPE):",
+                 Coloring::ALL.iter()
+                              .map(|&(name, description, coloring)|
+                                       (name, description, coloring == default_config.coloring))
+                              .collect());
+    add_registry("Interior colorings (--interior-coloring=TYPE):",
+                 InteriorColoring::ALL.iter()
+                                      .map(|&(name, description, interior_coloring)|
+                                               (name, description, interior_coloring == default_config.interior_coloring))
+                                      .collect());
+    add_registry("Palettes (--palette=NAME):",
+                 PALETTE_NAMES.iter()
+                              .map(|&(name, description, ())| (name, description, name == "default"))
+                              .collect());
+    text
+}
+
+
+// The palettes that --palette=NAME accepts (listed by --list-palettes),
+// with a description of each.
+const PALETTE_NAMES: &[(&str, &str, ())] = &[
+    ("default", "red to green to blue, and back to red", ()),
+    ("random", "smoothly blended random colors, different every run (see --palette-seed)", ()),
+];
+
+
+// Returns the palette for the given --palette=NAME (see PALETTE_NAMES).
+// A random palette that was already chosen (say, by --palette-seed)
+// is kept, so that the order of the switches doesn't matter; otherwise
+// a new one is made, seeded from the clock.
+fn parse_palette_name(name: &str, palette: Option<Palette>) -> Result<Option<Palette>, String> {
+    match name {
+        "default" => Ok(None),
+        "random" => Ok(Some(palette.unwrap_or_else(|| Palette::random(clock_seed())))),
+        _ => Err(format!("\"{name}\" is not a valid palette.  (It must be {}.)", quoted_names(PALETTE_NAMES))),
+    }
+}
+
+
+// Returns a seed that's different every run (taken from the clock),
+// for a --palette=random without a --palette-seed.
+fn clock_seed() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+
+// Famous places in the Mandelbrot set, for --goto=NAME (so that new
+// users can see some of its most spectacular views without having to
+// know their coordinates):  each one's name, a description, its center,
+// its distance (from center to edge), and a bailout that brings out
+// its detail.  (--list-locations lists them.)
+struct Location {
+    name: &'static str,
+    description: &'static str,
+    center: (Float, Float),
+    distance: Float,  // (from center to edge)
+    bailout: usize,
+}
+const LOCATIONS: &[Location] = &[
+    Location { name: "seahorse",
+               description: "Seahorse Valley, between the main cardioid and the big bulb to its left.",
+               center: (-0.745, 0.113), distance: 0.01, bailout: 1000 },
+    Location { name: "elephant",
+               description: "Elephant Valley, a parade of elephants in the main cardioid's cusp.",
+               center: (0.285, 0.011), distance: 0.01, bailout: 500 },
+    Location { name: "triple-spiral",
+               description: "Triple Spiral Valley, near where the top period-3 bulb joins the cardioid.",
+               center: (-0.090, 0.654), distance: 0.003, bailout: 2000 },
+    Location { name: "mini-mandelbrot",
+               description: "The biggest copy of the Mandelbrot set on the needle to its left.",
+               center: (-1.7548776662, 0.0), distance: 0.025, bailout: 500 },
+];
+
+
+// Returns the center, distance, and bailout of the --goto location
+// with the given name, if there is one (see LOCATIONS).
+fn location(name: &str) -> Option<((Float, Float), Float, usize)> {
+    LOCATIONS.iter()
+             .find(|location| location.name == name)
+             .map(|location| (location.center, location.distance, location.bailout))
+}
+
+
+// Returns the names of every --goto location, quoted and joined
+// (as with quoted_names()), for help and error messages.
+fn location_names() -> String {
+    let registry: Vec<(&str, &str, ())> = LOCATIONS.iter().map(|location| (location.name, location.description, ())).collect();
+    quoted_names(&registry)
+}
+
+
+// Returns the text printed by --list-locations:  every
+// location's name (for --goto=NAME) and description.
+fn location_list_text() -> String {
+    let name_width = LOCATIONS.iter().map(|location| location.name.len()).max().unwrap_or(0);
+    let mut text = "Locations (--goto=NAME):\n".to_string();
+    for Location { name, description, .. } in LOCATIONS {
+        text += &format!("   {name:name_width$}   {description}\n");
+    }
+    text
+}
+
+
+// Returns the text printed by --list-fractals.  (--fractal=NAME chooses
+// the formula, which is mandelbrot or tricorn; a Julia set of it is
+// drawn when --julia is given.  The F key switches between a formula's
+// set and its Julia sets while the program is running; see
+// next_fractal().)
+fn fractal_list_text() -> String {
+    "Fractals:
+   mandelbrot   The Mandelbrot set, iterating z^2 + c.  (the default)
+   tricorn      The Tricorn (or Mandelbar), iterating conj(z)^2 + c.
+   julia        A Julia set (of either one), for the c given with
+                --julia=X,Y (as X+Yi).
+".to_string()
+}
+
+
+// Returns the c to draw with (None for the Mandelbrot set) after the
+// F key switches to the next fractal.  From a Julia set, that's the
+// Mandelbrot set; from the Mandelbrot set, it's the Julia set that was
+// last drawn (or given with --julia), or if there wasn't one, the
+// Julia set for the point at the center of the view.  (The points of
+// the Mandelbrot set are exactly the c's whose Julia sets are
+// connected, so zooming in on an interesting part of it and pressing
+// F shows an interesting Julia set.)
+fn next_fractal(c: Option<(Float, Float)>,
+                last_julia_c: Option<(Float, Float)>,
+                (center_x, center_y): (Float, Float)) -> Option<(Float, Float)> {
+    match c {
+        Some(_) => None,
+        None => Some(last_julia_c.unwrap_or((center_x, center_y))),
+    }
+}
+
+
+// Returns the center that Shift+arrow keys nudge the view's center to:
+// exactly the given number of pixels over (columns to the right, and
+// rows down), so that a feature can be lined up precisely before
+// saving a bigger image of it.  (As the rows go down the window, the
+// y-coordinate goes the other way.)
+fn nudged_center(info: &WindowAndViewportInfo,
+                 (columns, rows): (isize, isize)) -> (Float, Float) {
+    (info.center_x + columns as Float * info.delta_x,
+     info.center_y - rows as Float * info.delta_y)
+}
+
+
+// Returns the window's title:  the one given with --title, if there
+// was one, or else the name of the fractal being drawn (for the given
+// formula and c, which is None for the Mandelbrot set or the Tricorn),
+// so that several windows open at once can be told apart.
+fn window_title(title: Option<&str>, formula: Formula, c: Option<(Float, Float)>) -> String {
+    match (title, formula, c) {
+        (Some(title), ..) => title.to_string(),
+        (None, Formula::Mandelbrot, None) => "The Mandelbrot Set".to_string(),
+        (None, Formula::Mandelbrot, Some((c_x, c_y))) => format!("The Julia Set for c = {c_x},{c_y}"),
+        (None, Formula::Tricorn, None) => "The Tricorn".to_string(),
+        (None, Formula::Tricorn, Some((c_x, c_y))) => format!("The Tricorn's Julia Set for c = {c_x},{c_y}"),
+    }
+}
+
+
+// Returns the name of the fractal drawn for the given formula and c
+// (None for the Mandelbrot set or the Tricorn), as printed when the F
+// key switches to it.
+fn fractal_name(formula: Formula, c: Option<(Float, Float)>) -> String {
+    match (formula, c) {
+        (Formula::Mandelbrot, None) => "the Mandelbrot set".to_string(),
+        (Formula::Mandelbrot, Some((c_x, c_y))) => format!("the Julia set for c = {c_x},{c_y}"),
+        (Formula::Tricorn, None) => "the Tricorn".to_string(),
+        (Formula::Tricorn, Some((c_x, c_y))) => format!("the Tricorn's Julia set for c = {c_x},{c_y}"),
+    }
+}
+
+
+// Returns the text printed by --version:  the version of the program
+// (from Cargo.toml), the Float type the math is done in, the optional
+// features it was compiled with (see Cargo.toml), and what it was built
+// for, so that a bug report can say exactly which build it's about.
+fn version_text() -> String {
+    let features: Vec<&str> = [("viewer", cfg!(feature = "viewer")), ("wasm", cfg!(feature = "wasm"))]
+                                  .iter()
+                                  .filter(|(_, enabled)| *enabled)
+                                  .map(|(name, _)| *name)
+                                  .collect();
+    let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    format!("JLR-Mandelbrot {}\n\
+             Float:  {}\n\
+             Features:  {}\n\
+             Target:  {}-{} ({}-bit, {} build)\n\
+             Threads:  {threads} (for --bands)\n",
+            env!("CARGO_PKG_VERSION"),
+            std::any::type_name::<Float>(),
+            if features.is_empty() { "(none)".to_string() } else { features.join(", ") },
+            std::env::consts::ARCH, std::env::consts::OS,
+            usize::BITS,
+            if cfg!(debug_assertions) { "debug" } else { "release" })
+}
+
+
+// Returns the help text suitable for printing when the
+// user specifies the --help switch.
+fn help_text() -> String {
+    format!("
+
+Program:  JLR-Mandelbrot
+          A Mandelbrot set viewer.
+
+Usage:  jlr-mandelbrot [options]
+Example usages:
+   jlr-mandelbrot
+   jlr-mandelbrot --size=256
+   jlr-mandelbrot --bailout=150
+   jlr-mandelbrot --julia=-0.835,-0.232
+   jlr-mandelbrot --center=-0.745,0.1 --distance=0.01
+   jlr-mandelbrot --bands=16
+   jlr-mandelbrot --interior-coloring=period
+   jlr-mandelbrot --coloring=boundary --boundary-color=255,255,0
+   jlr-mandelbrot --script=tour.txt
+   jlr-mandelbrot --format=ppm > mandelbrot.ppm
+
+Options:
+   -h, --help
+      Shows this help text and exits.
+   -V, --version
+      Prints the version of this program, and how it was built (its
+      Float type, optional features, and target), and exits.  (Please
+      include this when reporting a bug.)
+   --config=PATH
+      Reads settings from the TOML file at PATH.  Its settings (all
+      optional) are named like their switches:  size, bailout,
+      center, distance, julia, fractal, norm, coloring, interior_coloring,
+      boundary_color, boundary_background, bands, antialias,
+      aa_samples, aa_seed, dither, invert, color_period, palette,
+      palette_seed, float,
+      projection, cycle_threshold, cycle_detect (which is true unless --no-cycle-detect is
+      wanted), and max_threads.  For example:
+         size = 600
+         center = [-0.745, 0.1]
+         distance = 0.01
+         coloring = \"boundary\"
+         boundary_color = [255, 255, 0]
+      Switches given on the command line override the file's settings.
+      PATH can also be a PNG screenshot saved by this program, which
+      has the settings it was drawn with stored inside it.
+      (And the file's settings override the environment variables'.)
+   --watch
+      With --config=PATH, keeps checking the config file, and whenever
+      it's saved, applies its settings to the image shown:  the image
+      is just recolored if only its colors changed, and calculated
+      again otherwise.  (A changed center or distance goes back to
+      that view.)  If the file can't be read, the last settings that
+      could be are kept.  The size can't be changed this way, and
+      once the file has been changed, its settings override the
+      command line's.
+   --size=NUMBER
+      Displays the image in a square window of NUMBER by NUMBER pixels.
+      ({default_size} is the default, and {max_size} is the maximum.)
+      (The default can be changed with the JLR_MANDELBROT_SIZE
+      environment variable.)
+   --title=STRING
+      Shows STRING as the window's title, instead of the name of the
+      fractal being drawn (\"The Mandelbrot Set\", or \"The Julia Set
+      for c = X,Y\"), which is handy when several windows are open.
+   --bailout=NUMBER
+      Uses a bailout number, or a maximum number of iterations.
+      If this number is reached, then a point is considered to
+      be part of the set.  (A bailout number is not used by default,
+      unless one is given with the JLR_MANDELBROT_BAILOUT environment
+      variable.)
+   --bailout=auto
+      Picks the bailout from how far the view is zoomed in:  {auto_bailout_base}
+      for the whole set, and {auto_bailout_per_zoom_level} more for every time it's zoomed in
+      by a factor of two.  It's picked again after every zoom (until
+      the + or - key changes the bailout by hand).
+   --julia=X,Y
+      Instead of a Mandelbrot set, a Julia set will be generated
+      using X+Yi as the value for c.
+   --fractal=NAME
+      Chooses the formula iterated for each point.  NAME can be:
+         mandelbrot   (z^2 + c; the default)
+         tricorn      (conj(z)^2 + c, which flips z upside down before
+                      squaring it, for the Tricorn, or Mandelbar, with
+                      its three corners; with --julia, its Julia sets)
+      (--rays, --goto, and the minimap are only for the Mandelbrot set.)
+   --norm=NAME
+      Chooses how far z is measured to be from 0 for the escape test,
+      which changes the shape of the bands of colors around the set.
+      NAME can be:
+         l2    (sqrt(x^2 + y^2), the usual distance; the default.
+               The bands are rounded, escaping outside the circle of
+               radius 2.)
+         max   (max(|x|, |y|).  The bands are squared off, escaping
+               outside the square from -2 to 2 each way, whose corners
+               reach past the circle.)
+         l1    (|x| + |y|.  The bands are pointed, escaping outside
+               the diamond with corners at 2*sqrt(2), as one with
+               corners at 2 would cut into the circle.)
+      Each shape holds the whole circle of radius 2, so the set itself
+      is the same with every norm; only the escape values change.
+   --center=X,Y
+      Centers the image on X+Yi.  (The default is -0.5,0 for the
+      Mandelbrot set, and 0,0 for Julia sets.)
+   --distance=NUMBER
+      Uses NUMBER as the distance from the center of the image to
+      its edges.  (1.725 is the default.)
+   --dry-run
+      Prints the viewport (its bounds, span, and the size of each
+      pixel) that the other options would produce, and exits
+      without opening a window or drawing anything.
+   --float-bench
+      Calculates the image (without opening a window) twice, with
+      f32 and with f64 numbers, and prints how long each took and
+      how many pixels got a different escape value with f32.  (Try
+      it zoomed in, to see where f32 stops being precise enough.)
+   --diff=PATH
+      Calculates the image (without opening a window) twice:  once
+      with the other options as given, and once with the settings of
+      the PATH config file (see --config) applied on top of them (like
+      bailout = 100, or float = 'f32').  Then it saves an image with
+      the pixels that were in the set with only one of them in white,
+      the ones that escaped after a different number of iterations in
+      magenta, and the rest darkened, and prints how many of each
+      there were.  (Both are drawn with the same size and view, so
+      the file's size, center, and distance aren't used.)
+   --cycle-threshold=FACTOR
+      Points are taken to be in the set once their orbit comes back
+      to (within FACTOR pixels of) where it's been before.  (0.25 is
+      the default.)  A bigger FACTOR is faster, but can mistake
+      slowly-escaping points near the edge of the set for points in
+      it, making the edge look \"blobby\"; a smaller one is slower,
+      but more accurate.
+   --no-cycle-detect
+      Doesn't look for cycles at all, so points are only taken to
+      be in the set if they reach the bailout.  (This needs
+      --bailout, since otherwise points in the set would never
+      finish.)  Each iteration is quicker this way, so it's faster
+      for images that are mostly outside the set, or that use a
+      small bailout; but with a lot of the set showing and a big
+      bailout, cycle detection is faster, since it usually finds
+      out that a point is in the set long before the bailout.
+   --max-iterations=NUMBER
+      The most iterations any one point is ever given ({default_max_iterations} by
+      default), whatever the --bailout is, or even without one, so
+      that no point can take forever.  (Cycle detection can miss a
+      point in the set whose orbit never quite repeats.)  A point
+      that reaches it is taken to be part of the set, just as if it
+      had hit the bailout.  With --max-iterations=none, there's no
+      limit at all.
+   --auto-zoom
+      After each image is drawn (and shown for a second), zooms in on
+      its most interesting part:  the place near the middle with the
+      most different escape values around it, which is usually where
+      the boundary of the set has the most detail.  This goes on
+      until the pixels would be too close together to tell apart (or
+      until there's nothing left to zoom in on), for a hands-off
+      zoom.  (Clicking still zooms wherever it's clicked, too.)
+   --auto-zoom=SECONDS
+      Like --auto-zoom, but shows each image for SECONDS instead.
+   --progressive
+      Draws each image quickly with a low bailout ({first_bailout}) first,
+      and then, as long as the view isn't changed, keeps recalculating
+      the pixels that hit the bailout with higher and higher bailouts
+      (multiplying it by {bailout_step} each time) until the --bailout is
+      reached.  (Without a --bailout, no bailout is used after {max_ramp_bailout}.)
+   --max-threads=NUMBER
+      Uses no more than NUMBER threads for drawing.  (By default,
+      one thread is used for each CPU.)
+   --preview-scale=NUMBER
+      Before drawing each image, draws a quick preview of it (with
+      NUMBER times fewer pixels across and down), stretched smoothly
+      to fill the window, so there's something to see right away.
+      The full image is then drawn on top of it.  (The default is
+      1, which draws no preview.)
+   --bands=NUMBER
+      Draws the image with multiple threads (one per CPU), in NUMBER
+      bands going outward from the center, so the image appears as a
+      growing circle.  (By default, a single thread draws the image.)
+   --fast-fill
+      Draws the image with multiple threads (one per CPU) using the
+      \"Mariani-Silver\" algorithm:  each part of the image is split
+      into rectangles, and whenever every pixel along a rectangle's
+      border has the same escape value, its inside is filled in with
+      it instead of being calculated.  This is much quicker for
+      images with big areas of the set (or of a single color) in them,
+      but the image only appears once it's finished, and a thin
+      filament that crosses a rectangle without touching its border
+      can be filled over.  (It takes the place of --bands.  With
+      --coloring=potential, only rectangles of the set are filled in,
+      so that the shading stays smooth.)
+   --fast-fill-min=PIXELS
+      Like --fast-fill, but rectangles no bigger than PIXELS across
+      (and down) are always calculated, never filled in.  A bigger
+      PIXELS fills over fewer thin filaments, but is slower.  (The
+      default is {default_fast_fill_min_size}.)
+   --frame-budget-ms=T
+      Limits the calculating to T milliseconds for each time the
+      window is updated (about 60 times a second), picking up where it
+      left off after the update.  This keeps the window smooth while
+      drawing slow, deeply-zoomed images, at the cost of drawing them
+      more slowly.  (It doesn't apply to --bands, which draws whole
+      bands at a time.)
+   --double-buffer
+      Draws each image off-screen, keeping the last one in the window
+      until the new one is finished, so only finished images are ever
+      shown (instead of watching each one swirl outward as it's drawn).
+      (--preview-scale is ignored with it, as the preview wouldn't be
+      seen.  With --progressive, the image is shown once its first
+      pass is finished, and then refined in the window.)
+   --rays=ANGLES
+      Draws the Mandelbrot set's external rays with the given ANGLES
+      (separated by commas) on top of the image, as yellow lines.
+      Each angle is a fraction of a full turn, from 0 up to 1, given
+      as a fraction or a decimal.  For example, --rays=1/3,2/3 draws
+      the two rays that land where the biggest bulb (on the left)
+      meets the main cardioid, and --rays=0 draws the ray that lands
+      on the cusp of the cardioid (on the right).  (External rays are
+      the lines that would point straight out if the set had an
+      electric charge.)  This can't be used with --julia.
+   --sidecar
+      Whenever a screenshot is saved, also saves the settings it was
+      drawn with (its center, distance, bailout, and so on) next to
+      it, in a file with the same name but ending in .toml instead of
+      .png.  That file is in the same format as the --config file, so
+      the screenshot can be drawn again with --config=THAT_FILE.
+   --depth=BITS
+      The number of bits per color channel of saved screenshots:
+      8 (the default) or 16.  With 16, smooth colors (like those of
+      --coloring=potential, or of a long --color-period) are saved
+      without the slight \"bands\" of 8-bit colors, for editing them
+      afterwards.  (The window still shows 8-bit colors, and only PNG
+      files can be saved with 16 bits.)
+   --script=FILE
+      Reads commands from FILE (or from stdin, if FILE is \"-\"), one
+      per line, running each one after the current image is drawn.
+      The commands are:
+         goto X Y DISTANCE   (centers on X+Yi, DISTANCE to the edge)
+         goto X,Y DISTANCE   (the same thing)
+         zoomin FACTOR       (zooms in by FACTOR, keeping the center)
+         zoomout FACTOR      (zooms out by FACTOR, keeping the center)
+         pan DX DY           (moves the center right by DX, and up by
+                             DY, in fractions of the view's width:
+                             pan 0.5 0 moves it half of a view right)
+         bailout NUMBER      (uses NUMBER as the new bailout)
+         save FILENAME       (saves a screenshot to FILENAME)
+         wait MILLISECONDS   (waits before running the next command)
+         quit                (quits the program)
+      Blank lines and lines starting with a # are ignored.
+   --interior-coloring=TYPE
+      Chooses how the inside of the set is colored.  TYPE can be:
+         solid    (every point gets the same color; the default)
+         period   (points are colored by the period of the cycle
+                   their orbit falls into, so each bulb of the set
+                   gets its own color)
+      Note that \"period\" only works well when cycles are detected
+      before the bailout is reached, so with a small --bailout, some
+      of the inside may still be colored as if it were \"solid\".
+   --coloring=TYPE
+      Chooses how the image is colored.  TYPE can be:
+         escape-time   (points are colored by how many iterations
+                        they took to escape; the default)
+         boundary      (only the boundary of the set is drawn, like
+                        line art, once the whole image is calculated)
+         potential     (points are colored by their potential,
+                        ln|z| / 2^n, which changes smoothly instead
+                        of in bands of whole iterations)
+   --list-palettes
+      Prints every TYPE that --coloring and --interior-coloring
+      accept, and every NAME that --palette accepts (with a
+      description of each), and exits.
+   --list-fractals
+      Prints the fractals that can be drawn, and exits.
+   --goto=NAME
+      Starts at one of the Mandelbrot set's famous places, setting
+      the --center, --distance, and --bailout to show it off.  (Any
+      of them can still be changed by giving it after --goto.)  NAME
+      can be {location_names}.
+   --list-locations
+      Prints every NAME that --goto accepts (with a description of
+      each), and exits.
+   --background=R,G,B
+      The color of the window before the first image is drawn in
+      it (black by default), and of the empty spots of a
+      --contact-sheet (dark gray by default).  (Each value ranges
+      from 0 to 255, as with --boundary-color.)
+   --boundary-color=R,G,B
+      The color of the lines drawn with --coloring=boundary.
+      (Each value ranges from 0 to 255; 0,0,0 (black) is the default.)
+   --boundary-background=R,G,B
+      The color of everything else with --coloring=boundary.
+      (255,255,255 (white) is the default.)
+   --antialias=TYPE
+      Chooses how (or whether) the finished image is anti-aliased.
+      TYPE can be:
+         none   (the default)
+         fxaa   (jagged edges are found and blended with their
+                 neighbors, without recalculating any points; this
+                 is quick, but not as accurate as calculating more
+                 points per pixel would be)
+   --sharpen=AMOUNT
+      Sharpens the finished image (and its screenshots) with an
+      unsharp mask, so that the detail along the boundary of the set,
+      which can look soft at deep zooms, stands out more.  Each pixel
+      is pushed away from the blurred average of its neighborhood by
+      AMOUNT times the difference (from 0, the default, which leaves
+      the image alone, to {max_sharpen}; 1 is a good place to start).  Only the
+      colors are changed; nothing is recalculated.
+   --float=TYPE
+      Chooses the type of numbers the pixels are calculated with.
+      TYPE can be:
+         f64   (about 16 digits of precision; the default)
+         f32   (about 7 digits, so zooming in past a distance of
+                about 0.00001 shows blocks instead of detail; it's
+                mostly for comparing the two, like --float-bench)
+   --projection=TYPE
+      Chooses how the view is laid out in the window.
+      TYPE can be:
+         rectangular   (the usual view; the default)
+         logpolar      (the angle around the center goes across the
+                        window, and the distance from the center
+                        shrinks going down it, from --distance at the
+                        top to about 535 times smaller at the bottom,
+                        so many zoom levels are seen at once, and
+                        zooming in just scrolls the image up)
+   --aa-samples=NUMBER
+      Anti-aliases the image by calculating NUMBER points at random
+      places inside each pixel, and averaging their colors.  This is
+      accurate, but takes about NUMBER times as long.
+   --screenshot-ssaa=NUMBER
+      Like --aa-samples=NUMBER, but only for saved screenshots:  the
+      image in the window is drawn as usual (so zooming around stays
+      quick), and when a screenshot is saved, the view is drawn again
+      with NUMBER samples per pixel, and that's what gets saved.
+   --aa-seed=NUMBER
+      The seed for the random places used by --aa-samples.  The same
+      seed always gives the same image.  (The default is 0.)
+   --dither
+      Dithers the colors:  instead of every color being rounded to
+      the nearest one the screen can show, neighboring pixels are
+      rounded up or down in a fixed pattern, so that together they
+      average out to the exact color.  This hides the \"bands\" that
+      can show up in smooth gradients (especially with --aa-samples).
+   --invert
+      Runs the color palette the other way, so the colors cycle from
+      red to blue to green (instead of red to green to blue) as the
+      points take longer to escape.  The set's color isn't changed.
+   --color-period=NUMBER
+      How many iterations it takes to go through the whole color
+      palette once (from {min_color_period} to {max_color_period}; the default is {default_color_period}).
+      A smaller NUMBER packs the colors more tightly together, and a
+      bigger one spreads them out.  (The < and > keys change it.)
+   --palette=NAME
+      Chooses the colors that escaping points are colored with.
+      NAME can be \"default\" or \"random\", which blends {random_palette_colors}
+      random colors smoothly into a palette, with a new one every run.
+      (The seed it was made from is printed, and saved with
+      screenshots, so a palette worth keeping can be drawn again.)
+   --palette-seed=NUMBER
+      Uses a random palette (like --palette=random) made from the
+      seed NUMBER.  The same seed always gives the same palette.
+   --color-cycle
+      Once each image is drawn, keeps shifting its colors along the
+      palette, so that they seem to flow out of the set.  (Only the
+      colors change; nothing is recalculated, but with --aa-samples
+      every step has to sample the pixels again, so it's slow.)  The
+      A key turns color cycling on and off.
+   --cache-dir=DIR
+      Saves the calculated escape values of every image into the DIR
+      directory, and whenever an image with exactly the same settings
+      (size, center, distance, c, bailout, and interior coloring) is
+      needed again, loads them from there instead of recalculating
+      them.  Changing any of those settings simply uses a different
+      file, so the cache never needs to be cleared for correctness;
+      but nothing in it is ever deleted, so clear it out whenever it
+      gets too big (or after upgrading this program).
+   --format=ppm
+      Instead of opening a window, draws the image and writes it
+      to stdout as a binary PPM (P6) image, then exits.  (This
+      implies --quiet, so that nothing else is written to stdout.)
+   --contact-sheet
+   --contact-sheet=NUMBER
+      Instead of opening a window, draws a grid of small images,
+      each labeled, into one image, saves it as a PNG file, and
+      exits.  (With --format=ppm, it's written to stdout instead.)
+      With --julia=X,Y, the grid is NUMBER x NUMBER Julia sets
+      (4 x 4 by default), each with a slightly different c around
+      X+Yi.  Otherwise, it's the Mandelbrot set, drawn each of the
+      ways it can be colored.  The whole grid is about as wide as
+      the --size.
+   --julia-atlas
+   --julia-atlas=NUMBER
+      Instead of opening a window, draws a NUMBER x NUMBER grid
+      ({default_julia_atlas_grid_size} x {default_julia_atlas_grid_size} by default) of small Julia sets into one image, saves
+      it as a PNG file, and exits.  (With --format=ppm, it's written
+      to stdout instead.)  The grid covers the view of the Mandelbrot
+      set's plane given by --center and --distance, and each cell is
+      the Julia set for the c at that cell's center, so you can see
+      how the Julia sets change across the plane:  connected inside
+      of the Mandelbrot set, and falling apart into dust outside of
+      it.  (Any --julia is ignored.)  The whole grid is about as wide
+      as the --size.
+   --crop=X0,Y0,X1,Y1
+      Instead of opening a window, zooms in on just the rectangle
+      from X0,Y0 to X1,Y1 of the starting view (in pixels, where 0,0
+      is its upper-left corner, and --size,--size is its lower-right
+      one), draws it with its longer side as long as the --size,
+      saves it as a PNG file, and exits.  (With --format=ppm, it's
+      written to stdout instead.)  The settings saved inside of the
+      PNG are those of the square view around the rectangle, which
+      it was cut out of.
+   --deepzoom=PATH
+      Instead of opening a window, draws the view as a Deep Zoom
+      image (which viewers like OpenSeadragon and Leaflet can show
+      in a web page, zooming in as far as it was drawn), and exits.
+      It's saved as the PATH.dzi file, which describes the image,
+      and the PATH_files directory, which holds a directory of tiles
+      for each level of detail:  0 (one pixel) up to the full size,
+      each twice as wide as the one before it, with each tile saved
+      as COLUMN_ROW.png.  Every level is drawn from scratch, so even
+      the smaller ones are as sharp as they can be.
+   --tile-size=PIXELS
+      The width (and height) of each --deepzoom tile.  ({default_tile_size} is the
+      default.)
+   --deepzoom-levels=NUMBER
+      How many levels of --deepzoom tiles to draw (from 1 to {max_deepzoom_levels}):
+      the first one is a single tile, and each one after it is twice
+      as wide, so the full size is the --tile-size times 2 to the
+      power of NUMBER - 1.  ({default_deepzoom_levels} is the default, for {default_deepzoom_size} x {default_deepzoom_size} pixels.)
+   --export-npy=PATH
+      Instead of opening a window, calculates the escape value of
+      every pixel of the view, saves them to PATH as a NumPy .npy
+      file, and exits, for analyzing them in Python (with
+      numpy.load(PATH)).  It's a 2-D array of 32-bit ints (dtype
+      '<i4'), with the shape (height, width) (so, --size by --size),
+      whose first row is the top of the image.  Points in the set
+      have a value of {npy_in_set}.
+   --export-heightmap=PATH
+      Instead of opening a window, calculates the escape value of
+      every pixel of the view, saves them to PATH as a heightmap
+      (for 3-D programs like Blender), and exits.  The points that
+      took the longest to escape are the highest, and the points in
+      the set are a plateau at the very top.  If PATH ends in .png,
+      it's a 16-bit grayscale image (for displacement mapping), with
+      the highest points white.  If it ends in .obj, it's a mesh of
+      triangles (with the y axis up).  With --cache-dir, the escape
+      values are loaded from (or saved to) the cache.
+   --heightmap-step=NUMBER
+      Gives an --export-heightmap .obj file a vertex for only every
+      NUMBER-th pixel across and down, so that the file is about
+      NUMBER x NUMBER times smaller.  (1, every pixel, is the
+      default.)
+   --stats=PATH
+      Once each image is drawn, saves its statistics to PATH (as
+      text), replacing the last image's:  where it is, its bailout,
+      how many of its pixels are in the set, the lowest, highest,
+      mean, and median escape values of the rest, and a histogram of
+      their escape values (in {stats_histogram_bins} bins).  (This is more than the
+      report printed after each image, and it works with
+      --format=ppm, too.)
+   --stdin-coords
+      Instead of opening a window, reads points from stdin (one
+      \"X Y\" pair per line, or \"X,Y\"), and prints each one's
+      escape value after it (as \"X Y ESCAPE_VALUE\"), or \"in-set\"
+      (as \"X Y in-set\") if it's in the set.  The --bailout, --julia,
+      and other settings are used just as they are for the pixels of
+      the view.  Lines that aren't points get an error (on stderr)
+      and are skipped.  For example:
+         echo \"-2 0.5\" | jlr-mandelbrot --stdin-coords --bailout=100
+   --quiet
+      Doesn't print the welcome message or the elapsed times.
+   --notify
+      Rings the console's bell whenever an image is finished (with
+      --progressive, once it's finished being refined), so a long
+      drawing can be left to finish while you do something else.
+   --profile
+      After each image is drawn, prints how many pixels and iterations
+      it took (and the most iterations any one pixel took), and how
+      much of the time went to calculating the pixels vs. to updating
+      the window.  (With --format=ppm, this is printed to stderr.)
+   --coord-format=TYPE
+      Chooses how coordinates are printed when the C key is
+      pressed.  TYPE can be:
+         decimal        (rounded to more decimal places the more
+                         the image is zoomed in; the default)
+         scientific     (like 1.2345678e-9, which is easier to
+                         read at deep zooms)
+         command-line   (the switches that would start this program
+                         with the same view, to copy and paste)
+   --crosshair
+      Starts with a crosshair shown at the center of the window.
+      (The crosshair doesn't appear in saved screenshots.)
+   --crosshair=baked
+      Like --crosshair, but the crosshair also appears in saved
+      screenshots.
+   --smooth-labels
+      Draws the letters of labels (like the ones shown with the I
+      key, and the ones on a --contact-sheet) smoothly, with soft,
+      rounded edges, instead of as blocky squares.  (The smallest
+      labels, in windows narrower than 400 pixels, stay blocky.)
+   --bailout-heatmap
+      Starts with every pixel that hit the bailout tinted, showing
+      where a higher --bailout would show more detail.  (The tint
+      doesn't appear in saved screenshots.)
+   --bailout-tint=R,G,B
+      The color of the --bailout-heatmap tint.  (Each value ranges
+      from 0 to 255; 255,0,255 (magenta) is the default.)
+   --bailout-tint-opacity=NUMBER
+      How strong the --bailout-heatmap tint is, from 0 (invisible)
+      to 1 (solid).  (The default is 0.5.)
+
+Once the image is displayed:
+   A left-click of the mouse zooms in.
+   A right-click of the mouse zooms out.
+   Pressing the Z key zooms in on the point under the mouse, just
+      as a left-click there would (and Shift+Z zooms out, just as a
+      right-click would).  With the mouse outside of the window, it
+      zooms in (or out) without moving the center.
+   Pressing the ] key zooms in (and the [ key zooms out) without
+      moving the center, wherever the mouse is.
+   A left-click while holding down Ctrl prints the orbit of the
+      clicked point (every value z takes while it's calculated)
+      to the console, and whether it's taken to be in the set.
+      The orbit is also drawn on top of the image, as lines from
+      each z to the next (with the clicked point marked in green).
+   Pressing the O key will hide (or show again) the drawn orbit.
+   Pressing the I key will show (or hide) how many iterations the
+      pixel under the mouse took to escape (or whether it's in the
+      set), next to the mouse pointer.
+   Pressing the T key will show (or hide) how long the last image
+      took to draw, and how far it's zoomed in, in the upper-left
+      corner.  (It's only updated once an image is finished.)
+   Pressing the D key will show (or hide) the bailout, the cycle
+      detection threshold, and the width and height of a pixel
+      (delta x and delta y) of the image being drawn, in the
+      lower-left corner, to help tell which of them is behind
+      whatever looks wrong in it.
+   Pressing the C key will print coordinates to the console.
+   Pressing the X key will show (or hide) a crosshair at the center.
+   Pressing the M key will show (or hide) a minimap of the whole set,
+      with the current view marked on it.  (For a Julia set, the
+      point c is marked instead.)
+   Pressing the H key will tint (or untint) every pixel that hit the
+      bailout, showing where a higher bailout would show more detail.
+   Pressing the + key will double the bailout value, and pressing
+      the - key will halve it.  (If no bailout is being used, the
+      - key starts one at half the highest escape value shown.)
+   Pressing the > key will spread the colors out (making the color
+      period longer), and pressing the < key will pack them more
+      tightly together.  The image is recolored, not recalculated.
+   Pressing the ) key will double the number of --aa-samples (up to
+      {max_aa_samples_key}), and pressing the ( key will halve it (turning
+      anti-aliasing off once it's down to 1), so the image can be
+      explored quickly, and then drawn smoothly once it's worth it.
+      The image is drawn again each time.
+   Pressing the S key will save a screenshot in PNG format.
+   Pressing the B key will save a script (a shell script, or a .cmd
+      file on Windows) that runs this program with the switches that
+      draw the current view again, so it can be bookmarked.
+   Pressing Shift and a number key (from 1 to {number_of_bookmarks}) will bookmark
+      the current view under that number, and pressing just the
+      number key will go back to it, to hop between several views.
+      (These bookmarks are forgotten once the program exits.)
+   Pressing the Q key will quit.
+   Pressing the Escape key will also quit.
+
+Exit codes:
+   0   Everything went fine.
+   {usage_error}   A switch (or the config file, or an environment variable)
+       was given an invalid value.
+   {io_error}   A file couldn't be read (like the --script), or an image
+       couldn't be written (like with --format=ppm).
+   {display_error}   The window couldn't be opened (or updated).
+
+Author:  Jean-Luc Romano
+e-mail:  {username}@{domain}.{suffix}
+
+", default_size = DEFAULT_WINDOW_SIZE, max_size = MAX_WINDOW_SIZE,
+   first_bailout = PROGRESSIVE_FIRST_BAILOUT, bailout_step = PROGRESSIVE_BAILOUT_STEP,
+   max_ramp_bailout = PROGRESSIVE_MAX_RAMP_BAILOUT,
+   default_julia_atlas_grid_size = DEFAULT_JULIA_ATLAS_GRID_SIZE, npy_in_set = NPY_IN_SET,
+   max_sharpen = MAX_SHARPEN, max_aa_samples_key = MAX_AA_SAMPLES_KEY,
+   number_of_bookmarks = NUMBER_OF_BOOKMARKS,
+   location_names = location_names(),
+   default_tile_size = DEFAULT_DEEPZOOM_TILE_SIZE, max_deepzoom_levels = MAX_DEEPZOOM_LEVELS,
+   default_deepzoom_levels = DEFAULT_DEEPZOOM_LEVELS,
+   default_deepzoom_size = deepzoom_full_size(DEFAULT_DEEPZOOM_TILE_SIZE, DEFAULT_DEEPZOOM_LEVELS),
+   default_max_iterations = DEFAULT_MAX_ITERATIONS, stats_histogram_bins = STATS_HISTOGRAM_BINS,
+   auto_bailout_base = AUTO_BAILOUT_BASE, auto_bailout_per_zoom_level = AUTO_BAILOUT_PER_ZOOM_LEVEL,
+   min_color_period = MIN_COLOR_PERIOD, max_color_period = MAX_COLOR_PERIOD, default_color_period = NUM_COLORS,
+   random_palette_colors = RANDOM_PALETTE_CONTROL_POINTS,
+   default_fast_fill_min_size = DEFAULT_FAST_FILL_MIN_SIZE,
+   usage_error = EXIT_USAGE_ERROR, io_error = EXIT_IO_ERROR, display_error = EXIT_DISPLAY_ERROR,
+   username = "jl_post", domain = "hotmail", suffix = "com")
+}
+
+
+#[allow(dead_code)]
+fn test_inverted_palette() {
+    println!();
+    println!("Testing the inverted palette (--invert):");
+    for i in 0..1000 {
+        let inverted = inverted_palette_index(i);
+        assert!(inverted < NUM_COLORS);
+        assert_ne!(color(Some(inverted)), color(Some(i)), "{i} kept its color when inverted");
+        // Inverting twice should give the same color as not inverting at all:
+        assert_eq!(inverted_palette_index(inverted), i % NUM_COLORS);
+        assert_eq!(color(Some(inverted_palette_index(inverted))), color(Some(i)));
+    }
+    // The ends of the palette should swap places:
+    assert_eq!(inverted_palette_index(0), NUM_COLORS - 1);
+    assert_eq!(inverted_palette_index(NUM_COLORS - 1), 0);
+
+    // Points in the set should keep their color:
+    let mut config = Config::new();
+    config.invert = true;
+    assert_eq!(palette_index(None, &config), None);
+    assert_eq!(palette_index(Some(5), &config), Some(NUM_COLORS - 6));
+    config.invert = false;
+    assert_eq!(palette_index(Some(5), &config), Some(5));
+    println!("Inverting twice gave back the same color for every value from 0 to 999.");
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_color_period() {
+    println!();
+    println!("Testing the color period (--color-period, and the < and > keys):");
+    // The default period should give exactly the same colors as before,
+    // and a period of half that should skip every other color:
+    let (default_config, mut config) = (Config::new(), Config::new());
+    config.color_period = NUM_COLORS / 2;
+    for escape_value in 0..1000 {
+        let details = EscapeDetails { escape_value: Some(escape_value), ..EscapeDetails::default() };
+        let (r, g, b) = color(Some(escape_value));
+        assert_eq!(pixel_color(&details, &default_config), rgb_to_u32(r, g, b));
+        let (r, g, b) = color(Some(escape_value * 2));
+        assert_eq!(pixel_color(&details, &config), rgb_to_u32(r, g, b));
+    }
+    // (A period of twice that should blend the colors in between.)
+    config.color_period = NUM_COLORS * 2;
+    let details = EscapeDetails { escape_value: Some(1), ..EscapeDetails::default() };
+    let in_between = palette_color_components(0.5, &config);
+    assert_eq!(pixel_color_components(&details, &config), in_between);
+    assert_eq!(pixel_color(&EscapeDetails::default(), &config), pixel_color(&EscapeDetails::default(), &default_config));
+
+    // The keys should change the period by about a quarter,
+    // by at least one, and never past the limits:
+    let mut color_period = NUM_COLORS;
+    let mut periods = vec![color_period];
+    while color_period > MIN_COLOR_PERIOD {
+        color_period = adjusted_color_period(color_period, false);
+        periods.push(color_period);
+    }
+    println!("Pressing < from {NUM_COLORS}:  {periods:?}");
+    assert_eq!(adjusted_color_period(NUM_COLORS, true), 113);
+    assert_eq!(adjusted_color_period(NUM_COLORS, false), 72);
+    assert_eq!(adjusted_color_period(3, false), 2);
+    assert_eq!(adjusted_color_period(3, true), 4);
+    assert_eq!(adjusted_color_period(MIN_COLOR_PERIOD, false), MIN_COLOR_PERIOD);
+    assert_eq!(adjusted_color_period(MAX_COLOR_PERIOD, true), MAX_COLOR_PERIOD);
+
+    assert_eq!(parse_color_period("45"), Ok(45));
+    for bad_text in ["1", "0", "-5", "abc", "", "1000001"] {
+        println!("{bad_text:?}:  {:?}", parse_color_period(bad_text));
+        assert!(parse_color_period(bad_text).is_err());
+    }
+
+    // No period (and no escape value) should give a color that's out
+    // of the palette's range:  every color should still have a channel
+    // of zero, with the other two adding up to (almost) 255.
+    for color_period in [MIN_COLOR_PERIOD, 3, 7, 29, 45, 89, NUM_COLORS, 91, 1000, 65_536, MAX_COLOR_PERIOD] {
+        for invert in [false, true] {
+            let config = Config { color_period, invert, ..Config::new() };
+            let escape_values = (0..2000).chain([123_456_789, usize::MAX / 2, usize::MAX]);
+            for escape_value in escape_values {
+                let details = EscapeDetails { escape_value: Some(escape_value), ..EscapeDetails::default() };
+                let (r, g, b) = pixel_color_components(&details, &config);
+                assert!([r, g, b].iter().all(|channel| (0.0..=255.0).contains(channel)),
+                        "period {color_period}, escape value {escape_value}:  {:?}", (r, g, b));
+                let color = pixel_color(&details, &config);
+                let channels = [color >> 16, (color >> 8) & 0xff, color & 0xff];
+                let sum: u32 = channels.iter().sum();
+                assert!(channels.contains(&0) && (253..=255).contains(&sum),
+                        "period {color_period}, escape value {escape_value}:  {color:06x}");
+            }
+        }
+    }
+    println!("Every color period gave colors in the palette's range.");
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_color_cycle() {
+    println!();
+    println!("Testing color cycling (--color-cycle, and the A key):");
+    // The offset goes through the whole palette once, and then wraps:
+    let mut palette_offset = 0;
+    for step in 1..=NUM_COLORS {
+        palette_offset = next_palette_offset(palette_offset);
+        assert_eq!(palette_offset, step % NUM_COLORS);
+    }
+    assert_eq!(palette_offset, 0);
+
+    // Shifting the palette by an offset gives the color that many
+    // iterations further along it (also with --invert, and even for
+    // escape values too big to add the offset to), and points in the
+    // set keep their color:
+    for invert in [false, true] {
+        let config = Config { invert, ..Config::new() };
+        for palette_offset in [0, 1, 45, NUM_COLORS - 1] {
+            let shifted_config = Config { palette_offset, ..config.clone() };
+            for escape_value in [0, 1, 89, 1000, usize::MAX] {
+                let details = EscapeDetails { escape_value: Some(escape_value), ..EscapeDetails::default() };
+                let expected = EscapeDetails { escape_value: Some(escape_value % NUM_COLORS + palette_offset),
+                                               ..EscapeDetails::default() };
+                assert_eq!(pixel_color(&details, &shifted_config), pixel_color(&expected, &config),
+                           "invert {invert}, offset {palette_offset}, escape value {escape_value}");
+            }
+            assert_eq!(palette_index(None, &shifted_config), None);
+        }
+    }
+
+    // With a color period, the offset is still in palette colors:
+    let config = Config { color_period: 45, ..Config::new() };
+    let shifted_config = Config { palette_offset: NUM_COLORS / 2, ..config.clone() };
+    let position = scaled_palette_position(10.0, &config);
+    assert!((scaled_palette_position(10.0, &shifted_config)
+             - (position + (NUM_COLORS / 2) as Float).rem_euclid(NUM_COLORS as Float)).abs() < 1e-9);
+    println!("Shifting the palette moved every color along it, wrapping around at the end.");
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_float_benchmark() {
+    println!();
+    println!("Testing the f32 vs. f64 benchmark (--float-bench):");
+    // f32 and f64 should agree about points that are
+    // nowhere near the boundary (or exactly representable):
+    for (x, y) in [(0.0, 0.0), (-1.0, 0.0), (0.5, 0.5), (-0.5, 0.25), (2.0, 2.0)] {
+        assert_eq!(calculate_escape_value(x as f32, y as f32, None, Some(1e-4), Some(1000)),
+                   calculate_escape_value(x, y, None, Some(1e-4), Some(1000)));
+    }
+    assert_eq!(calculate_escape_details(-0.75f32, 0.01, None, Some(1e-4), Some(1000), false).escape_value,
+               calculate_escape_details(-0.75, 0.01, None, Some(1e-4), Some(1000), false).escape_value);
+
+    // The whole set should look (almost) the same either way, but
+    // zoomed in far enough, f32 can't tell the pixels apart anymore:
+    let mut config = Config::new();
+    config.bailout = Some(2000);
+    let whole_set = WindowAndViewportInfo::new(32, 32, -0.5, 0.0, 1.725, 0);
+    let zoomed_in = WindowAndViewportInfo::new(32, 32, -1.25, 0.02, 1e-6, 0);
+    let (whole_set_benchmark, zoomed_in_benchmark) = (float_benchmark(&whole_set, &config),
+                                                      float_benchmark(&zoomed_in, &config));
+    print!("The whole set:\n{}", float_benchmark_text(&whole_set_benchmark));
+    print!("Zoomed in:\n{}", float_benchmark_text(&zoomed_in_benchmark));
+    assert_eq!((whole_set_benchmark.number_of_pixels, zoomed_in_benchmark.number_of_pixels), (32 * 32, 32 * 32));
+    assert!(whole_set_benchmark.different_pixels < 32 * 32 / 20);
+    assert!(zoomed_in_benchmark.different_pixels > 32 * 32 / 4);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_diff() {
+    println!();
+    println!("Testing the image of the differences between two configs (--diff):");
+    let details = |escape_value: Option<usize>| EscapeDetails { escape_value, ..EscapeDetails::default() };
+    let first = [details(None), details(Some(5)), details(Some(5)), details(None), details(Some(7))];
+    let second = [details(None), details(Some(5)), details(Some(6)), details(Some(9)), details(None)];
+    let colors = [0x00_00_66, 0xff_00_00, 0x00_ff_00, 0x00_00_ff, 0x80_80_00];
+    let (image_buffer, difference) = diff_image(&first, &second, &colors);
+    assert_eq!(difference, EscapeDifference { number_of_pixels: 5, different_membership: 2, different_escape_values: 1 });
+    assert_eq!(image_buffer, [blend_colors(0x00_00_66, 0, DIFF_DARKENING), blend_colors(0xff_00_00, 0, DIFF_DARKENING),
+                              DIFF_ESCAPE_VALUE_COLOR, DIFF_MEMBERSHIP_COLOR, DIFF_MEMBERSHIP_COLOR]);
+
+    // The same config twice shouldn't differ anywhere.  A low bailout
+    // puts some escaping points in the set, but doesn't change any
+    // escape values (of the points that escape before it):
+    let info = WindowAndViewportInfo::new(32, 32, -0.5, 0.0, 1.725, 0);
+    let config = Config { bailout: Some(2000), ..Config::new() };
+    let (_, difference) = render_diff(&info, &config, &config);
+    assert_eq!((difference.different_membership, difference.different_escape_values), (0, 0));
+    let (_, difference) = render_diff(&info, &config, &Config { bailout: Some(20), ..config.clone() });
+    print!("{}", escape_difference_text(&difference));
+    assert!(difference.different_membership > 0);
+    assert_eq!(difference.different_escape_values, 0);
+
+    // Zoomed in far enough, f32 gets (mostly) different escape values:
+    let zoomed_in = WindowAndViewportInfo::new(32, 32, -1.25, 0.02, 1e-6, 0);
+    let (_, difference) = render_diff(&zoomed_in, &config, &Config { float_type: FloatType::F32, ..config.clone() });
+    print!("{}", escape_difference_text(&difference));
+    assert!(difference.different_membership + difference.different_escape_values > 32 * 32 / 4);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_deepzoom() {
+    println!();
+    println!("Testing the Deep Zoom tiles (--deepzoom):");
+    assert_eq!(deepzoom_full_size(256, 1), 256);
+    assert_eq!(deepzoom_full_size(256, 4), 2048);
+    assert_eq!(deepzoom_level_sizes(8), [1, 2, 4, 8]);
+    assert_eq!(deepzoom_level_sizes(1024).len(), 11);
+    assert_eq!(deepzoom_level_sizes(1000)[..], [1, 2, 4, 8, 16, 32, 63, 125, 250, 500, 1000]);
+    let descriptor = deepzoom_descriptor(2048, 256);
+    print!("{descriptor}");
+    assert!(descriptor.contains("TileSize=\"256\" Overlap=\"0\" Format=\"png\""));
+    assert!(descriptor.contains("<Size Width=\"2048\" Height=\"2048\"/>"));
+
+    // Each tile's pixels should be exactly where they are in the level:
+    let level_info = WindowAndViewportInfo::new(64, 64, -0.75, 0.1, 0.25, 0);
+    for (tile_row, tile_column) in [(0, 0), (0, 3), (2, 1), (3, 3)] {
+        let tile_info = deepzoom_tile_info(&level_info, 16, (tile_row, tile_column));
+        assert_eq!((tile_info.width, tile_info.height), (16, 16));
+        assert!((tile_info.delta_x - level_info.delta_x).abs() < 1e-15);
+        assert!((tile_info.delta_y - level_info.delta_y).abs() < 1e-15);
+        for (row, column) in [(0, 0), (5, 11), (15, 15)] {
+            let (x, y) = convert_row_and_column_to_x_and_y(&tile_info, row as Float, column as Float);
+            let (level_x, level_y) = convert_row_and_column_to_x_and_y(&level_info,
+                                                                       (tile_row * 16 + row) as Float,
+                                                                       (tile_column * 16 + column) as Float);
+            assert!((x - level_x).abs() < 1e-12 && (y - level_y).abs() < 1e-12);
+        }
+    }
+    // ...and a level smaller than a tile is all in one (smaller) tile:
+    let small_level_info = WindowAndViewportInfo::new(4, 4, -0.75, 0.1, 0.25, 0);
+    let tile_info = deepzoom_tile_info(&small_level_info, 16, (0, 0));
+    assert_eq!((tile_info.width, tile_info.height), (4, 4));
+    assert!((tile_info.center_x - -0.75).abs() < 1e-15 && (tile_info.center_y - 0.1).abs() < 1e-15);
+    assert!((tile_info.distance_from_center_to_edge - 0.25).abs() < 1e-15);
+
+    // Saving one should write the descriptor, and every level's tiles:
+    let path = std::env::temp_dir().join(format!("jlr-mandelbrot-test-deepzoom.{}", std::process::id()));
+    let path = path.to_str().unwrap();
+    let config = Config { bailout: Some(100), ..Config::new() };
+    let program_config = ProgramConfig { window_size: 32, quiet: true, ..ProgramConfig::new() };
+    assert_eq!(save_deepzoom(path, &config, &program_config, 16, 2), Ok(5 + 4));  // (Levels 0 to 4 have one tile, and level 5 has four.)
+    assert_eq!(std::fs::read_to_string(format!("{path}.dzi")).unwrap(), deepzoom_descriptor(32, 16));
+    for (level, size) in [(0, 1), (3, 8), (4, 16)] {
+        assert_eq!(image::image_dimensions(format!("{path}_files/{level}/0_0.png")).unwrap(), (size, size));
+    }
+    for tile_name in ["0_0", "1_0", "0_1", "1_1"] {
+        assert_eq!(image::image_dimensions(format!("{path}_files/5/{tile_name}.png")).unwrap(), (16, 16));
+    }
+    assert!(!std::path::Path::new(&format!("{path}_files/6")).exists());
+    std::fs::remove_file(format!("{path}.dzi")).unwrap();
+    std::fs::remove_dir_all(format!("{path}_files")).unwrap();
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_npy_bytes() {
+    println!();
+    println!("Testing npy_bytes() (for --export-npy):");
+    let info = WindowAndViewportInfo::new(5, 3, -0.5, 0.0, 1.5, 0);
+    let config = Config { bailout: Some(50), ..Config::new() };
+    let escape_details = render_escape_details(&info, &config);
+    let bytes = npy_bytes(&escape_details, 5, 3);
+
+    // The header is padded so that the data starts on a multiple of 64:
+    assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+    let header_length = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+    let data_start = 10 + header_length;
+    assert_eq!(data_start % 64, 0);
+    let header = std::str::from_utf8(&bytes[10..data_start]).unwrap();
+    println!("{header:?}");
+    assert!(header.starts_with("{'descr': '<i4', 'fortran_order': False, 'shape': (3, 5), }"));
+    assert!(header.ends_with(" \n"));
+
+    // ...followed by the escape values, row by row, from the top:
+    assert_eq!(bytes.len(), data_start + 5 * 3 * 4);
+    let values: Vec<i32> = bytes[data_start..].chunks_exact(4)
+                                              .map(|chunk| i32::from_le_bytes(chunk.try_into().unwrap()))
+                                              .collect();
+    for (value, details) in values.iter().zip(escape_details.iter()) {
+        assert_eq!(*value, details.escape_value.map_or(NPY_IN_SET, |escape_value| escape_value as i32));
+    }
+    assert!(values.contains(&NPY_IN_SET) && values.iter().any(|&value| value > 0));
+
+    // Escape values too big for an i32 are capped:
+    let huge = EscapeDetails { escape_value: Some(usize::MAX), ..EscapeDetails::default() };
+    assert!(npy_bytes(&[huge], 1, 1).ends_with(&i32::MAX.to_le_bytes()));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_heightmap() {
+    println!();
+    println!("Testing --export-heightmap:");
+    let escaped = |escape_value| EscapeDetails { escape_value: Some(escape_value), ..EscapeDetails::default() };
+    let escape_details = [escaped(0), escaped(5), escaped(10), EscapeDetails::default(),
+                          escaped(2), escaped(10), EscapeDetails::default(), EscapeDetails::default(),
+                          escaped(1), escaped(4), escaped(8), escaped(10)];  // (4 x 3 pixels)
+    let heights = heightmap_heights(&escape_details);
+    assert_eq!(heights[..4], [0.0, 0.5, 1.0, 1.0]);  // (The set is as high as the highest escape value.)
+    assert_eq!(heightmap_heights(&[EscapeDetails::default(); 2]), vec![1.0; 2]);
+    assert_eq!(heightmap_heights(&[escaped(0); 2]), vec![0.0; 2]);
+
+    // Every pixel is a vertex (with 2 triangles for each square of 4):
+    let obj = heightmap_obj(&heights, 4, 3, 1);
+    let lines = |prefix: &str| obj.lines().filter(|line| line.starts_with(prefix)).count();
+    assert_eq!((lines("v "), lines("f ")), (12, 2 * 3 * 2));
+    assert!(obj.contains("\nv 0 0 0\n"));
+    assert!(obj.contains(&format!("\nv 1 {HEIGHTMAP_OBJ_HEIGHT} 0\n")));  // (The upper-right pixel, in the set.)
+    assert!(obj.lines().any(|line| line == "f 1 5 2"));
+    // Every triangle faces up (its normal's y is positive):
+    let vertices: Vec<Vec<Float>> = obj.lines()
+                                       .filter_map(|line| line.strip_prefix("v "))
+                                       .map(|line| line.split(' ').map(|value| value.parse().unwrap()).collect())
+                                       .collect();
+    for face in obj.lines().filter_map(|line| line.strip_prefix("f ")) {
+        let [a, b, c]: [&Vec<Float>; 3] = face.split(' ')
+                                              .map(|index| &vertices[index.parse::<usize>().unwrap() - 1])
+                                              .collect::<Vec<_>>().try_into().unwrap();
+        let normal_y = (b[2] - a[2]) * (c[0] - a[0]) - (b[0] - a[0]) * (c[2] - a[2]);
+        assert!(normal_y > 0.0, "f {face}");
+    }
+
+    // With a step, only some of the pixels are vertices, but the last
+    // row and column still are, so the whole image is covered:
+    let obj = heightmap_obj(&heights, 4, 3, 2);
+    println!("{}", obj.lines().take(4).collect::<Vec<_>>().join("\n"));
+    let lines = |prefix: &str| obj.lines().filter(|line| line.starts_with(prefix)).count();
+    assert_eq!((lines("v "), lines("f ")), (3 * 2, 2 * 1 * 2));  // (Columns 0, 2, and 3; rows 0 and 2.)
+    assert!(obj.contains("\nv 1 0.25 0.6666666666666666\n"));  // (The lower-right pixel.)
+
+    assert!(is_obj_filename("mesh.OBJ") && !is_obj_filename("mesh.png") && !is_obj_filename("obj"));
+}
+
+
+#[allow(dead_code)]
+fn test_stdin_coords() {
+    println!();
+    println!("Testing stdin_coords_line() (for --stdin-coords):");
+    let config = Config { bailout: Some(100), ..Config::new() };
+    let threshold = 1e-9;
+    for (line, expected) in [
+        ("-2 0.5", Ok("-2 0.5 0".to_string())),  // (It starts out of bounds.)
+        ("  0\t0  ", Ok("0 0 in-set".to_string())),  // (The origin is in the set, found by cycle detection.)
+        ("0.3,0.5", Ok("0.3 0.5 in-set".to_string())),
+        ("1e-3 -0.25", Ok("1e-3 -0.25 in-set".to_string())),
+        ("3 4", Ok("3 4 0".to_string())),
+    ] {
+        let result = stdin_coords_line(line, &config, threshold);
+        println!("{line:?} => {result:?}");
+        assert_eq!(result, expected);
+    }
+    // The values printed are the same as the ones calculate_escape_value() gives:
+    let result = stdin_coords_line("-0.75 0.1", &config, threshold).unwrap();
+    let escape_value = calculate_escape_value(-0.75, 0.1, None, Some(threshold), config.bailout);
+    assert_eq!(result, format!("-0.75 0.1 {}", escape_value.unwrap()));
+    for bad_line in ["1", "1 2 3", "x 2", "1 inf", "1,2,3"] {
+        let result = stdin_coords_line(bad_line, &config, threshold);
+        println!("{bad_line:?} => {result:?}");
+        assert!(result.is_err());
+    }
+    println!();
+}
+
+#[allow(dead_code)]
+fn test_float_type() {
+    println!();
+    println!("Testing the pixels calculated in f32 (--float=f32):");
+    for float_type in [FloatType::F32, FloatType::F64] {
+        assert_eq!(FloatType::from_name(float_type.name()), Some(float_type));
+    }
+    assert_eq!(FloatType::from_name("f128"), None);
+
+    // A viewport (and its pixels' x & y) should come out
+    // the same in f32 as in f64, give or take f32's precision:
+    let info = WindowAndViewportInfo::new(200, 100, -0.5, 0.25, 1.5, 3);
+    let f32_info: WindowAndViewportInfo<f32> = info.converted().unwrap();
+    println!("{f32_info:?}");
+    assert_eq!((f32_info.width, f32_info.height, f32_info.zoom_level), (200, 100, 3));
+    assert_eq!((f32_info.min_x, f32_info.max_y, f32_info.delta_x), (-2.0, 1.75, 0.015));
+    for (row, column) in [(0, 0), (99, 199), (42, 17)] {
+        let (x, y) = convert_row_and_column_to_x_and_y(&info, row as Float, column as Float);
+        let (f32_x, f32_y) = convert_row_and_column_to_x_and_y(&f32_info, row as f32, column as f32);
+        assert!((f32_x.to_float() - x).abs() < 1e-6 && (f32_y.to_float() - y).abs() < 1e-6);
+    }
+    // (Converting back works everything out again from the center and
+    // distance, which f32 holds exactly here, so nothing is lost.)
+    assert_eq!(f32_info.converted::<f64>().unwrap().delta_x, info.delta_x);
+
+    // The whole set should look (almost) the same in f32, but a
+    // viewport that's fine in f64 can be too small for f32:
+    let info = WindowAndViewportInfo::new(48, 48, -0.5, 0.0, 1.725, 0);
+    let mut config = Config::new();
+    config.bailout = Some(200);
+    let mut f64_buffer = vec![0; 48 * 48];
+    render_into_buffer(&mut f64_buffer, &info, &config);
+    config.float_type = FloatType::F32;
+    let mut f32_buffer = vec![0; 48 * 48];
+    render_into_buffer(&mut f32_buffer, &info, &config);
+    let different_pixels = f64_buffer.iter().zip(&f32_buffer).filter(|(a, b)| a != b).count();
+    println!("{different_pixels} of the {} pixels were colored differently in f32.", 48 * 48);
+    assert!(different_pixels < 48 * 48 / 20);
+    let deep_info = WindowAndViewportInfo::new(48, 48, -0.745, 0.1, 1e-9, 0);
+    assert!(!deep_info.is_degenerate());
+    assert!(deep_info.converted::<f32>().unwrap().is_degenerate());
+    assert!(!deep_info.is_degenerate_for(FloatType::F64));
+    assert!(deep_info.is_degenerate_for(FloatType::F32));
+
+    // A viewport whose distance underflows in f32 (or whose center
+    // overflows) can't be converted at all, and is degenerate for f32:
+    for (center_x, distance) in [(0.0, 1e-46), (1e39, 1e30)] {
+        let info = WindowAndViewportInfo::new(8, 8, center_x, 0.0, distance, 0);
+        assert!(!info.is_degenerate_for(FloatType::F64));
+        assert!(info.converted::<f32>().is_none());
+        assert!(info.is_degenerate_for(FloatType::F32));
+        // (Drawing it anyway shouldn't panic.)
+        let mut buffer = vec![0; 8 * 8];
+        render_into_buffer(&mut buffer, &info, &config);
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_projection() {
+    println!();
+    println!("Testing the log-polar projection (--projection=logpolar):");
+    for projection in [Projection::Rectangular, Projection::LogPolar] {
+        assert_eq!(Projection::from_name(projection.name()), Some(projection));
+    }
+    assert_eq!(Projection::from_name("mercator"), None);
+
+    // The top row should be (almost) the distance from the center, and
+    // the columns should go once around it, counterclockwise:
+    let info = WindowAndViewportInfo::new(64, 64, -0.5, 0.25, 1.5, 0).with_projection(Projection::LogPolar);
+    let step = log_polar_step(64);
+    let (x, y) = convert_row_and_column_to_x_and_y(&info, -0.5, -0.5);
+    assert!((x - 1.0).abs() < 1e-12 && (y - 0.25).abs() < 1e-12);
+    let (x, y) = convert_row_and_column_to_x_and_y(&info, -0.5, 15.5);
+    assert!((x + 0.5).abs() < 1e-12 && (y - 1.75).abs() < 1e-12);
+    // (Each row is closer to the center by a factor of e^step.)
+    let (x, _) = convert_row_and_column_to_x_and_y(&info, 9.5, -0.5);
+    assert!((x + 0.5 - 1.5 * (-10.0 * step).exp()).abs() < 1e-12);
+
+    // The inverse should give back the same row & column:
+    for (row, column) in [(0.0, 0.0), (63.0, 63.0), (42.0, 17.0), (20.5, 31.5)] {
+        let (x, y) = convert_row_and_column_to_x_and_y(&info, row, column);
+        let (row_again, column_again) = convert_x_and_y_to_row_and_column(&info, x, y);
+        println!("({row}, {column}) -> ({x}, {y}) -> ({row_again}, {column_again})");
+        assert!((row_again - row).abs() < 1e-9 && (column_again - column).abs() < 1e-9);
+    }
+    assert_eq!(convert_x_and_y_to_row_and_column(&info, -0.5, 0.25).0, Float::INFINITY);
+
+    // The pixels at the bottom are much smaller, so the cycle threshold
+    // (and the degenerate check) should go by them, and the projection
+    // should survive being converted to f32:
+    let config = Config::new();
+    let rectangular_info = WindowAndViewportInfo::new(64, 64, -0.5, 0.25, 1.5, 0);
+    assert_eq!(cycle_threshold(&rectangular_info, &config), rectangular_info.delta_x / 4.0);
+    assert!(cycle_threshold(&info, &config) < rectangular_info.delta_x / 400.0);
+    assert_eq!(info.converted::<f32>().unwrap().projection, Projection::LogPolar);
+    let deep_info = WindowAndViewportInfo::new(64, 64, -0.745, 0.1, 1e-13, 0);
+    assert!(!deep_info.is_degenerate());
+    assert!(deep_info.with_projection(Projection::LogPolar).is_degenerate());
+
+    // And it should be rendered that way, too (the whole top row is
+    // outside of the set, while the bottom row, close to the center
+    // at -0.5+0.25i, is all inside of it):
+    let mut config = Config::new();
+    config.bailout = Some(200);
+    let escape_details = render_escape_details(&info, &config);
+    assert!(escape_details[..64].iter().all(|details| matches!(details.escape_value, Some(n) if n < 10)));
+    assert!(escape_details[63 * 64..].iter().all(|details| details.escape_value.is_none()));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_palette_ignores_bailout() {
+    println!();
+    println!("Testing that the colors don't depend on the bailout:");
+    // Every point that escapes with both bailouts should get the same
+    // color with both (only points that hit the lower bailout differ):
+    let info = WindowAndViewportInfo::new(64, 64, -0.745, 0.1, 0.01, 0);
+    for coloring in [Coloring::EscapeTime, Coloring::Potential] {
+        let (mut low_config, mut high_config) = (Config::new(), Config::new());
+        (low_config.coloring, high_config.coloring) = (coloring, coloring);
+        (low_config.bailout, high_config.bailout) = (Some(100), Some(1600));
+        let (mut low_buffer, mut high_buffer) = (vec![0; 64 * 64], vec![0; 64 * 64]);
+        render_into_buffer(&mut low_buffer, &info, &low_config);
+        render_into_buffer(&mut high_buffer, &info, &high_config);
+
+        let mut escaped_with_both = 0;
+        for row in 0..64 {
+            for column in 0..64 {
+                let details = calculate_pixel_details(&info, row, column, &low_config, 0.0);
+                if details.escape_value.is_some() {
+                    assert_eq!(low_buffer[row * 64 + column], high_buffer[row * 64 + column]);
+                    escaped_with_both += 1;
+                }
+            }
+        }
+        println!("{}:  {escaped_with_both} pixels escaped with both bailouts, and kept their colors.", coloring.name());
+        assert!(escaped_with_both > 0 && escaped_with_both < 64 * 64);
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_calculate_escape_value_function() {
+    println!();
+    println!("Testing the calculate_escape_value() function:");
+    let (x, y) = (0.0, 0.0);  println!("{:?}: {:?}", (x, y), calculate_escape_value(x, y, None, None, None));
+    let (x, y) = (-1., 0.4);  println!("{:?}: {:?}", (x, y), calculate_escape_value(x, y, None, None, None));
+    let (x, y) = (0.25, 0.5);  println!("{:?}: {:?}", (x, y), calculate_escape_value(x, y, None, Some(0.001), None));
+    let (x, y) = (-1., 0.25);  println!("{:?}: {:?}", (x, y), calculate_escape_value(x, y, None, Some(0.001), None));
+    let (x, y) = (-1., -0.25);  println!("{:?}: {:?}", (x, y), calculate_escape_value(x, y, None, Some(0.001), None));
+    let (x, y) = (0.25, -0.5);  println!("{:?}: {:?}", (x, y), calculate_escape_value(x, y, None, Some(0.001), None));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_julia_and_mandelbrot_modes() {
+    println!();
+    println!("Testing that calculate_escape_value() uses c correctly (for Mandelbrot and Julia sets):");
+    let (threshold, bailout): (Option<Float>, Option<usize>) = (Some(1e-6), Some(1000));
+
+    // The Mandelbrot set (with c = None, so c is the point itself):
+    // 0 stays at 0, -1 cycles between -1 and 0, and 1+i (or anything
+    // more than 2 away from 0) escapes right away:
+    assert_eq!(calculate_escape_value(0.0, 0.0, None, threshold, bailout), None);
+    assert_eq!(calculate_escape_value(-1.0, 0.0, None, threshold, bailout), None);
+    assert!(calculate_escape_value(1.0, 1.0, None, threshold, bailout).is_some());
+    assert!(calculate_escape_value(0.5, 0.0, None, threshold, bailout).is_some());
+    assert_eq!(calculate_escape_value(3.0, 0.0, None, threshold, bailout),
+               calculate_escape_value(3.0, 0.0, Some((0.0, 0.0)), threshold, bailout));
+
+    // The Julia set of c = 0 is the unit circle:  z just gets squared,
+    // so points inside of it stay there, and points outside escape.
+    // (So 0.5, which escapes from the Mandelbrot set, is in this one.)
+    for (x, y) in [(0.0, 0.0), (0.5, 0.0), (0.5, 0.5), (-0.7, 0.1), (0.0, -0.99)] {
+        assert_eq!(calculate_escape_value(x, y, Some((0.0, 0.0)), threshold, bailout), None, "{:?}", (x, y));
+    }
+    for (x, y) in [(1.1, 0.0), (0.8, 0.8), (-0.3, -1.1)] {
+        assert!(calculate_escape_value(x, y, Some((0.0, 0.0)), threshold, bailout).is_some(), "{:?}", (x, y));
+    }
+    // (And the Julia set of c = -1 has 0 cycling between 0 and -1, like above.)
+    assert_eq!(calculate_escape_value(0.0, 0.0, Some((-1.0, 0.0)), threshold, bailout), None);
+
+    // Giving each point as its own c should be the same as
+    // the Mandelbrot set, since that's just what it is:
+    let mut points_in_the_set = 0;
+    for i in 0..=24 {
+        for j in 0..=24 {
+            let (x, y) = (-2.0 + i as Float * 0.1, -1.2 + j as Float * 0.1);
+            let mandelbrot = calculate_escape_value(x, y, None, threshold, bailout);
+            assert_eq!(calculate_escape_value(x, y, Some((x, y)), threshold, bailout), mandelbrot, "{:?}", (x, y));
+            points_in_the_set += mandelbrot.is_none() as usize;
+        }
+    }
+    assert!(points_in_the_set > 0);
+
+    // The Mandelbrot set is symmetric across the real axis, while
+    // the Julia set of c = -0.8+0.156i isn't (it's symmetric around
+    // 0 instead, as z and -z both square to the same number):
+    let c = (-0.8, 0.156);
+    let (mut julia_differs_across_axis, mut modes_differ) = (false, false);
+    for i in 0..40 {
+        for j in 0..20 {
+            let (x, y) = (-1.6 + i as Float * 0.08, 0.03 + j as Float * 0.05);
+            let mandelbrot = calculate_escape_value(x, y, None, threshold, bailout);
+            assert_eq!(calculate_escape_value(x, -y, None, threshold, bailout), mandelbrot, "{:?}", (x, y));
+            let julia = calculate_escape_value(x, y, Some(c), threshold, bailout);
+            assert_eq!(calculate_escape_value(-x, -y, Some(c), threshold, bailout), julia, "{:?}", (x, y));
+            julia_differs_across_axis |= calculate_escape_value(x, -y, Some(c), threshold, bailout) != julia;
+            modes_differ |= julia != mandelbrot;
+        }
+    }
+    assert!(julia_differs_across_axis && modes_differ);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_next_fractal() {
+    println!();
+    println!("Testing next_fractal() (for the F key):");
+    // With no Julia set drawn yet, it's the one for the view's center:
+    assert_eq!(next_fractal(None, None, (-0.75, 0.1)), Some((-0.75, 0.1)));
+    // ...otherwise it's the last Julia set drawn:
+    assert_eq!(next_fractal(None, Some((-0.8, 0.156)), (-0.75, 0.1)), Some((-0.8, 0.156)));
+    // From a Julia set, it's back to the Mandelbrot set:
+    assert_eq!(next_fractal(Some((-0.8, 0.156)), Some((-0.8, 0.156)), (0.0, 0.0)), None);
+    // (So pressing F twice from the Mandelbrot set comes back to it.)
+    let julia = next_fractal(None, None, (0.25, 0.5));
+    assert_eq!(next_fractal(julia, julia, (0.25, 0.5)), None);
+
+    assert_eq!(fractal_name(Formula::Mandelbrot, None), "the Mandelbrot set");
+    assert_eq!(fractal_name(Formula::Mandelbrot, Some((-0.8, 0.156))), "the Julia set for c = -0.8,0.156");
+    assert_eq!(fractal_name(Formula::Tricorn, None), "the Tricorn");
+    println!("The F key switches between the Mandelbrot set and a Julia set.");
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_window_title() {
+    println!();
+    println!("Testing window_title() (and --title):");
+    assert_eq!(window_title(None, Formula::Mandelbrot, None), "The Mandelbrot Set");
+    assert_eq!(window_title(None, Formula::Mandelbrot, Some((-0.8, 0.156))), "The Julia Set for c = -0.8,0.156");
+    assert_eq!(window_title(None, Formula::Tricorn, None), "The Tricorn");
+    assert_eq!(window_title(None, Formula::Tricorn, Some((-0.8, 0.156))), "The Tricorn's Julia Set for c = -0.8,0.156");
+    // A --title is used as-is, whatever the fractal:
+    assert_eq!(window_title(Some("Seahorse valley"), Formula::Mandelbrot, None), "Seahorse valley");
+    assert_eq!(window_title(Some("Seahorse valley"), Formula::Tricorn, Some((-0.8, 0.156))), "Seahorse valley");
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_nudged_center() {
+    println!();
+    println!("Testing nudged_center() (for Shift+arrow keys):");
+    let info = WindowAndViewportInfo::new(200, 200, -0.5, 0.25, 1.0, 0);
+    assert_eq!(nudged_center(&info, (0, 0)), (-0.5, 0.25));
+    // (One pixel is 2.0/200 = 0.01 wide, and down is toward smaller y's.)
+    assert!((info.delta_x - 0.01).abs() < 1e-15);
+    assert_eq!(nudged_center(&info, (1, 0)), (-0.5 + info.delta_x, 0.25));
+    assert_eq!(nudged_center(&info, (-1, 0)), (-0.5 - info.delta_x, 0.25));
+    assert_eq!(nudged_center(&info, (0, 1)), (-0.5, 0.25 - info.delta_y));
+    assert_eq!(nudged_center(&info, (0, -1)), (-0.5, 0.25 + info.delta_y));
+    // Nudging the center moves the whole image by exactly one pixel,
+    // so what was the pixel to its right is now the center pixel:
+    let (x, y) = nudged_center(&info, (1, 0));
+    let nudged_info = WindowAndViewportInfo::new(200, 200, x, y, 1.0, 0);
+    let (old_x, old_y) = convert_row_and_column_to_x_and_y(&info, 100.0, 101.0);
+    let (new_x, new_y) = convert_row_and_column_to_x_and_y(&nudged_info, 100.0, 100.0);
+    assert!((old_x - new_x).abs() < 1e-12 && (old_y - new_y).abs() < 1e-12);
+    println!("Shift+arrow keys move the center by exactly one pixel.");
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_bookmarks() {
+    println!();
+    println!("Testing Bookmarks (Shift and the number keys):");
+    let mut bookmarks = Bookmarks::default();
+    for number in 1..=NUMBER_OF_BOOKMARKS {
+        assert!(bookmarks.go_to(number).is_none());
+    }
+
+    let seahorse = WindowAndViewportInfo::new(100, 100, -0.745, 0.113, 0.01, 7);
+    bookmarks.save(3, &seahorse);
+    bookmarks.save(NUMBER_OF_BOOKMARKS, &WindowAndViewportInfo::new(100, 100, -0.5, 0.0, 1.725, 0));
+    match bookmarks.go_to(3) {
+        Some(UserInput::GoTo(x, y, distance_from_center_to_edge)) => {
+            println!("Bookmark 3:  {x},{y} with a distance of {distance_from_center_to_edge}");
+            assert_eq!((x, y, distance_from_center_to_edge), (-0.745, 0.113, 0.01));
+        }
+        _ => panic!("Bookmark 3 should go to the seahorse valley."),
+    }
+    assert!(matches!(bookmarks.go_to(NUMBER_OF_BOOKMARKS), Some(UserInput::GoTo(x, _, _)) if x == -0.5));
+    assert!(bookmarks.go_to(2).is_none());
+
+    // Bookmarking another view under the same number replaces it:
+    bookmarks.save(3, &WindowAndViewportInfo::new(100, 100, 0.285, 0.011, 0.01, 7));
+    assert!(matches!(bookmarks.go_to(3), Some(UserInput::GoTo(x, _, _)) if x == 0.285));
+    println!();
+}
+
+
+// Compares the way calculate_escape_details() squares z (using 2xy)
+// against the "three-multiply" way (using (x+y)^2 - x^2 - y^2) over
+// a grid of points:  how long each takes, and how many points get a
+// different escape value.  The two ways round differently, so a few
+// points right at the edge of the set can differ (which is fine,
+// as long as it's only a very small fraction of them).
+#[allow(dead_code)]
+fn test_cycle_threshold() {
+    println!();
+    println!("Testing the cycle threshold (--cycle-threshold and --no-cycle-detect):");
+    // Near the "seahorse valley" (where many points escape slowly),
+    // a bigger threshold should take more points to be in the set,
+    // and every point taken to be in the set by a smaller threshold
+    // should still be in it with a bigger one:
+    let info = WindowAndViewportInfo::new(64, 64, -0.745, 0.1, 0.01, 0);
+    let mut config = Config::new();
+    let default_threshold = cycle_threshold(&info, &config);
+    assert_eq!(default_threshold, info.delta_x / 4.0);
+    let in_set = |config: &Config| -> Vec<bool> {
+        render_escape_details(&info, config).iter()
+                                            .map(|details| details.escape_value.is_none())
+                                            .collect()
+    };
+    let mut previous_in_set: Option<Vec<bool>> = None;
+    for factor in [0.01, 0.25, 4.0, 64.0] {
+        config.cycle_threshold_factor = factor;
+        let current_in_set = in_set(&config);
+        let count = current_in_set.iter().filter(|&&is_in_set| is_in_set).count();
+        println!("With a factor of {factor}, {count} of the {} pixels are in the set.", current_in_set.len());
+        if let Some(previous_in_set) = previous_in_set {
+            assert!(previous_in_set.iter().zip(&current_in_set).all(|(&before, &after)| !before || after),
+                    "A point in the set with a smaller threshold was outside it with {factor}.");
+        }
+        previous_in_set = Some(current_in_set);
+    }
+    // (A huge threshold should make a "blob":  far more points in the set.)
+    config.cycle_threshold_factor = 0.25;
+    let default_count = in_set(&config).iter().filter(|&&is_in_set| is_in_set).count();
+    config.cycle_threshold_factor = 64.0;
+    let blobby_count = in_set(&config).iter().filter(|&&is_in_set| is_in_set).count();
+    assert!(blobby_count > default_count);
+
+    // Without cycle detection, only the bailout
+    // should take a point to be in the set:
+    config.cycle_detection = false;
+    config.bailout = Some(500);
+    assert_eq!(cycle_threshold(&info, &config), 0.0);
+    let details = calculate_escape_details_without_cycle_detection(-0.1, 0.1, None, 500);
+    println!("(-0.1, 0.1) without cycle detection:  {details:?}");
+    assert_eq!(details, EscapeDetails { escape_value: None, period: None, iterations: 500, hit_bailout: true, escape_magnitude: 0.0 });
+    let exact_count = in_set(&config).iter().filter(|&&is_in_set| is_in_set).count();
+    println!("Without cycle detection (and a bailout of 500), {exact_count} pixels are in the set.");
+    assert!(exact_count >= default_count);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_no_cycle_detection_speed() {
+    println!();
+    println!("Comparing the speed with and without cycle detection (--no-cycle-detect):");
+    // Escaping points should get the same escape values either way:
+    for (x, y) in [(0.3, 0.5), (-0.75, 0.1), (-1.8, 0.01), (0.26, 0.0), (1.0, 1.0)] {
+        let with = calculate_escape_details(x, y, None, Some(1e-6), Some(10_000), false);
+        let without = calculate_escape_details_without_cycle_detection(x, y, None, 10_000);
+        println!("({x}, {y}):  {with:?} vs. {without:?}");
+        if with.escape_value.is_some() {
+            assert_eq!(with, without);
+        }
+    }
+
+    // Time a view of the whole set (a lot of it in the set) and a view
+    // of mostly-escaping points, each with a small and a big bailout:
+    let views = [("the whole set", -0.5, 0.0, 1.725), ("outside the set", 1.0, 1.0, 0.5)];
+    for (name, center_x, center_y, distance) in views {
+        let info = WindowAndViewportInfo::new(100, 100, center_x, center_y, distance, 0);
+        for bailout in [50, 5_000] {
+            let mut config = Config::new();
+            config.bailout = Some(bailout);
+            let mut time = |cycle_detection: bool| {
+                config.cycle_detection = cycle_detection;
+                let start_time = std::time::Instant::now();
+                let escape_details = render_escape_details(&info, &config);
+                (start_time.elapsed().as_secs_f64(), escape_details)
+            };
+            let (with_time, with_details) = time(true);
+            let (without_time, without_details) = time(false);
+            println!("{name}, bailout {bailout}:  {with_time:.6} sec. with cycle detection, {without_time:.6} sec. without");
+            // (Points that escape with cycle detection escape the same way without it.)
+            assert!(with_details.iter().zip(&without_details)
+                                .all(|(with, without)| with.escape_value.is_none() || with == without));
+        }
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_three_multiply_square() {
+    println!();
+    println!("Comparing squaring z with 2xy vs. with (x+y)^2 - x^2 - y^2:");
+
+    // (Plain escape-time loops, without cycle detection,
+    // so that only the squaring differs between them.)
+    fn escape_value_using_2xy(c_x: Float, c_y: Float, bailout: usize) -> Option<usize> {
+        let (mut x, mut y) = (c_x, c_y);
+        for iterations in 0..bailout {
+            let (x_squared, y_squared) = (x * x, y * y);
+            if x_squared + y_squared > 4.0 {
+                return Some(iterations)
+            }
+            (x, y) = (x_squared - y_squared + c_x, 2.0 * x * y + c_y);
+        }
+        None
+    }
+    fn escape_value_using_three_multiplies(c_x: Float, c_y: Float, bailout: usize) -> Option<usize> {
+        let (mut x, mut y) = (c_x, c_y);
+        for iterations in 0..bailout {
+            let (x_squared, y_squared) = (x * x, y * y);
+            if x_squared + y_squared > 4.0 {
+                return Some(iterations)
+            }
+            let sum_squared = (x + y) * (x + y);
+            (x, y) = (x_squared - y_squared + c_x, sum_squared - x_squared - y_squared + c_y);
+        }
+        None
+    }
+
+    let info = WindowAndViewportInfo::new(200, 200, -0.5, 0.0, 1.725, 0);
+    let points: Vec<(Float, Float)> = all_pixels(info.width, info.height)
+                                          .into_iter()
+                                          .map(|(row, column)| convert_row_and_column_to_x_and_y(&info, row as Float, column as Float))
+                                          .collect();
+    let bailout = 500;
+    let time_it = |escape_value: fn(Float, Float, usize) -> Option<usize>| {
+        let start_time = std::time::Instant::now();
+        let escape_values: Vec<_> = points.iter()
+                                          .map(|&(x, y)| escape_value(std::hint::black_box(x), std::hint::black_box(y), bailout))
+                                          .collect();
+        (escape_values, start_time.elapsed().as_micros() as Float / 1e6)
+    };
+    let (escape_values_using_2xy, seconds_using_2xy) = time_it(escape_value_using_2xy);
+    let (escape_values_using_three_multiplies, seconds_using_three_multiplies) = time_it(escape_value_using_three_multiplies);
+    let number_different = escape_values_using_2xy.iter()
+                                                  .zip(escape_values_using_three_multiplies.iter())
+                                                  .filter(|(a, b)| a != b)
+                                                  .count();
+    println!("Using 2xy:  {seconds_using_2xy} sec.");
+    println!("Using (x+y)^2 - x^2 - y^2:  {seconds_using_three_multiplies} sec.");
+    println!("Points with different escape values:  {number_different} of {}", points.len());
+    assert!(number_different * 1000 <= points.len());  // (No more than 0.1% may differ.)
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_period_detection() {
+    println!();
+    println!("Testing the period detection of calculate_escape_details():");
+    // The centers of the main cardioid, the big bulb to its left,
+    // and the bulb at the top (whose cycles have periods 1, 2, and 3):
+    let points_and_periods = [((0.0, 0.0), 1), ((-1.0, 0.0), 2), ((-0.122_561, 0.744_862), 3)];
+    for ((x, y), expected_period) in points_and_periods {
+        let details = calculate_escape_details(x, y, None, Some(0.000_001), Some(10_000), true);
+        println!("{:?}: {:?}", (x, y), details);
+        assert_eq!(details.period, Some(expected_period));
+    }
+    // Points that escape have no period:
+    let details = calculate_escape_details(1.0, 1.0, None, Some(0.000_001), Some(10_000), true);
+    println!("{:?}: {:?}", (1.0, 1.0), details);
+    assert_eq!(details.period, None);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_row_and_column_iterator() {
+    println!();
+    println!("Testing the RowAndColumnIterator:");
+    let mut row_and_column_iterator = RowAndColumnIterator::new(0, 0);
+    for _ in 0..50 {
+        println!("{:?}", row_and_column_iterator.next().unwrap());
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_swirling_pixels() {
+    println!();
+    println!("Testing swirling_pixels():");
+    // Every pixel of a window should be swirled through exactly once,
+    // starting from the center:
+    for (width, height) in [(1, 1), (2, 2), (7, 7), (64, 64)] {
+        let pixels: Vec<(usize, usize)> = swirling_pixels(width, height).collect();
+        assert_eq!(pixels.len(), width * height);
+        assert_eq!(pixels[0], (height / 2, width / 2));
+        let unique: std::collections::HashSet<_> = pixels.iter().collect();
+        assert_eq!(unique.len(), width * height);
+        assert!(pixels.iter().all(|&(row, column)| row < height && column < width));
+        println!("{width} x {height}:  {} pixels, starting at {:?}", pixels.len(), pixels[0]);
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_swirl_pause() {
+    println!();
+    println!("Testing that the swirling thread waits while the window's thread isn't listening:");
+    let info = WindowAndViewportInfo::new(64, 64, -0.5, 0.0, 1.5, 0);
+    let config = Config { bailout: Some(1000), ..Config::new() };
+    let threshold = cycle_threshold(&info, &config);
+    let expected = render_escape_details(&info, &config);
+    let mut escape_details = vec![EscapeDetails::default(); 64 * 64];
+    std::thread::scope(|scope| {
+        let (sender, receiver) = std::sync::mpsc::sync_channel(4);
+        let (_frame_sender, frame_receiver) = std::sync::mpsc::channel();
+        let calculation = scope.spawn(|| send_swirling_pixels(&info, &config, threshold, None, sender, frame_receiver));
+        // (Reading a few pixels, then "pausing" for a while, long
+        // enough for every pixel to be calculated otherwise...)
+        for _ in 0..10 {
+            let (row, column, details, _, _) = receiver.recv().unwrap();
+            escape_details[row * 64 + column] = details;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        assert!(!calculation.is_finished());
+        // (...and then the rest should come in, picking up mid-swirl.)
+        let rest: Vec<_> = receiver.iter().collect();
+        println!("After the pause, {} more pixels came in.", rest.len());
+        assert_eq!(rest.len(), 64 * 64 - 10);
+        for (row, column, details, _, _) in rest {
+            escape_details[row * 64 + column] = details;
+        }
+    });
+    assert_eq!(escape_details, expected);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_cancel_flag() {
+    println!();
+    println!("Testing the cancel flag (for stopping an image that's no longer wanted):");
+    // (The cusp of the main cardioid, at 0.25, takes a very long time
+    // to get anywhere, so with an exact cycle threshold and no bailout,
+    // it keeps going long after everything else is done.)
+    let info = WindowAndViewportInfo::new(3, 3, 0.25, 0.0, 1e-12, 0);
+    let cancel_flag = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let config = Config { bailout: Some(1_000_000), cancel_flag: Some(cancel_flag.clone()), ..Config::new() };
+
+    // Without being cancelled, the point takes all of its iterations:
+    let details = calculate_pixel_details(&info, 1, 1, &config, 0.0);
+    assert_eq!((details.iterations, details.hit_bailout), (1_000_000, true));
+    let without_cycle_detection = Config { cycle_detection: false, ..config.clone() };
+    assert_eq!(calculate_pixel_details(&info, 1, 1, &without_cycle_detection, 0.0).iterations, 1_000_000);
+
+    // Once it's cancelled, it gives up (as if it hit the bailout) early:
+    cancel_flag.store(true, std::sync::atomic::Ordering::Relaxed);
+    for config in [&config, &without_cycle_detection] {
+        let details = calculate_pixel_details(&info, 1, 1, config, 0.0);
+        println!("{details:?}");
+        assert!(details.hit_bailout && details.iterations < 100_000);
+    }
+
+    // ...even when it's cancelled by another thread in the middle of
+    // calculating a point that would never finish otherwise:
+    let cancel_flag = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let config = Config { bailout: None, cancel_flag: Some(cancel_flag.clone()), ..Config::new() };
+    let start_time = std::time::Instant::now();
+    let details = std::thread::scope(|scope| {
+        let calculation = scope.spawn(|| calculate_pixel_details(&info, 1, 1, &config, 0.0));
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        cancel_flag.store(true, std::sync::atomic::Ordering::Relaxed);
+        calculation.join().unwrap()
+    });
+    println!("Cancelled after {} sec.:  {details:?}", start_time.elapsed().as_micros() as Float / 1e6);
+    assert!(details.hit_bailout && details.escape_value.is_none());
+    assert!(start_time.elapsed() < std::time::Duration::from_secs(5));
+
+    // Once it's set, a whole image (drawn any of the ways the window
+    // draws one) returns without calculating any more of its pixels:
+    let info = WindowAndViewportInfo::new(64, 64, -0.5, 0.0, 1.725, 0);
+    let cancel_flag = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+    let slow_config = Config { bailout: Some(20_000), cycle_detection: false, cancel_flag: Some(cancel_flag), ..Config::new() };
+    let fast_fill_config = Config { fast_fill_min_size: Some(DEFAULT_FAST_FILL_MIN_SIZE), ..slow_config.clone() };
+    for config in [&slow_config, &fast_fill_config] {
+        for number_of_threads in [1, 4] {
+            let (escape_details, calculated) = render_and_count_escape_details(&info, config, number_of_threads);
+            assert_eq!((escape_details.len(), calculated), (info.width * info.height, 0));
+        }
+    }
+    let pixels = all_pixels(info.width, info.height);
+    let escape_details = calculate_pixel_details_in_parallel(&pixels, &info, &slow_config, 0.0);
+    assert_eq!(escape_details.len(), pixels.len());
+    assert!(escape_details.iter().all(|details| details.hit_bailout && details.iterations == 0));
+
+    // (Without a cancel flag, the results are just the same as ever.)
+    let plain_config = Config { bailout: Some(1000), ..Config::new() };
+    let flagged_config = Config { cancel_flag: Some(std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false))),
+                                  ..plain_config.clone() };
+    let info = WindowAndViewportInfo::new(16, 16, -0.5, 0.0, 1.5, 0);
+    assert_eq!(render_escape_details(&info, &plain_config), render_escape_details(&info, &flagged_config));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_parse_script_line() {
+    println!();
+    println!("Testing the parse_script_line() function:");
+    let lines = ["goto -0.75 0.1 0.05", "bailout 500", "save my tour.png", "wait 250", "quit",
+                 "", "# A comment", "goto 1 2", "bailout -3", "jump 1 2 3"];
+    for line in lines {
+        println!("{:?}: {:?}", line, parse_script_line(line));
+    }
+    assert_eq!(parse_script_line("goto -0.75 0.1 0.05"), Ok(Some(ScriptCommand::GoTo(-0.75, 0.1, 0.05))));
+    assert_eq!(parse_script_line("save my tour.png"), Ok(Some(ScriptCommand::Save("my tour.png".to_string()))));
+    assert_eq!(parse_script_line("# A comment"), Ok(None));
+    assert!(parse_script_line("goto 1 2").is_err());
+    assert_eq!(parse_script_line("goto -0.75,0.1 0.05"), Ok(Some(ScriptCommand::GoTo(-0.75, 0.1, 0.05))));
+    assert!(parse_script_line("goto 1,2,3 0.05").is_err());
+    assert!(parse_script_line("goto 1 2 inf").is_err());
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_relative_script_commands() {
+    println!();
+    println!("Testing the zoomin, zoomout, and pan script commands:");
+    for line in ["zoomin 2", "zoomout 4", "pan 0.1 -0.1", "zoomin 0", "zoomin", "pan 0.1", "zoomout nan"] {
+        println!("{:?}: {:?}", line, parse_script_line(line));
+    }
+    assert_eq!(parse_script_line("zoomin 2"), Ok(Some(ScriptCommand::ZoomIn(2.0))));
+    assert_eq!(parse_script_line("  zoomout   1.5 "), Ok(Some(ScriptCommand::ZoomOut(1.5))));
+    assert_eq!(parse_script_line("pan 0.1 -0.1"), Ok(Some(ScriptCommand::Pan(0.1, -0.1))));
+    for bad_line in ["zoomin 0", "zoomout -2", "zoomin", "zoomin 2 3", "zoomout inf", "pan 0.1", "pan x 0", "pan 1 2 3"] {
+        assert!(parse_script_line(bad_line).is_err(), "{bad_line}");
+    }
+
+    // They're relative to the current view:
+    let info = WindowAndViewportInfo::new(100, 100, -0.5, 0.25, 2.0, 0);
+    let go_to = |command: ScriptCommand| match command.to_user_input(&info) {
+        Some(UserInput::GoTo(x, y, distance)) => (x, y, distance),
+        _ => panic!("{command:?} isn't a GoTo"),
+    };
+    assert_eq!(go_to(ScriptCommand::ZoomIn(2.0)), (-0.5, 0.25, 1.0));
+    assert_eq!(go_to(ScriptCommand::ZoomOut(4.0)), (-0.5, 0.25, 8.0));
+    assert_eq!(go_to(ScriptCommand::Pan(0.5, -0.25)), (1.5, -0.75, 2.0));  // (The span is 4.)
+    assert_eq!(go_to(ScriptCommand::GoTo(0.1, 0.2, 0.3)), (0.1, 0.2, 0.3));
+    // (Going far enough out is refused by the GoTo handler.)
+    let (x, y, distance) = go_to(ScriptCommand::ZoomOut(Float::MAX));
+    assert!(!WindowAndViewportInfo::is_valid_view(x, y, distance));
+}
+
+
+#[allow(dead_code)]
+fn test_palette_registry() {
+    println!();
+    println!("Testing the palette registry (--list-palettes):");
+    let text = palette_list_text();
+    print!("{text}");
+    // Every registered name should be parsed (back into the same
+    // value) and listed:
+    for &(name, description, coloring) in Coloring::ALL {
+        assert_eq!(Coloring::from_name(name), Some(coloring));
+        assert!(text.contains(name) && text.contains(description));
+    }
+    for &(name, description, interior_coloring) in InteriorColoring::ALL {
+        assert_eq!(InteriorColoring::from_name(name), Some(interior_coloring));
+        assert!(text.contains(name) && text.contains(description));
+    }
+    for &(name, description, ()) in PALETTE_NAMES {
+        assert!(parse_palette_name(name, None).is_ok());
+        assert!(text.contains(name) && text.contains(description));
+    }
+    assert_eq!(Coloring::from_name("plaid"), None);
+    assert_eq!(text.matches("(the default)").count(), 3);
+
+    assert_eq!(quoted_names(InteriorColoring::ALL), "\"solid\" or \"period\"");
+    assert_eq!(quoted_names(&[("a", "", ()), ("b", "", ()), ("c", "", ())]), "\"a\", \"b\", or \"c\"");
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_random_palette() {
+    println!();
+    println!("Testing random palettes (--palette=random and --palette-seed):");
+    let palette = Palette::random(12345);
+    println!("Seed 12345:  {:?} ...", &palette.colors[..4]);
+    assert_eq!(palette.colors.len(), NUM_COLORS);
+    // The same seed always gives the same palette, and others don't:
+    assert_eq!(Palette::random(12345), palette);
+    assert_ne!(Palette::random(12346).colors, palette.colors);
+
+    // It's smooth all the way around (wrapping from the last color back
+    // to the first), with no channel jumping more than the steepest part
+    // of a cosine blend between two control points can:
+    let colors_per_control_point = NUM_COLORS / RANDOM_PALETTE_CONTROL_POINTS;
+    let max_jump = (std::f64::consts::PI / 2.0 * 255.0 / colors_per_control_point as f64).ceil() as i32;
+    for seed in [0, 1, 12345, u64::MAX] {
+        let colors = Palette::random(seed).colors;
+        for i in 0..NUM_COLORS {
+            let ((r1, g1, b1), (r2, g2, b2)) = (colors[i], colors[(i + 1) % NUM_COLORS]);
+            let jump = [(r1, r2), (g1, g2), (b1, b2)].iter()
+                           .map(|&(a, b)| (a as i32 - b as i32).abs()).max().unwrap();
+            assert!(jump <= max_jump, "seed {seed}:  a jump of {jump} after color {i}");
+        }
+    }
+
+    // Escaping points get the palette's colors (however they're
+    // placed in it), but points in the set keep their color:
+    let config = Config { palette: Some(palette.clone()), ..Config::new() };
+    for escape_value in [0, 1, 44, NUM_COLORS, 12345] {
+        let details = EscapeDetails { escape_value: Some(escape_value), ..EscapeDetails::default() };
+        let (r, g, b) = palette.colors[escape_value % NUM_COLORS];
+        assert_eq!(pixel_color(&details, &config), rgb_to_u32(r, g, b));
+        let blended_config = Config { color_period: NUM_COLORS * 2, ..config.clone() };
+        let (r, g, b) = palette.colors[(escape_value / 2) % NUM_COLORS];
+        if escape_value % 2 == 0 {
+            assert_eq!(pixel_color(&details, &blended_config), rgb_to_u32(r, g, b));
+        }
+    }
+    assert_eq!(pixel_color(&EscapeDetails::default(), &config), pixel_color(&EscapeDetails::default(), &Config::new()));
+
+    // --palette=random keeps a palette already chosen by --palette-seed
+    // (so the order of the switches doesn't matter):
+    assert_eq!(parse_palette_name("random", Some(palette.clone())), Ok(Some(palette.clone())));
+    assert!(matches!(parse_palette_name("random", None), Ok(Some(_))));
+    assert_eq!(parse_palette_name("default", Some(palette)), Ok(None));
+    assert!(parse_palette_name("plaid", None).is_err());
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_sample_palette() {
+    println!();
+    println!("Testing sample_palette() (for palettes of just a few colors):");
+    let palette = Palette { seed: 0, colors: vec![(255, 0, 0), (0, 255, 0), (0, 0, 255)] };
+    for (t, expected_color) in [(0.0, (255, 0, 0)), (1.0 / 3.0, (0, 255, 0)), (2.0 / 3.0, (0, 0, 255)),
+                                (1.0 / 6.0, (128, 128, 0)), (0.5, (0, 128, 128)),
+                                (5.0 / 6.0, (128, 0, 128)),  // (The last color blends back into the first.)
+                                (1.0, (255, 0, 0)), (-1.0 / 3.0, (0, 0, 255)), (7.0 / 3.0, (0, 255, 0))] {
+        let color = sample_palette(&palette, t);
+        println!("{t:.4}:  {color:?}");
+        assert_eq!(color, expected_color);
+    }
+
+    // Escaping points go smoothly through the palette's few colors,
+    // instead of jumping from one to the next:
+    let config = Config { palette: Some(palette.clone()), ..Config::new() };
+    let max_jump = (255 * palette.colors.len()).div_ceil(NUM_COLORS) as i32;
+    for i in 0..NUM_COLORS {
+        let ((r1, g1, b1), (r2, g2, b2)) = (configured_color(Some(i), &config), configured_color(Some(i + 1), &config));
+        let jump = [(r1, r2), (g1, g2), (b1, b2)].iter()
+                       .map(|&(a, b)| (a as i32 - b as i32).abs()).max().unwrap();
+        assert!(jump <= max_jump, "a jump of {jump} after color {i}");
+    }
+    assert_eq!(configured_color(Some(NUM_COLORS / 3), &config), (0, 255, 0));
+
+    // A palette of NUM_COLORS colors (like a random one) is sampled at
+    // exactly its own colors, just as if it were indexed:
+    let palette = Palette::random(7);
+    let config = Config { palette: Some(palette.clone()), ..Config::new() };
+    for i in 0..NUM_COLORS {
+        assert_eq!(configured_color(Some(i), &config), palette.colors[i]);
+        assert_eq!(sample_palette(&palette, i as Float / NUM_COLORS as Float), palette.colors[i]);
+    }
+}
+
+
+#[allow(dead_code)]
+fn test_locations() {
+    println!();
+    println!("Testing the --goto locations (and --list-locations):");
+    print!("{}", location_list_text());
+    assert_eq!(location("seahorse"), Some(((-0.745, 0.113), 0.01, 1000)));
+    assert_eq!(location("Seahorse"), None);
+    assert_eq!(location("nowhere"), None);
+    assert_eq!(location_names(), "\"seahorse\", \"elephant\", \"triple-spiral\", or \"mini-mandelbrot\"");
+    for (i, &Location { name, description, center, distance, bailout }) in LOCATIONS.iter().enumerate() {
+        // Every name is listed, and can't be mistaken for another:
+        assert!(location_list_text().lines().any(|line| line.trim_start().starts_with(name) && line.ends_with(description)));
+        assert!(LOCATIONS[..i].iter().all(|other| other.name != name));
+        // ...and every location actually shows the edge of the set (both
+        // points in the set and points that escape, in a variety of
+        // escape values):
+        let info = WindowAndViewportInfo::new(48, 48, center.0, center.1, distance, 0);
+        let escape_details = render_escape_details(&info, &Config { bailout: Some(bailout), ..Config::new() });
+        let escape_values: std::collections::HashSet<Option<usize>> = escape_details.iter()
+                                                                                    .map(|details| details.escape_value)
+                                                                                    .collect();
+        assert!(escape_values.contains(&None) && escape_values.len() > 20, "{name}");
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_version_text() {
+    println!();
+    println!("Testing the --version text:");
+    let text = version_text();
+    print!("{text}");
+    assert!(text.starts_with(&format!("JLR-Mandelbrot {}\n", env!("CARGO_PKG_VERSION"))));
+    assert!(text.contains(&format!("Float:  {}\n", std::any::type_name::<Float>())));
+    // (The program itself can't be built without the viewer feature.)
+    assert!(text.lines().any(|line| line.starts_with("Features:  ") && line.contains("viewer")));
+    assert!(text.contains(std::env::consts::ARCH) && text.contains(std::env::consts::OS));
+    // It's nothing like the help text:
+    assert!(!help_text().contains(&text));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_parse_coordinate_pair() {
+    println!();
+    println!("Testing the parse_coordinate_pair() function:");
+    for (text, expected) in [("-0.75,0.1", (-0.75, 0.1)),
+                             (" 1e-3 , +2 ", (0.001, 2.0)),
+                             ("\u{2212}0.5,\u{2212}1E2", (-0.5, -100.0)),  // (Unicode minus signs)
+                             ("\t-0.0,.5\n", (0.0, 0.5))] {
+        assert_eq!(parse_coordinate_pair(text), Ok(expected), "{text:?}");
+    }
+
+    // Each error message should say which value is wrong (and why):
+    for (text, expected_words) in [("", "X and Y values are both missing"),
+                                   (",1", "X value is missing"),
+                                   ("1, ", "Y value is missing"),
+                                   ("abc,1", "X value (\"abc\") is not a valid number"),
+                                   ("1,1.5e", "Y value (\"1.5e\") is not a valid number"),
+                                   ("inf,0", "X value (\"inf\") is not a finite number"),
+                                   ("0,NaN", "Y value (\"NaN\") is not a finite number"),
+                                   ("1;2", "needs a comma"),
+                                   ("1 2", "needs a comma"),
+                                   ("0,5,0,1", "decimal point")] {
+        let result = parse_coordinate_pair(text);
+        println!("{text:?}: {result:?}");
+        assert!(result.as_ref().is_err_and(|message| message.contains(expected_words)), "{text:?}");
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_format_coordinates() {
+    println!();
+    println!("Testing format_coordinates() (for --coord-format):");
+    let config = Config::new();
+    let info = WindowAndViewportInfo::new(512, 512, -0.5, 0.0, 1.725, 0);
+    let text = format_coordinates(&info, (-0.5, 0.0), &config, CoordinateFormat::Decimal);
+    println!("{text}");
+    assert!(text.contains("(-2.225, 1.725)"));
+    assert!(text.contains("Mouse coordinates:  (-0.5, 0.0)"));
+
+    // Zoomed in, the corners should still be told apart:
+    let info = WindowAndViewportInfo::new(512, 512, -0.743643887037151, 0.131825904205330, 1e-9, 0);
+    for format in [CoordinateFormat::Decimal, CoordinateFormat::Scientific] {
+        let text = format_coordinates(&info, (info.center_x, info.center_y), &config, format);
+        println!("{text}");
+        let lines: Vec<&str> = text.lines().collect();
+        let corners_of_line = |line: &str| -> Vec<String> {
+            line.trim_matches('|').split(")").filter(|part| part.contains('('))
+                .map(|part| part.trim().to_string()).collect()
+        };
+        let (top, bottom) = (corners_of_line(lines[2]), corners_of_line(lines[4]));
+        assert_eq!((top.len(), bottom.len()), (2, 2));
+        assert_ne!(top[0], top[1]);
+        assert_ne!(top[0], bottom[0]);
+        // (And the box should be wide enough for every line.)
+        assert!(lines[1..6].iter().all(|line| line.len() == lines[1].len()));
+    }
+
+    // The command line should give back exactly the same view:
+    let mut config = Config::new();
+    config.c = Some((-0.8, 0.156));
+    config.bailout = Some(1000);
+    let text = format_coordinates(&info, (0.25, -0.5), &config, CoordinateFormat::CommandLine);
+    println!("{text}");
+    assert!(text.contains("jlr-mandelbrot --center=-0.743643887037151,0.13182590420533 --distance=0.000000001 --julia=-0.8,0.156 --bailout=1000"));
+    assert!(text.contains("Mouse coordinates:  0.25,-0.5"));
+    assert_eq!(CoordinateFormat::from_name("scientific"), Some(CoordinateFormat::Scientific));
+    assert_eq!(CoordinateFormat::from_name("hex"), None);
+
+    // At every zoom level, neighboring pixels should
+    // be printed with different coordinates:
+    let config = Config::new();
+    for exponent in 0..=13 {
+        let distance = (10.0 as Float).powi(-exponent);
+        let info = WindowAndViewportInfo::new(512, 512, -0.743643887037151, 0.131825904205330, distance, 0);
+        let pixel = convert_row_and_column_to_x_and_y(&info, 100.0, 100.0);
+        let neighbor = convert_row_and_column_to_x_and_y(&info, 101.0, 101.0);
+        let pixel_text = format_coordinates(&info, pixel, &config, CoordinateFormat::Decimal);
+        let neighbor_text = format_coordinates(&info, neighbor, &config, CoordinateFormat::Decimal);
+        let mouse_line = |text: &str| text.lines().last().unwrap().to_string();
+        println!("Distance {distance:e} ({} decimal places):  {}", decimal_places_for(&info), mouse_line(&pixel_text));
+        assert_ne!(mouse_line(&pixel_text), mouse_line(&neighbor_text));
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_orbit() {
+    println!();
+    println!("Testing calculate_orbit() (for Ctrl+click):");
+    let mut config = Config::new();
+    let threshold = 1e-6;
+
+    // (1, 1) escapes right away:  z goes from 1+i to 1+3i.
+    let (details, orbit) = calculate_orbit(1.0, 1.0, &config, threshold);
+    print!("{}", format_orbit(1.0, 1.0, &details, &orbit));
+    assert_eq!(orbit, vec![(1.0, 1.0), (1.0, 3.0)]);
+    assert_eq!(details.escape_value, Some(1));
+
+    // Every escaping point's orbit should end with the z that escaped:
+    for (x, y) in [(-0.75, 0.1), (0.26, 0.0), (-1.8, 0.01)] {
+        let (details, orbit) = calculate_orbit(x, y, &config, threshold);
+        assert_eq!(details, calculate_escape_details(x, y, None, Some(threshold), None, false));
+        assert_eq!(orbit.len(), details.escape_value.unwrap() + 1);
+        let (z_x, z_y) = *orbit.last().unwrap();
+        assert!(z_x * z_x + z_y * z_y > 4.0);
+        assert!(orbit[..orbit.len() - 1].iter().all(|&(z_x, z_y)| z_x * z_x + z_y * z_y <= 4.0));
+    }
+
+    // A point in the main bulb should settle down (to its fixed point):
+    let (details, orbit) = calculate_orbit(-0.1, 0.1, &config, threshold);
+    let text = format_orbit(-0.1, 0.1, &details, &orbit);
+    println!("{}", text.lines().last().unwrap());
+    assert!(details.escape_value.is_none());
+    assert!(text.contains("Fell into a cycle"));
+
+    // With a bailout, a point in the set should stop at the bailout
+    // (and long orbits shouldn't all be printed):
+    config.bailout = Some(5000);
+    config.cycle_detection = false;
+    let (details, orbit) = calculate_orbit(-0.1, 0.1, &config, threshold);
+    let text = format_orbit(-0.1, 0.1, &details, &orbit);
+    println!("{}", text.lines().last().unwrap());
+    assert_eq!(orbit.len(), 5001);
+    assert!(text.contains("(4001 more)"));
+    assert!(text.contains("Hit the bailout after 5000 iterations"));
+    assert_eq!(text.lines().count(), 1 + ORBIT_PRINT_LIMIT + 1 + 1);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_tricorn() {
+    println!();
+    println!("Testing the Tricorn (--fractal=tricorn):");
+    for formula in [Formula::Mandelbrot, Formula::Tricorn] {
+        assert_eq!(Formula::from_name(formula.name()), Some(formula));
+    }
+    assert_eq!(Formula::from_name("julia"), None);
+    let mandelbrot = Config { bailout: Some(1000), ..Config::new() };
+    let tricorn = Config { formula: Formula::Tricorn, ..mandelbrot.clone() };
+    let threshold = 1e-9;
+
+    // c = i is in the Mandelbrot set (i, -1+i, -i, -1+i, ...), but
+    // conjugating z first sends it out (i, -1+i, 3i, ...):
+    let mandelbrot_details = calculate_point_details(0.0, 1.0, &mandelbrot, threshold);
+    let tricorn_details = calculate_point_details(0.0, 1.0, &tricorn, threshold);
+    println!("c = i:  {mandelbrot_details:?} vs. {tricorn_details:?}");
+    assert_eq!(mandelbrot_details.escape_value, None);
+    assert_eq!(tricorn_details.escape_value, Some(2));
+    let (_, orbit) = calculate_orbit(0.0, 1.0, &tricorn, threshold);
+    assert_eq!(orbit, [(0.0, 1.0), (-1.0, 1.0), (0.0, 3.0)]);
+
+    // Cycle detection still finds the points in the set (long before
+    // the bailout), and the escape values are the same without it:
+    let tricorn_without_cycle_detection = Config { cycle_detection: false, ..tricorn.clone() };
+    for (x, y) in [(0.0, 0.0), (-0.1, 0.05), (0.2, 0.0)] {
+        let details = calculate_point_details(x, y, &tricorn, threshold);
+        assert_eq!(details.escape_value, None);
+        assert!(!details.hit_bailout, "{x},{y} should have been caught by cycle detection:  {details:?}");
+    }
+    for row in 0..20 {
+        for column in 0..20 {
+            let (x, y) = (-2.0 + column as Float * 0.2, -2.0 + row as Float * 0.2);
+            let with = calculate_point_details(x, y, &tricorn, threshold).escape_value;
+            let without = calculate_point_details(x, y, &tricorn_without_cycle_detection, threshold).escape_value;
+            if with.is_some() || without.is_some() {
+                assert_eq!(with, without, "at {x},{y}");
+            }
+        }
+    }
+
+    // The Tricorn has three-fold symmetry:  turning c by a third of a
+    // turn (around 0) gives the same escape value.  (Points too near
+    // its boundary could come out differently from rounding, so only
+    // most of them have to match.)
+    let (sin, cos) = (2.0 * std::f64::consts::PI as Float / 3.0).sin_cos();
+    let (mut matches, mut total) = (0, 0);
+    for row in 0..40 {
+        for column in 0..40 {
+            let (x, y) = (-1.5 + column as Float * 0.075, -1.5 + row as Float * 0.075);
+            let escape_value = calculate_point_details(x, y, &tricorn, threshold).escape_value;
+            let turned = calculate_point_details(x * cos - y * sin, x * sin + y * cos, &tricorn, threshold).escape_value;
+            total += 1;
+            matches += (escape_value == turned) as usize;
+        }
+    }
+    println!("{matches} of {total} points match when turned a third of a turn.");
+    assert!(matches * 100 >= total * 95);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_norms() {
+    println!("Testing the escape norms (--norm=NAME):");
+    for norm in [Norm::L2, Norm::Max, Norm::L1] {
+        assert_eq!(Norm::from_name(norm.name()), Some(norm));
+    }
+    assert_eq!(Norm::from_name("euclidean"), None);
+    let l2 = Config { bailout: Some(1000), ..Config::new() };
+    let threshold = 1e-9;
+
+    // 1.9+1.9i is outside the circle of radius 2, but inside the
+    // square (so it escapes one iteration later with max) and
+    // outside the diamond with corners at 2*sqrt(2):
+    let escape_value = |norm: Norm, x: Float, y: Float|
+        calculate_point_details(x, y, &Config { norm, ..l2.clone() }, threshold).escape_value;
+    assert_eq!(escape_value(Norm::L2, 1.9, 1.9), Some(0));
+    assert_eq!(escape_value(Norm::Max, 1.9, 1.9), Some(1));
+    assert_eq!(escape_value(Norm::L1, 1.9, 1.9), Some(0));
+    // (And 2.5 is outside the circle and the square, but not the diamond.)
+    assert_eq!(escape_value(Norm::Max, 2.5, 0.0), Some(0));
+    assert_eq!(escape_value(Norm::L1, 2.5, 0.0), Some(1));
+
+    // Each norm's escape shape holds the circle, so the same points are
+    // in the set, and they never escape sooner than with L2 (with or
+    // without cycle detection):
+    for cycle_detection in [true, false] {
+        let mut differences = [0; 2];
+        for row in 0..40 {
+            for column in 0..40 {
+                let (x, y) = (-2.2 + column as Float * 0.07, -1.4 + row as Float * 0.07);
+                let config = Config { cycle_detection, ..l2.clone() };
+                let l2_value = calculate_point_details(x, y, &config, threshold).escape_value;
+                for (i, norm) in [Norm::Max, Norm::L1].into_iter().enumerate() {
+                    let value = calculate_point_details(x, y, &Config { norm, ..config.clone() }, threshold).escape_value;
+                    assert_eq!(value.is_none(), l2_value.is_none(), "{} at {x},{y}", norm.name());
+                    assert!(value >= l2_value, "{} at {x},{y}:  {value:?} vs. {l2_value:?}", norm.name());
+                    differences[i] += (value != l2_value) as usize;
+                }
+            }
+        }
+        println!("Escape values different from L2 (cycle detection {cycle_detection}):  max {}, l1 {}",
+                 differences[0], differences[1]);
+        assert!(differences.iter().all(|&count| count > 100));
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_non_finite_coordinates() {
+    println!();
+    println!("Testing infinite and NaN coordinates:");
+    // A point (or c) with an infinity or a NaN in it escapes right away,
+    // with or without a bailout, cycle detection, or f32:
+    let (nan, infinity) = (Float::NAN, Float::INFINITY);
+    for (x, y) in [(nan, 0.0), (0.0, nan), (infinity, nan), (-infinity, 0.0), (0.0, infinity)] {
+        for bailout in [None, Some(1000)] {
+            assert_eq!(calculate_escape_value(x, y, None, Some(1e-9), bailout), Some(0), "at {x},{y}");
+            assert_eq!(calculate_escape_value(0.0, 0.0, Some((x, y)), Some(1e-9), bailout), Some(0), "c = {x},{y}");
+        }
+        assert_eq!(calculate_escape_value(x as f32, y as f32, None, None, None), Some(0));
+        let details = calculate_escape_details_without_cycle_detection(x, y, None, 1000);
+        assert_eq!((details.escape_value, details.hit_bailout), (Some(0), false));
+        for norm in [Norm::L2, Norm::Max, Norm::L1] {
+            let config = Config { norm, ..Config::new() };
+            assert_eq!(calculate_point_details(x, y, &config, 1e-9).escape_value, Some(0));
+        }
+        println!("{x},{y}:  {details:?}");
+    }
+    // (Finite points far out still escape the usual way.)
+    assert_eq!(calculate_escape_value(1e300, 0.0, None, None, None), Some(0));
+    assert_eq!(calculate_escape_value(0.0, 0.0, None, None, Some(1000)), None);
+
+    // A view needs a finite center and a finite distance more than zero:
+    assert!(WindowAndViewportInfo::is_valid_view(-0.5, 0.0, 1.725));
+    assert!(WindowAndViewportInfo::is_valid_view(1e300, -1e300, 1e-300));
+    for (center_x, center_y, distance) in [(nan, 0.0, 1.0), (0.0, infinity, 1.0), (0.0, 0.0, infinity),
+                                           (0.0, 0.0, nan), (0.0, 0.0, 0.0), (0.0, 0.0, -1.0)] {
+        assert!(!WindowAndViewportInfo::is_valid_view(center_x, center_y, distance));
+    }
+    // Zooming out from a far-off point can overflow the new center,
+    // which is caught before it's made into a view:
+    let info = WindowAndViewportInfo::new(100, 100, 1.5e308, 0.0, 1e307, 0);
+    let (x, y) = zoom_out_center(&info, -1.5e308, 0.0);
+    assert!(!x.is_finite());
+    assert!(!WindowAndViewportInfo::is_valid_view(x, y, info.distance_from_center_to_edge * 2.0));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_max_iterations() {
+    println!();
+    println!("Testing --max-iterations:");
+    let mut config = Config::new();
+    assert_eq!(config.max_iterations, Some(DEFAULT_MAX_ITERATIONS));
+    assert_eq!(iteration_limit(&config), Some(DEFAULT_MAX_ITERATIONS));
+    config.max_iterations = Some(1000);
+    for (bailout, expected_limit) in [(None, Some(1000)), (Some(500), Some(500)), (Some(5000), Some(1000))] {
+        config.bailout = bailout;
+        assert_eq!(iteration_limit(&config), expected_limit);
+    }
+    config.max_iterations = None;
+    config.bailout = None;
+    assert_eq!(iteration_limit(&config), None);
+
+    // Without cycle detection (or a bailout), a point in the set would
+    // be iterated forever, but it stops at max_iterations instead, as
+    // if it had hit the bailout:
+    config.max_iterations = Some(1000);
+    config.cycle_detection = false;
+    let details = calculate_point_details(-0.1, 0.1, &config, 0.0);
+    println!("-0.1+0.1i, without cycle detection:  {details:?}");
+    assert_eq!((details.escape_value, details.iterations, details.hit_bailout), (None, 1000, true));
+    // With cycle detection, a cycle can still be found first:
+    config.cycle_detection = true;
+    let details = calculate_point_details(-0.1, 0.1, &config, 1e-9);
+    assert!(details.iterations < 1000 && !details.hit_bailout);
+
+    // Points that escape before it aren't affected:
+    let details = calculate_point_details(0.5, 0.5, &config, 1e-9);
+    assert_eq!(details, calculate_point_details(0.5, 0.5, &Config::new(), 1e-9));
+    assert!(details.escape_value.is_some());
+}
+
+
+#[allow(dead_code)]
+fn test_unbounded_point() {
+    println!();
+    println!("Testing that a point whose cycle is never detected still finishes:");
+    // With c = 0.25 (the cusp of the main cardioid), z creeps up on
+    // 0.5 ever more slowly, and without a threshold, it would take
+    // more than DEFAULT_MAX_ITERATIONS iterations to land on it
+    // exactly (if it ever does), so it stops there instead, as if it
+    // had hit the bailout:
+    let start_time = std::time::Instant::now();
+    let details = calculate_escape_details(0.25, 0.0, None, None, None, false);
+    println!("0.25+0i, with no threshold or bailout:  {details:?} in {} sec.",
+             start_time.elapsed().as_micros() as Float / 1e6);
+    assert_eq!((details.escape_value, details.iterations, details.hit_bailout), (None, DEFAULT_MAX_ITERATIONS, true));
+}
+
+
+#[allow(dead_code)]
+fn test_orbit_overlay() {
+    println!();
+    println!("Testing the orbit overlay (for Ctrl+click and the O key):");
+    // Converting to a row & column and back should give the same point:
+    let info = WindowAndViewportInfo::new(64, 48, -0.5, 0.25, 1.5, 0);
+    for (row, column) in [(0.0, 0.0), (10.5, 20.25), (47.0, 63.0), (-100.0, 1000.0)] {
+        let (x, y) = convert_row_and_column_to_x_and_y(&info, row, column);
+        let (new_row, new_column) = convert_x_and_y_to_row_and_column(&info, x, y);
+        assert!((new_row - row).abs() < 1e-9 && (new_column - column).abs() < 1e-9);
+    }
+
+    // A horizontal line should fill in every pixel it crosses:
+    let (width, height) = (16, 8);
+    let mut buffer = vec![0u32; width * height];
+    draw_line(&mut buffer, width, height, (3.0, 2.0), (3.0, 12.0), 1);
+    assert_eq!(buffer.iter().filter(|&&pixel| pixel == 1).count(), 11);
+    assert!((2..=12).all(|column| buffer[3 * width + column] == 1));
+
+    // Lines far outside of the buffer should be clipped (quickly),
+    // and lines that miss the buffer completely shouldn't draw anything:
+    let mut buffer = vec![0u32; width * height];
+    draw_line(&mut buffer, width, height, (-1e12, -1e12), (1e12, 1e12), 1);
+    assert!((0..height).all(|i| buffer[i * width + i] == 1));
+    let mut buffer = vec![0u32; width * height];
+    draw_line(&mut buffer, width, height, (-5.0, 0.0), (-5.0, 100.0), 1);
+    draw_line(&mut buffer, width, height, (0.0, 1e300), (Float::NAN, 0.0), 1);
+    assert!(buffer.iter().all(|&pixel| pixel == 0));
+
+    // Drawing the orbit of an escaping point should draw its
+    // start (in green) where the point is:
+    let info = WindowAndViewportInfo::new(64, 64, -0.5, 0.0, 1.725, 0);
+    let (x, y) = convert_row_and_column_to_x_and_y(&info, 20.0, 30.0);
+    let (_, orbit) = calculate_orbit(x, y, &Config::new(), info.delta_x / 4.0);
+    let mut overlays = Overlays::new();
+    assert!(!overlays.toggle_orbit());
+    overlays.show_orbit(orbit);
+    let mut buffer = vec![0u32; 64 * 64];
+    overlays.draw(&mut buffer, &info, &vec![EscapeDetails::default(); 64 * 64]);
+    assert_eq!(buffer[20 * 64 + 30], 0x00_ff_00);
+    assert!(buffer.contains(&0xff_ff_ff));
+    assert!(overlays.toggle_orbit());
+    assert!(!overlays.any_shown());
+    println!("Orbit lines were drawn and clipped as expected.");
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_external_rays() {
+    println!();
+    println!("Testing the external rays (--rays):");
+    assert_eq!(parse_ray_angle("1/3"), Ok((1, 3)));
+    assert_eq!(parse_ray_angle(" 2 / 4 "), Ok((1, 2)));
+    assert_eq!(parse_ray_angle("0.25"), Ok((1, 4)));
+    assert_eq!(parse_ray_angle(".1"), Ok((1, 10)));
+    assert_eq!(parse_ray_angle("0"), Ok((0, 1)));
+    for bad_text in ["1", "3/2", "1/0", "-0.5", "1.5", "0.5.5", "abc", "", ".", "0.1234567890123456789"] {
+        println!("{bad_text:?}:  {:?}", parse_ray_angle(bad_text));
+        assert!(parse_ray_angle(bad_text).is_err(), "{bad_text:?}");
+    }
+
+    // Each ray should head in from far away to where it's known to land:
+    // the rays of 0 and 1/2 along the real axis to the cusp (0.25) and
+    // the tip (-2), and the rays of 1/3 and 2/3 (mirror images of each
+    // other) to the root of the biggest bulb (-0.75).
+    for (angle, landing_point) in [((0, 1), (0.25, 0.0)), ((1, 2), (-2.0, 0.0)),
+                                   ((1, 3), (-0.75, 0.0)), ((2, 3), (-0.75, 0.0))] {
+        let ray = external_ray(angle, 1e-4, RAY_MAX_POINTS);
+        let &(x, y) = ray.last().unwrap();
+        println!("The ray of {}/{} has {} points, ending at ({x}, {y}).", angle.0, angle.1, ray.len());
+        assert!(ray[0].0.hypot(ray[0].1) > 1000.0);
+        assert!((x - landing_point.0).hypot(y - landing_point.1) < 0.1);
+        if angle.1 <= 2 {
+            assert!(ray.iter().all(|&(_, y)| y.abs() < 1e-9));  // (On the real axis, give or take rounding.)
+        }
+    }
+    let (ray_1, ray_2) = (external_ray((1, 3), 1e-3, 100), external_ray((2, 3), 1e-3, 100));
+    assert!(ray_1.iter().zip(&ray_2).all(|(a, b)| (a.0 - b.0).abs() < 1e-9 && (a.1 + b.1).abs() < 1e-9));
+
+    // The rays should be drawn (in yellow) over the image:
+    let info = WindowAndViewportInfo::new(100, 100, -0.5, 0.0, 1.725, 0);
+    let mut overlays = Overlays::new();
+    overlays.ray_angles = vec![(1, 3), (2, 3)];
+    overlays.trace_rays(&info);
+    let mut buffer = vec![0u32; 100 * 100];
+    overlays.draw(&mut buffer, &info, &vec![EscapeDetails::default(); 100 * 100]);
+    assert!(buffer.contains(&0xff_ff_00));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_potential_coloring() {
+    println!();
+    println!("Testing the potential (--coloring=potential):");
+    let details = |x: Float, y: Float| calculate_escape_details(x, y, None, None, Some(1000), false);
+
+    // A point that's already escaped has a potential of just ln|z|,
+    // and points in the set have no potential at all:
+    let far_away = details(3.0, 4.0);
+    assert_eq!((far_away.escape_value, far_away.escape_magnitude), (Some(0), 5.0));
+    assert!((escape_potential(&far_away).unwrap() - (5.0 as Float).ln()).abs() < 1e-12);
+    assert_eq!(escape_potential(&details(0.0, 0.0)), None);
+    assert_eq!(potential_palette_position(&details(0.0, 0.0), &Config::new()), None);
+
+    // The potential should be (close to) what's found by iterating
+    // for much longer, until |z| is huge (where ln|z| / 2^n is
+    // much more accurate):
+    for (x, y) in [(0.5, 0.0), (-0.75, 0.1), (0.5, 0.5), (-1.8, 0.2), (0.26, 0.0)] {
+        let (mut z_x, mut z_y, mut n): (Float, Float, i32) = (x, y, 0);
+        while z_x.hypot(z_y) < 1e100 {
+            (z_x, z_y) = (z_x * z_x - z_y * z_y + x, 2.0 * z_x * z_y + y);
+            n += 1;
+        }
+        let accurate_potential = z_x.hypot(z_y).ln() / (2.0 as Float).powi(n);
+        let potential = escape_potential(&details(x, y)).unwrap();
+        println!("({x}, {y}):  {potential} (and {accurate_potential} the long way)");
+        assert!((potential / accurate_potential - 1.0).abs() < 0.1);
+    }
+
+    // Unlike the escape value, the palette position shouldn't jump
+    // when moving a little bit, even across escape values:
+    let config = Config::new();
+    let positions: Vec<Float> = (0..1000).map(|i| potential_palette_position(&details(0.3 + i as Float * 1e-4, 0.0), &config).unwrap())
+                                         .collect();
+    let escape_values: Vec<usize> = (0..1000).map(|i| details(0.3 + i as Float * 1e-4, 0.0).escape_value.unwrap()).collect();
+    assert!(escape_values.windows(2).any(|pair| pair[0] != pair[1]));
+    let biggest_jump = positions.windows(2).map(|pair| (pair[1] - pair[0]).abs()).fold(0.0, Float::max);
+    println!("The escape values go from {} to {}, and the biggest jump in palette position is {biggest_jump}.",
+             escape_values[0], escape_values[999]);
+    assert!(biggest_jump < 0.2);
+
+    // Inverting the palette should run the positions the other way,
+    // and whole positions should give the palette's own colors:
+    let inverted_config = Config { invert: true, ..Config::new() };
+    let position = potential_palette_position(&details(0.5, 0.0), &config).unwrap();
+    assert_eq!(potential_palette_position(&details(0.5, 0.0), &inverted_config).unwrap(), NUM_COLORS as Float - 1.0 - position);
+    for i in 0..NUM_COLORS {
+        assert_eq!(palette_color_components(i as Float, &config), color_components(Some(i)));
+    }
+    let between = palette_color_components(0.5, &config);
+    let (first, second) = (color_components(Some(0)), color_components(Some(1)));
+    assert_eq!(between.1, (first.1 + second.1) / 2.0);
+
+    // The coloring should only change the color of escaping points:
+    let potential_config = Config { coloring: Coloring::Potential, ..Config::new() };
+    assert_eq!(pixel_color(&details(0.0, 0.0), &potential_config), pixel_color(&details(0.0, 0.0), &config));
+    assert_ne!(pixel_color(&details(0.5, 0.0), &potential_config), pixel_color(&details(0.5, 0.0), &config));
+    assert_eq!(Coloring::from_name("potential"), Some(Coloring::Potential));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_pixel_info() {
+    println!();
+    println!("Testing the pixel info overlay (the I key):");
+    let details = |escape_value: Option<usize>, hit_bailout: bool| {
+        EscapeDetails { escape_value, hit_bailout, ..EscapeDetails::default() }
+    };
+    assert_eq!(pixel_info_text(&details(Some(1), false)), "escaped after 1 iteration");
+    assert_eq!(pixel_info_text(&details(Some(37), false)), "escaped after 37 iterations");
+    assert_eq!(pixel_info_text(&details(None, false)), "in set");
+    assert_eq!(pixel_info_text(&details(None, true)), "in set (hit the bailout)");
+
+    // The label should be drawn next to the pixel, even in the corners:
+    let info = WindowAndViewportInfo::new(100, 100, -0.5, 0.0, 1.725, 0);
+    let escape_details = vec![details(Some(5), false); 100 * 100];
+    let mut overlays = Overlays::new();
+    overlays.show_pixel_info = true;
+    assert!(!overlays.any_shown());  // (Not until the mouse is over a pixel.)
+    for pixel in [(0, 0), (0, 99), (99, 0), (99, 99), (50, 50)] {
+        overlays.hovered_pixel = Some(pixel);
+        assert!(overlays.any_shown());
+        let mut buffer = vec![0x12_34_56; 100 * 100];
+        overlays.draw(&mut buffer, &info, &escape_details);
+        let labeled: Vec<usize> = (0..buffer.len()).filter(|&i| buffer[i] == 0xff_ff_ff).collect();
+        let distance = labeled.iter()
+                              .map(|&i| (i / 100).abs_diff(pixel.0).max((i % 100).abs_diff(pixel.1)))
+                              .min().unwrap();
+        println!("Hovering over {pixel:?}:  {} label pixels, the closest {distance} pixels away.", labeled.len());
+        assert!(distance < 40);
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_render_stats() {
+    println!();
+    println!("Testing the render stats overlay (the T key):");
+    assert_eq!(render_stats_text(0.1234, 1.0), "0.123 sec.  1.0x zoom");
+    assert_eq!(render_stats_text(2.5, 1024.0), "2.500 sec.  1024.0x zoom");
+    assert_eq!(render_stats_text(2.5, 3.0e12), "2.500 sec.  3.00e12x zoom");
+
+    let info = WindowAndViewportInfo::new(100, 100, -0.5, 0.0, 1.725, 0);
+    assert_eq!(magnification(&info), 1.0);
+    assert_eq!(magnification(&WindowAndViewportInfo::new(100, 100, -0.5, 0.0, 1.725 / 8.0, 3)), 8.0);
+
+    // Nothing is drawn until an image is finished (and the stats are known):
+    let mut overlays = Overlays::new();
+    overlays.show_render_stats = true;
+    assert!(!overlays.any_shown());
+    overlays.render_stats = Some(render_stats_text(0.5, magnification(&info)));
+    assert!(overlays.any_shown());
+    let mut buffer = vec![0x12_34_56; 100 * 100];
+    overlays.draw(&mut buffer, &info, &vec![EscapeDetails::default(); 100 * 100]);
+    let labeled: Vec<usize> = (0..buffer.len()).filter(|&i| buffer[i] == 0xff_ff_ff).collect();
+    println!("{} label pixels, all in the top {} rows.", labeled.len(), labeled.iter().max().unwrap() / 100 + 1);
+    assert!(!labeled.is_empty());
+    assert!(labeled.iter().all(|&i| i / 100 < 12));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_diagnostics() {
+    println!();
+    println!("Testing the diagnostics overlay (the D key):");
+    let info = WindowAndViewportInfo::new(100, 100, -0.5, 0.0, 1.0, 0);
+    let mut config = Config::new();
+    let threshold = cycle_threshold(&info, &config);
+    let lines = diagnostics_text(&info, &config, threshold);
+    println!("{lines:?}");
+    assert_eq!(lines, vec!["bailout = none", "threshold = 5.000e-3", "delta x = 2.000e-2", "delta y = 2.000e-2"]);
+    config.bailout = Some(500);
+    assert_eq!(diagnostics_text(&info, &config, threshold)[0], "bailout = 500");
+
+    // Nothing is drawn until there's an image (and diagnostics for it):
+    let mut overlays = Overlays::new();
+    overlays.show_diagnostics = true;
+    assert!(!overlays.any_shown());
+    overlays.diagnostics = lines;
+    assert!(overlays.any_shown());
+    let mut buffer = vec![0x12_34_56; 100 * 100];
+    overlays.draw(&mut buffer, &info, &vec![EscapeDetails::default(); 100 * 100]);
+    let labeled: Vec<usize> = (0..buffer.len()).filter(|&i| buffer[i] == 0xff_ff_ff).collect();
+    println!("{} label pixels, all in the bottom {} rows.", labeled.len(), 100 - labeled.iter().min().unwrap() / 100);
+    assert!(!labeled.is_empty());
+    assert!(labeled.iter().all(|&i| i / 100 >= 100 - 4 - 4 * 8));
+
+    // In a window too short for all of them, the top lines are left out:
+    let short_info = WindowAndViewportInfo::new(100, 20, -0.5, 0.0, 1.0, 0);
+    let mut buffer = vec![0x12_34_56; 100 * 20];
+    overlays.draw(&mut buffer, &short_info, &vec![EscapeDetails::default(); 100 * 20]);
+    assert!(buffer.contains(&0xff_ff_ff));
+}
+
+
+#[allow(dead_code)]
+fn test_shown_image() {
+    println!();
+    println!("Testing shown_image() (for --double-buffer):");
+    let new_info = WindowAndViewportInfo::new(4, 4, -0.5, 0.0, 1.725 / 2.0, 1);
+    let image_buffer = vec![0x11_11_11; 16];
+    let escape_details = vec![EscapeDetails { escape_value: Some(3), ..EscapeDetails::default() }; 16];
+
+    // Without a finished image set aside, the image being drawn is shown:
+    let (shown_buffer, shown_details, shown_info) = shown_image(None, &image_buffer, &escape_details, &new_info);
+    assert_eq!(shown_buffer, &image_buffer[..]);
+    assert_eq!(shown_details, &escape_details[..]);
+    assert_eq!(shown_info.zoom_level, 1);
+
+    // With one, it's shown instead, along with its own viewport:
+    let finished_image = FinishedImage {
+        image_buffer: vec![0x22_22_22; 16],
+        escape_details: vec![EscapeDetails::default(); 16],
+        info: WindowAndViewportInfo::new(4, 4, -0.5, 0.0, 1.725, 0),
+    };
+    let (shown_buffer, shown_details, shown_info) =
+        shown_image(Some(&finished_image), &image_buffer, &escape_details, &new_info);
+    assert!(shown_buffer.iter().all(|&color| color == 0x22_22_22));
+    assert!(shown_details.iter().all(|details| details.escape_value.is_none()));
+    assert_eq!(shown_info.zoom_level, 0);
+    assert_eq!(shown_info.distance_from_center_to_edge, 1.725);
+    println!("Shown:  zoom level {} instead of {}.", shown_info.zoom_level, new_info.zoom_level);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_frame_budget() {
+    println!("Testing FrameBudget (for --frame-budget-ms):");
+    let millisecond = std::time::Duration::from_millis(1);
+    let mut frame_budget = FrameBudget::new(5);
+    let pixels_per_frame: Vec<usize> = (0..3).map(|_| {
+        let mut pixels = 1;
+        while !frame_budget.spend(millisecond * 2) {
+            pixels += 1;
+        }
+        frame_budget.new_frame();
+        pixels
+    }).collect();
+    println!("Pixels (at 2 ms each) per 5 ms frame:  {pixels_per_frame:?}");
+    assert_eq!(pixels_per_frame, vec![3, 3, 3]);
+
+    // A pixel that takes longer than the whole budget uses it up by itself:
+    assert!(frame_budget.spend(millisecond * 50));
+    frame_budget.new_frame();
+    assert!(!frame_budget.spend(millisecond * 4));
+    assert!(frame_budget.spend(millisecond));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_changes_the_image() {
+    println!();
+    println!("Testing UserInput::changes_the_image():");
+    // Only these should make an image that's being drawn start over:
+    for user_input in [UserInput::ZoomIn(0.0, 0.0), UserInput::ZoomOut(0.0, 0.0),
+                       UserInput::GoTo(0.0, 0.0, 1.0), UserInput::SetBailout(100),
+                       UserInput::IncreaseBailout, UserInput::DecreaseBailout,
+                       UserInput::IncreaseColorPeriod, UserInput::DecreaseColorPeriod, UserInput::ToggleColorCycle,
+                       UserInput::NextFractal, UserInput::IncreaseAaSamples, UserInput::DecreaseAaSamples,
+                       UserInput::GoToBookmark(1)] {
+        assert!(user_input.changes_the_image());
+    }
+    for user_input in [UserInput::Nothing, UserInput::SaveScreenShot, UserInput::SaveCommandLine, UserInput::ShowCoordinates,
+                       UserInput::ToggleCrosshair, UserInput::ToggleMinimap, UserInput::ToggleBailoutHeatmap,
+                       UserInput::ToggleOrbit, UserInput::TogglePixelInfo,
+                       UserInput::SaveScreenShotAs("x.png".to_string()),
+                       UserInput::PrintOrbit(0.0, 0.0), UserInput::SaveBookmark(1)] {
+        assert!(!user_input.changes_the_image());
+    }
+    println!("Only the inputs that change the view (or the bailout, the colors, or the fractal) restart the drawing.");
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_mouse_info() {
+    println!();
+    println!("Testing MouseInfo (pressing and releasing the mouse buttons):");
+    // Returns (currently pressed, just pressed, just released)
+    // for the left and then the right mouse button:
+    let states = |mouse_info: &MouseInfo| -> [(bool, bool, bool); 2] {
+        [(mouse_info.left_mouse_button_currently_pressed(),
+          mouse_info.left_mouse_button_just_pressed(),
+          mouse_info.left_mouse_button_just_released()),
+         (mouse_info.right_mouse_button_currently_pressed(),
+          mouse_info.right_mouse_button_just_pressed(),
+          mouse_info.right_mouse_button_just_released())]
+    };
+    const UP: (bool, bool, bool) = (false, false, false);
+    const PRESSED: (bool, bool, bool) = (true, true, false);
+    const HELD: (bool, bool, bool) = (true, false, false);
+    const RELEASED: (bool, bool, bool) = (false, false, true);
+
+    // Nothing's pressed to begin with:
+    let mut mouse_info = MouseInfo::new();
+    assert_eq!(states(&mouse_info), [UP, UP]);
+
+    // Pressing, holding (two trues in a row), and releasing the left
+    // button is only "just pressed" and "just released" once each:
+    let mut left_button = MouseInfo::new();
+    for (pressed, expected) in [(true, PRESSED), (true, HELD), (true, HELD),
+                                (false, RELEASED), (false, UP), (false, UP)] {
+        left_button.set_left_mouse_button_pressed(pressed);
+        assert_eq!(states(&left_button), [expected, UP], "{pressed}");
+    }
+
+    // Toggling the right button every time makes every
+    // update either a press or a release:
+    let mut right_button = MouseInfo::new();
+    for (pressed, expected) in [(true, PRESSED), (false, RELEASED), (true, PRESSED),
+                                (false, RELEASED), (true, PRESSED), (true, HELD)] {
+        right_button.set_right_mouse_button_pressed(pressed);
+        assert_eq!(states(&right_button), [UP, expected], "{pressed}");
+    }
+
+    // The two buttons are kept track of separately
+    // (as set_mouse_buttons_pressed() sets them together):
+    for (left, right, expected) in [(true, false, [PRESSED, UP]),
+                                    (true, true, [HELD, PRESSED]),
+                                    (false, true, [RELEASED, HELD]),
+                                    (false, false, [UP, RELEASED]),
+                                    (false, false, [UP, UP])] {
+        mouse_info.set_mouse_buttons_pressed(left, right);
+        assert_eq!(states(&mouse_info), expected, "{left} {right}");
+    }
+
+    // A release followed right away by a press (say, a quick double
+    // click) is seen as both, one update after the other:
+    let mut mouse_info = MouseInfo::new();
+    mouse_info.set_mouse_buttons_pressed(true, false);
+    mouse_info.set_mouse_buttons_pressed(false, false);
+    assert!(mouse_info.left_mouse_button_just_released());
+    mouse_info.set_mouse_buttons_pressed(true, false);
+    assert!(mouse_info.left_mouse_button_just_pressed());
+    assert!(!mouse_info.left_mouse_button_just_released());
+
+    // Setting the same state twice (as wait_while_inactive() does)
+    // forgets any press or release that was in progress:
+    mouse_info.set_mouse_buttons_pressed(false, false);
+    mouse_info.set_mouse_buttons_pressed(false, false);
+    assert_eq!(states(&mouse_info), [UP, UP]);
+    println!("Each press and release of a mouse button is seen exactly once.");
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_next_user_input() {
+    println!();
+    println!("Testing next_user_input() (for inputs that come in the same frame):");
+    let mut pending_inputs = std::collections::VecDeque::new();
+    assert!(matches!(next_user_input(&mut pending_inputs, vec![]), UserInput::Nothing));
+    // A key pressed in the same frame as a click is handled
+    // right after it, instead of being dropped:
+    let user_input = next_user_input(&mut pending_inputs, vec![UserInput::ShowCoordinates, UserInput::ZoomIn(0.5, 0.25)]);
+    assert!(matches!(user_input, UserInput::ShowCoordinates));
+    let user_input = next_user_input(&mut pending_inputs, vec![UserInput::ToggleMinimap]);
+    assert!(matches!(user_input, UserInput::ZoomIn(x, y) if (x, y) == (0.5, 0.25)));
+    assert!(matches!(next_user_input(&mut pending_inputs, vec![]), UserInput::ToggleMinimap));
+    assert!(matches!(next_user_input(&mut pending_inputs, vec![]), UserInput::Nothing));
+    // Quitting doesn't wait for the inputs ahead of it:
+    next_user_input(&mut pending_inputs, vec![UserInput::ToggleCrosshair, UserInput::ToggleOrbit]);
+    assert!(matches!(next_user_input(&mut pending_inputs, vec![UserInput::Quit]), UserInput::Quit));
+    assert!(pending_inputs.is_empty());
+    println!("No input is lost, and quitting always comes first.");
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_keyboard_zoom_target() {
+    println!();
+    println!("Testing keyboard_zoom_target() (for the Z key):");
+    let info = WindowAndViewportInfo::new(200, 200, -0.5, 0.25, 1.0, 0);
+    // It's the point under the mouse, just like with a click...
+    for (column, row) in [(0.0, 0.0), (37.0, 150.0), (199.0, 199.0)] {
+        assert_eq!(keyboard_zoom_target(&info, Some((column, row))),
+                   convert_row_and_column_to_x_and_y(&info, row as Float, column as Float));
+    }
+    let (x, y) = keyboard_zoom_target(&info, Some((150.0, 50.0)));
+    assert!(x > info.center_x && y > info.center_y);  // (Up and to the right.)
+    // ...unless the mouse is outside of the window:
+    assert_eq!(keyboard_zoom_target(&info, None), (-0.5, 0.25));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_zoom_reversibility() {
+    println!();
+    println!("Testing that zooming in and back out (at the center) is reversible:");
+    for (center_x, center_y, distance) in [(-0.5, 0.0, 1.725), (-0.745, 0.1, 0.01), (0.3, -0.02, 3.0e-9)] {
+        let info = WindowAndViewportInfo::new(201, 151, center_x, center_y, distance, 0);
+        // A click at the exact center (the middle pixel of an odd-sized
+        // window) zooms in there, and a right-click at the center of the
+        // zoomed-in view zooms back out, several levels deep:
+        let (x, y) = convert_row_and_column_to_x_and_y(&info, 75.0, 100.0);
+        let mut zoomed = zoomed_info(&info, x, y, true);
+        for _ in 0..4 {
+            zoomed = zoomed_info(&zoomed, zoomed.center_x, zoomed.center_y, true);
+        }
+        assert_eq!(zoomed.zoom_level, 5);
+        for _ in 0..5 {
+            let (x, y) = convert_row_and_column_to_x_and_y(&zoomed, 75.0, 100.0);
+            let (x, y) = zoom_out_center(&zoomed, x, y);
+            zoomed = zoomed_info(&zoomed, x, y, false);
+        }
+        println!("({center_x}, {center_y}), distance {distance}:  back to ({}, {}), distance {}",
+                 zoomed.center_x, zoomed.center_y, zoomed.distance_from_center_to_edge);
+        assert_eq!(zoomed.zoom_level, 0);
+        assert_eq!(zoomed.distance_from_center_to_edge, distance);  // (Halving and doubling is exact.)
+        assert!((zoomed.center_x - center_x).abs() <= info.delta_x * 1e-6);
+        assert!((zoomed.center_y - center_y).abs() <= info.delta_y * 1e-6);
+    }
+
+    // Away from the center, a right-click keeps the clicked point under
+    // the mouse (rather than centering on it, like a left-click does):
+    let info = WindowAndViewportInfo::new(200, 100, -0.5, 0.25, 1.0, 3);
+    for (row, column) in [(0.0, 0.0), (20.0, 170.0), (99.0, 5.0)] {
+        let (x, y) = convert_row_and_column_to_x_and_y(&info, row, column);
+        let (center_x, center_y) = zoom_out_center(&info, x, y);
+        let zoomed_out = zoomed_info(&info, center_x, center_y, false);
+        let (x_after, y_after) = convert_row_and_column_to_x_and_y(&zoomed_out, row, column);
+        assert!((x_after - x).abs() < 1e-12 && (y_after - y).abs() < 1e-12, "at row {row}, column {column}");
+        assert_eq!((zoomed_out.zoom_level, zoomed_out.distance_from_center_to_edge), (2, 2.0));
+        let zoomed_in = zoomed_info(&info, x, y, true);
+        assert_eq!((zoomed_in.center_x, zoomed_in.center_y), (x, y));
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_crop() {
+    println!();
+    println!("Testing --crop:");
+    assert_eq!(parse_crop_rectangle("10, 20,30,40"), Ok((10, 20, 30, 40)));
+    for bad_text in ["10,20,30", "10,20,30,40,50", "10,20,-30,40", "10,20,3.5,40", "30,20,10,40", "10,40,30,40"] {
+        let message = parse_crop_rectangle(bad_text).unwrap_err();
+        println!("\"{bad_text}\":  {message}");
+    }
+
+    let info = WindowAndViewportInfo::new(100, 100, -0.5, 0.25, 1.5, 2);
+    // Cropping to the whole window just draws it again (bigger):
+    let (view, output_rectangle) = crop_view(&info, (0, 0, 100, 100), 300).unwrap();
+    assert_eq!((view.width, view.height), (300, 300));
+    assert_eq!((view.center_x, view.center_y, view.distance_from_center_to_edge), (-0.5, 0.25, 1.5));
+    assert_eq!(output_rectangle, (0, 0, 300, 300));
+
+    // The corners of the crop rectangle are the same points of the
+    // plane as the corners of the output, and the pixels stay square:
+    for (rectangle, output_size, expected_output_rectangle) in [((0, 0, 100, 50), 200, (0, 50, 200, 150)),
+                                                                ((10, 20, 30, 30), 200, (0, 50, 200, 150)),
+                                                                ((60, 5, 70, 45), 400, (150, 0, 250, 400))] {
+        let (view, output_rectangle) = crop_view(&info, rectangle, output_size).unwrap();
+        println!("{rectangle:?}:  ({}, {}), distance {}, output {output_rectangle:?}",
+                 view.center_x, view.center_y, view.distance_from_center_to_edge);
+        assert_eq!(output_rectangle, expected_output_rectangle);
+        assert!((view.delta_x - view.delta_y).abs() < 1e-15);
+        let (left, top, right, bottom) = rectangle;
+        let (output_left, output_top, output_right, output_bottom) = output_rectangle;
+        for ((row, column), (output_row, output_column)) in [((top, left), (output_top, output_left)),
+                                                            ((bottom, right), (output_bottom, output_right))] {
+            let (x, y) = convert_row_and_column_to_x_and_y(&info, row as Float - 0.5, column as Float - 0.5);
+            let (output_x, output_y) = convert_row_and_column_to_x_and_y(&view, output_row as Float - 0.5,
+                                                                         output_column as Float - 0.5);
+            assert!((x - output_x).abs() < 1e-12 && (y - output_y).abs() < 1e-12);
+        }
+    }
+
+    // It has to fit inside of the window, and be rectangular:
+    assert!(crop_view(&info, (50, 50, 101, 60), 100).is_err());
+    assert!(crop_view(&info.with_projection(Projection::LogPolar), (0, 0, 10, 10), 100).is_err());
+
+    // Cropping an image keeps just the pixels inside of the rectangle:
+    let image: Vec<u32> = (0..12).collect();  // (4 pixels wide, 3 tall)
+    assert_eq!(cropped_image(&image, 4, (1, 1, 3, 3)), vec![5, 6, 9, 10]);
+    assert_eq!(cropped_image(&image, 4, (0, 0, 4, 3)), image);
+}
+
+
+#[allow(dead_code)]
+fn test_parse_window_size() {
+    println!();
+    println!("Testing the parse_window_size() function:");
+    for size_text in ["512", "1", "0", "-5", "abc", "8192", "8193", "18446744073709551616"] {
+        println!("{:?}: {:?}", size_text, parse_window_size(size_text));
+    }
+    assert_eq!(parse_window_size("512"), Ok(512));
+    assert_eq!(parse_window_size(&MAX_WINDOW_SIZE.to_string()), Ok(MAX_WINDOW_SIZE));
+    assert!(parse_window_size(&(MAX_WINDOW_SIZE + 1).to_string()).is_err());
+    assert!(parse_window_size("0").is_err());
+    assert!(parse_window_size("abc").is_err());
+
+    // Check the overflow boundary of bytes_needed_for_window():  the
+    // largest window whose byte count still fits in a usize should be
+    // fine, but one pixel more should be detected as an overflow.
+    let bytes_per_pixel = bytes_needed_for_window(1, 1).unwrap();
+    let max_pixels = usize::MAX / bytes_per_pixel;
+    assert_eq!(bytes_needed_for_window(max_pixels, 1), Some(max_pixels * bytes_per_pixel));
+    assert_eq!(bytes_needed_for_window(max_pixels + 1, 1), None);
+    assert_eq!(bytes_needed_for_window(usize::MAX, usize::MAX), None);
+    assert!(bytes_needed_for_window(MAX_WINDOW_SIZE, MAX_WINDOW_SIZE).is_some());
+
+    // The same boundary on a 32-bit machine (whatever this one is).
+    // (EscapeDetails are smaller on a 32-bit machine, so its real
+    // boundary is a bit further out than this.)
+    let max_pixels_on_32_bit = MAX_BYTES_ON_32_BIT / bytes_per_pixel as u64;
+    assert!(bytes_needed_for_window_within(max_pixels_on_32_bit as usize, 1, MAX_BYTES_ON_32_BIT).is_some());
+    assert_eq!(bytes_needed_for_window_within(max_pixels_on_32_bit as usize + 1, 1, MAX_BYTES_ON_32_BIT), None);
+    let max_size_on_32_bit = (max_pixels_on_32_bit as Float).sqrt() as usize;
+    assert!(bytes_needed_for_window_within(max_size_on_32_bit, max_size_on_32_bit, MAX_BYTES_ON_32_BIT).is_some());
+    assert_eq!(bytes_needed_for_window_within(max_size_on_32_bit + 1, max_size_on_32_bit + 1, MAX_BYTES_ON_32_BIT), None);
+    println!("The biggest window a 32-bit machine could index:  about {max_size_on_32_bit} x {max_size_on_32_bit} pixels.");
+    // (65536 x 65536 pixels is exactly 2^32, which a 32-bit
+    // usize would wrap around to 0, so it must not sneak by.)
+    assert_eq!(bytes_needed_for_window_within(65536, 65536, MAX_BYTES_ON_32_BIT), None);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_adjusted_bailout() {
+    println!();
+    println!("Testing the adjusted_bailout() function:");
+    for (bailout, increase, highest_escape_value) in [(Some(100), true, None),
+                                                      (Some(100), false, None),
+                                                      (Some(1), false, None),
+                                                      (Some(usize::MAX), true, None),
+                                                      (None, true, Some(500)),
+                                                      (None, false, Some(500)),
+                                                      (None, false, None)] {
+        println!("{:?}, {}, {:?}: {:?}", bailout, if increase { "+" } else { "-" }, highest_escape_value,
+                 adjusted_bailout(bailout, increase, highest_escape_value));
+    }
+    assert_eq!(adjusted_bailout(Some(100), true, None), Some(200));
+    assert_eq!(adjusted_bailout(Some(100), false, None), Some(50));
+    assert_eq!(adjusted_bailout(Some(1), false, None), Some(1));
+    assert_eq!(adjusted_bailout(Some(usize::MAX), true, None), Some(usize::MAX));
+    assert_eq!(adjusted_bailout(None, true, Some(500)), None);
+    assert_eq!(adjusted_bailout(None, false, Some(500)), Some(250));
+    assert_eq!(adjusted_bailout(None, false, None), Some(1));
+
+    // The report after each image should only suggest raising the
+    // bailout when some point came close to it:
+    let details = |escape_value: Option<usize>| EscapeDetails { escape_value, ..EscapeDetails::default() };
+    let escape_details = [details(None), details(Some(3)), details(Some(95)), details(Some(40))];
+    assert_eq!(escape_value_report(&escape_details, None), "Highest escape value:  95");
+    assert_eq!(escape_value_report(&escape_details, Some(1000)), "Highest escape value:  95 (of a bailout of 1000)");
+    let report = escape_value_report(&escape_details, Some(100));
+    println!("{report}");
+    assert!(report.starts_with("Highest escape value:  95 (of a bailout of 100)\n"));
+    assert!(report.contains("Try --bailout=200 "));
+    assert!(escape_value_report(&escape_details, Some(105)).contains("Try --bailout=210 "));  // (95 is over 90% of 105...)
+    assert!(!escape_value_report(&escape_details, Some(106)).contains("Try"));  // (...but not of 106.)
+    assert_eq!(escape_value_report(&[details(None), details(None)], Some(100)), "No pixels escaped.");
+
+    // With --bailout=auto, the bailout goes up by the same amount
+    // each time the view is zoomed in, and never goes below the base:
+    assert_eq!(automatic_bailout(2.0), 250);
+    assert_eq!(automatic_bailout(1.0), 350);
+    assert_eq!(automatic_bailout(2.0 / 1024.0), 1250);
+    assert_eq!(automatic_bailout(1.725), automatic_bailout(2.0) + 21);
+    assert_eq!(automatic_bailout(100.0), 250);
+    let mut distance = 2.0;
+    while distance > 1e-300 {
+        assert!(automatic_bailout(distance / 2.0) > automatic_bailout(distance));
+        distance /= 2.0;
+    }
+    println!("--bailout=auto:  {} at the start, {} after 50 zooms.", automatic_bailout(1.725), automatic_bailout(1.725 / 2.0_f64.powi(50)));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_iteration_stats() {
+    println!();
+    println!("Testing the iteration statistics (for --stats):");
+    let info = WindowAndViewportInfo::new(3, 2, -0.5, 0.0, 1.725, 0);
+    let config = Config { bailout: Some(100), ..Config::new() };
+    let details = |escape_value: Option<usize>, iterations: usize, hit_bailout: bool| {
+        EscapeDetails { escape_value, iterations, hit_bailout, ..EscapeDetails::default() }
+    };
+    let escape_details = [details(Some(3), 3, false), details(Some(40), 40, false), details(None, 100, true),
+                          details(Some(1), 1, false), details(None, 12, false), details(Some(7), 7, false)];
+    let text = iteration_stats_text(&escape_details, &info, &config, "now");
+    print!("{text}");
+    for line in ["center = -0.5, 0.0", "distance = 1.725", "size = 3 x 2", "bailout = 100",
+                 &format!("max iterations = {DEFAULT_MAX_ITERATIONS}"),
+                 "pixels = 6", "in set = 2 (33.33%)", "hit the bailout = 1 (16.67%)", "escaped = 4 (66.67%)",
+                 "iterations calculated = 163 (27.2 per pixel)",
+                 "   lowest = 1", "   highest = 40", "   mean = 12.75", "   median = 5",
+                 "   0 to 2 = 1", "   3 to 5 = 1", "   6 to 8 = 1", "   39 to 40 = 1"] {
+        assert!(text.lines().any(|text_line| text_line == line), "missing \"{line}\"");
+    }
+    // (0 to 40 is 41 escape values, so to fit in 20 bins, each is 3
+    // wide, and 14 of them are enough.)
+    assert_eq!(text.lines().filter(|line| line.contains(" to ")).count(), 14);
+    let histogram_total: usize = text.lines()
+                                     .filter(|line| line.contains(" to "))
+                                     .map(|line| line.rsplit(' ').next().unwrap().parse::<usize>().unwrap())
+                                     .sum();
+    assert_eq!(histogram_total, 4);
+
+    // An odd number of escaping pixels has a middle one for the median,
+    // and an image with none escaping has no escape values to report:
+    let text = iteration_stats_text(&escape_details[..4], &info, &config, "now");
+    assert!(text.contains("\n   median = 3\n"));
+    let text = iteration_stats_text(&[details(None, 5, false)], &info, &Config::new(), "now");
+    assert!(text.contains("\nbailout = none\n") && text.ends_with("(No pixels escaped.)\n"));
+}
+
+
+#[allow(dead_code)]
+fn test_adjusted_aa_samples() {
+    println!();
+    println!("Testing the adjusted_aa_samples() function (for the ( and ) keys):");
+    let mut aa_samples = None;
+    let mut steps = vec![aa_samples];
+    while let Some(next) = Some(adjusted_aa_samples(aa_samples, true)).filter(|&next| next != aa_samples) {
+        aa_samples = next;
+        steps.push(aa_samples);
+    }
+    println!("Going up:  {steps:?}");
+    assert_eq!(steps, [None, Some(2), Some(4), Some(8), Some(16), Some(32), Some(64)]);
+    assert_eq!(adjusted_aa_samples(Some(64), true), Some(MAX_AA_SAMPLES_KEY));
+    assert_eq!(adjusted_aa_samples(Some(64), false), Some(32));
+    assert_eq!(adjusted_aa_samples(Some(2), false), None);
+    assert_eq!(adjusted_aa_samples(None, false), None);
+
+    // A number given with --aa-samples isn't cut down by the ) key:
+    assert_eq!(adjusted_aa_samples(Some(3), true), Some(6));
+    assert_eq!(adjusted_aa_samples(Some(3), false), None);
+    assert_eq!(adjusted_aa_samples(Some(100), true), Some(100));
+    assert_eq!(adjusted_aa_samples(Some(100), false), Some(50));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_minimap() {
+    println!();
+    println!("Testing the Minimap:");
+    let (width, height) = (256, 256);
+    let minimap = Minimap::new(width, height, None);
+    let (top, left) = minimap.top_left_corner(width, height);
+    println!("A {0}x{0} minimap at (row {1}, column {2})", minimap.info.width, top, left);
+    assert_eq!(minimap.info.width, 64);
+    assert_eq!((top, left), (256 - 64 - 8, 256 - 64 - 8));
+
+    // Counts the minimap pixels that the marker changed, when
+    // drawing the minimap for a view of the given center and distance:
+    let count_marked_pixels = |minimap: &Minimap, center_x: Float, center_y: Float, distance: Float| {
+        let info = WindowAndViewportInfo::new(width, height, center_x, center_y, distance, 0);
+        let mut buffer = vec![0u32; width * height];
+        minimap.draw(&mut buffer, &info);
+        (0..minimap.info.height).flat_map(|row| (0..minimap.info.width).map(move |column| (row, column)))
+                                .filter(|&(row, column)| buffer[(top + row) * width + left + column]
+                                                         != minimap.image_buffer[row * minimap.info.width + column])
+                                .count()
+    };
+    let quarter_view = count_marked_pixels(&minimap, -0.5, 0.0, 1.725 / 4.0);
+    let deep_view = count_marked_pixels(&minimap, -0.745, 0.1, 1e-6);
+    let julia_minimap = Minimap::new(width, height, Some((-0.8, 0.156)));
+    let julia_view = count_marked_pixels(&julia_minimap, 0.0, 0.0, 2.0);
+    println!("Marked pixels:  {quarter_view} (a quarter-sized view), {deep_view} (a deep view), {julia_view} (a Julia set)");
+    assert!(quarter_view >= 4 * 14);  // (A rectangle about 16 pixels on each side.)
+    assert_eq!(deep_view, 9);  // (A small plus sign.)
+    assert_eq!(julia_view, 9);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_jittered_antialiasing() {
+    println!();
+    println!("Testing the jittered anti-aliasing (--aa-samples):");
+    let mut rng = Xorshift64::new(0);
+    let numbers: Vec<Float> = (0..1000).map(|_| rng.next_float()).collect();
+    assert!(numbers.iter().all(|&number| (0.0..1.0).contains(&number)));
+    assert_eq!(Xorshift64::new(42).next_u64(), Xorshift64::new(42).next_u64());
+    assert_ne!(Xorshift64::new(42).next_u64(), Xorshift64::new(43).next_u64());
+
+    // A pixel on the edge of the set should get a blend of colors,
+    // and the same blend every time for the same seed:
+    let info = WindowAndViewportInfo::new(64, 64, -0.5, 0.0, 1.725, 0);
+    let threshold = info.delta_x / 4.0;
+    let mut config = Config::new();
+    config.aa_samples = Some(16);
+    let (row, column) = (32, 14);  // (Near the left tip of the main cardioid.)
+    let details = calculate_pixel_details(&info, row, column, &config, threshold);
+    let first_color = pixel_color_at(&info, row, column, &details, &config, threshold);
+    let second_color = pixel_color_at(&info, row, column, &details, &config, threshold);
+    config.aa_seed = 1;
+    let other_seed_color = pixel_color_at(&info, row, column, &details, &config, threshold);
+    println!("Seed 0:  {first_color:06x} (and {second_color:06x} again); seed 1:  {other_seed_color:06x}");
+    assert_eq!(first_color, second_color);
+
+    // A pixel deep inside the set is the same color no matter what:
+    let (row, column) = (32, 40);
+    let details = calculate_pixel_details(&info, row, column, &config, threshold);
+    assert_eq!(pixel_color_at(&info, row, column, &details, &config, threshold), pixel_color(&details, &config));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_dithering() {
+    println!();
+    println!("Testing the dithering (--dither):");
+    // Whole numbers should never be changed:
+    for row in 0..4 {
+        for column in 0..4 {
+            for value in [0.0, 1.0, 128.0, 254.0, 255.0] {
+                assert_eq!(dither(value, row, column) as Float, value);
+            }
+        }
+    }
+
+    // Fractions should average out over each 4x4 block:
+    for value in [8.5, 100.25, 200.75, 254.9] {
+        let mut total = 0;
+        for row in 0..4 {
+            for column in 0..4 {
+                total += dither(value, row + 4, column + 8) as usize;
+            }
+        }
+        let average = total as Float / 16.0;
+        println!("{value} dithers to an average of {average}");
+        assert!((average - value).abs() <= 1.0 / 16.0);
+    }
+
+    // With 8.5, half of the pixels should be rounded down and half up,
+    // with no two side-by-side pixels the same:
+    let row_of_pixels: Vec<u8> = (0..4).map(|column| dither(8.5, 0, column)).collect();
+    assert_eq!(row_of_pixels, vec![8, 9, 8, 9]);
+
+    // And without --dither, the colors should be the same as before:
+    let info = WindowAndViewportInfo::new(16, 16, -0.5, 0.0, 1.725, 0);
+    let threshold = info.delta_x / 4.0;
+    let config = Config::new();
+    for row in 0..16 {
+        for column in 0..16 {
+            let details = calculate_pixel_details(&info, row, column, &config, threshold);
+            assert_eq!(pixel_color_at(&info, row, column, &details, &config, threshold),
+                       pixel_color(&details, &config));
+        }
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_render_profile() {
+    println!();
+    println!("Testing the RenderProfile (for --profile):");
+    let mut profile = RenderProfile::new();
+    for (x, y) in [(0.0, 0.0), (-1.0, 0.4), (1.0, 1.0)] {
+        let details = calculate_escape_details(x, y, None, Some(1e-6), None, false);
+        println!("({x}, {y}): {details:?}");
+        profile.add_pixel(&details);
+    }
+    profile.calculation_time = std::time::Duration::from_millis(30);
+    profile.display_time = std::time::Duration::from_millis(10);
+    println!("{}", profile.summary(std::time::Duration::from_millis(40)));
+    assert_eq!(profile.number_of_pixels, 3);
+    assert_eq!(profile.total_iterations, 7);
+    assert_eq!(profile.max_iterations, 6);
+    assert!(profile.summary(std::time::Duration::from_millis(40)).contains("(75.0%)"));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_bailout_heatmap() {
+    println!();
+    println!("Testing the bailout heatmap:");
+    // (-0.75, 0.01) is just outside the set, but takes a few hundred
+    // iterations to escape, so a low bailout is hit before then:
+    let escaped = calculate_escape_details(-0.75, 0.01, None, Some(1e-9), Some(1000), false);
+    let bailed_out = calculate_escape_details(-0.75, 0.01, None, Some(1e-9), Some(10), false);
+    let in_set = calculate_escape_details(0.0, 0.0, None, Some(1e-9), Some(10), false);
+    println!("Bailout of 1000:  {escaped:?}");
+    println!("Bailout of 10:  {bailed_out:?}");
+    println!("(0, 0):  {in_set:?}");
+    assert!(escaped.escape_value.is_some() && !escaped.hit_bailout);
+    assert!(bailed_out.escape_value.is_none() && bailed_out.hit_bailout);
+    assert!(in_set.escape_value.is_none() && !in_set.hit_bailout);
+
+    let white = rgb_to_u32(255, 255, 255);
+    let mut buffer = vec![white; 3];
+    draw_bailout_heatmap(&mut buffer, &[escaped, bailed_out, in_set], (255, 0, 0), 0.5);
+    println!("Tinted:  {:06x} {:06x} {:06x}", buffer[0], buffer[1], buffer[2]);
+    assert_eq!(buffer, vec![white, rgb_to_u32(255, 128, 128), white]);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_progressive_refinement() {
+    println!();
+    println!("Testing the progressive refinement (--progressive):");
+    assert_eq!(progressive_bailouts(Some(1000)), vec![Some(64), Some(256), Some(1000)]);
+    assert_eq!(progressive_bailouts(Some(256)), vec![Some(64), Some(256)]);
+    assert_eq!(progressive_bailouts(Some(10)), vec![Some(10)]);
+    assert_eq!(progressive_bailouts(None), vec![Some(64), Some(256), Some(1024), Some(4096), None]);
+    assert!(ProgressiveRefinement::new(&[Some(10)], &Config::new(), true).is_none());
+
+    // Refining an image drawn with the lowest bailout should give
+    // exactly the same image as drawing it with the final bailout:
+    let info = WindowAndViewportInfo::new(48, 48, -0.745, 0.1, 0.01, 0);
+    let mut config = Config::new();
+    for final_bailout in [Some(2000), None] {
+        config.bailout = final_bailout;
+        let threshold = cycle_threshold(&info, &config);
+        let bailouts = progressive_bailouts(final_bailout);
+        let first_pass_config = Config { bailout: bailouts[0], ..config.clone() };
+        let mut escape_details = render_escape_details(&info, &first_pass_config);
+        let mut image_buffer = vec![0u32; info.width * info.height];
+        color_escape_details(&escape_details, &mut image_buffer, &info, &first_pass_config);
+        let first_pass_bailouts = escape_details.iter().filter(|details| details.hit_bailout).count();
+
+        let mut refinement = ProgressiveRefinement::new(&bailouts, &config, true).unwrap();
+        let mut number_of_calls = 0;
+        while !refinement.refine(&mut escape_details, &mut image_buffer, &info, threshold,
+                                 std::time::Duration::from_millis(1)) {
+            number_of_calls += 1;
+        }
+        let final_escape_details = render_escape_details(&info, &config);
+        let mut final_image_buffer = vec![0u32; info.width * info.height];
+        color_escape_details(&final_escape_details, &mut final_image_buffer, &info, &config);
+        println!("Bailout {final_bailout:?}:  {first_pass_bailouts} pixels hit the first bailout; refined in {} calls.",
+                 number_of_calls + 1);
+        assert!(first_pass_bailouts > 0);
+        assert!(escape_details == final_escape_details);
+        assert!(image_buffer == final_image_buffer);
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_chunked_rendering() {
+    println!();
+    println!("Comparing rendering in chunks of rows vs. in one piece per thread:");
+    // Each chunk should be whole rows, with enough chunks for every thread:
+    let bytes_per_row = 512 * std::mem::size_of::<EscapeDetails>();
+    assert_eq!(rows_per_chunk(512, 512, 1), (64 * 1024 / bytes_per_row).max(1));
+    assert_eq!(rows_per_chunk(16, 512, 8), 16);  // (512 rows / (8 threads * 4 chunks each))
+    assert_eq!(rows_per_chunk(100_000, 10, 4), 1);
+    assert_eq!(rows_per_chunk(0, 0, 4), 1);
+
+    let mut config = Config::new();
+    config.max_threads = Some(3);
+    assert!(number_of_threads(&config) >= 1 && number_of_threads(&config) <= 3);
+
+    // Both ways should give exactly the same details, with any number of threads:
+    let info = WindowAndViewportInfo::new(256, 256, -0.5, 0.0, 1.725, 0);
+    let config = Config::new();
+    let threshold = cycle_threshold(&info, &config);
+    let start_time = std::time::Instant::now();
+    let one_piece_per_thread = calculate_pixel_details_in_parallel(&all_pixels(info.width, info.height),
+                                                                    &info, &config, threshold);
+    let one_piece_time = start_time.elapsed().as_secs_f64();
+    for number_of_threads in [1, 2, 4, 8] {
+        let start_time = std::time::Instant::now();
+        let in_chunks = render_escape_details_in_chunks(&info, &config, number_of_threads);
+        println!("{number_of_threads} thread(s):  {:.6} sec. in chunks of {} rows",
+                 start_time.elapsed().as_secs_f64(), rows_per_chunk(info.width, info.height, number_of_threads));
+        assert!(in_chunks == one_piece_per_thread);
+    }
+    println!("(One piece per thread, with {} thread(s):  {one_piece_time:.6} sec.)", number_of_threads(&config));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_fast_fill() {
+    println!();
+    println!("Testing --fast-fill (the Mariani-Silver algorithm):");
+    let plain_config = Config { bailout: Some(500), ..Config::new() };
+    let fast_config = Config { fast_fill_min_size: Some(DEFAULT_FAST_FILL_MIN_SIZE), ..plain_config.clone() };
+
+    // Across the whole set, only a few pixels (in thin filaments) may
+    // come out differently, with any number of threads:
+    let info = WindowAndViewportInfo::new(256, 192, -0.5, 0.0, 1.725, 0);
+    let plain = render_escape_details(&info, &plain_config);
+    for number_of_threads in [1, 3, 8] {
+        let fast = render_escape_details_in_chunks(&info, &fast_config, number_of_threads);
+        let different = plain.iter().zip(fast.iter()).filter(|(a, b)| a.escape_value != b.escape_value).count();
+        println!("{number_of_threads} thread(s):  {different} of {} pixels different", plain.len());
+        assert!(different * 200 < plain.len());
+    }
+
+    // A view of nothing but the set is filled in exactly (though the
+    // filled-in pixels get the iterations of the pixel they're copied
+    // from), and so is any view where the rectangles are too small to
+    // fill (or the image is too small to have any):
+    let escape_values = |escape_details: Vec<EscapeDetails>| -> Vec<Option<usize>> {
+        escape_details.iter().map(|details| details.escape_value).collect()
+    };
+    let inside = WindowAndViewportInfo::new(64, 64, -0.2, 0.0, 0.1, 0);
+    assert_eq!(escape_values(render_escape_details(&inside, &fast_config)),
+               escape_values(render_escape_details(&inside, &plain_config)));
+    let never_filled_config = Config { fast_fill_min_size: Some(1000), ..fast_config.clone() };
+    assert_eq!(render_escape_details(&info, &never_filled_config), plain);
+    for (width, height) in [(1, 1), (2, 3), (3, 2), (5, 1), (0, 0)] {
+        let tiny = WindowAndViewportInfo::new(width, height, -0.5, 0.0, 1.725, 0);
+        assert_eq!(render_escape_details(&tiny, &fast_config), render_escape_details(&tiny, &plain_config));
+    }
+
+    // Without cycle detection, every pixel in the set takes the whole
+    // bailout, so skipping the insides of the set makes a big difference
+    // in a view that's mostly the set.  (Only how many pixels get
+    // calculated is checked, as how long they take depends on the machine.)
+    let slow_config = Config { bailout: Some(5000), cycle_detection: false, ..Config::new() };
+    let slow_fast_config = Config { fast_fill_min_size: Some(DEFAULT_FAST_FILL_MIN_SIZE), ..slow_config.clone() };
+    let info = WindowAndViewportInfo::new(64, 64, -0.2, 0.0, 0.5, 0);
+    let start_time = std::time::Instant::now();
+    let (_, plain_calculated) = render_and_count_escape_details(&info, &slow_config, 1);
+    let plain_time = start_time.elapsed().as_secs_f64();
+    let start_time = std::time::Instant::now();
+    let (_, fast_calculated) = render_and_count_escape_details(&info, &slow_fast_config, 1);
+    let fast_time = start_time.elapsed().as_secs_f64();
+    println!("Without cycle detection:  {plain_calculated} pixels in {plain_time:.4} sec. vs. \
+              {fast_calculated} pixels in {fast_time:.4} sec. with --fast-fill");
+    assert_eq!(plain_calculated, info.width * info.height);
+    assert!(fast_calculated < plain_calculated / 2);
+    // (With more threads, a bigger image is split into more chunks,
+    // each with its own border to calculate, but that's still fewer.)
+    for number_of_threads in [2, 3] {
+        let (_, calculated) = render_and_count_escape_details(&info, &slow_fast_config, number_of_threads);
+        println!("{number_of_threads} thread(s):  {calculated} pixels with --fast-fill");
+        assert!(calculated < plain_calculated);
+    }
+
+    // With --coloring=potential, only the set is filled in, so every
+    // escaped pixel keeps its own escape magnitude (for smooth shading):
+    let potential_config = Config { coloring: Coloring::Potential, ..fast_config.clone() };
+    let info = WindowAndViewportInfo::new(256, 192, -0.5, 0.0, 1.725, 0);
+    let (potential, potential_calculated) = render_and_count_escape_details(&info, &potential_config, 1);
+    let (_, fast_calculated) = render_and_count_escape_details(&info, &fast_config, 1);
+    println!("With --coloring=potential:  {potential_calculated} pixels calculated, vs. {fast_calculated} without");
+    assert!(plain.iter().zip(potential.iter())
+                 .all(|(plain, potential)| potential.escape_value.is_none() || plain == potential));
+    assert!(fast_calculated < potential_calculated && potential_calculated < plain.len());
+    assert_ne!(escape_cache_filename("", &info, &fast_config, 0.0), escape_cache_filename("", &info, &potential_config, 0.0));
+
+    // render_into_buffer() fills in rectangles, too:
+    let mut plain_buffer = vec![0u32; info.width * info.height];
+    let mut fast_buffer = vec![0u32; info.width * info.height];
+    render_into_buffer(&mut plain_buffer, &info, &plain_config);
+    render_into_buffer(&mut fast_buffer, &info, &fast_config);
+    let different = plain_buffer.iter().zip(fast_buffer.iter()).filter(|(a, b)| a != b).count();
+    println!("render_into_buffer():  {different} of {} pixels different", plain_buffer.len());
+    assert!(different * 200 < plain_buffer.len());
+
+    // Even a full-sized window, split among several threads, should
+    // have most of its pixels filled in (rather than calculated):
+    let info = WindowAndViewportInfo::new(DEFAULT_WINDOW_SIZE, DEFAULT_WINDOW_SIZE, -0.5, 0.0, 1.725, 0);
+    for number_of_threads in [2, 8] {
+        let (_, calculated) = render_and_count_escape_details(&info, &fast_config, number_of_threads);
+        println!("{number_of_threads} thread(s):  {calculated} of {} pixels of a {}-pixel-wide view with --fast-fill",
+                 info.width * info.height, info.width);
+        assert!(calculated < info.width * info.height / 2);
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_render_into_buffer() {
+    println!();
+    println!("Testing the render_into_buffer() function:");
+    let info = WindowAndViewportInfo::new(32, 24, -0.5, 0.0, 1.725, 0);
+    let config = Config::new();
+    let mut buffer = vec![0u32; info.width * info.height];
+    render_into_buffer(&mut buffer, &info, &config);
+
+    // Every pixel should be the same as when it's calculated by itself:
+    let threshold = info.delta_x / 4.0;
+    for (i, (row, column)) in all_pixels(info.width, info.height).into_iter().enumerate() {
+        let details = calculate_pixel_details(&info, row, column, &config, threshold);
+        assert_eq!(buffer[i], pixel_color(&details, &config), "at (row {row}, column {column})");
+    }
+    let center = buffer[(info.height / 2) * info.width + info.width / 2];
+    println!("A {}x{} image; its center pixel is {:06x}", info.width, info.height, center);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_render_progress_and_cancel() {
+    println!();
+    println!("Testing the progress and cancelling of render_into_buffer_with_progress():");
+    let info = WindowAndViewportInfo::new(128, 128, -0.75, 0.1, 0.05, 0);
+    let config = Config::new();
+
+    let mut fractions: Vec<f32> = Vec::new();
+    let mut buffer = vec![0u32; info.width * info.height];
+    let finished = render_into_buffer_with_progress(&mut buffer, &info, &config,
+                                                    Some(&mut |fraction| fractions.push(fraction)), None);
+    println!("Finished:  {finished}; progress:  {fractions:?}");
+    assert!(finished);
+    assert_eq!(fractions.last(), Some(&1.0));
+    assert!(fractions.windows(2).all(|pair| pair[0] < pair[1]));
+    let mut expected_buffer = vec![0u32; info.width * info.height];
+    render_into_buffer(&mut expected_buffer, &info, &config);
+    assert!(buffer == expected_buffer);
+
+    // An already-cancelled render should stop before drawing anything:
+    let cancel = std::sync::atomic::AtomicBool::new(true);
+    let mut buffer = vec![0u32; info.width * info.height];
+    let finished = render_into_buffer_with_progress(&mut buffer, &info, &config, None, Some(&cancel));
+    println!("Finished (when cancelled):  {finished}");
+    assert!(!finished);
+    assert!(buffer.iter().all(|&pixel| pixel == 0));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_degenerate_viewports() {
+    println!();
+    println!("Testing the detection of degenerate viewports (WindowAndViewportInfo::is_degenerate()):");
+    let viewport = |center_x: Float, distance: Float| WindowAndViewportInfo::new(800, 800, center_x, 0.0, distance, 0);
+    assert!(!viewport(-0.5, 1.725).is_degenerate());
+    assert!(!viewport(-0.5, 1e-12).is_degenerate());
+    assert!(!viewport(0.0, 1e-300).is_degenerate());   // (Near zero, there's lots of precision.)
+    assert!(viewport(0.0, 1e-320).is_degenerate());    // (A subnormal distance.)
+    assert!(viewport(1.0, 1e-20).is_degenerate());     // (Every pixel would land on x=1.)
+    assert!(viewport(0.0, Float::MAX).is_degenerate());  // (The span overflows.)
+
+    // Zooming in on x=1 should stop long before the distance underflows:
+    let mut distance = 1.0;
+    let mut zoom_level = 0;
+    while !viewport(1.0, distance / 2.0).is_degenerate() {
+        distance /= 2.0;
+        zoom_level += 1;
+    }
+    println!("Zooming in on 1+0i stops at zoom level {zoom_level} (a distance of {distance:e}).");
+    assert!(zoom_level > 30 && zoom_level < 60);
+
+    // The error message should say which way the view went wrong,
+    // without writing out hundreds of digits:
+    for (center_x, distance, float_type, expected) in [(-0.5, 1e308, FloatType::F64, "A distance of 1e308 is too big"),
+                                                       (-0.5, 1e-320, FloatType::F64, "A distance of 1e-320 is too small"),
+                                                       (1.0, 1e-20, FloatType::F64, "too small to draw around the center 1.0,0.0."),
+                                                       (0.0, 1e-46, FloatType::F32, "too small to draw around the center 0.0,0.0 in f32."),
+                                                       (1e39, 1e30, FloatType::F32, "too big to draw around the center 1e39,0.0 in f32.")] {
+        let info = viewport(center_x, distance);
+        assert!(info.is_degenerate_for(float_type));
+        let message = degenerate_view_message(&info, float_type);
+        println!("{message}");
+        assert!(message.contains(expected), "{message}");
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_config_watch() {
+    println!();
+    println!("Testing the watching of the config file (--watch):");
+    // Changing just the colors should only need a recolor,
+    // but anything else should be calculated again:
+    let info = WindowAndViewportInfo::new(64, 64, -0.5, 0.0, 1.725, 0);
+    let config = Config::new();
+    let recolorable = [Config { coloring: Coloring::Potential, ..config.clone() },
+                       Config { invert: true, color_period: 45, dither: true, ..config.clone() },
+                       Config { boundary_color: (255, 255, 0), antialiasing: Antialiasing::Fxaa, ..config.clone() }];
+    for new_config in &recolorable {
+        assert!(!config_change_needs_recalculation((&info, &config), (&info, new_config)), "{new_config:?}");
+    }
+    let recalculated = [Config { bailout: Some(500), ..config.clone() },
+                        Config { c: Some((-0.8, 0.156)), ..config.clone() },
+                        Config { float_type: FloatType::F32, ..config.clone() },
+                        Config { projection: Projection::LogPolar, ..config.clone() },
+                        Config { cycle_threshold_factor: 0.1, ..config.clone() },
+                        Config { interior_coloring: InteriorColoring::Period, ..config.clone() }];
+    for new_config in &recalculated {
+        assert!(config_change_needs_recalculation((&info, &config), (&info, new_config)), "{new_config:?}");
+    }
+    // (...and so should a new view.)
+    let program_config = ProgramConfig::new();
+    let new_views = [ProgramConfig { center: (-0.75, 0.1), ..program_config.clone() },
+                     ProgramConfig { distance_from_center_to_edge: 0.5, ..program_config.clone() }];
+    for new_program_config in &new_views {
+        let new_info = reloaded_view(&info, &program_config, new_program_config, &config).unwrap();
+        assert!(config_change_needs_recalculation((&info, &config), (&new_info, &config)), "{new_program_config:?}");
+    }
+
+    // A new view starts over at zoom level 0, but a view that can't be
+    // drawn (even just in f32) should be turned away:
+    let zoomed_info = WindowAndViewportInfo::new(64, 64, -0.75, 0.1, 0.01, 7);
+    assert_eq!(reloaded_view(&zoomed_info, &program_config, &program_config, &recolorable[0]).unwrap().zoom_level, 7);
+    assert_eq!(reloaded_view(&zoomed_info, &program_config, &new_views[0], &config).unwrap().zoom_level, 0);
+    let unreadable = [(ProgramConfig { distance_from_center_to_edge: 1e-320, ..program_config.clone() }, FloatType::F64),
+                      (ProgramConfig { center: (1.0, 0.0), distance_from_center_to_edge: 1e-20, ..program_config.clone() }, FloatType::F64),
+                      (ProgramConfig { distance_from_center_to_edge: 1e-46, ..program_config.clone() }, FloatType::F32)];
+    for (new_program_config, float_type) in &unreadable {
+        let new_config = Config { float_type: *float_type, ..config.clone() };
+        let error = reloaded_view(&info, &program_config, new_program_config, &new_config).unwrap_err();
+        println!("{error}");
+    }
+    let deep_info = WindowAndViewportInfo::new(64, 64, -0.745, 0.1, 1e-9, 30);
+    assert!(reloaded_view(&deep_info, &program_config, &program_config, &recalculated[2]).is_err());
+
+    // A file that turns off cycle detection needs a bailout, too (from
+    // the file itself, or from the settings it's applied on top of):
+    for (text, bailout, expected) in [("cycle_detect = false\n", None, true),
+                                      ("cycle_detect = false\nbailout = 500\n", None, false),
+                                      ("cycle_detect = false\n", Some(500), false),
+                                      ("bailout = 500\n", None, false)] {
+        let config_file: ConfigFile = toml::from_str(text).unwrap();
+        let mut new_config = Config { bailout, ..config.clone() };
+        config_file.apply_to(&mut new_config, &mut program_config.clone()).unwrap();
+        assert_eq!(needs_a_bailout(&new_config), expected, "{text:?}");
+    }
+
+    // The watcher should notice the file being saved again (once):
+    let filename = std::env::temp_dir().join(format!("jlr-mandelbrot-test.{}.toml", std::process::id()));
+    std::fs::write(&filename, "bailout = 100\n").unwrap();
+    let mut watcher = ConfigFileWatcher::new(filename.to_str().unwrap());
+    assert!(!watcher.has_changed());
+    let saved_later = watcher.last_modified.unwrap() + std::time::Duration::from_secs(5);
+    std::fs::File::options().write(true).open(&filename).unwrap().set_modified(saved_later).unwrap();
+    assert!(!watcher.has_changed());  // (It isn't time to check yet.)
+    watcher.last_checked -= WATCH_INTERVAL;
+    assert!(watcher.has_changed());
+    watcher.last_checked -= WATCH_INTERVAL;
+    assert!(!watcher.has_changed());
+    std::fs::remove_file(&filename).unwrap();
+    watcher.last_checked -= WATCH_INTERVAL;
+    assert!(!watcher.has_changed());  // (A missing file isn't a change.)
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_auto_zoom() {
+    println!();
+    println!("Testing the choice of where to zoom in (--auto-zoom):");
+    assert_eq!(parse_auto_zoom_delay("2.5"), Ok(std::time::Duration::from_millis(2500)));
+    assert_eq!(parse_auto_zoom_delay("0"), Ok(std::time::Duration::ZERO));
+    for text in ["-1", "soon", "inf", "NaN", ""] {
+        assert!(parse_auto_zoom_delay(text).is_err(), "{text:?}");
+    }
+
+    // Nothing is interesting about a blank image (or the inside of the set):
+    let mut escape_details = vec![EscapeDetails { escape_value: Some(7), ..EscapeDetails::default() }; 64 * 64];
+    assert_eq!(most_interesting_pixel(&escape_details, 64, 64), None);
+    assert_eq!(most_interesting_pixel(&vec![EscapeDetails::default(); 64 * 64], 64, 64), None);
+
+    // A patch of different escape values should be found, but only
+    // if it's in the middle half of the image:
+    for (row, column) in [(2, 2), (3, 3), (2, 3)] {
+        escape_details[row * 64 + column].escape_value = Some(5000);
+    }
+    assert_eq!(most_interesting_pixel(&escape_details, 64, 64), None);
+    for (row, column) in [(40, 20), (41, 21), (40, 21)] {
+        escape_details[row * 64 + column].escape_value = Some(5000);
+    }
+    let (row, column) = most_interesting_pixel(&escape_details, 64, 64).unwrap();
+    println!("The patch around (40, 20) was found at ({row}, {column}).");
+    assert!(row.abs_diff(40) <= AUTO_ZOOM_NEIGHBORHOOD && column.abs_diff(21) <= AUTO_ZOOM_NEIGHBORHOOD);
+
+    // And the whole set should have somewhere to zoom in on, near its boundary:
+    let info = WindowAndViewportInfo::new(64, 64, -0.5, 0.0, 1.725, 0);
+    let mut config = Config::new();
+    config.bailout = Some(500);
+    let escape_details = render_escape_details(&info, &config);
+    let (row, column) = most_interesting_pixel(&escape_details, 64, 64).unwrap();
+    println!("The most interesting pixel of the whole set is at ({row}, {column}).");
+    assert!((16..48).contains(&row) && (16..48).contains(&column));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_smooth_labels() {
+    println!();
+    println!("Testing the smooth labels (--smooth-labels):");
+    let scale = 6;
+    for character in "AB8xk-.?".chars() {
+        let coverage = smooth_glyph_coverage(character, scale);
+        assert_eq!(coverage.len(), 3 * scale * 5 * scale);
+        // The middle of every glyph pixel should be covered exactly
+        // when the blocky glyph has it lit, with softer edges around:
+        for (glyph_row, bits) in glyph(character).iter().enumerate() {
+            for glyph_column in 0..3 {
+                let lit = bits & (0b100 >> glyph_column) != 0;
+                let middle = (glyph_row * scale + scale / 2) * 3 * scale + glyph_column * scale + scale / 2;
+                assert_eq!(coverage[middle], if lit { 1.0 } else { 0.0 }, "{character:?}");
+            }
+        }
+        assert!(coverage.iter().any(|&fraction| fraction > 0.0 && fraction < 1.0), "{character:?}");
+    }
+    assert!(smooth_glyph_coverage(' ', scale).iter().all(|&fraction| fraction == 0.0));
+    for row in smooth_glyph_coverage('A', scale).chunks_exact(3 * scale) {
+        println!("{}", row.iter().map(|&fraction| match fraction {
+                                               0.0 => ' ',
+                                               1.0 => '#',
+                                               _ => '+',
+                                           }).collect::<String>());
+    }
+
+    // A smooth label should fill the same box, blending the letters'
+    // edges into it (and at a scale of 1, it's the same as before):
+    let (mut blocky, mut smooth) = (vec![0x12_34_56; 40 * 20], vec![0x12_34_56; 40 * 20]);
+    draw_label(&mut blocky, 40, 20, "A1", (2, 3), 2, false);
+    draw_label(&mut smooth, 40, 20, "A1", (2, 3), 2, true);
+    let in_box = |buffer: &[u32]| buffer.iter().filter(|&&pixel| pixel != 0x12_34_56).count();
+    assert_eq!(in_box(&smooth), in_box(&blocky));
+    assert!(smooth.iter().any(|&pixel| pixel != 0x00_00_00 && pixel != 0xff_ff_ff && pixel != 0x12_34_56));
+    let (mut blocky, mut smooth) = (vec![0x12_34_56; 40 * 20], vec![0x12_34_56; 40 * 20]);
+    draw_label(&mut blocky, 40, 20, "A1", (2, 3), 1, false);
+    draw_label(&mut smooth, 40, 20, "A1", (2, 3), 1, true);
+    assert_eq!(smooth, blocky);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_contact_sheet() {
+    println!();
+    println!("Testing the contact sheet (--contact-sheet):");
+    assert_eq!(contact_sheet_layout(1), (1, 1));
+    assert_eq!(contact_sheet_layout(6), (3, 2));
+    assert_eq!(contact_sheet_layout(16), (4, 4));
+    assert_eq!(contact_sheet_layout(17), (5, 4));
+
+    // A Julia set's grid should be centered on its c, with c growing
+    // to the right (in x) and up (in y):
+    let mut config = Config::new();
+    config.c = Some((-0.8, 0.156));
+    config.bailout = Some(100);
+    let thumbnails = contact_sheet_thumbnails(&config, 3);
+    let labels: Vec<&str> = thumbnails.iter().map(|(label, _)| label.as_str()).collect();
+    println!("{labels:?}");
+    assert_eq!(labels.len(), 9);
+    assert_eq!(labels[4], "c=-0.800,0.156");
+    assert_eq!(labels[0], "c=-0.820,0.176");
+    assert_eq!(labels[8], "c=-0.780,0.136");
+
+    // Every label should be drawable (no question marks), and a
+    // label should draw exactly the pixels of its glyphs:
+    config.c = None;
+    for (label, _) in contact_sheet_thumbnails(&config, 3).iter().chain(thumbnails.iter()) {
+        assert!(label.chars().all(|character| character == '?' || glyph(character) != glyph('?')), "{label}");
+    }
+    let mut buffer = vec![0x12_34_56; 20 * 10];
+    draw_label(&mut buffer, 20, 10, "1", (0, 0), 1, false);
+    assert_eq!(buffer.iter().filter(|&&pixel| pixel == 0xff_ff_ff).count(), 8);  // (The "1" glyph has 8 pixels.)
+    assert_eq!(buffer.iter().filter(|&&pixel| pixel == 0x00_00_00).count(), 5 * 7 - 8);
+    draw_label(&mut buffer, 20, 10, "cut off", (8, 15), 2, false);  // (This shouldn't panic.)
+    draw_label(&mut buffer, 20, 10, "cut off", (8, 15), 2, true);  // (Nor should this.)
+
+    // The sheet of the Mandelbrot set's colorings should have all
+    // of the thumbnails (which are all different) tiled into it:
+    let thumbnails = contact_sheet_thumbnails(&config, 3);
+    let program_config = ProgramConfig::new();
+    let (sheet, width, height) = render_contact_sheet(&thumbnails, 32, program_config.center, program_config.distance_from_center_to_edge,
+                                                      false, CONTACT_SHEET_EMPTY_COLOR);
+    assert_eq!((width, height), (96, 96));
+    let tile = |i: usize| -> Vec<u32> {
+        let (top, left) = (i / 3 * 32, i % 3 * 32);
+        (0..20).flat_map(|row| sheet[(top + row) * width + left..][..32].to_vec()).collect()
+    };
+    for i in 0..thumbnails.len() {
+        for j in 0..i {
+            assert_ne!(tile(i), tile(j), "Thumbnails {j} and {i} are the same.");
+        }
+    }
+    // ...and with three of them, the empty fourth spot (of the 2 x 2
+    // grid) should be left in the given color (see --background):
+    let (sheet, width, height) = render_contact_sheet(&thumbnails[..3], 32, program_config.center, program_config.distance_from_center_to_edge,
+                                                      false, 0x12_34_56);
+    assert_eq!((width, height), (64, 64));
+    assert!((32..64).all(|row| sheet[row * width + 32..][..32].iter().all(|&pixel| pixel == 0x12_34_56)));
+    assert!(!sheet[..32].iter().all(|&pixel| pixel == 0x12_34_56));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_julia_atlas() {
+    println!();
+    println!("Testing the Julia atlas (--julia-atlas):");
+    // The cells' c values should be at the centers of the cells of
+    // the view, going right along x and up along y:
+    let mut config = Config::new();
+    config.bailout = Some(50);
+    let cells = julia_atlas_cells(&config, (-0.5, 0.0), 2.0, 4);
+    assert_eq!(cells.len(), 16);
+    let c = |i: usize| cells[i].1.c.unwrap();
+    assert_eq!(c(0), (-2.0, 1.5));
+    assert_eq!(c(3), (1.0, 1.5));
+    assert_eq!(c(12), (-2.0, -1.5));
+    assert_eq!(c(5), (-1.0, 0.5));
+    assert!(cells.iter().all(|(label, _)| label.is_empty()));
+
+    // A c inside the Mandelbrot set (like 0) has a connected Julia set,
+    // with plenty of points in it, while one far outside (like 1+1.5i)
+    // is nothing but dust.  So the cells show the Mandelbrot set's shape:
+    let in_set = |c: (Float, Float)| -> usize {
+        let info = WindowAndViewportInfo::new(32, 32, 0.0, 0.0, JULIA_ATLAS_DISTANCE, 0);
+        let cell_config = Config { c: Some(c), ..config.clone() };
+        render_escape_details(&info, &cell_config).iter()
+                                                   .filter(|details| details.escape_value.is_none())
+                                                   .count()
+    };
+    assert!(in_set((0.0, 0.0)) > 100);
+    assert_eq!(in_set(c(3)), 0);
+
+    // ...and the atlas is laid out like a contact sheet, unlabeled:
+    let (atlas, width, height) = render_contact_sheet(&cells, 8, (0.0, 0.0), JULIA_ATLAS_DISTANCE,
+                                                      false, CONTACT_SHEET_EMPTY_COLOR);
+    assert_eq!((width, height), (32, 32));
+    let mut cell = vec![0u32; 8 * 8];
+    render_into_buffer(&mut cell, &WindowAndViewportInfo::new(8, 8, 0.0, 0.0, JULIA_ATLAS_DISTANCE, 0), &cells[5].1);
+    let (top, left) = (8, 8);
+    assert!((0..8).all(|row| atlas[(top + row) * width + left..][..8] == cell[row * 8..][..8]));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_upscale_bilinear() {
+    println!();
+    println!("Testing the upscale_bilinear() function (for --preview-scale):");
+    // A solid image should stay solid:
+    let mut destination = vec![0u32; 8 * 6];
+    upscale_bilinear(&[0x12_34_56; 4], 2, 2, &mut destination, 8, 6);
+    assert!(destination.iter().all(|&pixel| pixel == 0x12_34_56));
+
+    // Scaling to the same size should change nothing:
+    let source: Vec<u32> = (0..12).map(|i| rgb_to_u32(i * 20, 255 - i * 20, i)).collect();
+    let mut destination = vec![0u32; 12];
+    upscale_bilinear(&source, 4, 3, &mut destination, 4, 3);
+    assert_eq!(destination, source);
+
+    // Stretching black-to-white should give a smooth ramp,
+    // with the ends staying black and white:
+    let mut destination = vec![0u32; 8];
+    upscale_bilinear(&[0x00_00_00, 0xff_ff_ff], 2, 1, &mut destination, 8, 1);
+    let blues: Vec<u32> = destination.iter().map(|pixel| pixel & 0xff).collect();
+    println!("Black to white, stretched to 8 pixels:  {blues:?}");
+    assert_eq!((blues[0], blues[7]), (0, 255));
+    assert!(blues.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(blues.iter().any(|&blue| blue > 0 && blue < 255));
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_apply_fxaa() {
+    println!();
+    println!("Testing the apply_fxaa() function:");
+    let (width, height) = (4, 4);
+    let black = rgb_to_u32(0, 0, 0);
+    let white = rgb_to_u32(255, 255, 255);
+
+    // A flat image has no edges, so it shouldn't change at all:
+    let mut image_buffer = vec![white; width * height];
+    apply_fxaa(&mut image_buffer, width, height);
+    assert!(image_buffer.iter().all(|&color| color == white));
+
+    // A jagged black/white staircase should get some gray pixels
+    // along its edge, but its flat corners should be left alone:
+    let mut image_buffer: Vec<u32> = (0..width * height)
+                                         .map(|i| if i % width > i / width { white } else { black })
+                                         .collect();
+    apply_fxaa(&mut image_buffer, width, height);
+    for row in 0..height {
+        let row_colors: Vec<_> = image_buffer[row * width..(row + 1) * width]
+                                     .iter()
+                                     .map(|color| format!("{:06x}", color))
+                                     .collect();
+        println!("{}", row_colors.join(" "));
+    }
+    assert!(image_buffer.iter().any(|&color| color != black && color != white));
+    assert_eq!(image_buffer[width - 1], white);
+    assert_eq!(image_buffer[(height - 1) * width], black);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_apply_unsharp_mask() {
+    println!();
+    println!("Testing the apply_unsharp_mask() function (for --sharpen):");
+    let (width, height) = (6, 6);
+    let gray = rgb_to_u32(128, 128, 128);
+
+    // A flat image has nothing to sharpen, so it shouldn't change at all:
+    let mut image_buffer = vec![gray; width * height];
+    apply_unsharp_mask(&mut image_buffer, width, height, 2.0);
+    assert!(image_buffer.iter().all(|&color| color == gray));
+
+    // Along an edge between dark and light gray, the dark side should
+    // get darker and the light side lighter (but no farther than black
+    // and white), while the pixels away from the edge are left alone:
+    let (dark, light) = (rgb_to_u32(64, 64, 64), rgb_to_u32(192, 192, 192));
+    let edge: Vec<u32> = (0..width * height).map(|i| if i % width < 3 { dark } else { light }).collect();
+    for amount in [0.5, 1.0, MAX_SHARPEN] {
+        let mut image_buffer = edge.clone();
+        apply_unsharp_mask(&mut image_buffer, width, height, amount);
+        let components = |column: usize| {
+            let color = image_buffer[2 * width + column];
+            (color >> 16 & 0xff, color >> 8 & 0xff, color & 0xff)
+        };
+        println!("{amount}:  {:?}", (0..width).map(|column| components(column).0).collect::<Vec<_>>());
+        assert_eq!((components(0), components(5)), ((64, 64, 64), (192, 192, 192)));
+        assert!(components(2).0 < 64 && components(3).0 > 192);
+        assert!(components(2).0 == components(2).1 && components(2).1 == components(2).2);
+    }
+    let mut image_buffer = edge.clone();
+    apply_unsharp_mask(&mut image_buffer, width, height, MAX_SHARPEN);
+    assert_eq!((image_buffer[2], image_buffer[3]), (rgb_to_u32(0, 0, 0), rgb_to_u32(255, 255, 255)));
+
+    // ...and it's part of the post-processing, but only with --sharpen:
+    let escape_details = vec![EscapeDetails::default(); width * height];
+    let mut config = Config::new();
+    let mut image_buffer = edge.clone();
+    post_process_image(&escape_details, &mut image_buffer, width, height, &config);
+    assert_eq!(image_buffer, edge);
+    config.sharpen = 1.0;
+    post_process_image(&escape_details, &mut image_buffer, width, height, &config);
+    assert_ne!(image_buffer, edge);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_config_file() {
+    println!();
+    println!("Testing the ConfigFile settings:");
+    let text = "size = 300\ncenter = [-0.745, 0.1]\ndistance = 0.01\ncoloring = \"boundary\"\n";
+    let config_file: ConfigFile = toml::from_str(text).unwrap();
+    println!("{:?}", config_file);
+    let (mut config, mut program_config) = (Config::new(), ProgramConfig::new());
+    assert_eq!(config_file.apply_to(&mut config, &mut program_config), Ok(()));
+    assert_eq!(program_config.window_size, 300);
+    assert_eq!(program_config.center, (-0.745, 0.1));
+    assert_eq!(program_config.distance_from_center_to_edge, 0.01);
+    assert!(config.coloring == Coloring::Boundary);
+
+    // Bad values should be reported the same way bad switches are:
+    for bad_text in ["size = 0", "distance = -1.0", "coloring = \"plaid\"", "sise = 300", "center = [1, ",
+                     "center = [inf, nan]", "julia = [nan, 0.0]"] {
+        let result = toml::from_str::<ConfigFile>(bad_text)
+                         .map_err(|error| error.to_string())
+                         .and_then(|config_file| config_file.apply_to(&mut Config::new(), &mut ProgramConfig::new()));
+        println!("{:?}: {:?}", bad_text, result);
+        assert!(result.is_err());
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_command_line_script() {
+    println!();
+    println!("Testing the saved command line (the B key):");
+    let mut config = Config::new();
+    config.c = Some((-0.8, 0.156));
+    config.bailout = Some(1000);
+    config.invert = true;
+    config.color_period = 45;
+    let info = WindowAndViewportInfo::new(300, 300, 0.125, -1e-7, 0.001953125, 9);
+    let switches = command_line_switches(&info, &config);
+    println!("{switches:?}");
+    assert_eq!(switches, ["--size=300", "--center=0.125,-1e-7", "--distance=0.001953125", "--bailout=1000",
+                          "--julia=-0.8,0.156", "--coloring=escape-time", "--interior-coloring=solid",
+                          "--invert", "--color-period=45"]);
+    // (Default settings are left out, and boundary colors are only
+    // given for --coloring=boundary.)
+    let switches = command_line_switches(&info, &Config { coloring: Coloring::Boundary, ..Config::new() });
+    assert!(!switches.iter().any(|switch| switch.starts_with("--bailout") || switch.starts_with("--julia")));
+    assert!(switches.contains(&"--boundary-color=0,0,0".to_string()));
+
+    // The program's path should be quoted when it needs to be:
+    let switches = ["--size=300".to_string(), "--center=-0.5,0".to_string()];
+    let text = command_line_script_text("/usr/bin/jlr-mandelbrot", &switches, "now", false);
+    print!("{text}");
+    assert_eq!(text, "#!/bin/sh\n# The JLR-Mandelbrot view saved now.\n/usr/bin/jlr-mandelbrot --size=300 --center=-0.5,0 \"$@\"\n");
+    let text = command_line_script_text("/home/jl's files/jlr-mandelbrot", &switches, "now", false);
+    assert!(text.contains("'/home/jl'\\''s files/jlr-mandelbrot' --size=300"));
+    let text = command_line_script_text("C:\\My Programs\\jlr-mandelbrot.exe", &switches, "now", true);
+    print!("{text}");
+    assert!(text.starts_with("@echo off\r\n") && text.ends_with("\"C:\\My Programs\\jlr-mandelbrot.exe\" --size=300 --center=-0.5,0 %*\r\n"));
+
+    // And the shell should run it with exactly those switches
+    // (using echo in place of the program, to see what it gets):
+    if cfg!(unix) {
+        let filename = std::env::temp_dir().join(format!("jlr-mandelbrot-test.{}.sh", std::process::id()));
+        std::fs::write(&filename, command_line_script_text("echo", &switches, "now", false)).unwrap();
+        let output = std::process::Command::new("sh").arg(&filename).arg("--quiet").output().unwrap();
+        std::fs::remove_file(&filename).unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "--size=300 --center=-0.5,0 --quiet\n");
+    }
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_screenshot_sidecar() {
+    println!();
+    println!("Testing the screenshot sidecar file (--sidecar):");
+    let mut config = Config::new();
+    config.c = Some((-0.8, 0.156));
+    config.bailout = Some(1000);
+    config.coloring = Coloring::Boundary;
+    config.boundary_color = (255, 255, 0);
+    config.invert = true;
+    config.color_period = 45;
+    config.palette = Some(Palette::random(42));
+    config.float_type = FloatType::F32;
+    let info = WindowAndViewportInfo::new(300, 300, 0.125, -1e-7, 0.001953125, 9)
+                   .with_projection(Projection::LogPolar);
+    let text = screenshot_settings_text("tour.png", &info, &config, "2026-10-15 12:00:00 UTC");
+    print!("{text}");
+    assert!(text.contains("zoom level 9") && text.contains("2026-10-15 12:00:00 UTC") && text.contains("Julia"));
+
+    // It should be a valid config file that gives back the same settings:
+    let (mut loaded_config, mut loaded_program_config) = (Config::new(), ProgramConfig::new());
+    assert_eq!(toml::from_str::<ConfigFile>(&text).map_err(|error| error.to_string())
+                   .and_then(|config_file| config_file.apply_to(&mut loaded_config, &mut loaded_program_config)),
+               Ok(()));
+    assert_eq!(loaded_program_config.window_size, info.width);
+    assert_eq!(loaded_program_config.center, (info.center_x, info.center_y));
+    assert_eq!(loaded_program_config.distance_from_center_to_edge, info.distance_from_center_to_edge);
+    assert_eq!(loaded_config.c, config.c);
+    assert_eq!(loaded_config.bailout, config.bailout);
+    assert_eq!((loaded_config.coloring, loaded_config.interior_coloring, loaded_config.invert),
+               (config.coloring, config.interior_coloring, config.invert));
+    assert_eq!(loaded_config.color_period, config.color_period);
+    assert_eq!(loaded_config.palette, config.palette);
+    assert_eq!(loaded_config.float_type, config.float_type);
+    assert_eq!(loaded_config.projection, info.projection);
+    assert_eq!((loaded_config.boundary_color, loaded_config.boundary_background_color),
+               (config.boundary_color, config.boundary_background_color));
+
+    // And the Mandelbrot set (with no bailout) should work, too:
+    let text = screenshot_settings_text("m.png", &info, &Config::new(), "now");
+    assert!(!text.contains("bailout") && !text.contains("julia"));
+    assert!(toml::from_str::<ConfigFile>(&text).is_ok());
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_deep_color_screenshot() {
+    println!();
+    println!("Testing 16-bit screenshots (--depth=16):");
+    let info = WindowAndViewportInfo::new(32, 32, -0.745, 0.1, 0.01, 0);
+    let mut config = Config::new();
+    config.coloring = Coloring::Potential;
+    config.bailout = Some(500);
+    let escape_details = render_escape_details(&info, &config);
+    let mut image_buffer = vec![0u32; 32 * 32];
+    color_escape_details(&escape_details, &mut image_buffer, &info, &config);
+
+    // The 16-bit colors should round down to the 8-bit ones, and (being
+    // smooth colors) plenty of them shouldn't be a multiple of 257.
+    // (Every palette color has a channel of 0, so at most two thirds can be.)
+    let pixels = deep_color_pixels(&image_buffer, &escape_details, &config);
+    assert_eq!(pixels.len(), 32 * 32 * 3);
+    for (&pixel, deep_pixel) in image_buffer.iter().zip(pixels.chunks_exact(3)) {
+        let channels = [pixel >> 16 & 0xff, pixel >> 8 & 0xff, pixel & 0xff];
+        for (channel, &deep_channel) in channels.iter().zip(deep_pixel) {
+            assert!((0.0..=1.0).contains(&(deep_channel as Float / 257.0 - *channel as Float)),
+                    "{deep_channel} isn't {channel} scaled up to 16 bits");
+        }
+    }
+    let smooth_values = pixels.iter().filter(|&&value| value % 257 != 0).count();
+    println!("{smooth_values} of the {} 16-bit values are between the 8-bit ones.", pixels.len());
+    assert!(smooth_values > pixels.len() / 4);
+
+    // Pixels that aren't the color of their escape details (like
+    // under an overlay) should keep their 8-bit colors:
+    let mut covered_buffer = image_buffer.clone();
+    covered_buffer[0] = 0xff_ff_ff;
+    let covered_pixels = deep_color_pixels(&covered_buffer, &escape_details, &config);
+    assert_eq!(covered_pixels[..3], [65535, 65535, 65535]);
+    assert_eq!(covered_pixels[3..], pixels[3..]);
+
+    // The saved PNG should have the same 16-bit colors (and settings),
+    // and other kinds of files should be refused:
+    let program_config = ProgramConfig { screenshot_bit_depth: 16, ..ProgramConfig::new() };
+    let filename = std::env::temp_dir().join(format!("jlr-mandelbrot-test.{}.png", std::process::id()));
+    let filename = filename.to_str().unwrap();
+    save_screenshot_to_filename(&image_buffer, &escape_details, &info, &config, &program_config, filename).unwrap();
+    let png = std::fs::read(filename).unwrap();
+    std::fs::remove_file(filename).unwrap();
+    let decoded = image::load_from_memory(&png).unwrap();
+    assert_eq!(decoded.color(), image::ColorType::Rgb16);
+    assert_eq!(decoded.to_rgb16().into_raw(), pixels);
+    assert!(read_png_text(&png, PNG_SETTINGS_KEYWORD).is_some_and(|settings| settings.contains("potential")));
+    let jpeg_filename = filename.replace(".png", ".jpg");
+    let result = save_screenshot_to_filename(&image_buffer, &escape_details, &info, &config, &program_config, &jpeg_filename);
+    println!("{result:?}");
+    assert!(result.is_err_and(|message| message.contains("--depth=16")));
+    assert!(!std::path::Path::new(&jpeg_filename).exists());
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_supersampled_screenshot() {
+    println!();
+    println!("Testing screenshots drawn again with anti-aliasing (--screenshot-ssaa):");
+    let info = WindowAndViewportInfo::new(24, 24, -0.75, 0.1, 0.05, 0);
+    let config = Config { bailout: Some(500), ..Config::new() };
+    let (image_buffer, escape_details, supersampled_config) = supersampled_screenshot(&info, &config, 8);
+    // It should be drawn just like the whole image would be
+    // with --aa-samples (and saved with that setting):
+    assert_eq!(supersampled_config.aa_samples, Some(8));
+    assert_eq!(supersampled_config.bailout, config.bailout);
+    let mut anti_aliased = vec![0; info.width * info.height];
+    render_into_buffer(&mut anti_aliased, &info, &supersampled_config);
+    assert_eq!(image_buffer, anti_aliased);
+    // ...with the same escape details (at the centers of the pixels)
+    // as without it, but some of the colors blended:
+    let threshold = cycle_threshold(&info, &config);
+    let plain_escape_details = calculate_pixel_details_in_parallel(&all_pixels(info.width, info.height),
+                                                                   &info, &config, threshold);
+    assert_eq!(escape_details, plain_escape_details);
+    let mut plain = vec![0; info.width * info.height];
+    render_into_buffer(&mut plain, &info, &config);
+    let blended_pixels = plain.iter().zip(&image_buffer).filter(|(a, b)| a != b).count();
+    println!("{blended_pixels} of the {} pixels were blended.", plain.len());
+    assert!(blended_pixels > 0);
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_png_settings() {
+    println!();
+    println!("Testing the settings stored in PNG screenshots:");
+    assert_eq!(crc32(b"123456789"), 0xcbf4_3926);  // (The standard check value.)
+
+    // Store some settings (with a non-ASCII filename) in a small PNG:
+    let (width, height) = (4, 3);
+    let pixels: Vec<u8> = (0..width * height * 3).map(|i| (i * 7) as u8).collect();
+    let mut png = Vec::new();
+    image::png::PngEncoder::new(&mut png)
+        .encode(&pixels, width as u32, height as u32, image::ColorType::Rgb8).unwrap();
+    assert_eq!(read_png_text(&png, PNG_SETTINGS_KEYWORD), None);
+    let mut config = Config::new();
+    config.bailout = Some(500);
+    let info = WindowAndViewportInfo::new(width, height, -0.745, 0.1, 0.01, 7);
+    let settings = screenshot_settings_text("\u{e9}t\u{e9}.png", &info, &config, "now");
+    let png_with_settings = embed_png_text(&png, PNG_SETTINGS_KEYWORD, &settings);
+
+    // The image itself should be unchanged (and still a valid PNG),
+    // and the settings should come back out the same:
+    let decoded = image::load_from_memory(&png_with_settings).unwrap().to_rgb8();
+    assert_eq!(decoded.into_raw(), pixels);
+    assert_eq!(read_png_text(&png_with_settings, PNG_SETTINGS_KEYWORD), Some(settings.clone()));
+    assert_eq!(read_png_text(&png_with_settings, "some-other-keyword"), None);
+    assert_eq!(read_png_text(b"not a PNG at all", PNG_SETTINGS_KEYWORD), None);
+    assert_eq!(read_png_text(&png_with_settings[..40], PNG_SETTINGS_KEYWORD), None);  // (A cut-off file.)
+
+    // And the PNG should work as a --config file:
+    let filename = std::env::temp_dir().join(format!("jlr-mandelbrot-test.{}.png", std::process::id()));
+    std::fs::write(&filename, &png_with_settings).unwrap();
+    let loaded = ConfigFile::load(filename.to_str().unwrap());
+    std::fs::write(&filename, &png).unwrap();
+    let loaded_without_settings = ConfigFile::load(filename.to_str().unwrap());
+    std::fs::remove_file(&filename).unwrap();
+    println!("{loaded:?}");
+    println!("{loaded_without_settings:?}");
+    let loaded = loaded.unwrap();
+    assert_eq!((loaded.center, loaded.distance, loaded.bailout), (Some((-0.745, 0.1)), Some(0.01), Some(500)));
+    assert!(loaded_without_settings.is_err());
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_environment_variables() {
+    println!();
+    println!("Testing the JLR_MANDELBROT_SIZE and JLR_MANDELBROT_BAILOUT environment variables:");
+    let apply_with_args = |size: &str, bailout: &str, args: &[&str]| {
+        let (mut config, mut program_config, mut auto_bailout) = (Config::new(), ProgramConfig::new(), false);
+        let lookup = |name: &str| match name {
+            "JLR_MANDELBROT_SIZE" => Some(size.to_string()),
+            "JLR_MANDELBROT_BAILOUT" => Some(bailout.to_string()),
+            _ => None,
+        };
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        apply_environment_variables((&mut config, &mut program_config, &mut auto_bailout), &args, lookup)
+            .map(|()| (program_config.window_size, config.bailout, auto_bailout))
+    };
+    assert_eq!(parse_bailout("500"), Ok(Some(500)));
+    assert_eq!(parse_bailout("auto"), Ok(None));
+    assert!(parse_bailout("-3").is_err_and(|message| message.contains("or \"auto\"")));
+    let apply = |size: &str, bailout: &str| apply_with_args(size, bailout, &[]);
+    assert_eq!(apply("300", "500"), Ok((300, Some(500), false)));
+    assert_eq!(apply("", ""), Ok((DEFAULT_WINDOW_SIZE, Config::new().bailout, false)));  // (Set to nothing.)
+    assert_eq!(apply("", "auto"), Ok((DEFAULT_WINDOW_SIZE, None, true)));
+    for (size, bailout) in [("0", ""), ("big", ""), ("", "-5"), ("", "lots")] {
+        let result = apply(size, bailout);
+        println!("JLR_MANDELBROT_SIZE={size:?}, JLR_MANDELBROT_BAILOUT={bailout:?}:  {result:?}");
+        assert!(result.is_err());
+    }
+    // (The bailout's error should be the same as --bailout's.)
+    assert_eq!(apply("", "lots").unwrap_err(),
+               format!("{}  (It came from the JLR_MANDELBROT_BAILOUT environment variable.)", parse_bailout("lots").unwrap_err()));
+
+    // A variable whose switch is on the command line isn't even checked,
+    // as the switch overrides it, but one after "--" doesn't count:
+    assert_eq!(apply_with_args("abc", "-3", &["--size=8", "--bailout=5"]), Ok((DEFAULT_WINDOW_SIZE, None, false)));
+    assert_eq!(apply_with_args("300", "-3", &["--bailout=5"]), Ok((300, None, false)));
+    assert!(apply_with_args("abc", "", &["--", "--size=8"]).is_err());
+    println!();
+}
+
+
+#[allow(dead_code)]
+fn test_all() {
+    println!();
+    test_color_function();
+    println!();
+    test_color_function_over_a_large_range();
+    test_inverted_palette();
+    test_palette_ignores_bailout();
+    test_color_period();
+    test_color_cycle();
+    test_float_benchmark();
+    test_diff();
+    test_deepzoom();
+    test_npy_bytes();
+    test_heightmap();
+    test_stdin_coords();
+    test_float_type();
+    test_projection();
+    println!();
+    test_calculate_escape_value_function();
+    test_julia_and_mandelbrot_modes();
+    test_next_fractal();
+    test_window_title();
+    test_nudged_center();
+    test_bookmarks();
+    println!();
+    test_period_detection();
+    test_cycle_threshold();
+    test_no_cycle_detection_speed();
+    test_three_multiply_square();
+    println!();
+    test_row_and_column_iterator();
+    test_swirling_pixels();
+    test_swirl_pause();
+    test_cancel_flag();
+    println!();
+    test_parse_script_line();
+    test_relative_script_commands();
+    test_parse_coordinate_pair();
+    test_palette_registry();
+    test_random_palette();
+    test_sample_palette();
+    test_locations();
+    test_version_text();
+    test_changes_the_image();
+    test_mouse_info();
+    test_next_user_input();
+    test_keyboard_zoom_target();
+    test_zoom_reversibility();
+    test_crop();
+    println!();
+    test_parse_window_size();
+    test_format_coordinates();
+    test_orbit();
+    test_tricorn();
+    test_norms();
+    test_non_finite_coordinates();
+    test_max_iterations();
+    test_unbounded_point();
+    test_orbit_overlay();
+    test_external_rays();
+    test_potential_coloring();
+    test_pixel_info();
+    test_render_stats();
+    test_diagnostics();
+    test_shown_image();
+    test_frame_budget();
+    test_adjusted_bailout();
+    test_iteration_stats();
+    test_adjusted_aa_samples();
+    test_minimap();
+    test_jittered_antialiasing();
+    test_dithering();
+    test_render_profile();
+    test_bailout_heatmap();
+    test_render_into_buffer();
+    test_chunked_rendering();
+    test_fast_fill();
+    test_progressive_refinement();
+    test_render_progress_and_cancel();
+    test_apply_fxaa();
+    test_apply_unsharp_mask();
+    test_upscale_bilinear();
+    test_contact_sheet();
+    test_julia_atlas();
+    test_smooth_labels();
+    test_auto_zoom();
+    test_config_watch();
+    test_deep_color_screenshot();
+    test_supersampled_screenshot();
+    test_degenerate_viewports();
+    test_config_file();
+    test_screenshot_sidecar();
+    test_command_line_script();
+    test_png_settings();
+    test_environment_variables();
+    println!();
+}
+
+
+fn main() {
+    // These are "practically global" variables, in that
+    // they're used (and sometimes changed) all throughout
+    // the main() function:
+    let mut config = Config::new();
+    let mut program_config = ProgramConfig::new();
+    let mut overlays = Overlays::new();
+    let mut coordinate_format = CoordinateFormat::Decimal;
+    let mut progressive = false;
+    let mut preview_scale: usize = 1;
+    let mut contact_sheet: Option<usize> = None;  // (The grid size, for Julia sets.)
+    let mut julia_atlas: Option<usize> = None;  // (The grid size.)
+    let mut crop_rectangle: Option<CropRectangle> = None;  // (With --crop.)
+    let mut float_bench = false;
+    let mut diff_config_path: Option<String> = None;  // (The config file to compare with, for --diff.)
+    let mut deepzoom_path: Option<String> = None;
+    let mut deepzoom_tile_size = DEFAULT_DEEPZOOM_TILE_SIZE;
+    let mut deepzoom_levels = DEFAULT_DEEPZOOM_LEVELS;
+    let mut npy_path: Option<String> = None;  // (With --export-npy.)
+    let mut heightmap_path: Option<String> = None;  // (With --export-heightmap.)
+    let mut stats_path: Option<String> = None;  // (With --stats.)
+    let mut heightmap_step: usize = 1;  // (Every how many pixels an OBJ heightmap has a vertex.)
+    let mut double_buffer = false;
+    let mut frame_budget: Option<FrameBudget> = None;  // (Only with --frame-budget-ms.)
+    let mut stdin_coords = false;
+    let mut auto_zoom_delay: Option<std::time::Duration> = None;  // (Only with --auto-zoom.)
+    let mut color_cycling = false;
+    let mut auto_bailout = false;  // (With --bailout=auto.)
+    let mut notify = false;
+    let mut background_color: Option<(u8, u8, u8)> = None;  // (With --background.)
+    let mut title: Option<String> = None;  // (With --title; otherwise, it's the fractal's name.)
+    let mut watch = false;
+    let config_path: Option<String>;  // (The --config=PATH, for --watch.)
 
     // Parse command-line arguments:
     {
         let args: Vec<String> = std::env::args().skip(1).collect();
+
+        // The environment variables give the defaults that everything
+        // else (the config file and the other switches) overrides:
+        if let Err(message) = apply_environment_variables((&mut config, &mut program_config, &mut auto_bailout), &args,
+                                                          |name| std::env::var(name).ok()) {
+            eprintln!("Error:  {message}");
+            std::process::exit(EXIT_USAGE_ERROR)
+        }
+
+        // If a config file was given, apply its settings first,
+        // so that the other command-line switches override them:
+        let config_filename = args.iter()
+                                  .take_while(|arg| *arg != "--")
+                                  .filter_map(|arg| arg.strip_prefix("--config="))
+                                  .last();
+        let mut center_was_given = false;  // (So --julia knows not to change it.)
+        config_path = config_filename.map(str::to_string);
+        if let Some(config_filename) = config_filename {
+            let result = ConfigFile::load(config_filename)
+                             .and_then(|config_file| {
+                                 center_was_given = config_file.center.is_some();
+                                 config_file.apply_to(&mut config, &mut program_config)
+                             });
+            if let Err(message) = result {
+                eprintln!("Error:  {message}");
+                std::process::exit(EXIT_USAGE_ERROR)
+            }
+        }
+
         let mut still_looking_for_options = true;
         for arg in args {
             if still_looking_for_options && arg == "--" {  // (The "--" option signifies the end of the options.)
                 still_looking_for_options = false;
+            } else if still_looking_for_options && arg.starts_with("--config=") {
+                ()  // (The config file was already read, above.)
+            } else if still_looking_for_options && arg == "--config" {
+                eprintln!("Error:  The --config=PATH argument seems to be missing the \"=PATH\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
             } else if still_looking_for_options && (arg == "-h" || arg == "--help") {
                 println!("{}", help_text());
                 return ()
+            } else if still_looking_for_options && (arg == "-V" || arg == "--version") {
+                print!("{}", version_text());
+                return ()
+            } else if still_looking_for_options && arg == "--list-palettes" {
+                print!("{}", palette_list_text());
+                return ()
+            } else if still_looking_for_options && arg == "--list-locations" {
+                print!("{}", location_list_text());
+                return ()
+            } else if still_looking_for_options && arg == "--list-fractals" {
+                print!("{}", fractal_list_text());
+                return ()
             } else if still_looking_for_options && arg == "--test" {
                 // --test is an undocumented option;
                 // it is only used for diagnostic purposes.
@@ -675,158 +8632,1562 @@ fn main() {
             } else if still_looking_for_options && arg.starts_with("--size=") {
                 let prefix_length = "--size=".len();
                 let size_text = &arg[prefix_length..];
-                window_size_to_use = match size_text.parse() {
+                program_config.window_size = match parse_window_size(size_text) {
                     Ok(size) => size,
+                    Err(message) => {
+                        eprintln!("Error:  {message}");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--size" {
+                eprintln!("Error:  The --size=NUMBER argument seems to be missing the \"=NUMBER\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--bailout=") {
+                let prefix_length = "--bailout=".len();
+                let bailout_text = &arg[prefix_length..];
+                config.bailout = match parse_bailout(bailout_text) {
+                    Ok(bailout) => bailout,
+                    Err(message) => {
+                        eprintln!("Error:  {message}");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+                auto_bailout = config.bailout.is_none();  // (Picked once the distance is known; see below.)
+            } else if still_looking_for_options && arg == "--bailout" {
+                eprintln!("Error:  The --bailout=NUMBER argument seems to be missing the \"=NUMBER\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--julia=") {
+                let prefix_length = "--julia=".len();
+                let julia_text = &arg[prefix_length..];
+                config.c = match parse_coordinate_pair(julia_text) {
+                    Ok(c) => Some(c),
+                    Err(message) => {
+                        eprintln!("Error:  The X,Y value in --julia=X,Y (\"{julia_text}\") is not valid:");
+                        eprintln!("        {message}");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+                if !center_was_given {
+                    program_config.center = (0.0, 0.0);  // We'll start centered for Julia sets.
+                }
+            } else if still_looking_for_options && arg == "--julia" {
+                eprintln!("Error:  The --julia=X,Y argument seems to be missing the \"=X,Y\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--center=") {
+                let prefix_length = "--center=".len();
+                let center_text = &arg[prefix_length..];
+                program_config.center = match parse_coordinate_pair(center_text) {
+                    Ok(center) => center,
+                    Err(message) => {
+                        eprintln!("Error:  The X,Y value in --center=X,Y (\"{center_text}\") is not valid:");
+                        eprintln!("        {message}");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+                center_was_given = true;
+            } else if still_looking_for_options && arg == "--center" {
+                eprintln!("Error:  The --center=X,Y argument seems to be missing the \"=X,Y\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--goto=") {
+                let prefix_length = "--goto=".len();
+                let name = &arg[prefix_length..];
+                let (center, distance, bailout) = match location(name) {
+                    Some(location) => location,
+                    None => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{name}\".");
+                        eprintln!("        (It must be {}.)", location_names());
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+                // (Any of these can still be changed by the switches after it.)
+                program_config.center = center;
+                program_config.distance_from_center_to_edge = distance;
+                config.bailout = Some(bailout);
+                center_was_given = true;
+            } else if still_looking_for_options && arg == "--goto" {
+                eprintln!("Error:  The --goto=NAME argument seems to be missing the \"=NAME\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?  See --list-locations for the NAMEs.)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--distance=") {
+                let prefix_length = "--distance=".len();
+                let distance_text = &arg[prefix_length..];
+                program_config.distance_from_center_to_edge = match distance_text.parse() {
+                    Ok(distance) => distance,
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{distance_text}\".");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+                if !program_config.distance_from_center_to_edge.is_finite() || program_config.distance_from_center_to_edge <= 0.0 {
+                    eprintln!("Error:  The NUMBER in --distance=NUMBER must be a (finite) number more than zero.");
+                    std::process::exit(EXIT_USAGE_ERROR)
+                }
+            } else if still_looking_for_options && arg == "--distance" {
+                eprintln!("Error:  The --distance=NUMBER argument seems to be missing the \"=NUMBER\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--cache-dir=") {
+                let prefix_length = "--cache-dir=".len();
+                let cache_dir = &arg[prefix_length..];
+                if cache_dir.is_empty() {
+                    eprintln!("Error:  The DIR in --cache-dir=DIR must not be empty.");
+                    std::process::exit(EXIT_USAGE_ERROR)
+                }
+                program_config.cache_dir = Some(cache_dir.to_string());
+            } else if still_looking_for_options && arg == "--cache-dir" {
+                eprintln!("Error:  The --cache-dir=DIR argument seems to be missing the \"=DIR\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--title=") {
+                let prefix_length = "--title=".len();
+                title = Some(arg[prefix_length..].to_string());
+            } else if still_looking_for_options && arg == "--title" {
+                eprintln!("Error:  The --title=STRING argument seems to be missing the \"=STRING\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg == "--dry-run" {
+                program_config.dry_run = true;
+            } else if still_looking_for_options && arg == "--float-bench" {
+                float_bench = true;
+            } else if still_looking_for_options && arg == "--stdin-coords" {
+                stdin_coords = true;
+            } else if still_looking_for_options && arg == "--double-buffer" {
+                double_buffer = true;
+            } else if still_looking_for_options && arg.starts_with("--frame-budget-ms=") {
+                let prefix_length = "--frame-budget-ms=".len();
+                let budget_text = &arg[prefix_length..];
+                frame_budget = match budget_text.parse() {
+                    Ok(milliseconds) if milliseconds > 0 => Some(FrameBudget::new(milliseconds)),
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{budget_text}\".");
+                        eprintln!("        (It must be a whole number of milliseconds, more than zero.)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--frame-budget-ms" {
+                eprintln!("Error:  The --frame-budget-ms=T argument seems to be missing the \"=T\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--diff=") {
+                let prefix_length = "--diff=".len();
+                diff_config_path = Some(arg[prefix_length..].to_string());
+            } else if still_looking_for_options && arg == "--diff" {
+                eprintln!("Error:  The --diff=PATH argument seems to be missing the \"=PATH\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--bands=") {
+                let prefix_length = "--bands=".len();
+                let bands_text = &arg[prefix_length..];
+                config.number_of_bands = match bands_text.parse() {
+                    Ok(bands) => Some(bands),
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{bands_text}\".");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+                if config.number_of_bands == Some(0) {
+                    eprintln!("Error:  The NUMBER in --bands=NUMBER must be more than zero.");
+                    std::process::exit(EXIT_USAGE_ERROR)
+                }
+            } else if still_looking_for_options && arg == "--fast-fill" {
+                config.fast_fill_min_size = config.fast_fill_min_size.or(Some(DEFAULT_FAST_FILL_MIN_SIZE));
+            } else if still_looking_for_options && arg.starts_with("--fast-fill-min=") {
+                let prefix_length = "--fast-fill-min=".len();
+                let min_size_text = &arg[prefix_length..];
+                config.fast_fill_min_size = match min_size_text.parse() {
+                    Ok(min_size) if min_size > 0 => Some(min_size),
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{min_size_text}\".");
+                        eprintln!("        (It must be a number of pixels, more than zero.)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--fast-fill-min" {
+                eprintln!("Error:  The --fast-fill-min=PIXELS argument seems to be missing the \"=PIXELS\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg == "--bands" {
+                eprintln!("Error:  The --bands=NUMBER argument seems to be missing the \"=NUMBER\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--script=") {
+                let prefix_length = "--script=".len();
+                let filename = &arg[prefix_length..];
+                if filename.is_empty() {
+                    eprintln!("Error:  The FILE in --script=FILE must not be empty.");
+                    std::process::exit(EXIT_USAGE_ERROR)
+                }
+                program_config.script_filename = Some(filename.to_string());
+            } else if still_looking_for_options && arg == "--script" {
+                eprintln!("Error:  The --script=FILE argument seems to be missing the \"=FILE\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--format=") {
+                let prefix_length = "--format=".len();
+                let format_text = &arg[prefix_length..];
+                if format_text != "ppm" {
+                    eprintln!("Error:  Unsupported format in {arg}.  (Only \"ppm\" is supported.)");
+                    std::process::exit(EXIT_USAGE_ERROR)
+                }
+                program_config.write_ppm_to_stdout = true;
+                program_config.quiet = true;  // (So that nothing but the image is written to stdout.)
+            } else if still_looking_for_options && arg == "--format" {
+                eprintln!("Error:  The --format=ppm argument seems to be missing the \"=ppm\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--interior-coloring=") {
+                let prefix_length = "--interior-coloring=".len();
+                let coloring_text = &arg[prefix_length..];
+                config.interior_coloring = match InteriorColoring::from_name(coloring_text) {
+                    Some(interior_coloring) => interior_coloring,
+                    None => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{coloring_text}\".");
+                        eprintln!("        (It must be {}.)", quoted_names(InteriorColoring::ALL));
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--interior-coloring" {
+                eprintln!("Error:  The --interior-coloring=TYPE argument seems to be missing the \"=TYPE\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--coloring=") {
+                let prefix_length = "--coloring=".len();
+                let coloring_text = &arg[prefix_length..];
+                config.coloring = match Coloring::from_name(coloring_text) {
+                    Some(coloring) => coloring,
+                    None => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{coloring_text}\".");
+                        eprintln!("        (It must be {}.)", quoted_names(Coloring::ALL));
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg.starts_with("--antialias=") {
+                let prefix_length = "--antialias=".len();
+                let antialias_text = &arg[prefix_length..];
+                config.antialiasing = match Antialiasing::from_name(antialias_text) {
+                    Some(antialiasing) => antialiasing,
+                    None => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{antialias_text}\".");
+                        eprintln!("        (It must be \"none\" or \"fxaa\".)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg.starts_with("--sharpen=") {
+                let prefix_length = "--sharpen=".len();
+                let amount_text = &arg[prefix_length..];
+                config.sharpen = match amount_text.parse::<Float>() {
+                    Ok(amount) if (0.0..=MAX_SHARPEN).contains(&amount) => amount,
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{amount_text}\".");
+                        eprintln!("        (It must be a number from 0 to {MAX_SHARPEN}.)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--sharpen" {
+                eprintln!("Error:  The --sharpen=AMOUNT argument seems to be missing the \"=AMOUNT\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--fractal=") {
+                let prefix_length = "--fractal=".len();
+                let fractal_text = &arg[prefix_length..];
+                config.formula = match Formula::from_name(fractal_text) {
+                    Some(formula) => formula,
+                    None if fractal_text == "julia" => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{fractal_text}\".");
+                        eprintln!("        (For a Julia set, use --julia=X,Y instead.)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                    None => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{fractal_text}\".");
+                        eprintln!("        (It must be \"mandelbrot\" or \"tricorn\".)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg.starts_with("--norm=") {
+                let prefix_length = "--norm=".len();
+                let norm_text = &arg[prefix_length..];
+                config.norm = match Norm::from_name(norm_text) {
+                    Some(norm) => norm,
+                    None => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{norm_text}\".");
+                        eprintln!("        (It must be \"l2\", \"max\", or \"l1\".)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--norm" {
+                eprintln!("Error:  The --norm=NAME argument seems to be missing the \"=NAME\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg == "--fractal" {
+                eprintln!("Error:  The --fractal=NAME argument seems to be missing the \"=NAME\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--float=") {
+                let prefix_length = "--float=".len();
+                let float_text = &arg[prefix_length..];
+                config.float_type = match FloatType::from_name(float_text) {
+                    Some(float_type) => float_type,
+                    None => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{float_text}\".");
+                        eprintln!("        (It must be \"f32\" or \"f64\".)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--float" {
+                eprintln!("Error:  The --float=TYPE argument seems to be missing the \"=TYPE\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--projection=") {
+                let prefix_length = "--projection=".len();
+                let projection_text = &arg[prefix_length..];
+                config.projection = match Projection::from_name(projection_text) {
+                    Some(projection) => projection,
+                    None => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{projection_text}\".");
+                        eprintln!("        (It must be \"rectangular\" or \"logpolar\".)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--projection" {
+                eprintln!("Error:  The --projection=TYPE argument seems to be missing the \"=TYPE\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--aa-samples=") {
+                let prefix_length = "--aa-samples=".len();
+                let samples_text = &arg[prefix_length..];
+                config.aa_samples = match samples_text.parse() {
+                    Ok(samples) => Some(samples),
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{samples_text}\".");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+                if config.aa_samples == Some(0) {
+                    eprintln!("Error:  The NUMBER in --aa-samples=NUMBER must be more than zero.");
+                    std::process::exit(EXIT_USAGE_ERROR)
+                }
+            } else if still_looking_for_options && arg == "--aa-samples" {
+                eprintln!("Error:  The --aa-samples=NUMBER argument seems to be missing the \"=NUMBER\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--screenshot-ssaa=") {
+                let prefix_length = "--screenshot-ssaa=".len();
+                let samples_text = &arg[prefix_length..];
+                program_config.screenshot_aa_samples = match samples_text.parse() {
+                    Ok(samples) if samples > 0 => Some(samples),
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{samples_text}\".");
+                        eprintln!("        (It must be a number more than zero.)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--screenshot-ssaa" {
+                eprintln!("Error:  The --screenshot-ssaa=NUMBER argument seems to be missing the \"=NUMBER\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--aa-seed=") {
+                let prefix_length = "--aa-seed=".len();
+                let seed_text = &arg[prefix_length..];
+                config.aa_seed = match seed_text.parse() {
+                    Ok(seed) => seed,
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{seed_text}\".");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--aa-seed" {
+                eprintln!("Error:  The --aa-seed=NUMBER argument seems to be missing the \"=NUMBER\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg == "--antialias" {
+                eprintln!("Error:  The --antialias=TYPE argument seems to be missing the \"=TYPE\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg == "--coloring" {
+                eprintln!("Error:  The --coloring=TYPE argument seems to be missing the \"=TYPE\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--background=") {
+                let prefix_length = "--background=".len();
+                background_color = match parse_rgb_triplet(&arg[prefix_length..]) {
+                    Ok(color) => Some(color),
+                    Err(message) => {
+                        eprintln!("Error:  {message}");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--background" {
+                eprintln!("Error:  The --background=R,G,B argument seems to be missing the \"=R,G,B\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && (arg.starts_with("--boundary-color=")
+                                                    || arg.starts_with("--boundary-background=")) {
+                let (name, color_text) = arg.split_once("=").unwrap();
+                let color = match parse_rgb_triplet(color_text) {
+                    Ok(color) => color,
+                    Err(message) => {
+                        eprintln!("Error:  {message}");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+                if name == "--boundary-color" {
+                    config.boundary_color = color;
+                } else {
+                    config.boundary_background_color = color;
+                }
+            } else if still_looking_for_options && (arg == "--boundary-color" || arg == "--boundary-background") {
+                eprintln!("Error:  The {arg}=R,G,B argument seems to be missing the \"=R,G,B\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg == "--quiet" {
+                program_config.quiet = true;
+            } else if still_looking_for_options && arg == "--notify" {
+                notify = true;
+            } else if still_looking_for_options && arg.starts_with("--cycle-threshold=") {
+                let prefix_length = "--cycle-threshold=".len();
+                let factor_text = &arg[prefix_length..];
+                config.cycle_threshold_factor = match factor_text.parse::<Float>() {
+                    Ok(factor) if factor.is_finite() && factor > 0.0 => factor,
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{factor_text}\".");
+                        eprintln!("        (It must be a number more than zero.)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--cycle-threshold" {
+                eprintln!("Error:  The --cycle-threshold=FACTOR argument seems to be missing the \"=FACTOR\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg == "--no-cycle-detect" {
+                config.cycle_detection = false;
+            } else if still_looking_for_options && arg.starts_with("--max-iterations=") {
+                let prefix_length = "--max-iterations=".len();
+                let max_iterations_text = &arg[prefix_length..];
+                config.max_iterations = match max_iterations_text.parse() {
+                    Ok(max_iterations) if max_iterations > 0 => Some(max_iterations),
+                    _ if max_iterations_text == "none" => None,
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{max_iterations_text}\".");
+                        eprintln!("        (It must be a number more than zero, or \"none\".)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--max-iterations" {
+                eprintln!("Error:  The --max-iterations=NUMBER argument seems to be missing the \"=NUMBER\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg == "--invert" {
+                config.invert = true;
+            } else if still_looking_for_options && arg == "--color-cycle" {
+                color_cycling = true;
+            } else if still_looking_for_options && arg.starts_with("--color-period=") {
+                let prefix_length = "--color-period=".len();
+                let color_period_text = &arg[prefix_length..];
+                config.color_period = match parse_color_period(color_period_text) {
+                    Ok(color_period) => color_period,
+                    Err(message) => {
+                        eprintln!("Error:  {message}");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--color-period" {
+                eprintln!("Error:  The --color-period=NUMBER argument seems to be missing the \"=NUMBER\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--palette=") {
+                let prefix_length = "--palette=".len();
+                let palette_text = &arg[prefix_length..];
+                config.palette = match parse_palette_name(palette_text, config.palette.take()) {
+                    Ok(palette) => palette,
+                    Err(message) => {
+                        eprintln!("Error:  {message}");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--palette" {
+                eprintln!("Error:  The --palette=NAME argument seems to be missing the \"=NAME\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--palette-seed=") {
+                let prefix_length = "--palette-seed=".len();
+                let seed_text = &arg[prefix_length..];
+                config.palette = match seed_text.parse() {
+                    Ok(seed) => Some(Palette::random(seed)),
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{seed_text}\".");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--palette-seed" {
+                eprintln!("Error:  The --palette-seed=NUMBER argument seems to be missing the \"=NUMBER\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--max-threads=") {
+                let prefix_length = "--max-threads=".len();
+                let threads_text = &arg[prefix_length..];
+                config.max_threads = match threads_text.parse() {
+                    Ok(max_threads) if max_threads > 0 => Some(max_threads),
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{threads_text}\".");
+                        eprintln!("        (It must be a number more than zero.)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--max-threads" {
+                eprintln!("Error:  The --max-threads=NUMBER argument seems to be missing the \"=NUMBER\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--preview-scale=") {
+                let prefix_length = "--preview-scale=".len();
+                let scale_text = &arg[prefix_length..];
+                preview_scale = match scale_text.parse() {
+                    Ok(scale) if scale > 0 => scale,
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{scale_text}\".");
+                        eprintln!("        (It must be a number more than zero.)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--preview-scale" {
+                eprintln!("Error:  The --preview-scale=NUMBER argument seems to be missing the \"=NUMBER\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--deepzoom=") {
+                let prefix_length = "--deepzoom=".len();
+                let path = &arg[prefix_length..];
+                // (The .dzi is added back on; see save_deepzoom().)
+                deepzoom_path = Some(path.strip_suffix(".dzi").unwrap_or(path).to_string());
+            } else if still_looking_for_options && arg.starts_with("--tile-size=") {
+                let prefix_length = "--tile-size=".len();
+                let tile_size_text = &arg[prefix_length..];
+                deepzoom_tile_size = match tile_size_text.parse() {
+                    Ok(tile_size) if (1..=MAX_WINDOW_SIZE).contains(&tile_size) => tile_size,
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{tile_size_text}\".");
+                        eprintln!("        (It must be a number from 1 to {MAX_WINDOW_SIZE}.)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg.starts_with("--deepzoom-levels=") {
+                let prefix_length = "--deepzoom-levels=".len();
+                let levels_text = &arg[prefix_length..];
+                deepzoom_levels = match levels_text.parse() {
+                    Ok(levels) if (1..=MAX_DEEPZOOM_LEVELS).contains(&levels) => levels,
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{levels_text}\".");
+                        eprintln!("        (It must be a number from 1 to {MAX_DEEPZOOM_LEVELS}.)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg.starts_with("--export-npy=") {
+                let prefix_length = "--export-npy=".len();
+                let path = &arg[prefix_length..];
+                if path.is_empty() {
+                    eprintln!("Error:  The PATH in --export-npy=PATH must not be empty.");
+                    std::process::exit(EXIT_USAGE_ERROR)
+                }
+                npy_path = Some(path.to_string());
+            } else if still_looking_for_options && arg.starts_with("--export-heightmap=") {
+                let prefix_length = "--export-heightmap=".len();
+                let path = &arg[prefix_length..];
+                if !is_png_filename(path) && !is_obj_filename(path) {
+                    eprintln!("Error:  The PATH in --export-heightmap=PATH must end in .png or .obj.");
+                    std::process::exit(EXIT_USAGE_ERROR)
+                }
+                heightmap_path = Some(path.to_string());
+            } else if still_looking_for_options && arg.starts_with("--heightmap-step=") {
+                let prefix_length = "--heightmap-step=".len();
+                let step_text = &arg[prefix_length..];
+                heightmap_step = match step_text.parse() {
+                    Ok(step) if step > 0 => step,
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{step_text}\".");
+                        eprintln!("        (It must be a number more than zero.)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && (arg == "--export-heightmap" || arg == "--heightmap-step") {
+                eprintln!("Error:  The {arg} argument seems to be missing its \"=\" part.");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--stats=") {
+                let prefix_length = "--stats=".len();
+                let path = &arg[prefix_length..];
+                if path.is_empty() {
+                    eprintln!("Error:  The PATH in --stats=PATH must not be empty.");
+                    std::process::exit(EXIT_USAGE_ERROR)
+                }
+                stats_path = Some(path.to_string());
+            } else if still_looking_for_options && arg == "--stats" {
+                eprintln!("Error:  The --stats=PATH argument seems to be missing the \"=PATH\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg == "--export-npy" {
+                eprintln!("Error:  The --export-npy=PATH argument seems to be missing the \"=PATH\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && (arg == "--deepzoom" || arg == "--tile-size" || arg == "--deepzoom-levels") {
+                eprintln!("Error:  The {arg} argument seems to be missing its \"=\" part.");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg == "--contact-sheet" {
+                contact_sheet = Some(DEFAULT_CONTACT_SHEET_GRID_SIZE);
+            } else if still_looking_for_options && arg.starts_with("--contact-sheet=") {
+                let prefix_length = "--contact-sheet=".len();
+                let grid_size_text = &arg[prefix_length..];
+                contact_sheet = match grid_size_text.parse() {
+                    Ok(grid_size) if grid_size > 0 => Some(grid_size),
                     _ => {
-                        println!("Error:  {arg} has an invalid value of \"{size_text}\".");
-                        std::process::exit(1)
+                        eprintln!("Error:  {arg} has an invalid value of \"{grid_size_text}\".");
+                        eprintln!("        (It must be a number more than zero.)");
+                        std::process::exit(EXIT_USAGE_ERROR)
                     }
                 };
-                if window_size_to_use == 0 {
-                    println!("Error:  The NUMBER in --size=NUMBER must be more than zero.");
-                    std::process::exit(1)
-                }
-            } else if still_looking_for_options && arg == "--size" {
-                println!("Error:  The --size=NUMBER argument seems to be missing the \"=NUMBER\" part.");
-                println!("        (Did you forget the \"=\" sign?)");
-                std::process::exit(1)
-            } else if still_looking_for_options && arg.starts_with("--bailout=") {
-                let prefix_length = "--bailout=".len();
-                let bailout_text = &arg[prefix_length..];
-                bailout_value_to_use = match bailout_text.parse() {
-                    Ok(size) => Some(size),
+            } else if still_looking_for_options && arg.starts_with("--crop=") {
+                let prefix_length = "--crop=".len();
+                let rectangle_text = &arg[prefix_length..];
+                crop_rectangle = match parse_crop_rectangle(rectangle_text) {
+                    Ok(rectangle) => Some(rectangle),
+                    Err(message) => {
+                        eprintln!("Error:  {arg} has an invalid value:");
+                        eprintln!("        {message}");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--crop" {
+                eprintln!("Error:  The --crop=X0,Y0,X1,Y1 argument seems to be missing the \"=X0,Y0,X1,Y1\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg == "--julia-atlas" {
+                julia_atlas = Some(DEFAULT_JULIA_ATLAS_GRID_SIZE);
+            } else if still_looking_for_options && arg.starts_with("--julia-atlas=") {
+                let prefix_length = "--julia-atlas=".len();
+                let grid_size_text = &arg[prefix_length..];
+                julia_atlas = match grid_size_text.parse() {
+                    Ok(grid_size) if grid_size > 0 => Some(grid_size),
                     _ => {
-                        println!("Error:  {arg} has an invalid value of \"{bailout_text}\".");
-                        std::process::exit(1)
+                        eprintln!("Error:  {arg} has an invalid value of \"{grid_size_text}\".");
+                        eprintln!("        (It must be a number more than zero.)");
+                        std::process::exit(EXIT_USAGE_ERROR)
                     }
                 };
-            } else if still_looking_for_options && arg == "--bailout" {
-                println!("Error:  The --bailout=NUMBER argument seems to be missing the \"=NUMBER\" part.");
-                println!("        (Did you forget the \"=\" sign?)");
-                std::process::exit(1)
-            } else if still_looking_for_options && arg.starts_with("--julia=") {
-                let prefix_length = "--julia=".len();
-                let julia_text = &arg[prefix_length..];
-                let text_values: Vec<_> = julia_text.split(",").collect();
-                if text_values.len() != 2 {
-                    println!("Error:  The X,Y value in --julia=X,Y ({julia_text}) needs exactly one comma.");
-                    std::process::exit(1)
-                }
-                let (x_text, y_text) = (text_values[0], text_values[1]);
-                let x_result = x_text.parse::<Float>();
-                if x_result.is_err() {
-                    println!("Error:  The X value in --julia=X,Y ({julia_text}) is not a valid number.");
-                    std::process::exit(1)
-                }
-                let y_result = y_text.parse::<Float>();
-                if y_result.is_err() {
-                    println!("Error:  The Y value in --julia=X,Y ({julia_text}) is not a valid number.");
-                    std::process::exit(1)
-                }
-                c = Some((x_result.unwrap(), y_result.unwrap()));
-                original_center_to_use = (0.0, 0.0);  // We'll start centered for Julia sets.
-            } else if still_looking_for_options && arg == "--julia" {
-                println!("Error:  The --julia=X,Y argument seems to be missing the \"=X,Y\" part.");
-                println!("        (Did you forget the \"=\" sign?)");
-                std::process::exit(1)
+            } else if still_looking_for_options && arg.starts_with("--rays=") {
+                let prefix_length = "--rays=".len();
+                let angles_text = &arg[prefix_length..];
+                overlays.ray_angles = match angles_text.split(',').map(parse_ray_angle).collect() {
+                    Ok(angles) => angles,
+                    Err(message) => {
+                        eprintln!("Error:  {arg} has an invalid value:");
+                        eprintln!("        {message}");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--rays" {
+                eprintln!("Error:  The --rays=ANGLES argument seems to be missing the \"=ANGLES\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg == "--sidecar" {
+                program_config.save_sidecar = true;
+            } else if still_looking_for_options && arg.starts_with("--depth=") {
+                let prefix_length = "--depth=".len();
+                let depth_text = &arg[prefix_length..];
+                program_config.screenshot_bit_depth = match depth_text {
+                    "8" => 8,
+                    "16" => 16,
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{depth_text}\".");
+                        eprintln!("        (It must be \"8\" or \"16\".)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--depth" {
+                eprintln!("Error:  The --depth=BITS argument seems to be missing the \"=BITS\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg == "--watch" {
+                watch = true;
+            } else if still_looking_for_options && arg == "--auto-zoom" {
+                auto_zoom_delay = Some(std::time::Duration::from_secs_f64(DEFAULT_AUTO_ZOOM_DELAY));
+            } else if still_looking_for_options && arg.starts_with("--auto-zoom=") {
+                let prefix_length = "--auto-zoom=".len();
+                auto_zoom_delay = match parse_auto_zoom_delay(&arg[prefix_length..]) {
+                    Ok(delay) => Some(delay),
+                    Err(message) => {
+                        eprintln!("Error:  {message}");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--progressive" {
+                progressive = true;
+            } else if still_looking_for_options && arg == "--dither" {
+                config.dither = true;
+            } else if still_looking_for_options && arg == "--profile" {
+                program_config.profile = true;
+            } else if still_looking_for_options && arg.starts_with("--coord-format=") {
+                let prefix_length = "--coord-format=".len();
+                let format_text = &arg[prefix_length..];
+                coordinate_format = match CoordinateFormat::from_name(format_text) {
+                    Some(format) => format,
+                    None => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{format_text}\".");
+                        eprintln!("        (It must be \"decimal\", \"scientific\", or \"command-line\".)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg == "--coord-format" {
+                eprintln!("Error:  The --coord-format=TYPE argument seems to be missing the \"=TYPE\" part.");
+                eprintln!("        (Did you forget the \"=\" sign?)");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg == "--crosshair" {
+                overlays.show_crosshair = true;
+            } else if still_looking_for_options && arg == "--crosshair=baked" {
+                overlays.show_crosshair = true;
+                overlays.bake_crosshair_into_screenshots = true;
+            } else if still_looking_for_options && arg == "--smooth-labels" {
+                overlays.smooth_labels = true;
+            } else if still_looking_for_options && arg == "--bailout-heatmap" {
+                overlays.show_bailout_heatmap = true;
+            } else if still_looking_for_options && arg.starts_with("--bailout-tint=") {
+                let prefix_length = "--bailout-tint=".len();
+                overlays.bailout_tint_color = match parse_rgb_triplet(&arg[prefix_length..]) {
+                    Ok(color) => color,
+                    Err(message) => {
+                        eprintln!("Error:  {message}");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && arg.starts_with("--bailout-tint-opacity=") {
+                let prefix_length = "--bailout-tint-opacity=".len();
+                let opacity_text = &arg[prefix_length..];
+                overlays.bailout_tint_opacity = match opacity_text.parse::<f32>() {
+                    Ok(opacity) if (0.0..=1.0).contains(&opacity) => opacity,
+                    _ => {
+                        eprintln!("Error:  {arg} has an invalid value of \"{opacity_text}\".");
+                        eprintln!("        (It must be a number from 0 to 1.)");
+                        std::process::exit(EXIT_USAGE_ERROR)
+                    }
+                };
+            } else if still_looking_for_options && (arg == "--bailout-tint" || arg == "--bailout-tint-opacity") {
+                eprintln!("Error:  The {arg} argument seems to be missing its \"=\" part.");
+                std::process::exit(EXIT_USAGE_ERROR)
+            } else if still_looking_for_options && arg.starts_with("--crosshair=") {
+                eprintln!("Error:  Invalid value in {arg}.  (Only \"baked\" is allowed.)");
+                std::process::exit(EXIT_USAGE_ERROR)
             } else if still_looking_for_options && arg.starts_with("--") {
-                println!("Error:  Invalid option:  {arg}");
-                std::process::exit(1)
+                eprintln!("Error:  Invalid option:  {arg}");
+                std::process::exit(EXIT_USAGE_ERROR)
             } else {
-                println!("Error:  Invalid argument:  {arg}");
-                std::process::exit(1)
+                eprintln!("Error:  Invalid argument:  {arg}");
+                std::process::exit(EXIT_USAGE_ERROR)
             }
         }
     }  // (End of parsing command-line arguments.)
 
-    println!();
-    println!();
-    println!("Welcome to JLR-Mandelbrot!");
-    println!();
-    println!("---=== A Mandelbrot set viewer ===---");
-    println!();
-    println!("Programmed in the Rust programming language by Jean-Luc Romano.");
-    println!("(Programming work was started on Thursday, November 17, 2022.)");
-    println!("Contact info:  {}@{}.{}", "jl_post", "hotmail", "com");
-    println!();
-    println!();
-    println!("Instructions:");
-    println!();
-    println!(" * Left-click to zoom in.");
-    println!(" * Right-click to zoom out.");
-    println!(" * Press S to save a screenshot.");
-    println!(" * Press C to print coordinates (to this console).");
-    println!(" * Press the Q key or the Escape key to quit/exit the program.");
-    println!();
-    println!("For additional help, run this program with the --help switch.");
-    println!();
-    println!();
+    // With --bailout=auto, the bailout is picked for the starting view
+    // (and picked again whenever the view changes; see below):
+    if auto_bailout {
+        config.bailout = Some(automatic_bailout(program_config.distance_from_center_to_edge));
+    }
+
+    // Without cycle detection, most points in the set would
+    // be iterated forever, so a bailout is needed:
+    if needs_a_bailout(&config) {
+        eprintln!("Error:  --no-cycle-detect needs a --bailout=NUMBER to go with it.");
+        std::process::exit(EXIT_USAGE_ERROR)
+    }
+
+    // There's nothing to watch without a config file:
+    if watch && config_path.is_none() {
+        eprintln!("Error:  --watch needs a config file to watch (given with --config=PATH).");
+        std::process::exit(EXIT_USAGE_ERROR)
+    }
+
+    // External rays are only traced for the Mandelbrot set:
+    if !overlays.ray_angles.is_empty() && config.c.is_some() {
+        eprintln!("Error:  --rays only works with the Mandelbrot set (not with --julia).");
+        std::process::exit(EXIT_USAGE_ERROR)
+    }
+
+    // A distance that's too small for the center (e.g., --distance=1e-320,
+    // or --distance=1e-20 with --center=1,0) would draw every pixel as
+    // the same point, so it's refused up front:
+    {
+        let info = program_config.starting_info(&config);
+        if info.is_degenerate_for(config.float_type) {
+            eprintln!("Error:  {}", degenerate_view_message(&info, config.float_type));
+            std::process::exit(EXIT_USAGE_ERROR)
+        }
+    }
+
+    // If a dry run was requested, show the viewport that
+    // would be drawn, and exit without drawing anything:
+    if program_config.dry_run {
+        let info = program_config.starting_info(&config);
+        println!("Viewport:  {info:#?}");
+        return ()
+    }
+
+    // If a float benchmark was requested, calculate the image (without
+    // opening a window) in both f32 and f64, report the results, and exit:
+    if float_bench {
+        let info = program_config.starting_info(&config);
+        print!("{}", float_benchmark_text(&float_benchmark(&info, &config)));
+        return ()
+    }
+
+    // If a diff was requested, draw the image (without opening a
+    // window) with the settings as given, and again with the --diff
+    // file's settings applied on top of them, save the image of their
+    // differences (or write it to stdout, with --format=ppm), report
+    // how many pixels differed, and exit:
+    if let Some(diff_config_path) = &diff_config_path {
+        let mut second_config = config.clone();
+        if let Err(message) = ConfigFile::load(diff_config_path)
+                                  .and_then(|config_file| config_file.apply_to(&mut second_config, &mut program_config.clone())) {
+            eprintln!("Error:  {message}");
+            std::process::exit(EXIT_USAGE_ERROR)
+        }
+        if needs_a_bailout(&second_config) {
+            eprintln!("Error:  The --diff file turns off cycle detection, but doesn't give a bailout to go with it.");
+            std::process::exit(EXIT_USAGE_ERROR)
+        }
+        // (Both are drawn in the first config's view, so the
+        // --diff file's size, center, and distance are ignored.)
+        let info = program_config.starting_info(&config);
+        let (image_buffer, difference) = render_diff(&info, &config, &second_config);
+        if program_config.write_ppm_to_stdout {
+            // (The report goes to stderr, as stdout is where the image is going.)
+            eprint!("{}", escape_difference_text(&difference));
+            if let Err(error) = write_ppm(&image_buffer, info.width, info.height, &mut std::io::stdout().lock()) {
+                eprintln!("Error:  Unable to write the PPM image to stdout:  {error}");
+                std::process::exit(EXIT_IO_ERROR)
+            }
+        } else {
+            print!("{}", escape_difference_text(&difference));
+            let now = chrono::Utc::now();
+            let filename = now.format("jlr-mandelbrot.diff.%Y%m%d.%H%M%S.%3f.png").to_string();
+            if let Err(message) = save_image_to_filename(&image_buffer, info.width, info.height, &filename, None) {
+                eprintln!("Error:  {message}");
+                std::process::exit(EXIT_IO_ERROR)
+            }
+        }
+        return ()
+    }
+
+    // If a Deep Zoom image was requested, draw and save all of its
+    // tiles (without opening a window), and exit:
+    if let Some(deepzoom_path) = &deepzoom_path {
+        if config.projection != Projection::Rectangular {
+            eprintln!("Error:  --deepzoom only works with --projection=rectangular.");
+            std::process::exit(EXIT_USAGE_ERROR)
+        }
+        let start_time = std::time::Instant::now();
+        match save_deepzoom(deepzoom_path, &config, &program_config, deepzoom_tile_size, deepzoom_levels) {
+            Ok(number_of_tiles) => println!("Saved {number_of_tiles} tiles, described by \"{deepzoom_path}.dzi\", in {} sec.",
+                                            start_time.elapsed().as_micros() as Float / 1e6),
+            Err(message) => {
+                eprintln!("Error:  {message}");
+                std::process::exit(EXIT_IO_ERROR)
+            }
+        }
+        return ()
+    }
+
+    // If a heightmap was asked for, calculate the escape values (or
+    // load them from the cache directory, if they're there), save
+    // them as heights, and exit (without opening a window):
+    if let Some(heightmap_path) = &heightmap_path {
+        let (width, height) = (program_config.window_size, program_config.window_size);
+        let info = program_config.starting_info(&config);
+        let start_time = std::time::Instant::now();
+        let (escape_details, _) = cached_escape_details(&info, &config, program_config.cache_dir.as_deref());
+        let heights = heightmap_heights(&escape_details);
+        let result = if is_obj_filename(heightmap_path) {
+            std::fs::write(heightmap_path, heightmap_obj(&heights, width, height, heightmap_step))
+                .map_err(|error| format!("Unable to save the heightmap to a file named \"{heightmap_path}\":  {error}"))
+        } else {
+            save_heightmap_png(&heights, width, height, heightmap_path)
+        };
+        if let Err(message) = result {
+            eprintln!("Error:  {message}");
+            std::process::exit(EXIT_IO_ERROR)
+        }
+        if !program_config.quiet {
+            println!("Saved the heightmap to \"{heightmap_path}\" in {} sec.",
+                     start_time.elapsed().as_micros() as Float / 1e6);
+        }
+        return ()
+    }
+
+    // If the escape values were asked for as a NumPy array, calculate
+    // them (without opening a window), save them, and exit:
+    if let Some(npy_path) = &npy_path {
+        let (width, height) = (program_config.window_size, program_config.window_size);
+        let info = program_config.starting_info(&config);
+        let start_time = std::time::Instant::now();
+        let escape_details = render_escape_details(&info, &config);
+        if let Err(error) = std::fs::write(npy_path, npy_bytes(&escape_details, width, height)) {
+            eprintln!("Error:  Unable to save the escape values to \"{npy_path}\":  {error}");
+            std::process::exit(EXIT_IO_ERROR)
+        }
+        if !program_config.quiet {
+            println!("Saved the escape values (as a {height} x {width} array) to \"{npy_path}\" in {} sec.",
+                     start_time.elapsed().as_micros() as Float / 1e6);
+        }
+        return ()
+    }
+
+    // With --stdin-coords, print the escape value of every point
+    // read from stdin (without opening a window), and exit once
+    // stdin runs out.  (Lines that aren't points are complained
+    // about on stderr, and skipped.)
+    if stdin_coords {
+        use std::io::{BufRead, Write};
+        // (The cycle threshold is that of the view given by
+        // --size, --center, and --distance, as always.)
+        let info = program_config.starting_info(&config);
+        let threshold = cycle_threshold(&info, &config);
+        let mut stdout = std::io::stdout().lock();
+        for (line_number, line) in std::io::stdin().lock().lines().enumerate() {
+            let line = match line {
+                Ok(line) => line,
+                Err(error) => {
+                    eprintln!("Error:  Unable to read from stdin:  {error}");
+                    std::process::exit(EXIT_IO_ERROR)
+                }
+            };
+            if line.trim().is_empty() {
+                continue
+            }
+            match stdin_coords_line(&line, &config, threshold) {
+                Ok(output_line) => {
+                    if let Err(error) = writeln!(stdout, "{output_line}") {
+                        eprintln!("Error:  Unable to write to stdout:  {error}");
+                        std::process::exit(EXIT_IO_ERROR)
+                    }
+                }
+                Err(message) => eprintln!("Error:  Line {} of stdin:  {message}", line_number + 1),
+            }
+        }
+        return ()
+    }
+
+    // If a crop was requested, zoom in on its rectangle of the
+    // starting view, draw just that part (as big as the --size),
+    // save it (or write it to stdout, with --format=ppm), and exit:
+    if let Some(rectangle) = crop_rectangle {
+        let info = program_config.starting_info(&config);
+        let (view, output_rectangle) = match crop_view(&info, rectangle, program_config.window_size) {
+            Ok(crop) => crop,
+            Err(message) => {
+                eprintln!("Error:  {message}");
+                std::process::exit(EXIT_USAGE_ERROR)
+            }
+        };
+        if view.is_degenerate_for(config.float_type) {
+            eprintln!("Error:  The rectangle {left},{top},{right},{bottom} is too small to draw at this center and distance.",
+                      left = rectangle.0, top = rectangle.1, right = rectangle.2, bottom = rectangle.3);
+            eprintln!("        (The pixels would be too close together to tell apart.)");
+            std::process::exit(EXIT_USAGE_ERROR)
+        }
+        let escape_details = render_escape_details(&view, &config);
+        let mut image_buffer = vec![0u32; view.width * view.height];
+        color_escape_details(&escape_details, &mut image_buffer, &view, &config);
+        let image = cropped_image(&image_buffer, view.width, output_rectangle);
+        let (left, top, right, bottom) = output_rectangle;
+        let (width, height) = (right - left, bottom - top);
+        if program_config.write_ppm_to_stdout {
+            if let Err(error) = write_ppm(&image, width, height, &mut std::io::stdout().lock()) {
+                // (This goes to stderr, as stdout is where the image was going.)
+                eprintln!("Error:  Unable to write the PPM image to stdout:  {error}");
+                std::process::exit(EXIT_IO_ERROR)
+            }
+        } else {
+            let now = chrono::Utc::now();
+            let filename = now.format("jlr-mandelbrot.crop.%Y%m%d.%H%M%S.%3f.png").to_string();
+            let timestamp = now.format("%Y-%m-%d %H:%M:%S UTC").to_string();
+            let settings = screenshot_settings_text(&filename, &view, &config, &timestamp);
+            if let Err(message) = save_image_to_filename(&image, width, height, &filename, Some(&settings)) {
+                eprintln!("Error:  {message}");
+                std::process::exit(EXIT_IO_ERROR)
+            }
+        }
+        return ()
+    }
+
+    // If a Julia atlas was requested, draw it (without opening a
+    // window), save it (or write it to stdout, with --format=ppm),
+    // and exit.  (It's laid out just like a contact sheet.)
+    if let Some(grid_size) = julia_atlas {
+        let cells = julia_atlas_cells(&config, program_config.center, program_config.distance_from_center_to_edge, grid_size);
+        let cell_size = (program_config.window_size / grid_size).max(1);
+        let empty_color = background_color.map_or(CONTACT_SHEET_EMPTY_COLOR, |(r, g, b)| rgb_to_u32(r, g, b));
+        let (atlas, width, height) = render_contact_sheet(&cells, cell_size, (0.0, 0.0), JULIA_ATLAS_DISTANCE,
+                                                          overlays.smooth_labels, empty_color);
+        if program_config.write_ppm_to_stdout {
+            if let Err(error) = write_ppm(&atlas, width, height, &mut std::io::stdout().lock()) {
+                // (This goes to stderr, as stdout is where the image was going.)
+                eprintln!("Error:  Unable to write the PPM image to stdout:  {error}");
+                std::process::exit(EXIT_IO_ERROR)
+            }
+        } else {
+            let now = chrono::Utc::now();
+            let filename = now.format("jlr-mandelbrot.julia-atlas.%Y%m%d.%H%M%S.%3f.png").to_string();
+            if let Err(message) = save_image_to_filename(&atlas, width, height, &filename, None) {
+                eprintln!("Error:  {message}");
+                std::process::exit(EXIT_IO_ERROR)
+            }
+        }
+        return ()
+    }
+
+    // If a contact sheet was requested, draw it (without opening a
+    // window), save it (or write it to stdout, with --format=ppm),
+    // and exit:
+    if let Some(grid_size) = contact_sheet {
+        let thumbnails = contact_sheet_thumbnails(&config, grid_size);
+        let (columns, _) = contact_sheet_layout(thumbnails.len());
+        let thumbnail_size = (program_config.window_size / columns).max(1);
+        let empty_color = background_color.map_or(CONTACT_SHEET_EMPTY_COLOR, |(r, g, b)| rgb_to_u32(r, g, b));
+        let (sheet, width, height) = render_contact_sheet(&thumbnails, thumbnail_size, program_config.center,
+                                                          program_config.distance_from_center_to_edge,
+                                                          overlays.smooth_labels, empty_color);
+        if program_config.write_ppm_to_stdout {
+            if let Err(error) = write_ppm(&sheet, width, height, &mut std::io::stdout().lock()) {
+                // (This goes to stderr, as stdout is where the image was going.)
+                eprintln!("Error:  Unable to write the PPM image to stdout:  {error}");
+                std::process::exit(EXIT_IO_ERROR)
+            }
+        } else {
+            let now = chrono::Utc::now();
+            let filename = now.format("jlr-mandelbrot.contact-sheet.%Y%m%d.%H%M%S.%3f.png").to_string();
+            if let Err(message) = save_image_to_filename(&sheet, width, height, &filename, None) {
+                eprintln!("Error:  {message}");
+                std::process::exit(EXIT_IO_ERROR)
+            }
+        }
+        return ()
+    }
+
+    // If PPM output was requested, draw the image without
+    // opening a window, write it to stdout, and exit:
+    if program_config.write_ppm_to_stdout {
+        let (width, height) = (program_config.window_size, program_config.window_size);
+        let info = program_config.starting_info(&config);
+        let (escape_details, calculation_time) = cached_escape_details(&info, &config, program_config.cache_dir.as_deref());
+        if let Some(calculation_time) = calculation_time.filter(|_| program_config.profile) {
+            let mut profile = RenderProfile::new();
+            escape_details.iter().for_each(|details| profile.add_pixel(details));
+            profile.calculation_time = calculation_time;
+            // (This goes to stderr, as stdout is where the image is going.)
+            eprintln!("{}", profile.summary(calculation_time));
+        }
+        // (This goes to stderr, as stdout is where the image is going.)
+        if let Err(message) = stats_path.as_deref().map_or(Ok(()), |path| save_iteration_stats(path, &escape_details, &info, &config)) {
+            eprintln!("Warning:  {message}");
+        }
+        let mut image_buffer = vec![0u32; width * height];
+        color_escape_details(&escape_details, &mut image_buffer, &info, &config);
+        if let Err(error) = write_ppm(&image_buffer, width, height, &mut std::io::stdout().lock()) {
+            // (This goes to stderr, as stdout is where the image was going.)
+            eprintln!("Error:  Unable to write the PPM image to stdout:  {error}");
+            std::process::exit(EXIT_IO_ERROR)
+        }
+        return ()
+    }
+
+    // If a script was given, start reading its commands now
+    // (so that a missing script file is reported right away):
+    let script_commands = program_config.script_filename.as_ref().map(|filename| start_reading_script(filename));
+    let mut script_wait_until: Option<std::time::Instant> = None;
+    // (With --auto-zoom, when the current image was first shown finished.)
+    let mut image_shown_since: Option<std::time::Instant> = None;
+    // (With color cycling, when the palette was last shifted.)
+    let mut last_color_cycle_time = std::time::Instant::now();
+    let mut config_file_watcher = config_path.as_deref().filter(|_| watch).map(ConfigFileWatcher::new);
 
-    let (width, height) = (window_size_to_use, window_size_to_use);
+    let (width, height) = (program_config.window_size, program_config.window_size);
+    // (parse_window_size() already made sure of this, but the indexing
+    // of every buffer below counts on it, so it's checked once more
+    // before they're allocated; see bytes_needed_for_window().)
+    assert!(bytes_needed_for_window(width, height).is_some(),
+            "FATAL ERROR:  The buffers of a {width} x {height} window would be too big to index.");
 
     let mut window = minifb::Window::new(
-        "The Mandelbrot Set",
+        &window_title(title.as_deref(), config.formula, config.c),
         width,
         height,
         minifb::WindowOptions::default()
-    ).expect("Unable to create window.");
+    ).unwrap_or_else(|error| {
+        eprintln!("Error:  Unable to create the window:  {error:?}");
+        std::process::exit(EXIT_DISPLAY_ERROR)
+    });
+
+    // (The welcome banner waits until everything that could fail at
+    // startup has succeeded, so an error never follows it.)
+    if !program_config.quiet {
+        println!();
+        println!();
+        println!("Welcome to JLR-Mandelbrot!");
+        println!();
+        println!("---=== A Mandelbrot set viewer ===---");
+        println!();
+        println!("Programmed in the Rust programming language by Jean-Luc Romano.");
+        println!("(Programming work was started on Thursday, November 17, 2022.)");
+        println!("Contact info:  {}@{}.{}", "jl_post", "hotmail", "com");
+        println!();
+        println!();
+        println!("Instructions:");
+        println!();
+        println!(" * Left-click to zoom in.");
+        println!(" * Right-click to zoom out.");
+        println!(" * Press Z (or Shift+Z) to zoom in (or out) where the mouse is.");
+        println!(" * Press ] or [ to zoom in or out without moving the center.");
+        println!(" * Press Shift and an arrow key to nudge the center by one pixel.");
+        println!(" * Press Shift and a number key to bookmark a view, and the number key to go back.");
+        println!(" * Ctrl+click to print (and draw) a point's orbit.");
+        println!(" * Press O to hide or show the drawn orbit.");
+        println!(" * Press I to show or hide the iterations of the pixel under the mouse.");
+        println!(" * Press T to show or hide how long the image took to draw.");
+        println!(" * Press S to save a screenshot.");
+        println!(" * Press C to print coordinates (to this console).");
+        println!(" * Press X to show or hide a crosshair at the center.");
+        println!(" * Press M to show or hide a minimap of the whole set.");
+        println!(" * Press H to tint (or untint) the pixels that hit the bailout.");
+        println!(" * Press F to switch between the Mandelbrot set and a Julia set.");
+        println!(" * Press A to start or stop cycling the colors.");
+        println!(" * Press + or - to double or halve the bailout value.");
+        println!(" * Press ) or ( to double or halve the anti-aliasing samples.");
+        println!(" * Press the Q key or the Escape key to quit/exit the program.");
+        println!();
+        println!("For additional help, run this program with the --help switch.");
+        println!();
+        println!();
+        // (So that a random palette worth keeping can be drawn again.)
+        if let Some(palette) = &config.palette {
+            println!("Palette seed:  {}  (--palette-seed={} gives this palette again.)", palette.seed, palette.seed);
+            println!();
+        }
+    }
 
     // Use this to limit to max ~60 fps update rate:
     // window.limit_update_rate(Some(std::time::Duration::from_micros(16600)));
     // Use this to update with no delay:
     window.limit_update_rate(None);
 
-    let mut image_buffer: Vec<u32> = vec![0u32; width * height];
-
-    let (original_center_x, original_center_y) = original_center_to_use;
+    // (Until the first image is drawn, the window shows the --background.)
+    let (r, g, b) = background_color.unwrap_or((0, 0, 0));
+    let mut image_buffer: Vec<u32> = vec![rgb_to_u32(r, g, b); width * height];
+    let mut display_buffer: Vec<u32> = Vec::with_capacity(width * height);  // (Scratch space for overlays.)
+    // The escape details of every pixel in the image_buffer.
+    // (This is what gets saved to (and loaded from) the --cache-dir.)
+    let mut escape_details: Vec<EscapeDetails> = vec![EscapeDetails::default(); width * height];
 
-    let mut info = WindowAndViewportInfo::new(
-        width, height,  // (in pixels)
-        original_center_x, original_center_y,
-        original_distance_from_center_to_edge,
-        0);
+    let mut info = program_config.starting_info(&config);
     let mut mouse_info = MouseInfo::new();
 
+    // If drawing in bands was requested, figure out which pixels go into
+    // which band.  (As the window size never changes, we only do it once.)
+    // With --fast-fill, the whole image is a single band (row by row),
+    // as it's filled in by render_escape_details() all at once.
+    let bands = if config.fast_fill_min_size.is_some() {
+        Some(vec![all_pixels(width, height)])
+    } else {
+        config.number_of_bands.map(|number_of_bands| schedule_bands(width, height, number_of_bands))
+    };
+
+    // (With --progressive, this holds the passes still to be done
+    // for the current image, after its first pass has been drawn.)
+    let mut progressive_refinement: Option<ProgressiveRefinement> = None;
+
+    // (The Julia set's c, kept for when the F key switches
+    // back to it after showing the Mandelbrot set.)
+    let mut last_julia_c = config.c;
+
+    // (The user inputs that came in the same frame as another one,
+    // waiting to be handled in the frames after it.)
+    let mut pending_inputs = std::collections::VecDeque::new();
+
+    // (The views bookmarked with Shift and the number keys.)
+    let mut bookmarks = Bookmarks::default();
+
+    // With --stats, the statistics of each finished image are saved
+    // (replacing the last image's), so they're always the current one's:
+    // (--quiet can't be changed by the config file, so it's copied here.)
+    let quiet = program_config.quiet;
+    let save_stats = |escape_details: &[EscapeDetails], info: &WindowAndViewportInfo, config: &Config| {
+        if let Some(path) = &stats_path {
+            match save_iteration_stats(path, escape_details, info, config) {
+                Ok(()) if !quiet => println!("Saved the iteration statistics to \"{path}\"."),
+                Ok(()) => (),
+                Err(message) => eprintln!("Warning:  {message}"),
+            }
+        }
+    };
+
+    // (With --double-buffer, this holds the last finished image while
+    // the next one is drawn.  finished_info is the viewport of the image
+    // in the image_buffer, for as long as that image is finished; it's
+    // None while an image is being drawn.)
+    let mut finished_image: Option<FinishedImage> = None;
+    let mut finished_info = Some(info.clone());  // (The --background counts as finished.)
+
     let mut done = false;
-    window.update_with_buffer(&image_buffer, info.width, info.height).unwrap();
-    let mut user_input = get_user_input(&window, &info, &mut mouse_info);
+    update_window(&mut window, &image_buffer, &escape_details, &mut display_buffer, &overlays, &info);
+    let mut user_input = next_user_input(&mut pending_inputs, get_user_inputs(&window, &info, &mut mouse_info));
 
     'main_event_loop:
     loop {
         match user_input {
             UserInput::Quit => break 'main_event_loop,
-            UserInput::SaveScreenShot => save_screenshot(&image_buffer, info.width, info.height),
-            UserInput::ShowCoordinates => print_coordinates(&window, &info),
             UserInput::ZoomIn(x, y) => {
-                info = WindowAndViewportInfo::new(
-                    info.width, info.height,
-                    x, y, info.distance_from_center_to_edge / 2.0,
-                    info.zoom_level + 1);
-                done = false;  // Let the drawing begin again!
+                let new_info = zoomed_info(&info, x, y, true);
+                if new_info.is_degenerate_for(config.float_type) {
+                    eprintln!("Unable to zoom in any further:  The pixels would be too close together to tell apart.");
+                } else {
+                    info = new_info;
+                    done = false;  // Let the drawing begin again!
+                }
                 user_input = UserInput::Nothing;
                 continue 'main_event_loop
             }
             UserInput::ZoomOut(x, y) => {
-                info = WindowAndViewportInfo::new(
+                // (Far enough out, the new center or distance can
+                // overflow to infinity, which isn't a view at all.)
+                let new_info = WindowAndViewportInfo::is_valid_view(x, y, info.distance_from_center_to_edge * 2.0)
+                                   .then(|| zoomed_info(&info, x, y, false));
+                if new_info.as_ref().is_none_or(|new_info| new_info.is_degenerate_for(config.float_type)) {
+                    eprintln!("Unable to zoom out any further:  The distance would be too big to represent.");
+                } else if let Some(new_info) = new_info {
+                    info = new_info;
+                    done = false;  // Let the drawing begin again!
+                }
+                user_input = UserInput::Nothing;
+                continue 'main_event_loop
+            }
+            UserInput::GoTo(x, y, distance_from_center_to_edge) => {
+                // The zoom level is however many times the original
+                // distance would have to be halved to get here:
+                let zoom_level = (program_config.distance_from_center_to_edge / distance_from_center_to_edge)
+                                     .log2().round() as isize;
+                // (A script's zoomout or pan can go so far that the new
+                // center or distance overflows to infinity.)
+                if !WindowAndViewportInfo::is_valid_view(x, y, distance_from_center_to_edge) {
+                    eprintln!("Unable to go to {x:?},{y:?} with a distance of {distance_from_center_to_edge:?}:  The view would be too big to represent.");
+                    user_input = UserInput::Nothing;
+                    continue 'main_event_loop
+                }
+                let new_info = WindowAndViewportInfo::new(
                     info.width, info.height,
-                    x, y, info.distance_from_center_to_edge * 2.0,
-                    info.zoom_level - 1);
+                    x, y, distance_from_center_to_edge,
+                    zoom_level)
+                    .with_projection(info.projection);
+                if new_info.is_degenerate_for(config.float_type) {
+                    eprintln!("Unable to go to {x:?},{y:?} with a distance of {distance_from_center_to_edge:?}:  The pixels would be too close together to tell apart.");
+                } else {
+                    info = new_info;
+                    done = false;  // Let the drawing begin again!
+                }
+                user_input = UserInput::Nothing;
+                continue 'main_event_loop
+            }
+            UserInput::GoToBookmark(number) => {
+                // (Going to a bookmark is just like any other GoTo.)
+                user_input = match bookmarks.go_to(number) {
+                    Some(go_to) => {
+                        println!("Bookmark {number}");
+                        go_to
+                    }
+                    None => {
+                        println!("(There's no bookmark {number} yet.  Press Shift+{number} to bookmark the current view.)");
+                        UserInput::Nothing
+                    }
+                };
+                continue 'main_event_loop
+            }
+            UserInput::Nudge(columns, rows) => {
+                // (Only the center moves; the distance and zoom level stay the same.)
+                // (Far enough from zero, the pixels can get too close
+                // together, especially in f32.)
+                let (x, y) = nudged_center(&info, (columns, rows));
+                let new_info = WindowAndViewportInfo::is_valid_view(x, y, info.distance_from_center_to_edge)
+                                   .then(|| WindowAndViewportInfo::new(
+                                       info.width, info.height,
+                                       x, y, info.distance_from_center_to_edge,
+                                       info.zoom_level)
+                                       .with_projection(info.projection));
+                match new_info {
+                    Some(new_info) if !new_info.is_degenerate_for(config.float_type) => {
+                        info = new_info;
+                        println!("Center:  {x},{y}");
+                        done = false;  // Let the drawing begin again!
+                    }
+                    _ => eprintln!("Unable to move any further:  The pixels would be too close together to tell apart."),
+                }
+                user_input = UserInput::Nothing;
+                continue 'main_event_loop
+            }
+            UserInput::NextFractal => {
+                if config.c.is_some() {
+                    last_julia_c = config.c;
+                }
+                config.c = next_fractal(config.c, last_julia_c, (info.center_x, info.center_y));
+                if let Some(minimap) = &mut overlays.minimap {
+                    minimap.c = config.c;
+                }
+                println!("Fractal:  {}", fractal_name(config.formula, config.c));
+                window.set_title(&window_title(title.as_deref(), config.formula, config.c));
+                done = false;  // Let the drawing begin again!
+                user_input = UserInput::Nothing;
+                continue 'main_event_loop
+            }
+            UserInput::SetBailout(bailout) => {
+                config.bailout = Some(bailout);
+                auto_bailout = false;  // (The bailout was picked by hand.)
                 done = false;  // Let the drawing begin again!
                 user_input = UserInput::Nothing;
                 continue 'main_event_loop
             }
-            _ => ()
+            UserInput::IncreaseBailout | UserInput::DecreaseBailout => {
+                let increase = matches!(user_input, UserInput::IncreaseBailout);
+                let highest_escape_value = escape_details.iter()
+                                                         .filter_map(|details| details.escape_value)
+                                                         .max();
+                let new_bailout = adjusted_bailout(config.bailout, increase, highest_escape_value);
+                user_input = UserInput::Nothing;
+                if new_bailout == config.bailout {
+                    match new_bailout {
+                        None => println!("The bailout is already unlimited."),
+                        Some(bailout) => println!("The bailout is already at its limit of {bailout}."),
+                    }
+                } else {
+                    config.bailout = new_bailout;
+                    auto_bailout = false;  // (The bailout was picked by hand.)
+                    println!("Bailout:  {}", new_bailout.unwrap());
+                    done = false;  // Let the drawing begin again!
+                }
+                continue 'main_event_loop
+            }
+            UserInput::IncreaseAaSamples | UserInput::DecreaseAaSamples => {
+                let increase = matches!(user_input, UserInput::IncreaseAaSamples);
+                let new_aa_samples = adjusted_aa_samples(config.aa_samples, increase);
+                user_input = UserInput::Nothing;
+                if new_aa_samples == config.aa_samples {
+                    match new_aa_samples {
+                        None => println!("Anti-aliasing is already off."),
+                        Some(samples) => println!("Anti-aliasing is already at its limit of {samples} samples per pixel."),
+                    }
+                } else {
+                    config.aa_samples = new_aa_samples;
+                    match new_aa_samples {
+                        None => println!("Anti-aliasing:  off"),
+                        Some(samples) => println!("Anti-aliasing:  {samples} samples per pixel"),
+                    }
+                    // (Every pixel's samples are different, so unlike
+                    // the color period, this needs a whole new image.)
+                    done = false;  // Let the drawing begin again!
+                }
+                continue 'main_event_loop
+            }
+            UserInput::IncreaseColorPeriod | UserInput::DecreaseColorPeriod => {
+                let increase = matches!(user_input, UserInput::IncreaseColorPeriod);
+                let new_color_period = adjusted_color_period(config.color_period, increase);
+                user_input = UserInput::Nothing;
+                if new_color_period == config.color_period {
+                    println!("The color period is already at its limit of {new_color_period}.");
+                } else {
+                    config.color_period = new_color_period;
+                    println!("Color period:  {new_color_period}");
+                    // (Only the colors change, so the image is recolored
+                    // from its escape details instead of recalculated.)
+                    color_escape_details(&escape_details, &mut image_buffer, &info, &config);
+                    if let Some(refinement) = &mut progressive_refinement {
+                        refinement.config.color_period = new_color_period;
+                    }
+                }
+                continue 'main_event_loop
+            }
+            UserInput::ToggleColorCycle => {
+                color_cycling = !color_cycling;
+                user_input = UserInput::Nothing;
+                println!("Color cycling:  {}", if color_cycling { "on" } else { "off" });
+                if !color_cycling && config.palette_offset != 0 {
+                    // (The colors go back to where they started, so that
+                    // they match the settings saved with screenshots.)
+                    config.palette_offset = 0;
+                    color_escape_details(&escape_details, &mut image_buffer, &info, &config);
+                    if let Some(refinement) = &mut progressive_refinement {
+                        refinement.config.palette_offset = 0;
+                    }
+                }
+                continue 'main_event_loop
+            }
+            _ => handle_display_input(&mut user_input, &window, (&image_buffer, &escape_details), (&mut overlays, &mut bookmarks),
+                                      &info, (&config, &program_config), coordinate_format),
         }
 
         if done {
+            // While the window isn't active (such as when it's minimized),
+            // it's checked less often, and the image isn't refined (or
+            // recolored) any further.  But scripts, --watch (whose file is
+            // usually saved from another window), and --auto-zoom carry on:
+            let unattended = script_commands.is_some() || config_file_watcher.is_some() || auto_zoom_delay.is_some();
+            let paused = !unattended && !window.is_active();
+
             // Limit to max ~60 fps update rate:
-            window.limit_update_rate(Some(std::time::Duration::from_micros(16600)));
+            window.limit_update_rate(Some(if paused {
+                std::time::Duration::from_millis(INACTIVE_UPDATE_INTERVAL)
+            } else {
+                std::time::Duration::from_micros(16600)
+            }));
+
+            // (The pixel info (shown with the I key) follows the mouse.)
+            overlays.hovered_pixel = window.get_mouse_pos(minifb::MouseMode::Discard)
+                                           .map(|(column, row)| (row as usize, column as usize))
+                                           .filter(|&(row, column)| row < info.height && column < info.width);
 
             // Refresh the screen and get window inputs:
-            window.update_with_buffer(&image_buffer, info.width, info.height).unwrap();
+            update_window(&mut window, &image_buffer, &escape_details, &mut display_buffer, &overlays, &info);
 
             // Examine the window to determine the user's input:
-            user_input = get_user_input(&window, &info, &mut mouse_info);
+            user_input = next_user_input(&mut pending_inputs, get_user_inputs(&window, &info, &mut mouse_info));
+            if paused && matches!(user_input, UserInput::Nothing) {
+                continue 'main_event_loop
+            }
+
+            // If the user didn't do anything, and the image is still
+            // being refined (with --progressive), refine it some more:
+            if let (UserInput::Nothing, Some(refinement)) = (&user_input, &mut progressive_refinement) {
+                let threshold = cycle_threshold(&info, &config);
+                // (Refining stops for a moment every frame, so that
+                // the window stays responsive while it's going on.)
+                let finished = refinement.refine(&mut escape_details, &mut image_buffer, &info, threshold,
+                                                 std::time::Duration::from_millis(15));
+                if finished {
+                    progressive_refinement = None;
+                    if !program_config.quiet {
+                        println!("Zoom level {}:  Finished refining.", info.zoom_level);
+                        println!("{}", escape_value_report(&escape_details, config.bailout));
+                    }
+                    save_stats(&escape_details, &info, &config);
+                    if notify {
+                        ring_bell();
+                    }
+                    if let Some(cache_dir) = &program_config.cache_dir {
+                        let filename = escape_cache_filename(cache_dir, &info, &config, threshold);
+                        if let Err(error) = save_escape_details(&filename, &escape_details) {
+                            eprintln!("Warning:  Unable to save to the cache file {}:  {error}", filename.display());
+                        }
+                    }
+                }
+            }
+
+            // If the user didn't do anything, and a script is running
+            // (and isn't waiting, or waiting for the image to be
+            // refined), then run its next command:
+            if let (UserInput::Nothing, Some(receiver), None) = (&user_input, &script_commands, &progressive_refinement) {
+                let waiting = script_wait_until.is_some_and(|time| std::time::Instant::now() < time);
+                if !waiting {
+                    if let Ok(command) = receiver.try_recv() {
+                        if let ScriptCommand::Wait(milliseconds) = command {
+                            script_wait_until = Some(std::time::Instant::now()
+                                                     + std::time::Duration::from_millis(milliseconds));
+                        } else if let Some(input) = command.to_user_input(&info) {
+                            user_input = input;
+                        }
+                    }
+                }
+            }
+
+            // If the user didn't do anything, and the config file (with
+            // --watch) was saved since it was last checked, then apply its
+            // settings, recoloring or recalculating the image to match:
+            if let (UserInput::Nothing, Some(watcher), None) = (&user_input, &mut config_file_watcher, &progressive_refinement) {
+                if watcher.has_changed() {
+                    let (mut new_config, mut new_program_config) = (config.clone(), program_config.clone());
+                    // (A view that can't be drawn is turned away just
+                    // like a setting that can't be read.)
+                    let new_info = ConfigFile::load(&watcher.filename)
+                        .and_then(|config_file| config_file.apply_to(&mut new_config, &mut new_program_config))
+                        .and_then(|()| if needs_a_bailout(&new_config) {
+                            Err("The config file turns off cycle detection, but doesn't give a bailout to go with it.".to_string())
+                        } else {
+                            Ok(())
+                        })
+                        .and_then(|()| reloaded_view(&info, &program_config, &new_program_config, &new_config));
+                    match new_info {
+                        Err(message) => {
+                            eprintln!("Warning:  {message}");
+                            eprintln!("          (Keeping the last settings that could be read.)");
+                        }
+                        Ok(new_info) => {
+                            if new_program_config.window_size != program_config.window_size {
+                                eprintln!("Warning:  The size can't be changed while the program is running.");
+                                new_program_config.window_size = program_config.window_size;
+                            }
+                            let needs_recalculation = config_change_needs_recalculation((&info, &config), (&new_info, &new_config));
+                            config = new_config;
+                            program_config = new_program_config;
+                            if let Some(minimap) = &mut overlays.minimap {
+                                minimap.c = config.c;
+                            }
+                            window.set_title(&window_title(title.as_deref(), config.formula, config.c));
+                            info = new_info;
+                            if !program_config.quiet {
+                                println!("Applied the changed settings from {}.", watcher.filename);
+                            }
+                            if needs_recalculation {
+                                done = false;  // Let the drawing begin again!
+                            } else {
+                                color_escape_details(&escape_details, &mut image_buffer, &info, &config);
+                            }
+                        }
+                    }
+                }
+            }
+
+            // If the user didn't do anything, and --auto-zoom was given
+            // (and the image is finished), then once the image has been
+            // shown for the delay, zoom in on its most interesting part:
+            if let (UserInput::Nothing, Some(delay), None) = (&user_input, auto_zoom_delay, &progressive_refinement) {
+                let shown_since = *image_shown_since.get_or_insert_with(std::time::Instant::now);
+                if shown_since.elapsed() >= delay {
+                    let interesting_pixel = most_interesting_pixel(&escape_details, info.width, info.height);
+                    let zoomed_in = interesting_pixel.map(|(row, column)| {
+                        let (x, y) = convert_row_and_column_to_x_and_y(&info, row as Float, column as Float);
+                        let new_info = WindowAndViewportInfo::new(info.width, info.height,
+                                                                  x, y, info.distance_from_center_to_edge / 2.0,
+                                                                  info.zoom_level + 1)
+                                           .with_projection(info.projection);
+                        (x, y, new_info.is_degenerate_for(config.float_type))
+                    });
+                    match zoomed_in {
+                        Some((x, y, false)) => user_input = UserInput::ZoomIn(x, y),
+                        Some((_, _, true)) => {
+                            println!("Auto-zoom stopped:  The pixels would be too close together to tell apart.");
+                            auto_zoom_delay = None;
+                        }
+                        None => {
+                            println!("Auto-zoom stopped:  There's nothing left in the middle of the image to zoom in on.");
+                            auto_zoom_delay = None;
+                        }
+                    }
+                }
+            }
+
+            // If the user didn't do anything, and the colors are cycling
+            // (and the image is finished), then every so often shift the
+            // palette by one color, recoloring the image from its
+            // escape details:
+            if let (UserInput::Nothing, true, None) = (&user_input, color_cycling, &progressive_refinement) {
+                if last_color_cycle_time.elapsed() >= std::time::Duration::from_millis(COLOR_CYCLE_INTERVAL) {
+                    config.palette_offset = next_palette_offset(config.palette_offset);
+                    color_escape_details(&escape_details, &mut image_buffer, &info, &config);
+                    last_color_cycle_time = std::time::Instant::now();
+                }
+            }
 
             continue;  // Since we're done drawing the frame, don't draw it again.
         }
@@ -834,76 +10195,301 @@ fn main() {
 
         // If we get here, then we're generating a fractal image!
 
-        let threshold = info.delta_x / 4.0;
+        // With --bailout=auto, pick the bailout for this view:
+        if auto_bailout {
+            let bailout = automatic_bailout(info.distance_from_center_to_edge);
+            if config.bailout != Some(bailout) {
+                config.bailout = Some(bailout);
+                if !program_config.quiet {
+                    println!("Bailout:  {bailout}  (auto)");
+                }
+            }
+        }
+
+        let threshold = cycle_threshold(&info, &config);
+        // (See the comment above about pausing while the window isn't active.)
+        let unattended = script_commands.is_some() || config_file_watcher.is_some() || auto_zoom_delay.is_some();
+        progressive_refinement = None;  // (In case the last image was still being refined.)
+        image_shown_since = None;
+        overlays.hovered_pixel = None;  // (Its escape details are about to be replaced.)
+        overlays.trace_rays(&info);
+        overlays.diagnostics = diagnostics_text(&info, &config, threshold);
+        let start_time = std::time::Instant::now();
+
+        // If the escape details of this image were already calculated
+        // and saved to the cache directory, just load them from there:
+        let cache_filename = program_config.cache_dir.as_ref()
+                                 .map(|cache_dir| escape_cache_filename(cache_dir, &info, &config, threshold));
+        if let Some(cached_escape_details) = cache_filename.as_ref()
+                                                 .and_then(|filename| load_escape_details(filename, escape_details.len())) {
+            escape_details = cached_escape_details;
+            color_escape_details(&escape_details, &mut image_buffer, &info, &config);
+            done = true;
+            finished_image = None;
+            finished_info = Some(info.clone());
+            overlays.render_stats = Some(render_stats_text(start_time.elapsed().as_secs_f64(), magnification(&info)));
+            if !program_config.quiet {
+                println!("Zoom level {}:  Loaded from the cache.", info.zoom_level);
+            }
+            save_stats(&escape_details, &info, &config);
+            continue 'main_event_loop
+        }
 
         window.limit_update_rate(None);
 
-        let start_time = std::time::Instant::now();
+        // With --double-buffer, set aside the last finished image to keep
+        // showing while this one's drawn.  (If the last image wasn't
+        // finished, then the one set aside before it is still there.)
+        if let (true, Some(finished_info)) = (double_buffer, finished_info.take()) {
+            finished_image = Some(FinishedImage { image_buffer: image_buffer.clone(),
+                                                  escape_details: escape_details.clone(),
+                                                  info: finished_info });
+        }
+
+        // With --progressive, the first pass uses the lowest bailout
+        // (with the rest of the passes done once it's drawn):
+        let bailouts = if progressive { progressive_bailouts(config.bailout) } else { vec![config.bailout] };
+        let pass_config = Config { bailout: bailouts[0], ..config.clone() };
+
         let mut last_update_time = std::time::Instant::now();
+        let mut profile = RenderProfile::new();
+
+        // If a preview was requested, draw the whole image at a lower
+        // resolution first, and stretch it to fill the image_buffer.
+        // (Its escape details are stretched, too, for the overlays.)
+        // The full-resolution image then gets drawn on top of it.
+        // (With --double-buffer, nobody would see the preview.)
+        if preview_scale > 1 && !double_buffer {
+            let calculation_start_time = std::time::Instant::now();
+            let preview_info = WindowAndViewportInfo::new(
+                (info.width / preview_scale).max(1), (info.height / preview_scale).max(1),
+                info.center_x, info.center_y,
+                info.distance_from_center_to_edge,
+                info.zoom_level)
+                .with_projection(info.projection);
+            let preview_escape_details = render_escape_details(&preview_info, &pass_config);
+            let mut preview_buffer = vec![0u32; preview_info.width * preview_info.height];
+            color_escape_details(&preview_escape_details, &mut preview_buffer, &preview_info, &pass_config);
+            upscale_bilinear(&preview_buffer, preview_info.width, preview_info.height,
+                             &mut image_buffer, info.width, info.height);
+            for (i, details) in escape_details.iter_mut().enumerate() {
+                let (row, column) = (i / info.width, i % info.width);
+                let preview_row = (row * preview_info.height / info.height).min(preview_info.height - 1);
+                let preview_column = (column * preview_info.width / info.width).min(preview_info.width - 1);
+                *details = preview_escape_details[preview_row * preview_info.width + preview_column];
+            }
+            profile.calculation_time += calculation_start_time.elapsed();
+
+            let display_start_time = std::time::Instant::now();
+            update_window(&mut window, &image_buffer, &escape_details, &mut display_buffer, &overlays, &info);
+            profile.display_time += display_start_time.elapsed();
+            user_input = next_user_input(&mut pending_inputs, get_user_inputs(&window, &info, &mut mouse_info));
+            match user_input {
+                UserInput::Quit => break 'main_event_loop,
+                _ if user_input.changes_the_image() => continue 'main_event_loop,
+                _ => handle_display_input(&mut user_input, &window, (&image_buffer, &escape_details), (&mut overlays, &mut bookmarks),
+                                          &info, (&config, &program_config), coordinate_format),
+            }
+        }
+
+        // The pixels are calculated on other threads, so that this one
+        // can keep the window responsive the whole time, even while a
+        // single pixel takes a very long time to calculate.  If the user
+        // asks to quit, or for a different image, the cancel flag stops
+        // the calculation right away (even in the middle of a pixel),
+        // instead of waiting for it to get to the next pixel.
+        let cancel_flag = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let pass_config = Config { cancel_flag: Some(cancel_flag.clone()), ..pass_config };
+        let cancel = || cancel_flag.store(true, std::sync::atomic::Ordering::Relaxed);
+
+        if let Some(bands) = &bands {
+            // Fill out every pixel in the image_buffer, one band at a time,
+            // with each band's pixels calculated by several threads:
+            for band in bands {
+                let calculation_start_time = std::time::Instant::now();
+                let calculated_band = std::thread::scope(|scope| {
+                    let calculation = scope.spawn(|| {
+                        let band_escape_details = if pass_config.fast_fill_min_size.is_some() {
+                            render_escape_details(&info, &pass_config)  // (The band is every pixel, row by row.)
+                        } else {
+                            calculate_pixel_details_in_parallel(band, &info, &pass_config, threshold)
+                        };
+                        let band_colors = pixel_colors(band, &band_escape_details, &info, &pass_config, threshold);
+                        (band_escape_details, band_colors)
+                    });
+                    // (While the band is being calculated, keep the
+                    // window (as it was) updated and handle any input.)
+                    while !calculation.is_finished() {
+                        std::thread::sleep(std::time::Duration::from_millis(CALCULATION_POLL_INTERVAL));
+                        let (shown_buffer, shown_details, shown_info) =
+                            shown_image(finished_image.as_ref(), &image_buffer, &escape_details, &info);
+                        update_window(&mut window, shown_buffer, shown_details, &mut display_buffer, &overlays, shown_info);
+                        user_input = next_user_input(&mut pending_inputs, get_user_inputs(&window, shown_info, &mut mouse_info));
+                        match user_input {
+                            UserInput::Quit => { cancel(); return Err(Interruption::Quit) }
+                            _ if user_input.changes_the_image() => { cancel(); return Err(Interruption::StartOver) }
+                            _ => handle_display_input(&mut user_input, &window, (shown_buffer, shown_details), (&mut overlays, &mut bookmarks),
+                                                      shown_info, (&config, &program_config), coordinate_format),
+                        }
+                    }
+                    Ok(calculation.join().unwrap())
+                });
+                let (band_escape_details, band_colors) = match calculated_band {
+                    Ok(calculated_band) => calculated_band,
+                    Err(Interruption::Quit) => break 'main_event_loop,
+                    Err(Interruption::StartOver) => continue 'main_event_loop,
+                };
+                profile.calculation_time += calculation_start_time.elapsed();
+                for ((&(row, column), details), &color) in band.iter().zip(band_escape_details.iter()).zip(band_colors.iter()) {
+                    let i = row * info.width + column;
+                    escape_details[i] = *details;
+                    image_buffer[i] = color;
+                    profile.add_pixel(details);
+                }
 
-        // Create an iterator that will return pixel coordinates,
-        // swirling outward from the center of the window:
-        let (half_width, half_height) = (info.width / 2, info.height / 2);  // (in pixels)
-        let mut row_and_column_iterator = RowAndColumnIterator::new(half_width as isize,
-                                                                    half_height as isize);
-
-        // Fill out every pixel in the image_buffer:
-        for _ in 0..(info.width * info.height) {
-            // Find the coordinate (as (row, column))
-            // of the next pixel to operate on:
-            let (row, column): (usize, usize) = loop {
-                let (current_row, current_column) = row_and_column_iterator.next().unwrap();
-                // Check to see if the (current_row, current_column)
-                // pixel coordinate is in the window.  If not, keep
-                // looping until we find one that is in the window:
-                if current_row < 0 {
-                    continue  // (Out of bounds, so try again.)
-                } else if current_column < 0 {
-                    continue  // (Out of bounds, so try again.)
-                } else if current_row >= info.height as isize {
-                    continue  // (Out of bounds, so try again.)
-                } else if  current_column >= info.width as isize {
-                    continue  // (Out of bounds, so try again.)
-                } else {  // (Success!  We can keep this value.)
-                    break (current_row.try_into().unwrap(), current_column.try_into().unwrap())
+                // Refresh the image and get user input after every band:
+                let display_start_time = std::time::Instant::now();
+                let (shown_buffer, shown_details, shown_info) =
+                    shown_image(finished_image.as_ref(), &image_buffer, &escape_details, &info);
+                update_window(&mut window, shown_buffer, shown_details, &mut display_buffer, &overlays, shown_info);
+                profile.display_time += display_start_time.elapsed();
+                if !unattended && !window.is_active() {
+                    wait_while_inactive(&mut window, &mut mouse_info, program_config.quiet);
                 }
-            };
-            // Convert row & column into x & y:
-            let (x, y) = convert_row_and_column_to_x_and_y(&info, row as Float, column as Float);
-
-            // Is (x, y) part of the set?  Let's find out.
-            // And whatever the answer, find the color to
-            // plot at the pixel's row & column of the
-            // image_buffer:
-            let escape_value = calculate_escape_value(x, y, c, Some(threshold), bailout_value_to_use);
-            let (r, g, b) = color(escape_value);
-            let color_as_integer = rgb_to_u32(r, g, b);
-
-            // Set the pixel (at the row & column) of the
-            // image_buffer to the color we just calculated:
-            let i = row * info.width + column;
-            image_buffer[i] = color_as_integer;
-
-            // Periodically refresh the image and get user input:
-            if last_update_time.elapsed().as_millis() >= 1 {
-                window.update_with_buffer(&image_buffer, info.width, info.height).unwrap();
-                last_update_time = std::time::Instant::now();
-                user_input = get_user_input(&window, &info, &mut mouse_info);
+                user_input = next_user_input(&mut pending_inputs, get_user_inputs(&window, shown_info, &mut mouse_info));
 
                 match user_input {
-                    UserInput::Nothing => (),
                     UserInput::Quit => break 'main_event_loop,
-                    UserInput::SaveScreenShot => save_screenshot(&image_buffer, info.width, info.height),
-                    UserInput::ShowCoordinates => print_coordinates(&window, &info),
-                    _ => continue 'main_event_loop  // (The rest are handled at the top of the loop.)
+                    // (Inputs that change the image are handled at the top
+                    // of the loop, which then starts drawing it over.  The
+                    // rest are handled here, so drawing can carry on.)
+                    _ if user_input.changes_the_image() => continue 'main_event_loop,
+                    _ => handle_display_input(&mut user_input, &window, (shown_buffer, shown_details), (&mut overlays, &mut bookmarks),
+                                              shown_info, (&config, &program_config), coordinate_format),
+                }
+            }
+        } else {
+            let drawn = std::thread::scope(|scope| {
+                // One thread calculates (and colors) every pixel, and sends
+                // each one back here as it's done (see send_swirling_pixels()).
+                // (Its sender is dropped, and the receiver knows, once every
+                // pixel is sent.)
+                let (sender, receiver) = std::sync::mpsc::sync_channel(SWIRL_CHANNEL_CAPACITY);
+                let (frame_sender, frame_receiver) = std::sync::mpsc::channel();
+                let worker_frame_budget = frame_budget.clone();
+                scope.spawn(|| send_swirling_pixels(&info, &pass_config, threshold, worker_frame_budget,
+                                                    sender, frame_receiver));
+                let update_interval = if frame_budget.is_some() {
+                    std::time::Duration::from_micros(FRAME_INTERVAL)
+                } else {
+                    std::time::Duration::from_millis(CALCULATION_POLL_INTERVAL)
+                };
+
+                // Meanwhile, fill out every pixel in the image_buffer as
+                // it comes in, and periodically refresh the image and get
+                // user input (even while no pixels are coming in):
+                loop {
+                    match receiver.recv_timeout(std::time::Duration::from_millis(CALCULATION_POLL_INTERVAL)) {
+                        Ok((row, column, details, color, calculation_time)) => {
+                            // Set the pixel (at the row & column) of the
+                            // image_buffer to the color for what was just calculated:
+                            let i = row * info.width + column;
+                            escape_details[i] = details;
+                            image_buffer[i] = color;
+                            profile.calculation_time += calculation_time;
+                            profile.add_pixel(&details);
+                        }
+                        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => (),
+                        Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return Ok(()),  // (Every pixel is done.)
+                    }
+
+                    if last_update_time.elapsed() >= update_interval {
+                        let display_start_time = std::time::Instant::now();
+                        let (shown_buffer, shown_details, shown_info) =
+                            shown_image(finished_image.as_ref(), &image_buffer, &escape_details, &info);
+                        update_window(&mut window, shown_buffer, shown_details, &mut display_buffer, &overlays, shown_info);
+                        profile.display_time += display_start_time.elapsed();
+                        if frame_budget.is_some() {
+                            let _ = frame_sender.send(());  // (It's fine if every pixel's already done.)
+                        }
+                        if !unattended && !window.is_active() {
+                            wait_while_inactive(&mut window, &mut mouse_info, program_config.quiet);
+                        }
+                        last_update_time = std::time::Instant::now();
+                        user_input = next_user_input(&mut pending_inputs, get_user_inputs(&window, shown_info, &mut mouse_info));
+
+                        match user_input {
+                            UserInput::Quit => { cancel(); return Err(Interruption::Quit) }
+                            // (Inputs that change the image are handled at the top
+                            // of the loop, which then starts drawing it over.  The
+                            // rest are handled here, so drawing can carry on.)
+                            _ if user_input.changes_the_image() => { cancel(); return Err(Interruption::StartOver) }
+                            _ => handle_display_input(&mut user_input, &window, (shown_buffer, shown_details), (&mut overlays, &mut bookmarks),
+                                                      shown_info, (&config, &program_config), coordinate_format),
+                        }
+                    }
                 }
+            });
+            match drawn {
+                Ok(()) => (),
+                Err(Interruption::Quit) => break 'main_event_loop,
+                Err(Interruption::StartOver) => continue 'main_event_loop,
             }
         }
+        post_process_image(&escape_details, &mut image_buffer, info.width, info.height, &pass_config);
         done = true;
-        println!("Zoom level {}:  Elapsed time:  {} sec.",
-                 info.zoom_level,
-                 start_time.elapsed().as_micros() as Float / 1e6);
+        finished_image = None;  // (The image_buffer is finished, so it can be shown now.)
+        finished_info = Some(info.clone());
+        progressive_refinement = ProgressiveRefinement::new(&bailouts, &config, program_config.quiet);
+        // (The render stats are only updated here, once the image is
+        // finished, rather than every time the window is updated.)
+        overlays.render_stats = Some(render_stats_text(start_time.elapsed().as_secs_f64(), magnification(&info)));
+        if !program_config.quiet {
+            println!("Zoom level {}:  Elapsed time:  {} sec.",
+                     info.zoom_level,
+                     start_time.elapsed().as_micros() as Float / 1e6);
+        }
+        if program_config.profile {
+            println!("{}", profile.summary(start_time.elapsed()));
+        }
+        // (With --progressive, the report waits until it's refined,
+        // as the first pass's low bailout would always be reached.)
+        if !program_config.quiet && progressive_refinement.is_none() {
+            println!("{}", escape_value_report(&escape_details, config.bailout));
+        }
+        if progressive_refinement.is_none() {
+            save_stats(&escape_details, &info, &config);
+        }
+        // (With --progressive, the bell waits until it's refined.)
+        if notify && progressive_refinement.is_none() {
+            ring_bell();
+        }
+        // (A --progressive image is only saved to the cache once
+        // it's finished being refined.)
+        if let (Some(filename), None) = (&cache_filename, &progressive_refinement) {
+            if let Err(error) = save_escape_details(filename, &escape_details) {
+                eprintln!("Warning:  Unable to save to the cache file {}:  {error}", filename.display());
+            }
+        }
     }  // (End of 'main_event_loop.)
 }
 
 
+
+
+// Most of the tests above are only run with --test (see test_all()),
+// but these are also run by "cargo test":
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_function_over_a_large_range() {
+        test_color_function_over_a_large_range();
+    }
+
+    #[test]
+    fn mouse_info() {
+        test_mouse_info();
+    }
+}
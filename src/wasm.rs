@@ -0,0 +1,75 @@
+// The WebAssembly entry points of JLR-Mandelbrot, for drawing the
+// fractal onto a web page's <canvas>.  (This module is only built
+// with the "wasm" feature.)  Build it with:
+//
+//    cargo build --lib --no-default-features --features wasm --target wasm32-unknown-unknown
+//
+// and then run wasm-bindgen on the resulting .wasm file to get the
+// JavaScript glue.  From JavaScript, it's used like this:
+//
+//    const rgba = render_rgba(width, height, -0.5, 0.0, 1.725, 1000);
+//    const imageData = new ImageData(new Uint8ClampedArray(rgba.buffer), width, height);
+//    canvas.getContext("2d").putImageData(imageData, 0, 0);
+//
+// (Nothing here uses a window, the clock, or files, none of which
+// are available to WebAssembly in a browser.)
+
+use wasm_bindgen::prelude::*;
+
+use crate::{render_into_buffer, Config, EscapeDetails, WindowAndViewportInfo};
+
+
+// The most memory render_rgba() will ask for to draw one image (in
+// bytes):  its EscapeDetails, its pixels, and the RGBA bytes returned.
+// (A 32-bit WebAssembly module can't have more than 4 GiB of memory in
+// all, and browsers often give it less, so a bigger image would run
+// out of memory and trap, rather than being turned away as an Error.
+// It's also under isize::MAX, the most any one allocation can be.)
+const MAX_IMAGE_BYTES: usize = 1 << 30;
+
+// How many bytes each pixel of the image takes up (see MAX_IMAGE_BYTES).
+const BYTES_PER_PIXEL: usize = std::mem::size_of::<EscapeDetails>() + std::mem::size_of::<u32>() + 4;
+
+
+// Draws the Mandelbrot set centered on center_x + center_y*i (with
+// distance from the center to the edges) into a width x height image,
+// and returns its pixels as RGBA bytes (four per pixel, row by row),
+// which is what a canvas's ImageData expects.
+//
+// A bailout of 0 means no bailout is used (just like not giving
+// --bailout to the JLR-Mandelbrot program).
+//
+// A view that can't be drawn (a distance that isn't a positive
+// number, a center that isn't finite, or pixels too close together to
+// tell apart), or an image too big to hold, is thrown to JavaScript
+// as an Error, instead of trapping the whole module.
+#[wasm_bindgen]
+pub fn render_rgba(width: usize, height: usize,
+                   center_x: f64, center_y: f64,
+                   distance: f64,
+                   bailout: usize) -> Result<Vec<u8>, JsError> {
+    if !WindowAndViewportInfo::is_valid_view(center_x, center_y, distance) {
+        return Err(JsError::new(&format!("Can't draw around the center {center_x},{center_y} with a distance of {distance:e}:  \
+                                          The center must be finite, and the distance must be a positive number.")))
+    }
+    let info = WindowAndViewportInfo::new(width, height, center_x, center_y, distance, 0);
+    if info.is_degenerate() {
+        return Err(JsError::new(&format!("Can't draw around the center {center_x},{center_y} with a distance of {distance:e}:  \
+                                          The pixels would be too close together to tell apart.")))
+    }
+    // (On 32-bit WebAssembly, a usize only goes up to about 4 billion.)
+    let Some(pixel_count) = width.checked_mul(height)
+                                 .filter(|pixel_count| pixel_count.checked_mul(BYTES_PER_PIXEL)
+                                                                  .is_some_and(|bytes| bytes <= MAX_IMAGE_BYTES)) else {
+        return Err(JsError::new(&format!("A {width} x {height} image is too big to draw.  \
+                                          (It would need more than {MAX_IMAGE_BYTES} bytes of memory.)")))
+    };
+    let mut config = Config::new();
+    config.bailout = if bailout == 0 { None } else { Some(bailout) };
+
+    let mut buffer = vec![0u32; pixel_count];
+    render_into_buffer(&mut buffer, &info, &config);
+    Ok(buffer.iter()
+             .flat_map(|&pixel| [(pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8, 0xff])
+             .collect())
+}